@@ -0,0 +1,103 @@
+/// Splits a command line into arguments, like a shell would
+/// (but without any variable expansion or globbing).
+///
+/// - Whitespace separates arguments, unless quoted
+///   (`-Dsomething="a b"` is one argument: `-Dsomething=a b`).
+/// - Both `"double"` and `'single'` quotes work.
+/// - `\` escapes a quote or whitespace (`\"` for a literal `"`).
+///   Other backslashes are kept as-is, so Windows paths
+///   like `C:\Users\me` don't need escaping.
+/// - An unclosed quote runs until the end of the input.
+///
+/// # Example
+/// ```
+/// # use ql_core::split_args;
+/// assert_eq!(
+///     split_args(r#"-Xmx2G -Dpath="C:\Program Files\x""#),
+///     ["-Xmx2G", r"-Dpath=C:\Program Files\x"]
+/// );
+/// ```
+#[must_use]
+pub fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // Separate from `current.is_empty()`, so `""` gives an empty argument
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            // Inside single quotes, everything is literal
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => current.push(c),
+
+            (_, '\\') => match chars.peek() {
+                Some(&next) if next == '"' || next == '\'' || next.is_whitespace() => {
+                    current.push(next);
+                    chars.next();
+                    in_arg = true;
+                }
+                _ => {
+                    current.push(c);
+                    in_arg = true;
+                }
+            },
+
+            (Some(_), '"') => quote = None,
+            (Some(_), _) => current.push(c),
+
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, _) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, _) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_tokens() {
+        assert_eq!(
+            split_args(r#"-Xmx2G -Dsomething="a b" --dir 'my folder'"#),
+            ["-Xmx2G", "-Dsomething=a b", "--dir", "my folder"]
+        );
+        assert_eq!(split_args(r#"--title """#), ["--title", ""]);
+        // Unclosed quote, still being typed
+        assert_eq!(split_args(r#"-Dx="a b"#), ["-Dx=a b"]);
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        assert_eq!(split_args(r#"-Dmsg=\"hi\""#), [r#"-Dmsg="hi""#]);
+        assert_eq!(
+            split_args(r#""say \"hello there\"""#),
+            [r#"say "hello there""#]
+        );
+        assert_eq!(split_args(r"a\ b"), ["a b"]);
+        assert_eq!(split_args(r"C:\Users\me"), [r"C:\Users\me"]);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(split_args("").is_empty());
+        assert!(split_args("   \t ").is_empty());
+    }
+}
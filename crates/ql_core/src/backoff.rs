@@ -0,0 +1,172 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::StatusCode;
+
+/// Upper limit for a single wait between attempts.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Whether a response was rate limited (`429 Too Many Requests`)
+/// or a server error (`5xx`), for use as
+/// [`retry_with_backoff`]'s `should_retry`.
+#[must_use]
+pub fn should_retry_response(response: &reqwest::Response) -> bool {
+    is_retry_status(response.status())
+}
+
+fn is_retry_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Like [`crate::retry`], but waits between attempts
+/// (exponential backoff, with jitter).
+///
+/// Use this for servers that may be briefly overloaded or
+/// rate limiting (auth servers, store APIs), where retrying
+/// immediately would just fail again.
+///
+/// Besides errors, results that `should_retry` returns `true` for
+/// are tried again (eg: [`should_retry_response`] for HTTP `429`
+/// or `5xx` responses). Pass `|_| false` to only retry errors.
+/// If the last attempt gets one, it's returned as-is.
+///
+/// - `attempts`: Total number of tries (including the first one)
+/// - `base_delay`: Wait after the first failure, doubled after each
+///   one after that (up to 30 seconds), plus up to 50% random jitter
+///   so many clients don't retry at the exact same time.
+///
+/// # Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use ql_core::{retry_with_backoff, should_retry_response};
+/// # async fn f() -> Result<reqwest::Response, reqwest::Error> {
+/// retry_with_backoff(3, Duration::from_millis(500), should_retry_response, || {
+///     ql_core::CLIENT.get("https://api.modrinth.com/v2").send()
+/// })
+/// .await
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns the last error if all attempts failed.
+pub async fn retry_with_backoff<F, Fut, T, E>(
+    attempts: usize,
+    base_delay: Duration,
+    should_retry: impl Fn(&T) -> bool,
+    f: F,
+) -> Result<T, E>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut delay = base_delay;
+    for _ in 1..attempts {
+        match f().await {
+            Ok(n) if !should_retry(&n) => return Ok(n),
+            Ok(_) | Err(_) => {
+                tokio::time::sleep(delay + jitter(delay / 2)).await;
+                delay = (delay * 2).min(MAX_DELAY);
+            }
+        }
+    }
+    f().await
+}
+
+/// A pseudo-random duration up to `max`.
+///
+/// Doesn't need to be good randomness,
+/// just different across clients.
+fn jitter(max: Duration) -> Duration {
+    let max_nanos = max.as_nanos();
+    if max_nanos == 0 {
+        return Duration::ZERO;
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    let nanos = u128::from(nanos) % max_nanos;
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(5);
+
+    #[tokio::test]
+    async fn succeeds_after_failures() {
+        let calls = AtomicUsize::new(0);
+        let result = retry_with_backoff(
+            5,
+            DELAY,
+            |_| false,
+            || async {
+                let n = calls.fetch_add(1, Ordering::SeqCst);
+                if n < 2 { Err(n) } else { Ok("done") }
+            },
+        )
+        .await;
+        assert_eq!(result, Ok("done"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_busy_results() {
+        let is_busy = |n: &&str| *n == "busy";
+        let calls = AtomicUsize::new(0);
+        let result: Result<&str, ()> = retry_with_backoff(5, DELAY, is_busy, || async {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            Ok(if n < 2 { "busy" } else { "done" })
+        })
+        .await;
+        assert_eq!(result, Ok("done"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Still busy at the end, returned anyway
+        let result: Result<&str, ()> =
+            retry_with_backoff(2, DELAY, is_busy, || async { Ok("busy") }).await;
+        assert_eq!(result, Ok("busy"));
+    }
+
+    #[test]
+    fn retry_statuses() {
+        assert!(is_retry_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retry_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retry_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retry_status(StatusCode::OK));
+        assert!(!is_retry_status(StatusCode::NOT_FOUND));
+        assert!(!is_retry_status(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_attempts() {
+        let calls = AtomicUsize::new(0);
+        let start = std::time::Instant::now();
+        let result: Result<(), usize> = retry_with_backoff(
+            3,
+            DELAY,
+            |_| false,
+            || async { Err(calls.fetch_add(1, Ordering::SeqCst)) },
+        )
+        .await;
+        // Last error is returned
+        assert_eq!(result, Err(2));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        // Waited 5ms, then 10ms (plus jitter)
+        assert!(start.elapsed() >= DELAY * 3);
+
+        // Always tried at least once
+        let result: Result<(), ()> =
+            retry_with_backoff(0, DELAY, |_| false, || async { Err(()) }).await;
+        assert_eq!(result, Err(()));
+    }
+
+    #[test]
+    fn jitter_in_range() {
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+        assert!(jitter(DELAY) < DELAY);
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use thiserror::Error;
+
+/// Lets the user cancel a long-running operation
+/// (like creating an instance or installing Java).
+///
+/// Unlike aborting the task, the operation notices this
+/// between files, so it can clean up after itself
+/// instead of leaving half-downloaded stuff around.
+///
+/// Clone it, hand one to the operation, and call
+/// [`CancellationToken::cancel`] on the other.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Call this between steps of an operation,
+    /// to stop (with `?`) if it was cancelled.
+    ///
+    /// # Errors
+    /// If [`CancellationToken::cancel`] was called.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The operation was stopped through a [`CancellationToken`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("cancelled by user")]
+pub struct Cancelled;
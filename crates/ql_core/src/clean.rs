@@ -1,11 +1,12 @@
 use std::{collections::HashSet, fs::Metadata, path::Path};
 
 use fs::DirEntry;
+use sha1::{Digest, Sha1};
 use tokio::fs;
 
 use crate::{
     IntoIoError, IntoJsonError, IoError, JsonFileError, LAUNCHER_DIR,
-    file_utils::{exists, get_launcher_dir},
+    file_utils::{self, exists, get_launcher_dir},
     info,
     json::{AssetIndex, VersionDetails},
     pt,
@@ -200,3 +201,223 @@ async fn get_used_indexes() -> Result<Vec<String>, JsonFileError> {
 
     Ok(used_files)
 }
+
+/// The result of [`dedupe_assets`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DedupeReport {
+    /// How much disk space was freed, in bytes.
+    pub reclaimed_bytes: u64,
+    /// How many per-instance asset files were
+    /// replaced with links to the shared copy.
+    pub files_linked: usize,
+}
+
+/// Deduplicates asset objects stored inside instances against
+/// the shared `QuantumLauncher/assets/dir/objects/` folder:
+/// - `instances/<NAME>/assets/objects/`, left by old launcher
+///   versions if not migrated yet
+/// - `instances/<NAME>/private_assets/objects/`, for instances
+///   with their own assets (see `InstanceConfigJson::private_assets`)
+///
+/// Each per-instance object is verified by its SHA1 hash (which is also
+/// its file name) before being replaced with a hard link to the shared copy.
+/// Old `assets` fall back to a symlink if hard links aren't supported,
+/// `private_assets` are left as-is (they must stay real files, so the
+/// instance can be moved). Objects missing from the shared
+/// folder are copied there first.
+///
+/// Corrupted objects (hash doesn't match) are left untouched.
+///
+/// Does nothing on Windows, where linked
+/// files can't be told apart from copies.
+///
+/// # Errors
+/// - User lacks permissions
+/// - Neither hard links nor symlinks could be created
+pub async fn dedupe_assets() -> Result<DedupeReport, IoError> {
+    if cfg!(not(unix)) {
+        return Ok(DedupeReport::default());
+    }
+    let objects_dir = LAUNCHER_DIR.join("assets/dir/objects");
+    let instances_dir = LAUNCHER_DIR.join("instances");
+    dedupe_assets_in(&objects_dir, &instances_dir).await
+}
+
+async fn dedupe_assets_in(
+    objects_dir: &Path,
+    instances_dir: &Path,
+) -> Result<DedupeReport, IoError> {
+    let mut report = DedupeReport::default();
+    if !exists(instances_dir).await {
+        return Ok(report);
+    }
+
+    let mut instances = fs::read_dir(instances_dir).await.dir(instances_dir)?;
+    while let Some(instance) = instances.next_entry().await.dir(instances_dir)? {
+        // (folder, whether symlinks are fine)
+        for (dir, allow_symlink) in [("assets/objects", true), ("private_assets/objects", false)] {
+            let instance_objects = instance.path().join(dir);
+            if !exists(&instance_objects).await {
+                continue;
+            }
+
+            let mut prefixes = fs::read_dir(&instance_objects)
+                .await
+                .dir(&instance_objects)?;
+            while let Some(prefix) = prefixes.next_entry().await.dir(&instance_objects)? {
+                let prefix_dir = prefix.path();
+                if !prefix_dir.is_dir() {
+                    continue;
+                }
+                let mut objects = fs::read_dir(&prefix_dir).await.dir(&prefix_dir)?;
+                while let Some(object) = objects.next_entry().await.dir(&prefix_dir)? {
+                    let shared = objects_dir
+                        .join(prefix.file_name())
+                        .join(object.file_name());
+                    dedupe_asset_object(&object.path(), &shared, allow_symlink, &mut report)
+                        .await?;
+                }
+            }
+        }
+    }
+
+    if report.files_linked > 0 {
+        info!(
+            "Deduplicated {} asset objects ({:.1} MB reclaimed)",
+            report.files_linked,
+            report.reclaimed_bytes as f64 / (1024.0 * 1024.0)
+        );
+    }
+    Ok(report)
+}
+
+async fn dedupe_asset_object(
+    path: &Path,
+    shared: &Path,
+    allow_symlink: bool,
+    report: &mut DedupeReport,
+) -> Result<(), IoError> {
+    let metadata = fs::symlink_metadata(path).await.path(path)?;
+    if !metadata.is_file() || is_same_file(path, shared).await {
+        return Ok(());
+    }
+    let Some(hash) = path.file_name().and_then(|n| n.to_str()) else {
+        return Ok(());
+    };
+
+    let bytes = fs::read(path).await.path(path)?;
+    if !sha1_matches(&bytes, hash) {
+        pt!("Skipping corrupted asset object: {path:?}");
+        return Ok(());
+    }
+
+    let mut reclaimed = 0;
+    if exists(shared).await {
+        let shared_bytes = fs::read(shared).await.path(shared)?;
+        if sha1_matches(&shared_bytes, hash) {
+            reclaimed = metadata.len();
+        } else {
+            // The shared copy is the broken one, replace it
+            fs::write(shared, &bytes).await.path(shared)?;
+        }
+    } else {
+        if let Some(parent) = shared.parent() {
+            fs::create_dir_all(parent).await.path(parent)?;
+        }
+        fs::copy(path, shared).await.path(shared)?;
+    }
+
+    let tmp_path = path.with_extension("ql-link");
+    _ = fs::remove_file(&tmp_path).await;
+    if fs::hard_link(shared, &tmp_path).await.is_err() {
+        if !allow_symlink {
+            return Ok(());
+        }
+        file_utils::create_symlink(shared, &tmp_path)?;
+    }
+    fs::rename(&tmp_path, path).await.path(path)?;
+    report.files_linked += 1;
+    report.reclaimed_bytes += reclaimed;
+
+    Ok(())
+}
+
+fn sha1_matches(bytes: &[u8], hash: &str) -> bool {
+    let mut hasher = Sha1::default();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize()).eq_ignore_ascii_case(hash)
+}
+
+#[cfg(unix)]
+async fn is_same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a).await, fs::metadata(b).await) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// Not supported, [`dedupe_assets`] is skipped on Windows.
+#[cfg(not(unix))]
+#[allow(clippy::unused_async)]
+async fn is_same_file(_: &Path, _: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn dedupe_two_instances() {
+        const DATA: &[u8] = b"some sound effect";
+
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let objects_dir = root.join("assets/dir/objects");
+        let instances_dir = root.join("instances");
+
+        let mut hasher = Sha1::default();
+        hasher.update(DATA);
+        let hash = format!("{:x}", hasher.finalize());
+        let relative = Path::new(&hash[0..2]).join(&hash);
+
+        for dir in [
+            objects_dir.clone(),
+            instances_dir.join("A/assets/objects"),
+            instances_dir.join("B/assets/objects"),
+            instances_dir.join("C/private_assets/objects"),
+        ] {
+            let path = dir.join(&relative);
+            fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+            fs::write(&path, DATA).await.unwrap();
+        }
+
+        let report = dedupe_assets_in(&objects_dir, &instances_dir)
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            DedupeReport {
+                reclaimed_bytes: 3 * DATA.len() as u64,
+                files_linked: 3,
+            }
+        );
+        let linked = instances_dir.join("A/assets/objects").join(&relative);
+        assert_eq!(fs::read(&linked).await.unwrap(), DATA);
+        // Still a real file (hard link)
+        let private = instances_dir
+            .join("C/private_assets/objects")
+            .join(&relative);
+        assert!(fs::symlink_metadata(&private).await.unwrap().is_file());
+
+        // Running again shouldn't do anything
+        #[cfg(unix)]
+        assert_eq!(
+            dedupe_assets_in(&objects_dir, &instances_dir)
+                .await
+                .unwrap(),
+            DedupeReport::default()
+        );
+    }
+}
@@ -22,7 +22,15 @@ cfg_if!(
     }
 );
 
+/// Whether this is a MUSL build (eg: for Alpine Linux).
+/// Natives built for glibc (like Mojang's) don't load there.
+pub const IS_MUSL: bool = cfg!(target_env = "musl");
+
 pub const DEFAULT_RAM_MB_FOR_INSTANCE: usize = 2048;
+/// The least memory an instance can be given,
+/// same as the bottom of the launcher's memory slider.
+/// Anything lower and the game doesn't start.
+pub const MIN_RAM_MB_FOR_INSTANCE: usize = 256;
 
 cfg_if!(
     if #[cfg(any(
@@ -38,5 +46,7 @@ cfg_if!(
         pub const ARCH: &str = "arm32";
     } else if #[cfg(target_arch = "x86")] {
         pub const ARCH: &str = "x86";
+    } else {
+        pub const ARCH: &str = std::env::consts::ARCH;
     }
 );
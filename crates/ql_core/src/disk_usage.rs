@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use tokio::fs;
+
+use crate::{Instance, IntoIoError, IoError, JsonFileError, file_utils::exists};
+
+/// How much disk space an instance takes up,
+/// with a breakdown of the usual suspects.
+///
+/// All sizes are in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Everything inside the instance folder.
+    pub total: u64,
+    /// The `mods` folder.
+    pub mods: u64,
+    /// The `saves` folder (clients), or world folders (servers).
+    pub saves: u64,
+    /// Whether the instance uses the shared `QuantumLauncher/assets/`
+    /// folder (`true`), or has its own copy of the assets (`false`).
+    ///
+    /// Shared assets aren't counted in [`DiskUsage::total`].
+    pub assets_linked: bool,
+}
+
+/// Calculates how much disk space an instance uses,
+/// so the user can decide what to delete.
+///
+/// Symlinks aren't followed, so shared files
+/// aren't counted towards the instance.
+///
+/// # Errors
+/// - Instance doesn't exist
+/// - Instance's `config.json` couldn't be read
+/// - User lacks permissions to read the instance folder
+pub async fn instance_disk_usage(instance: &Instance) -> Result<DiskUsage, JsonFileError> {
+    Ok(get_usage(
+        &instance.get_instance_path(),
+        &instance.get_game_dir().await?,
+        instance.is_server(),
+    )
+    .await?)
+}
+
+async fn get_usage(
+    instance_dir: &Path,
+    dot_minecraft: &Path,
+    is_server: bool,
+) -> Result<DiskUsage, IoError> {
+    let total = dir_size(instance_dir).await?;
+    let mods = dir_size(&dot_minecraft.join("mods")).await?;
+
+    let saves = if is_server {
+        // Servers keep their worlds (`world`, `world_nether`, ...)
+        // directly in the server folder
+        let mut saves = 0;
+        let mut entries = fs::read_dir(dot_minecraft).await.dir(dot_minecraft)?;
+        while let Some(entry) = entries.next_entry().await.dir(dot_minecraft)? {
+            let path = entry.path();
+            if exists(path.join("level.dat")).await {
+                saves += dir_size(&path).await?;
+            }
+        }
+        saves
+    } else {
+        dir_size(&dot_minecraft.join("saves")).await?
+    };
+
+    // Old launcher versions (and some imports) stored
+    // assets per-instance instead of in the shared folder,
+    // and instances can opt into a private copy
+    let assets_linked = is_server
+        || !(exists(instance_dir.join("assets")).await
+            || exists(instance_dir.join("private_assets")).await);
+
+    Ok(DiskUsage {
+        total,
+        mods,
+        saves,
+        assets_linked,
+    })
+}
+
+async fn dir_size(dir: &Path) -> Result<u64, IoError> {
+    if !exists(dir).await {
+        return Ok(0);
+    }
+
+    let mut size = 0;
+    let mut entries = fs::read_dir(dir).await.dir(dir)?;
+    while let Some(entry) = entries.next_entry().await.dir(dir)? {
+        let path = entry.path();
+        let metadata = fs::symlink_metadata(&path).await.path(&path)?;
+        if metadata.is_dir() {
+            size += Box::pin(dir_size(&path)).await?;
+        } else if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn usage_breakdown() {
+        let temp = tempfile::tempdir().unwrap();
+        let instance_dir = temp.path();
+        let dot_minecraft = instance_dir.join(".minecraft");
+
+        let files: &[(&str, usize)] = &[
+            ("config.json", 10),
+            (".minecraft/mods/a.jar", 100),
+            (".minecraft/mods/b.jar", 200),
+            (".minecraft/saves/World/level.dat", 50),
+            (".minecraft/saves/World/region/r.0.0.mca", 1000),
+            (".minecraft/options.txt", 5),
+        ];
+        for (path, len) in files {
+            let path = instance_dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+            fs::write(&path, vec![0u8; *len]).await.unwrap();
+        }
+
+        let usage = get_usage(instance_dir, &dot_minecraft, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            usage,
+            DiskUsage {
+                total: 1365,
+                mods: 300,
+                saves: 1050,
+                assets_linked: true,
+            }
+        );
+
+        fs::create_dir_all(instance_dir.join("assets/objects"))
+            .await
+            .unwrap();
+        let usage = get_usage(instance_dir, &dot_minecraft, false)
+            .await
+            .unwrap();
+        assert!(!usage.assets_linked);
+    }
+}
@@ -76,6 +76,8 @@ pub enum IoError {
     LauncherDirNotFound,
     #[error("directory is outside parent directory. POTENTIAL SECURITY RISK AVOIDED")]
     DirEscapeAttack,
+    #[error("couldn't stop process: {0}")]
+    Process(std::io::Error),
 }
 
 /// Converts any `std::io::Result<T>` into
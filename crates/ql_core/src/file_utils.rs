@@ -3,7 +3,7 @@ use std::{
     ffi::OsStr,
     io::{Cursor, Write},
     path::{MAIN_SEPARATOR, Path, PathBuf},
-    sync::LazyLock,
+    sync::{LazyLock, mpsc::Sender},
 };
 
 use flate2::read::GzDecoder;
@@ -13,7 +13,10 @@ use thiserror::Error;
 use walkdir::WalkDir;
 use zip::{ZipArchive, ZipWriter, write::FileOptions};
 
-use crate::{IntoIoError, JsonDownloadError, download, error::IoError};
+use crate::{
+    DownloadFileError, GenericProgress, IntoIoError, JsonDownloadError, download, error::IoError,
+    request::ProgressFn,
+};
 
 /// The path to the QuantumLauncher root folder.
 ///
@@ -228,6 +231,71 @@ pub async fn download_file_to_bytes(url: &str, user_agent: bool) -> Result<Vec<u
     r.bytes().await
 }
 
+/// Downloads a file from the given URL straight to `dest`,
+/// without keeping it all in memory (good for big files
+/// like server jars).
+///
+/// If `resume` is true and `dest` already has part of the file
+/// (from an earlier failed download), only the rest is downloaded
+/// (if the server supports it, see [`crate::request::DownloadRequest::resume`]).
+///
+/// Progress is sent to `sender` in bytes (`done` out of `total`),
+/// based on the `Content-Length` header. If the server doesn't
+/// send it, `total` stays at `1` and only the message is updated.
+///
+/// # Errors
+/// Returns an error if:
+/// - Error sending request
+/// - Request is rejected (HTTP status code)
+/// - `dest` couldn't be written to
+pub async fn download_file_to_path(
+    url: &str,
+    dest: &Path,
+    resume: bool,
+    sender: Option<&Sender<GenericProgress>>,
+) -> Result<(), DownloadFileError> {
+    let on_progress = |done: u64, total: Option<u64>| {
+        let Some(sender) = sender else {
+            return;
+        };
+        let mb = |n: u64| n as f64 / (1024.0 * 1024.0);
+        let (done, total, message) = match total {
+            Some(total) => (
+                done as usize,
+                total as usize,
+                format!("Downloading ({:.1} / {:.1} MB)", mb(done), mb(total)),
+            ),
+            None => (0, 1, format!("Downloading ({:.1} MB)", mb(done))),
+        };
+        _ = sender.send(GenericProgress {
+            done,
+            total,
+            message: Some(message),
+            has_finished: false,
+        });
+    };
+    download_file_to_path_with(url, dest, resume, &on_progress).await
+}
+
+/// Like [`download_file_to_path`], but reports progress by
+/// calling `on_progress` (see [`ProgressFn`]) directly,
+/// for callers with their own kind of progress.
+///
+/// # Errors
+/// Same as [`download_file_to_path`].
+pub async fn download_file_to_path_with(
+    url: &str,
+    dest: &Path,
+    resume: bool,
+    on_progress: ProgressFn<'_>,
+) -> Result<(), DownloadFileError> {
+    let mut request = download(url).on_progress(on_progress);
+    if resume {
+        request = request.resume();
+    }
+    request.path(dest).await
+}
+
 const NETWORK_ERROR_MSG: &str = r"
 - Check your internet connection
 - Check if you are behind a firewall/proxy
@@ -490,6 +558,51 @@ pub async fn extract_zip_archive<
     Ok(())
 }
 
+/// Extract a ZIP archive to a directory, skipping any entries
+/// whose path starts with one of the `exclude` prefixes
+/// (for example, `META-INF/`).
+///
+/// Unlike [`extract_zip_archive`] this never strips the
+/// top-level directory, and symlinks are extracted as regular files.
+pub async fn extract_zip_archive_excluding<
+    R: std::io::Read + std::io::Seek + Send + 'static,
+    P: AsRef<Path>,
+>(
+    reader: R,
+    extract_to: P,
+    exclude: Vec<String>,
+) -> Result<(), zip::result::ZipError> {
+    let mut archive = ZipArchive::new(reader)?;
+    let extract_to = canonicalize_a(extract_to).await;
+
+    tokio::task::spawn_blocking(move || {
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            // `enclosed_name` rejects paths escaping the target directory
+            let Some(relative_path) = file.enclosed_name() else {
+                continue;
+            };
+            if exclude.iter().any(|n| file.name().starts_with(n.as_str())) {
+                continue;
+            }
+
+            let out_path = extract_to.join(relative_path);
+            if file.is_dir() {
+                std::fs::create_dir_all(&out_path)?;
+                continue;
+            }
+            if let Some(parent) = out_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut out_file = std::fs::File::create(&out_path)?;
+            std::io::copy(&mut file, &mut out_file)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|n| zip::result::ZipError::Io(n.into()))?
+}
+
 pub async fn zip_directory_to_bytes<P: AsRef<Path>>(dir: P) -> std::io::Result<Vec<u8>> {
     let mut buffer = Cursor::new(Vec::new());
     let mut zip = ZipWriter::new(&mut buffer);
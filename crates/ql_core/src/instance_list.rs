@@ -0,0 +1,189 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{
+    Instance, InstanceConfigJson, InstanceKind, IoError, LAUNCHER_DIR, Loader,
+    file_utils::read_filenames_from_dir,
+};
+
+/// Basic info about an instance or server,
+/// enough to show it in a list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceSummary {
+    pub name: String,
+    /// Minecraft version, or `None` if `details.json`
+    /// couldn't be read (broken instance).
+    pub version: Option<String>,
+    /// Or `None` if `config.json` couldn't be read (broken instance).
+    pub loader: Option<Loader>,
+    pub is_server: bool,
+}
+
+impl InstanceSummary {
+    #[must_use]
+    pub fn instance(&self) -> Instance {
+        Instance::new(
+            &self.name,
+            if self.is_server {
+                InstanceKind::Server
+            } else {
+                InstanceKind::Client
+            },
+        )
+    }
+}
+
+/// Lists the names of all instances (or servers) of `kind`.
+///
+/// The folder is created if it doesn't exist yet.
+///
+/// # Errors
+/// If the `instances`/`servers` folder couldn't be read
+/// or created ([`IoError::ReadDir`] or [`IoError::Io`] respectively).
+pub async fn get_entries(kind: InstanceKind) -> Result<Vec<String>, IoError> {
+    get_entries_in(&LAUNCHER_DIR.join(kind.get_dir_name())).await
+}
+
+async fn get_entries_in(root: &Path) -> Result<Vec<String>, IoError> {
+    Ok(read_filenames_from_dir(root)
+        .await?
+        .into_iter()
+        .filter(|n| !n.is_file)
+        .map(|n| n.name)
+        .collect())
+}
+
+/// Lists all instances and servers (sorted by name, clients first),
+/// along with their version and loader.
+///
+/// Broken instances are still listed, with the
+/// info that couldn't be read left as `None`.
+///
+/// # Errors
+/// If the `instances` or `servers` folder couldn't be read
+/// (or created, if missing).
+pub async fn list_instances() -> Result<Vec<InstanceSummary>, IoError> {
+    list_instances_in(&LAUNCHER_DIR).await
+}
+
+async fn list_instances_in(launcher_dir: &Path) -> Result<Vec<InstanceSummary>, IoError> {
+    let mut summaries = Vec::new();
+    for kind in [InstanceKind::Client, InstanceKind::Server] {
+        let root = launcher_dir.join(kind.get_dir_name());
+        let mut names = get_entries_in(&root).await?;
+        names.sort_unstable();
+
+        for name in names {
+            let dir = root.join(&name);
+            summaries.push(InstanceSummary {
+                version: read_version(&dir).await,
+                loader: InstanceConfigJson::read_from_dir(&dir)
+                    .await
+                    .ok()
+                    .map(|n| n.mod_type),
+                is_server: kind.is_server(),
+                name,
+            });
+        }
+    }
+    Ok(summaries)
+}
+
+/// Reads only the version name from `details.json`,
+/// instead of parsing the whole thing into a
+/// [`crate::json::VersionDetails`].
+async fn read_version(dir: &Path) -> Option<String> {
+    #[derive(Deserialize)]
+    struct VersionId {
+        id: String,
+    }
+
+    let file = tokio::fs::read_to_string(dir.join("details.json"))
+        .await
+        .ok()?;
+    serde_json::from_str::<VersionId>(&file).ok().map(|n| n.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn one_client_one_server() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let files = [
+            ("instances/Survival/details.json", r#"{"id":"1.21.4"}"#),
+            (
+                "instances/Survival/config.json",
+                r#"{"ram_in_mb":2048,"mod_type":"Fabric"}"#,
+            ),
+            ("servers/SMP/details.json", r#"{"id":"1.20.1"}"#),
+            (
+                "servers/SMP/config.json",
+                r#"{"ram_in_mb":2048,"mod_type":"Paper"}"#,
+            ),
+        ];
+        for (path, contents) in files {
+            let path = dir.join(path);
+            tokio::fs::create_dir_all(path.parent().unwrap())
+                .await
+                .unwrap();
+            tokio::fs::write(&path, contents).await.unwrap();
+        }
+
+        let list = list_instances_in(dir).await.unwrap();
+        assert_eq!(
+            list,
+            [
+                InstanceSummary {
+                    name: "Survival".to_owned(),
+                    version: Some("1.21.4".to_owned()),
+                    loader: Some(Loader::Fabric),
+                    is_server: false,
+                },
+                InstanceSummary {
+                    name: "SMP".to_owned(),
+                    version: Some("1.20.1".to_owned()),
+                    loader: Some(Loader::Paper),
+                    is_server: true,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn unreadable_dir_is_typed_error() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        // Not a directory
+        let file = dir.join("instances");
+        tokio::fs::write(&file, "").await.unwrap();
+        let result = get_entries_in(&file).await;
+        assert!(
+            matches!(&result, Err(IoError::ReadDir { parent, .. }) if *parent == file),
+            "{result:?}"
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let locked = dir.join("servers");
+            tokio::fs::create_dir(&locked).await.unwrap();
+            std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+            // root ignores permissions, nothing to test there
+            if std::fs::read_dir(&locked).is_err() {
+                let result = get_entries_in(&locked).await;
+                assert!(
+                    matches!(&result, Err(IoError::ReadDir { parent, .. }) if *parent == locked),
+                    "{result:?}"
+                );
+            }
+            std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+}
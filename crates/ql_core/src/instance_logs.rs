@@ -0,0 +1,154 @@
+use std::{path::Path, time::SystemTime};
+
+use tokio::fs;
+
+use crate::{Instance, IntoIoError, IoError, JsonFileError, file_utils::exists, info};
+
+/// A file in an instance's `logs` folder,
+/// from [`list_instance_logs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFileInfo {
+    /// File name (eg: `2024-05-01-2.log.gz`, `latest.log`)
+    pub name: String,
+    /// Size in bytes
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl LogFileInfo {
+    /// Whether this is an old, compressed log
+    /// (which the game never touches again).
+    #[must_use]
+    pub fn is_archived(&self) -> bool {
+        self.name.ends_with(".log.gz")
+    }
+
+    /// `(date, number)` from the game's `YYYY-MM-DD-N.log.gz`
+    /// naming, for sorting (`-10` comes after `-9`).
+    fn sort_key(&self) -> (Option<&str>, u32, Option<SystemTime>) {
+        let (date, number) = self
+            .name
+            .strip_suffix(".log.gz")
+            .and_then(|n| n.rsplit_once('-'))
+            .filter(|(date, _)| date.len() == 10)
+            .map_or((None, 0), |(date, n)| {
+                (Some(date), n.parse().unwrap_or_default())
+            });
+        (date, number, self.modified)
+    }
+}
+
+/// Lists the game logs of an instance (in `.minecraft/logs/`),
+/// newest first.
+///
+/// # Errors
+/// If the logs folder or the instance's
+/// `config.json` couldn't be read.
+pub async fn list_instance_logs(instance: &Instance) -> Result<Vec<LogFileInfo>, JsonFileError> {
+    Ok(list_logs_in(&instance.get_game_dir().await?.join("logs")).await?)
+}
+
+/// Deletes all but the newest `keep` old logs
+/// (`*.log.gz`) of an instance, returning how many bytes
+/// were freed.
+///
+/// The current logs (`latest.log`, `debug.log`)
+/// are always kept.
+///
+/// # Errors
+/// If the logs folder or the instance's `config.json`
+/// couldn't be read, or a log couldn't be deleted.
+pub async fn clear_old_instance_logs(
+    instance: &Instance,
+    keep: usize,
+) -> Result<u64, JsonFileError> {
+    Ok(clear_logs_in(&instance.get_game_dir().await?.join("logs"), keep).await?)
+}
+
+async fn list_logs_in(logs_dir: &Path) -> Result<Vec<LogFileInfo>, IoError> {
+    if !exists(logs_dir).await {
+        return Ok(Vec::new());
+    }
+    let mut logs = Vec::new();
+    let mut entries = fs::read_dir(logs_dir).await.dir(logs_dir)?;
+    while let Some(entry) = entries.next_entry().await.dir(logs_dir)? {
+        let path = entry.path();
+        let metadata = entry.metadata().await.path(&path)?;
+        if !metadata.is_file() {
+            continue;
+        }
+        logs.push(LogFileInfo {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+        });
+    }
+    logs.sort_by(|a, b| b.sort_key().cmp(&a.sort_key()));
+    Ok(logs)
+}
+
+async fn clear_logs_in(logs_dir: &Path, keep: usize) -> Result<u64, IoError> {
+    let old_logs = list_logs_in(logs_dir)
+        .await?
+        .into_iter()
+        .filter(LogFileInfo::is_archived);
+
+    let mut freed = 0;
+    for log in old_logs.skip(keep) {
+        let path = logs_dir.join(&log.name);
+        fs::remove_file(&path).await.path(&path)?;
+        freed += log.size;
+    }
+    if freed > 0 {
+        info!(
+            "Cleared old logs ({:.1} MB)",
+            freed as f64 / (1024.0 * 1024.0)
+        );
+    }
+    Ok(freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn keeps_newest_logs() {
+        let temp = tempfile::tempdir().unwrap();
+        let logs_dir = temp.path();
+
+        let files = [
+            "2024-01-05-1.log.gz",
+            "2024-03-02-2.log.gz",
+            "2024-03-02-10.log.gz",
+            "2023-12-31-1.log.gz",
+            "2024-03-02-1.log.gz",
+            "latest.log",
+        ];
+        for name in files {
+            fs::write(logs_dir.join(name), [0u8; 10]).await.unwrap();
+        }
+
+        let names = |logs: Vec<LogFileInfo>| -> Vec<String> {
+            logs.into_iter()
+                .filter(LogFileInfo::is_archived)
+                .map(|n| n.name)
+                .collect()
+        };
+        assert_eq!(
+            names(list_logs_in(logs_dir).await.unwrap()),
+            [
+                "2024-03-02-10.log.gz",
+                "2024-03-02-2.log.gz",
+                "2024-03-02-1.log.gz",
+                "2024-01-05-1.log.gz",
+                "2023-12-31-1.log.gz",
+            ]
+        );
+
+        assert_eq!(clear_logs_in(logs_dir, 2).await.unwrap(), 30);
+        let left = list_logs_in(logs_dir).await.unwrap();
+        assert!(left.iter().any(|n| n.name == "latest.log"));
+        assert_eq!(names(left), ["2024-03-02-10.log.gz", "2024-03-02-2.log.gz"]);
+    }
+}
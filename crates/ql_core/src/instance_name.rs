@@ -0,0 +1,170 @@
+use thiserror::Error;
+
+/// Why an instance (or server) name was rejected
+/// by [`validate_instance_name`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NameError {
+    #[error("instance name is empty")]
+    Empty,
+    #[error("an instance named {0:?} already exists")]
+    AlreadyExists(String),
+    #[error("instance name can't contain {0:?}")]
+    InvalidChar(char),
+    #[error("{0:?} is a reserved name (not allowed by Windows)")]
+    ReservedName(String),
+    #[error("instance name can't end with a dot or space (not allowed by Windows)")]
+    TrailingDotOrSpace,
+}
+
+/// Characters that aren't allowed in file names on
+/// some OS (or would escape the instances folder).
+fn is_disallowed(c: char) -> bool {
+    matches!(
+        c,
+        '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '\'' | '\u{7F}'
+    ) || c.is_ascii_control()
+}
+
+/// Names Windows doesn't allow for files, even with an
+/// extension (`CON`, `nul.txt`, ...). Rejected everywhere,
+/// so instances can be moved between computers.
+fn is_reserved(name: &str) -> bool {
+    const RESERVED: &[&str] = &["CON", "PRN", "AUX", "NUL"];
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED.iter().any(|n| stem.eq_ignore_ascii_case(n)) {
+        return true;
+    }
+    // COM1-COM9, LPT1-LPT9
+    stem.len() == 4
+        && (stem[..3].eq_ignore_ascii_case("COM") || stem[..3].eq_ignore_ascii_case("LPT"))
+        && matches!(stem.as_bytes()[3], b'1'..=b'9')
+}
+
+/// Checks whether `name` can be used for a new
+/// (or renamed) instance or server.
+///
+/// `existing` is the list of names already taken
+/// (see [`crate::get_entries`]). When renaming,
+/// leave out the instance's current name. Names are
+/// compared ignoring case, since Windows and macOS do.
+///
+/// See [`sanitize_instance_name`] for cleaning up
+/// a name (from user input) instead of rejecting it.
+///
+/// # Errors
+/// With the (first) reason the name isn't allowed.
+pub fn validate_instance_name(name: &str, existing: &[String]) -> Result<(), NameError> {
+    if name.trim().is_empty() {
+        return Err(NameError::Empty);
+    }
+    if let Some(c) = name.chars().find(|c| is_disallowed(*c)) {
+        return Err(NameError::InvalidChar(c));
+    }
+    if name == "." || name == ".." || is_reserved(name) {
+        return Err(NameError::ReservedName(name.to_owned()));
+    }
+    // Windows silently strips these, so "a." and "a" would be the same folder
+    if name.ends_with(['.', ' ']) {
+        return Err(NameError::TrailingDotOrSpace);
+    }
+    let lowercase = name.to_lowercase();
+    if existing.iter().any(|n| n.to_lowercase() == lowercase) {
+        return Err(NameError::AlreadyExists(name.to_owned()));
+    }
+    Ok(())
+}
+
+/// Removes disallowed characters (and surrounding
+/// whitespace, and trailing dots) from an instance name.
+///
+/// The result may still be rejected by [`validate_instance_name`]
+/// (for example, if it's empty or already taken).
+#[must_use]
+pub fn sanitize_instance_name(mut name: String) -> String {
+    name.retain(|c| !is_disallowed(c));
+    name.trim_start()
+        .trim_end_matches(|c: char| c == '.' || c.is_whitespace())
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_names() {
+        let existing = vec!["Survival".to_owned()];
+        assert_eq!(validate_instance_name("Creative 1.21", &existing), Ok(()));
+
+        assert_eq!(validate_instance_name("", &existing), Err(NameError::Empty));
+        assert_eq!(
+            validate_instance_name("   ", &existing),
+            Err(NameError::Empty)
+        );
+        assert_eq!(
+            validate_instance_name("Survival", &existing),
+            Err(NameError::AlreadyExists("Survival".to_owned()))
+        );
+        assert_eq!(
+            validate_instance_name("survival", &existing),
+            Err(NameError::AlreadyExists("survival".to_owned()))
+        );
+        assert_eq!(
+            validate_instance_name("Survival.", &existing),
+            Err(NameError::TrailingDotOrSpace)
+        );
+        assert_eq!(
+            validate_instance_name("Creative ", &existing),
+            Err(NameError::TrailingDotOrSpace)
+        );
+        assert_eq!(
+            validate_instance_name("../escape", &existing),
+            Err(NameError::InvalidChar('/'))
+        );
+        assert_eq!(
+            validate_instance_name("a\\b", &existing),
+            Err(NameError::InvalidChar('\\'))
+        );
+        assert_eq!(
+            validate_instance_name("tab\there", &existing),
+            Err(NameError::InvalidChar('\t'))
+        );
+        assert_eq!(
+            validate_instance_name("..", &existing),
+            Err(NameError::ReservedName("..".to_owned()))
+        );
+    }
+
+    #[test]
+    fn rejects_windows_reserved_names() {
+        for name in [
+            "CON",
+            "con",
+            "Nul.txt",
+            "aux",
+            "PRN",
+            "COM1",
+            "lpt9",
+            "com3.tar.gz",
+        ] {
+            assert_eq!(
+                validate_instance_name(name, &[]),
+                Err(NameError::ReservedName(name.to_owned())),
+                "{name}"
+            );
+        }
+        for name in ["CONSOLE", "COM0", "COM10", "LPT", "Null", "my con"] {
+            assert_eq!(validate_instance_name(name, &[]), Ok(()), "{name}");
+        }
+    }
+
+    #[test]
+    fn sanitized_names() {
+        assert_eq!(sanitize_instance_name(" a/b:c ".to_owned()), "abc");
+        assert_eq!(sanitize_instance_name("1.21. .".to_owned()), "1.21");
+        assert_eq!(
+            validate_instance_name(&sanitize_instance_name("x<>|?*".to_owned()), &[]),
+            Ok(())
+        );
+    }
+}
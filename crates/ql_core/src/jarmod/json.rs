@@ -1,6 +1,7 @@
 use crate::{Instance, IntoIoError, JsonFileError};
 use crate::{IntoJsonError, IoError, err, file_utils};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct JarMods {
@@ -9,7 +10,11 @@ pub struct JarMods {
 
 impl JarMods {
     pub async fn read(instance: &Instance) -> Result<Self, JsonFileError> {
-        let path = instance.get_instance_path().join("jarmods.json");
+        Self::read_from_dir(&instance.get_instance_path()).await
+    }
+
+    pub(crate) async fn read_from_dir(instance_dir: &Path) -> Result<Self, JsonFileError> {
+        let path = instance_dir.join("jarmods.json");
 
         if path.is_file() {
             let file = tokio::fs::read_to_string(&path).await.path(path)?;
@@ -41,7 +46,11 @@ impl JarMods {
     }
 
     pub async fn expand(&mut self, instance: &Instance) -> Result<(), IoError> {
-        let path = instance.get_instance_path().join("jarmods");
+        self.expand_in_dir(&instance.get_instance_path()).await
+    }
+
+    pub(crate) async fn expand_in_dir(&mut self, instance_dir: &Path) -> Result<(), IoError> {
+        let path = instance_dir.join("jarmods");
         if !path.is_dir() {
             tokio::fs::create_dir_all(&path).await.path(path)?;
             return Ok(());
@@ -52,50 +52,179 @@ pub async fn remove(instance: &Instance, filename: &str) -> Result<(), JsonFileE
     Ok(())
 }
 
-pub async fn insert(instance: Instance, bytes: Vec<u8>, name: &str) -> Result<(), JsonFileError> {
-    let filename = format!("{name}.zip");
+/// Adds a jar mod (a zip of classes/resources to patch
+/// into the Minecraft jar) to the instance, and enables it.
+///
+/// It's saved as `jarmods/<name>.zip`, replacing
+/// (and re-enabling) any jar mod with the same name.
+///
+/// # Errors
+/// - `bytes` isn't a zip, or doesn't look like a jar mod
+///   (like a Fabric/Forge mod or a modpack, see [`JarModError::NotAJarMod`])
+/// - The jar mod couldn't be saved
+pub async fn insert(instance: Instance, bytes: Vec<u8>, name: &str) -> Result<(), JarModError> {
     let mut jarmods = JarMods::read(&instance).await?;
-    if let Some(entry) = jarmods.mods.iter_mut().find(|n| n.filename == filename) {
-        entry.enabled = true;
-        return Ok(());
-    }
+    insert_into(&instance, &mut jarmods, &bytes, name).await?;
+    jarmods.save(&instance).await?;
+    Ok(())
+}
+
+/// Like [`insert`], but adds the jar mod to an already loaded
+/// `jarmods` (for example, one being edited in a menu),
+/// leaving saving `jarmods.json` up to the caller.
+///
+/// # Errors
+/// - `bytes` isn't a zip, or doesn't look like a jar mod
+///   (like a Fabric/Forge mod or a modpack, see [`JarModError::NotAJarMod`])
+/// - The jar mod couldn't be written
+pub async fn insert_into(
+    instance: &Instance,
+    jarmods: &mut JarMods,
+    bytes: &[u8],
+    name: &str,
+) -> Result<(), JarModError> {
+    insert_in_dir(&instance.get_instance_path(), jarmods, bytes, name).await
+}
 
-    let jarmods_dir = instance.get_instance_path().join("jarmods");
+async fn insert_in_dir(
+    instance_dir: &Path,
+    jarmods: &mut JarMods,
+    bytes: &[u8],
+    name: &str,
+) -> Result<(), JarModError> {
+    validate(bytes, name)?;
+    let jarmods_dir = instance_dir.join("jarmods");
     if !jarmods_dir.is_dir() {
         tokio::fs::create_dir_all(&jarmods_dir)
             .await
             .path(&jarmods_dir)?;
     }
 
+    let filename = format!("{name}.zip");
     let file_path = jarmods_dir.join(&filename);
-    tokio::fs::write(&file_path, &bytes)
-        .await
-        .path(&file_path)?;
-
-    jarmods.mods.push(JarMod {
-        filename,
-        enabled: true,
-    });
-    jarmods.save(&instance).await?;
+    tokio::fs::write(&file_path, bytes).await.path(&file_path)?;
 
+    if let Some(entry) = jarmods.mods.iter_mut().find(|n| n.filename == filename) {
+        entry.enabled = true;
+    } else {
+        jarmods.mods.push(JarMod {
+            filename,
+            enabled: true,
+        });
+    }
     Ok(())
 }
 
+/// Builds the Minecraft jar with all enabled jar mods patched in
+/// (`INSTANCE/build.jar`), and returns its path.
+///
+/// If there are no jar mods, the original jar's path is returned.
+/// The build is cached: it's only redone if the jar mods
+/// (or the original jar) changed since last time, see [`needs_rebuild`].
 pub async fn build(instance: &Instance) -> Result<PathBuf, JarModError> {
     let instance_dir = instance.get_instance_path();
+    let original_jar = get_original_jar(instance, &instance_dir).await?;
+    build_in_dir(&instance_dir, original_jar).await
+}
+
+/// Whether [`build`] would have to (re)build the jar, because
+/// the jar mods changed (or were toggled) since the last build.
+///
+/// `false` if there are no jar mods (nothing to build).
+pub async fn needs_rebuild(instance: &Instance) -> bool {
+    let instance_dir = instance.get_instance_path();
+    let Ok(original_jar) = get_original_jar(instance, &instance_dir).await else {
+        return true;
+    };
+    needs_rebuild_in_dir(&instance_dir, &original_jar).await
+}
+
+/// The jar built by [`build`] (`INSTANCE/build.jar`), if it's
+/// up to date with the jar mods. Doesn't build anything.
+///
+/// `None` if it needs to be (re)built, or if there are no jar mods.
+pub async fn built_jar_path(instance: &Instance) -> Option<PathBuf> {
+    let instance_dir = instance.get_instance_path();
+    let original_jar = get_original_jar(instance, &instance_dir).await.ok()?;
+    built_jar_in_dir(&instance_dir, &original_jar).await
+}
+
+async fn has_jarmods(jarmods_dir: &Path) -> bool {
+    jarmods_dir.is_dir() && !is_dir_empty(jarmods_dir).await
+}
+
+async fn needs_rebuild_in_dir(instance_dir: &Path, original_jar: &Path) -> bool {
+    has_jarmods(&instance_dir.join("jarmods")).await
+        && built_jar_in_dir(instance_dir, original_jar).await.is_none()
+}
+
+async fn built_jar_in_dir(instance_dir: &Path, original_jar: &Path) -> Option<PathBuf> {
+    if !has_jarmods(&instance_dir.join("jarmods")).await {
+        return None;
+    }
+    let out_jar = instance_dir.join(BUILD_JAR);
+    if !out_jar.is_file() {
+        return None;
+    }
+    let stamp = tokio::fs::read_to_string(instance_dir.join(BUILD_STAMP))
+        .await
+        .ok()?;
+    let fingerprint = build_fingerprint(instance_dir, original_jar).await.ok()?;
+    (stamp == fingerprint).then_some(out_jar)
+}
+
+const BUILD_JAR: &str = "build.jar";
+/// What `build.jar` was built from (see [`build_fingerprint`]).
+///
+/// Not in the `jarmods` folder, since every file
+/// in there is treated as a jar mod.
+const BUILD_STAMP: &str = "build.jar.stamp";
+
+/// Describes everything `build.jar` is built from: the original
+/// jar and enabled jar mods (in order), with their sizes
+/// and modification times. If this changes, a rebuild is needed.
+async fn build_fingerprint(
+    instance_dir: &Path,
+    original_jar: &Path,
+) -> Result<String, JarModError> {
+    let mut index = JarMods::read_from_dir(instance_dir).await?;
+    index.expand_in_dir(instance_dir).await?;
     let jarmods_dir = instance_dir.join("jarmods");
 
-    let original_jar = get_original_jar(instance, &instance_dir).await?;
+    let mut fingerprint = file_stamp(original_jar).await?;
+    for jar in index.mods.iter().filter(|n| n.enabled) {
+        fingerprint.push('\n');
+        fingerprint.push_str(&file_stamp(&jarmods_dir.join(&jar.filename)).await?);
+    }
+    Ok(fingerprint)
+}
+
+async fn file_stamp(path: &Path) -> Result<String, IoError> {
+    let metadata = tokio::fs::metadata(path).await.path(path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|n| n.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |n| n.as_nanos());
+    Ok(format!("{}:{}:{modified}", path.display(), metadata.len()))
+}
+
+async fn build_in_dir(instance_dir: &Path, original_jar: PathBuf) -> Result<PathBuf, JarModError> {
+    let jarmods_dir = instance_dir.join("jarmods");
 
-    if !jarmods_dir.is_dir() || is_dir_empty(&jarmods_dir).await {
+    if !has_jarmods(&jarmods_dir).await {
         tokio::fs::create_dir_all(&jarmods_dir)
             .await
             .path(&jarmods_dir)?;
         return Ok(original_jar);
     }
+    if let Some(out_jar) = built_jar_in_dir(instance_dir, &original_jar).await {
+        pt!("Jar mods unchanged, using last build");
+        return Ok(out_jar);
+    }
 
-    let mut index = JarMods::read(instance).await?;
-    index.expand(instance).await?;
+    let mut index = JarMods::read_from_dir(instance_dir).await?;
+    index.expand_in_dir(instance_dir).await?;
 
     let tmp_dir = jarmods_dir.join("tmp");
     tokio::fs::create_dir_all(&tmp_dir).await.path(&tmp_dir)?;
@@ -122,14 +251,26 @@ pub async fn build(instance: &Instance) -> Result<PathBuf, JarModError> {
     let zip = zip_directory_to_bytes(&tmp_dir)
         .await
         .map_err(JarModError::ZipWriteError)?;
-    let out_jar = instance_dir.join("build.jar");
+    let out_jar = instance_dir.join(BUILD_JAR);
     tokio::fs::write(&out_jar, &zip).await.path(&out_jar)?;
 
     tokio::fs::remove_dir_all(&tmp_dir).await.path(&tmp_dir)?;
 
+    let stamp_path = instance_dir.join(BUILD_STAMP);
+    let fingerprint = build_fingerprint(instance_dir, &original_jar).await?;
+    tokio::fs::write(&stamp_path, fingerprint)
+        .await
+        .path(&stamp_path)?;
+
     Ok(out_jar)
 }
 
+/// The game's jar without any jar mods
+/// (the one [`build`] patches them into).
+pub async fn original_jar(instance: &Instance) -> Result<PathBuf, JarModError> {
+    get_original_jar(instance, &instance.get_instance_path()).await
+}
+
 async fn get_original_jar(
     instance: &Instance,
     instance_dir: &Path,
@@ -148,6 +289,60 @@ async fn get_original_jar(
     Ok(path)
 }
 
+/// Files that mean an archive is something else, not a jar mod.
+const NOT_JARMOD_FILES: &[(&str, &str)] = &[
+    (
+        "fabric.mod.json",
+        "it's a Fabric mod, add it to the mods folder instead",
+    ),
+    (
+        "quilt.mod.json",
+        "it's a Quilt mod, add it to the mods folder instead",
+    ),
+    (
+        "META-INF/mods.toml",
+        "it's a Forge mod, add it to the mods folder instead",
+    ),
+    (
+        "META-INF/neoforge.mods.toml",
+        "it's a NeoForge mod, add it to the mods folder instead",
+    ),
+    (
+        "modrinth.index.json",
+        "it's a Modrinth modpack, install it from the Mods menu instead",
+    ),
+    (
+        "manifest.json",
+        "it's a CurseForge modpack, install it from the Mods menu instead",
+    ),
+];
+
+/// Checks that `bytes` is a zip that looks like a jar mod,
+/// to catch obviously wrong files (like loader mods or modpacks).
+fn validate(bytes: &[u8], name: &str) -> Result<(), JarModError> {
+    let not_jarmod = |reason: &'static str| JarModError::NotAJarMod {
+        name: name.to_owned(),
+        reason,
+    };
+
+    let archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+    if archive.is_empty() {
+        return Err(not_jarmod("the archive is empty"));
+    }
+    for file in archive.file_names() {
+        let file = file.trim_start_matches("./");
+        if let Some((_, reason)) = NOT_JARMOD_FILES.iter().find(|(n, _)| *n == file) {
+            return Err(not_jarmod(reason));
+        }
+        if file.starts_with(".minecraft/") || file.contains("/.minecraft/") {
+            return Err(not_jarmod(
+                "it's a whole instance (has a .minecraft folder), import it instead",
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub async fn is_dir_empty(path: &Path) -> bool {
     let Ok(mut dir) = tokio::fs::read_dir(path).await else {
         return false;
@@ -170,6 +365,8 @@ pub enum JarModError {
     ZipError(#[from] zip::result::ZipError),
     #[error("{JARMOD_ERR_PREFIX}while reading from zip:\n{0}")]
     ZipWriteError(std::io::Error),
+    #[error("{JARMOD_ERR_PREFIX}{name} isn't a jar mod: {reason}")]
+    NotAJarMod { name: String, reason: &'static str },
 }
 
 impl From<JsonFileError> for JarModError {
@@ -180,3 +377,128 @@ impl From<JsonFileError> for JarModError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn zip_with(files: &[&str]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for file in files {
+            zip.start_file(*file, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"data").unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn rejects_loader_mods() {
+        let jarmod = zip_with(&[
+            "aaa.class",
+            "net/minecraft/client/Minecraft.class",
+            "terrain.png",
+        ]);
+        validate(&jarmod, "Cool Mod").unwrap();
+
+        let fabric_mod = zip_with(&["fabric.mod.json", "com/example/Mod.class"]);
+        let err = validate(&fabric_mod, "sodium").unwrap_err();
+        assert!(
+            matches!(&err, JarModError::NotAJarMod { name, .. } if name == "sodium"),
+            "{err:?}"
+        );
+        assert!(err.to_string().contains("Fabric mod"));
+
+        let instance = zip_with(&["pack/.minecraft/options.txt"]);
+        assert!(validate(&instance, "pack").is_err());
+        assert!(validate(&zip_with(&[]), "empty").is_err());
+        assert!(matches!(
+            validate(b"not a zip", "text"),
+            Err(JarModError::ZipError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn inserts_and_replaces() {
+        let temp = tempfile::tempdir().unwrap();
+        let instance_dir = temp.path();
+        let mut jarmods = JarMods { mods: Vec::new() };
+
+        insert_in_dir(
+            instance_dir,
+            &mut jarmods,
+            &zip_with(&["aaa.class"]),
+            "Cool Mod",
+        )
+        .await
+        .unwrap();
+        jarmods.mods[0].enabled = false;
+        // Adding it again replaces the file and enables it
+        let newer = zip_with(&["aaa.class", "bbb.class"]);
+        insert_in_dir(instance_dir, &mut jarmods, &newer, "Cool Mod")
+            .await
+            .unwrap();
+        assert_eq!(jarmods.mods.len(), 1);
+        assert!(jarmods.mods[0].enabled);
+        assert_eq!(
+            tokio::fs::read(instance_dir.join("jarmods/Cool Mod.zip"))
+                .await
+                .unwrap(),
+            newer
+        );
+
+        let fabric_mod = zip_with(&["fabric.mod.json"]);
+        assert!(
+            insert_in_dir(instance_dir, &mut jarmods, &fabric_mod, "sodium")
+                .await
+                .is_err()
+        );
+        assert!(!instance_dir.join("jarmods/sodium.zip").exists());
+        assert_eq!(jarmods.mods.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rebuild_after_toggle() {
+        let temp = tempfile::tempdir().unwrap();
+        let instance_dir = temp.path();
+        let jarmods_dir = instance_dir.join("jarmods");
+        tokio::fs::create_dir_all(&jarmods_dir).await.unwrap();
+
+        let original_jar = instance_dir.join("1.2.5.jar");
+        tokio::fs::write(
+            &original_jar,
+            zip_with(&["net/minecraft/client/Minecraft.class"]),
+        )
+        .await
+        .unwrap();
+        // No jar mods, nothing to build
+        assert!(!needs_rebuild_in_dir(instance_dir, &original_jar).await);
+
+        tokio::fs::write(jarmods_dir.join("Cool Mod.zip"), zip_with(&["aaa.class"]))
+            .await
+            .unwrap();
+        assert!(needs_rebuild_in_dir(instance_dir, &original_jar).await);
+
+        let built = build_in_dir(instance_dir, original_jar.clone())
+            .await
+            .unwrap();
+        assert_eq!(built, instance_dir.join(BUILD_JAR));
+        assert!(!needs_rebuild_in_dir(instance_dir, &original_jar).await);
+        assert_eq!(
+            built_jar_in_dir(instance_dir, &original_jar).await,
+            Some(built)
+        );
+
+        let mut index = JarMods::read_from_dir(instance_dir).await.unwrap();
+        index.expand_in_dir(instance_dir).await.unwrap();
+        index.mods[0].enabled = false;
+        let json = serde_json::to_string(&index).unwrap();
+        tokio::fs::write(instance_dir.join("jarmods.json"), json)
+            .await
+            .unwrap();
+        assert!(needs_rebuild_in_dir(instance_dir, &original_jar).await);
+        assert_eq!(built_jar_in_dir(instance_dir, &original_jar).await, None);
+    }
+}
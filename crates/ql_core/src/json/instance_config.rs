@@ -6,8 +6,8 @@ use std::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    DEFAULT_RAM_MB_FOR_INSTANCE, Instance, InstanceKind, IntoIoError, IntoJsonError, JsonFileError,
-    Loader,
+    DEFAULT_RAM_MB_FOR_INSTANCE, Instance, InstanceKind, IntoIoError, IntoJsonError, JavaVersion,
+    JsonFileError, Loader, err, json::VersionDetails,
 };
 
 /// Configuration for a specific instance.
@@ -32,8 +32,11 @@ pub struct InstanceConfigJson {
     // Since: v0.5.0
     pub mod_type_info: Option<ModTypeInfo>,
 
-    /// Use a different **launcher-provided** java version.
+    /// Use a different **launcher-provided** java version
+    /// (major version, like `17`), for mods that need a specific Java.
     /// Prioritized over [`Self::java_override`]
+    ///
+    /// See [`Self::get_java_version`].
     // Since: v0.5.1
     pub java_override_version: Option<usize>,
     /// Use a different **user-provided** `java` binary (path)
@@ -72,6 +75,8 @@ pub struct InstanceConfigJson {
 
     /// Whether global launcher-wide Java arguments will be used
     /// (default: `true`)
+    ///
+    /// See [`JavaArgsMode`] (and [`Self::java_args_mode`]).
     pub global_java_args_enable: Option<bool>,
 
     /// Controls how this instance's pre-launch prefix commands interact with global pre-launch prefix.
@@ -97,6 +102,57 @@ pub struct InstanceConfigJson {
     /// Mainly only used for debugging purposes.
     pub main_class_override: Option<String>,
 
+    /// **Client only**
+    /// UUID of the account to launch this instance with,
+    /// instead of the one selected in the launcher.
+    ///
+    /// Ignored if that account isn't logged in anymore.
+    ///
+    /// **Default: `None`** (use the selected account)
+    // Since: TBD
+    pub default_account: Option<String>,
+
+    /// Whether to check for (and install) mod updates
+    /// every time before launching the instance.
+    ///
+    /// **Default: `false`**
+    // Since: TBD
+    pub auto_update_mods: Option<bool>,
+
+    /// **Client and Server**
+    /// Built-in tuned JVM flags (mainly for garbage collection),
+    /// added before the global and instance Java arguments.
+    ///
+    /// **Default: `None`** (see [`JavaFlagsPreset`])
+    // Since: TBD
+    pub java_flags_preset: Option<JavaFlagsPreset>,
+
+    /// **Client only**
+    /// Whether this instance keeps its own copy of the game assets
+    /// (in `private_assets/` inside the instance folder) instead of
+    /// using the launcher-wide shared ones.
+    ///
+    /// Makes the instance self-contained (for moving/exporting),
+    /// at the cost of disk space. The copy is made on next launch.
+    ///
+    /// **Default: `false`**
+    // Since: TBD
+    pub private_assets: Option<bool>,
+
+    /// **Client only**
+    /// A custom folder for the game's own files (worlds, mods,
+    /// settings, ...) instead of the instance's `.minecraft` folder,
+    /// for example to keep them on a faster drive.
+    ///
+    /// The launcher's files for the instance (`config.json`,
+    /// `details.json`, libraries, ...) stay in the instance folder.
+    /// Set it with [`set_game_dir_override`], which checks that
+    /// the folder is writable. Read it with [`Self::get_game_dir`].
+    ///
+    /// **Default: `None`** (`.minecraft` inside the instance folder)
+    // Since: TBD
+    pub game_dir_override: Option<PathBuf>,
+
     #[serde(flatten)]
     _extra: HashMap<String, serde_json::Value>,
 }
@@ -127,10 +183,58 @@ impl InstanceConfigJson {
 
             version_info: Some(version_info),
             main_class_override: None,
+            default_account: None,
+            auto_update_mods: None,
+            java_flags_preset: None,
+            private_assets: None,
+            game_dir_override: None,
             _extra: HashMap::new(),
         }
     }
 
+    /// A copy of this config with every setting back to
+    /// its default, keeping only what the instance needs to
+    /// work (loader and its info, main class override,
+    /// version info, client/server, custom game folder). Legacy and unknown fields
+    /// are kept so older/newer launchers still read it right.
+    ///
+    /// If `keep_java_path` is `true`, the custom Java
+    /// ([`Self::java_override`], [`Self::java_override_version`])
+    /// is kept too.
+    #[must_use]
+    pub fn reset(&self, keep_java_path: bool) -> Self {
+        #[allow(deprecated)]
+        Self {
+            mod_type: self.mod_type,
+            mod_type_info: self.mod_type_info.clone(),
+            java_override_version: self.java_override_version.filter(|_| keep_java_path),
+            java_override: self.java_override.clone().filter(|_| keep_java_path),
+            ram_in_mb: DEFAULT_RAM_MB_FOR_INSTANCE,
+            enable_logger: Some(true),
+            java_args: None,
+            game_args: None,
+
+            is_server: self.is_server,
+            is_classic_server: self.is_classic_server,
+
+            omniarchive: self.omniarchive.clone(),
+            close_on_start: None,
+            global_settings: None,
+            global_java_args_enable: None,
+            custom_jar: None,
+            pre_launch_prefix_mode: None,
+
+            version_info: self.version_info.clone(),
+            main_class_override: self.main_class_override.clone(),
+            default_account: None,
+            auto_update_mods: None,
+            java_flags_preset: None,
+            private_assets: None,
+            game_dir_override: self.game_dir_override.clone(),
+            _extra: self._extra.clone(),
+        }
+    }
+
     /// Returns a String containing the Java argument to
     /// allocate the configured amount of RAM.
     #[must_use]
@@ -138,6 +242,23 @@ impl InstanceConfigJson {
         format!("-Xmx{}M", self.ram_in_mb)
     }
 
+    /// Gets the folder the game runs in: the
+    /// [`Self::game_dir_override`] if set, otherwise
+    /// [`Instance::get_dot_minecraft_path`].
+    #[must_use]
+    pub fn get_game_dir(&self, instance: &Instance) -> PathBuf {
+        match &self.game_dir_override {
+            Some(dir) if !instance.is_server() => dir.clone(),
+            _ => instance.get_dot_minecraft_path(),
+        }
+    }
+
+    /// Updates the version info, after the instance
+    /// was moved to another version of the game.
+    pub fn set_version_info(&mut self, version_info: VersionInfo) {
+        self.version_info = Some(version_info);
+    }
+
     /// Loads the launcher-specific instance configuration from disk,
     /// based on a path to the root of the instance directory.
     ///
@@ -200,24 +321,59 @@ impl InstanceConfigJson {
         )
     }
 
-    /// Gets Java arguments (combining them with global args based on configuration)
     #[must_use]
-    #[allow(clippy::missing_panics_doc)] // Won't panic
-    pub fn get_java_args(&self, global_args: &[String]) -> Vec<String> {
-        let use_global_args = self.global_java_args_enable.unwrap_or(true);
-        let mut instance_args = self.java_args.clone().unwrap_or_default();
-
-        if use_global_args {
-            instance_args.extend(global_args.iter().filter(|n| !n.trim().is_empty()).cloned());
+    pub fn java_args_mode(&self) -> JavaArgsMode {
+        if self.global_java_args_enable.unwrap_or(true) {
+            JavaArgsMode::Append
+        } else {
+            JavaArgsMode::Override
         }
-        instance_args.retain(|n| !n.trim().is_empty());
-        instance_args
+    }
+
+    /// Gets Java arguments, combined with the global
+    /// (launcher-wide) ones depending on [`Self::java_args_mode`]:
+    ///
+    /// - [`JavaArgsMode::Append`]: global args, then instance args
+    /// - [`JavaArgsMode::Override`]: only instance args
+    ///
+    /// Java uses the *last* occurrence of a repeated
+    /// flag (`-Xss`, `-Dkey=...`), so instance args
+    /// take precedence over global ones in both modes.
+    ///
+    /// The [`Self::java_flags_preset`] flags come first,
+    /// so the user's own arguments can override them.
+    ///
+    /// Blank arguments are removed.
+    #[must_use]
+    pub fn get_java_args(&self, global_args: &[String]) -> Vec<String> {
+        let instance_args = self.java_args.iter().flatten();
+        let args: Vec<&String> = match self.java_args_mode() {
+            JavaArgsMode::Append => global_args.iter().chain(instance_args).collect(),
+            JavaArgsMode::Override => instance_args.collect(),
+        };
+        self.java_flags_preset
+            .unwrap_or_default()
+            .get_flags(self.ram_in_mb)
+            .into_iter()
+            .chain(args.into_iter().cloned())
+            .filter(|n| !n.trim().is_empty())
+            .collect()
     }
 
     /// Gets pre-launch prefix commands, (empty if none).
     ///
     /// Whether to combine with global prefixes, and how,
-    /// depends on the instance's [`PreLaunchPrefixMode`].
+    /// depends on the instance's [`PreLaunchPrefixMode`]:
+    ///
+    /// - [`PreLaunchPrefixMode::CombineGlobalLocal`] (default):
+    ///   global prefix, then instance prefix
+    /// - [`PreLaunchPrefixMode::CombineLocalGlobal`]:
+    ///   instance prefix, then global prefix
+    /// - [`PreLaunchPrefixMode::Disable`]: only instance prefix
+    ///   (global one is ignored)
+    ///
+    /// Unlike Java args, the *first* prefix is the outermost
+    /// command (`prime-run gamemoderun java ...`).
     #[must_use]
     pub fn build_launch_prefix(&mut self, global_prefix: &[String]) -> Vec<String> {
         let mode = self.pre_launch_prefix_mode.unwrap_or_default();
@@ -268,6 +424,26 @@ impl InstanceConfigJson {
                 .then_some(MainClassMode::Custom))
     }
 
+    /// The Java version to run this instance with:
+    /// [`Self::java_override_version`] if set, otherwise the one
+    /// the game version asks for (Java 8 if it doesn't say).
+    ///
+    /// An override the launcher can't install (like `11`)
+    /// is ignored, with an error logged.
+    #[must_use]
+    pub fn get_java_version(&self, version_json: &VersionDetails) -> JavaVersion {
+        if let Some(major) = self.java_override_version {
+            if let Some(version) = JavaVersion::from_major(major) {
+                return version;
+            }
+            err!("Java {major} (instance override) isn't available in the launcher, ignoring it");
+        }
+        version_json
+            .javaVersion
+            .clone()
+            .map_or(JavaVersion::Java8, Into::into)
+    }
+
     #[must_use]
     pub fn get_java_override(&self) -> Option<PathBuf> {
         fn inner(path: &str) -> Option<PathBuf> {
@@ -414,6 +590,146 @@ impl std::fmt::Display for PreLaunchPrefixMode {
     }
 }
 
+/// Resets an instance's `config.json` to the defaults
+/// (see [`InstanceConfigJson::reset`]), for undoing bad edits
+/// without deleting the instance.
+///
+/// The old config is backed up to `config.json.bak` first.
+///
+/// # Errors
+/// - `config.json` couldn't be read, parsed or written
+/// - The backup couldn't be written
+pub async fn reset_instance_config(
+    instance: &Instance,
+    keep_java_path: bool,
+) -> Result<(), JsonFileError> {
+    reset_config_in_dir(&instance.get_instance_path(), keep_java_path).await
+}
+
+async fn reset_config_in_dir(dir: &Path, keep_java_path: bool) -> Result<(), JsonFileError> {
+    let config = InstanceConfigJson::read_from_dir(dir).await?;
+
+    let config_path = dir.join("config.json");
+    let backup_path = dir.join("config.json.bak");
+    tokio::fs::copy(&config_path, &backup_path)
+        .await
+        .path(backup_path)?;
+
+    config.reset(keep_java_path).save_to_dir(dir).await
+}
+
+/// Sets (or with `None`, removes) the custom game folder
+/// of an instance ([`InstanceConfigJson::game_dir_override`]).
+///
+/// The folder is created if needed. Existing files
+/// aren't moved over, that's up to the user.
+///
+/// # Errors
+/// - The folder couldn't be created or written to
+/// - `config.json` couldn't be read, parsed or written
+pub async fn set_game_dir_override(
+    instance: &Instance,
+    dir: Option<PathBuf>,
+) -> Result<(), JsonFileError> {
+    set_game_dir_in(&instance.get_instance_path(), dir).await
+}
+
+async fn set_game_dir_in(instance_dir: &Path, dir: Option<PathBuf>) -> Result<(), JsonFileError> {
+    if let Some(dir) = &dir {
+        tokio::fs::create_dir_all(dir).await.dir(dir)?;
+        // Better to find out now than when the game crashes
+        let test_file = dir.join(".ql_write_test");
+        tokio::fs::write(&test_file, "").await.path(&test_file)?;
+        tokio::fs::remove_file(&test_file).await.path(&test_file)?;
+    }
+    let mut config = InstanceConfigJson::read_from_dir(instance_dir).await?;
+    config.game_dir_override = dir;
+    config.save_to_dir(instance_dir).await
+}
+
+/// How instance Java arguments interact with global
+/// (launcher-wide) ones. Stored as
+/// [`InstanceConfigJson::global_java_args_enable`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum JavaArgsMode {
+    /// Global args, followed by instance args (default)
+    #[default]
+    Append,
+    /// Only instance args, global args are ignored
+    Override,
+}
+
+/// Built-in sets of JVM flags, stored as
+/// [`InstanceConfigJson::java_flags_preset`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum JavaFlagsPreset {
+    /// Aikar's G1GC flags, widely used for
+    /// modded clients and servers to reduce lag spikes.
+    ///
+    /// <https://docs.papermc.io/paper/aikars-flags>
+    #[serde(rename = "aikar")]
+    Aikar,
+    /// No extra flags, the JVM's defaults
+    #[serde(rename = "none")]
+    #[default]
+    #[serde(other)]
+    None,
+}
+
+impl JavaFlagsPreset {
+    pub const ALL: &[Self] = &[Self::None, Self::Aikar];
+
+    /// Above this much memory, Aikar recommends
+    /// a larger young generation and region size.
+    const AIKAR_LARGE_HEAP_MB: usize = 12 * 1024;
+
+    /// The flags for this preset, scaled for an instance
+    /// with `ram_in_mb` of memory allocated.
+    #[must_use]
+    pub fn get_flags(self, ram_in_mb: usize) -> Vec<String> {
+        match self {
+            JavaFlagsPreset::None => Vec::new(),
+            JavaFlagsPreset::Aikar => {
+                let large = ram_in_mb > Self::AIKAR_LARGE_HEAP_MB;
+                let (new_size, max_new_size, region_size, reserve, occupancy) = if large {
+                    (40, 50, 16, 15, 20)
+                } else {
+                    (30, 40, 8, 20, 15)
+                };
+                vec![
+                    "-XX:+UseG1GC".to_owned(),
+                    "-XX:+ParallelRefProcEnabled".to_owned(),
+                    "-XX:MaxGCPauseMillis=200".to_owned(),
+                    "-XX:+UnlockExperimentalVMOptions".to_owned(),
+                    "-XX:+DisableExplicitGC".to_owned(),
+                    "-XX:+AlwaysPreTouch".to_owned(),
+                    format!("-XX:G1NewSizePercent={new_size}"),
+                    format!("-XX:G1MaxNewSizePercent={max_new_size}"),
+                    format!("-XX:G1HeapRegionSize={region_size}M"),
+                    format!("-XX:G1ReservePercent={reserve}"),
+                    "-XX:G1HeapWastePercent=5".to_owned(),
+                    "-XX:G1MixedGCCountTarget=4".to_owned(),
+                    format!("-XX:InitiatingHeapOccupancyPercent={occupancy}"),
+                    "-XX:G1MixedGCLiveThresholdPercent=90".to_owned(),
+                    "-XX:G1RSetUpdatingPauseTimePercent=5".to_owned(),
+                    "-XX:SurvivorRatio=32".to_owned(),
+                    "-XX:+PerfDisableSharedMem".to_owned(),
+                    "-XX:MaxTenuringThreshold=1".to_owned(),
+                ]
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for JavaFlagsPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JavaFlagsPreset::None => write!(f, "None (default)"),
+            JavaFlagsPreset::Aikar => write!(f, "Aikar's flags (G1GC)"),
+        }
+    }
+}
+
 /// Configuration for using a custom Minecraft JAR file
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 pub struct CustomJarConfig {
@@ -439,3 +755,152 @@ pub enum MainClassMode {
     SafeFallback,
     Custom,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> InstanceConfigJson {
+        let mut config =
+            InstanceConfigJson::new(InstanceKind::Client, false, VersionInfo::new("1.21.4"));
+        config.java_args = Some(vec!["-Xss2M".to_owned(), " ".to_owned()]);
+        config.c_global_settings().pre_launch_prefix = Some(vec!["gamemoderun".to_owned()]);
+        config
+    }
+
+    fn strings(s: &[&str]) -> Vec<String> {
+        s.iter().map(|n| (*n).to_owned()).collect()
+    }
+
+    #[test]
+    fn java_args_append() {
+        let config = config();
+        assert_eq!(config.java_args_mode(), JavaArgsMode::Append);
+        assert_eq!(
+            config.get_java_args(&strings(&["-Xss1M", "-XX:+UseG1GC", ""])),
+            ["-Xss1M", "-XX:+UseG1GC", "-Xss2M"]
+        );
+    }
+
+    #[test]
+    fn java_args_override() {
+        let mut config = config();
+        config.global_java_args_enable = Some(false);
+        assert_eq!(config.java_args_mode(), JavaArgsMode::Override);
+        assert_eq!(
+            config.get_java_args(&strings(&["-Xss1M", "-XX:+UseG1GC"])),
+            ["-Xss2M"]
+        );
+    }
+
+    #[test]
+    fn java_flags_preset() {
+        let mut config = config();
+        config.global_java_args_enable = Some(false);
+        assert_eq!(config.get_java_args(&[]), ["-Xss2M"]);
+
+        config.java_flags_preset = Some(JavaFlagsPreset::Aikar);
+        config.ram_in_mb = 4096;
+        let args = config.get_java_args(&[]);
+        assert_eq!(args.first().map(String::as_str), Some("-XX:+UseG1GC"));
+        assert_eq!(args.last().map(String::as_str), Some("-Xss2M"));
+        assert!(args.iter().any(|n| n == "-XX:G1HeapRegionSize=8M"));
+        assert!(args.iter().any(|n| n == "-XX:G1NewSizePercent=30"));
+
+        config.ram_in_mb = 16384;
+        let args = config.get_java_args(&[]);
+        assert!(args.iter().any(|n| n == "-XX:G1HeapRegionSize=16M"));
+        assert!(args.iter().any(|n| n == "-XX:G1NewSizePercent=40"));
+    }
+
+    #[test]
+    fn java_version_override() {
+        let version = |java: serde_json::Value| -> VersionDetails {
+            let mut json = crate::test_utils::version_json("1.21.4", "2024-12-03T10:12:57+00:00");
+            json["javaVersion"] = java;
+            serde_json::from_value(json).unwrap()
+        };
+        let modern =
+            version(serde_json::json!({ "component": "java-runtime-delta", "majorVersion": 21 }));
+        let old = version(serde_json::Value::Null);
+
+        let mut config = config();
+        assert_eq!(config.get_java_version(&modern), JavaVersion::Java21);
+        assert_eq!(config.get_java_version(&old), JavaVersion::Java8);
+
+        config.java_override_version = Some(17);
+        assert_eq!(config.get_java_version(&modern), JavaVersion::Java17);
+        assert_eq!(config.get_java_version(&old), JavaVersion::Java17);
+
+        // Not one the launcher can install
+        config.java_override_version = Some(11);
+        assert_eq!(config.get_java_version(&modern), JavaVersion::Java21);
+    }
+
+    #[tokio::test]
+    async fn reset_keeps_loader() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let mut config = config();
+        config.ram_in_mb = 8192;
+        config.mod_type = Loader::Fabric;
+        config.mod_type_info = Some(ModTypeInfo::new_regular("0.16.10".to_owned()));
+        // Set for MultiMC imports
+        config.main_class_override =
+            Some("net.fabricmc.loader.impl.launch.knot.KnotClient".to_owned());
+        config.java_override = Some("/usr/bin/java".to_owned());
+        config
+            ._extra
+            .insert("from_newer_version".to_owned(), serde_json::json!(1));
+        config.save_to_dir(dir).await.unwrap();
+
+        reset_config_in_dir(dir, false).await.unwrap();
+        let reset = InstanceConfigJson::read_from_dir(dir).await.unwrap();
+        assert!(reset._extra.contains_key("from_newer_version"));
+        assert_eq!(reset.ram_in_mb, DEFAULT_RAM_MB_FOR_INSTANCE);
+        assert_eq!(reset.java_args, None);
+        assert!(reset.global_settings.is_none());
+        assert_eq!(reset.java_override, None);
+        assert_eq!(reset.mod_type, Loader::Fabric);
+        assert_eq!(
+            reset.mod_type_info.and_then(|n| n.version).as_deref(),
+            Some("0.16.10")
+        );
+        assert_eq!(
+            reset.main_class_override.as_deref(),
+            Some("net.fabricmc.loader.impl.launch.knot.KnotClient")
+        );
+
+        // The old config is backed up
+        let backup = tokio::fs::read_to_string(dir.join("config.json.bak"))
+            .await
+            .unwrap();
+        assert!(backup.contains("8192"));
+
+        config.save_to_dir(dir).await.unwrap();
+        reset_config_in_dir(dir, true).await.unwrap();
+        let reset = InstanceConfigJson::read_from_dir(dir).await.unwrap();
+        assert_eq!(reset.java_override.as_deref(), Some("/usr/bin/java"));
+    }
+
+    #[test]
+    fn launch_prefix_modes() {
+        let global = strings(&["prime-run", " "]);
+        let mut config = config();
+
+        assert_eq!(
+            config.build_launch_prefix(&global),
+            ["prime-run", "gamemoderun"]
+        );
+
+        config.pre_launch_prefix_mode = Some(PreLaunchPrefixMode::CombineLocalGlobal);
+        assert_eq!(
+            config.build_launch_prefix(&global),
+            ["gamemoderun", "prime-run"]
+        );
+
+        config.pre_launch_prefix_mode = Some(PreLaunchPrefixMode::Disable);
+        assert_eq!(config.build_launch_prefix(&global), ["gamemoderun"]);
+    }
+}
@@ -11,7 +11,9 @@ pub use fabric::FabricJSON;
 pub use optifine::{JsonOptifine, OptifineArguments, OptifineLibrary};
 
 pub use asset_index::AssetIndex;
-pub use instance_config::{GlobalSettings, InstanceConfigJson};
+pub use instance_config::{
+    GlobalSettings, InstanceConfigJson, reset_instance_config, set_game_dir_override,
+};
 pub use manifest::Manifest;
 pub use version::{
     V_1_5_2, V_1_12_2, V_OFFICIAL_FABRIC_SUPPORT, V_PAULSCODE_LAST, V_PRECLASSIC_LAST,
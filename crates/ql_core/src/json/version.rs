@@ -4,6 +4,7 @@ use cfg_if::cfg_if;
 use chrono::DateTime;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha1::{Digest, Sha1};
 
 use crate::{Instance, IntoIoError, IntoJsonError, JsonFileError, OS_NAME, constants::*, err, pt};
 
@@ -202,6 +203,15 @@ impl VersionDetails {
     pub fn get_id(&self) -> &str {
         self.id.strip_suffix("-lwjgl3").unwrap_or(&self.id)
     }
+
+    /// Whether the game can launch straight into a world
+    /// or server (Quick Play, added in 1.20 snapshot 23w14a).
+    #[must_use]
+    pub fn supports_quick_play(&self) -> bool {
+        self.arguments
+            .as_ref()
+            .is_some_and(Arguments::supports_quick_play)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -218,6 +228,22 @@ pub struct Arguments {
     jvm: Vec<Value>,
 }
 
+impl Arguments {
+    /// Versions with Quick Play list its arguments
+    /// (behind a feature rule) in their game arguments.
+    #[must_use]
+    pub fn supports_quick_play(&self) -> bool {
+        self.game
+            .iter()
+            .filter_map(|arg| arg.get("value"))
+            .any(|value| match value {
+                Value::String(n) => n == "--quickPlaySingleplayer",
+                Value::Array(n) => n.iter().any(|n| n == "--quickPlaySingleplayer"),
+                _ => false,
+            })
+    }
+}
+
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AssetIndexInfo {
@@ -228,6 +254,20 @@ pub struct AssetIndexInfo {
     pub url: String,
 }
 
+impl AssetIndexInfo {
+    /// Size of the asset index JSON file, in bytes.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.size as u64
+    }
+
+    /// Combined size of all the assets listed in the index, in bytes.
+    #[must_use]
+    pub fn total_size(&self) -> u64 {
+        self.totalSize as u64
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Downloads {
     pub client: Download,
@@ -243,6 +283,14 @@ pub struct Download {
     pub url: String,
 }
 
+impl Download {
+    /// Size of the file, in bytes.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.size as u64
+    }
+}
+
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct JavaVersionJson {
@@ -458,6 +506,35 @@ pub struct LibraryClassifier {
     pub url: String,
 }
 
+impl LibraryClassifier {
+    /// Size of the file in bytes, or `0` if unknown.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.size.as_u64().unwrap_or_default()
+    }
+
+    /// Checks whether the downloaded `bytes` match the
+    /// SHA1 hash and size listed in the version JSON.
+    #[must_use]
+    pub fn verify(&self, bytes: &[u8]) -> bool {
+        verify_sha1_and_size(bytes, &self.sha1, &self.size)
+    }
+}
+
+fn verify_sha1_and_size(bytes: &[u8], sha1: &str, size: &serde_json::Number) -> bool {
+    if let Some(size) = size.as_u64().filter(|n| *n > 0) {
+        if bytes.len() as u64 != size {
+            return false;
+        }
+    }
+    if sha1.is_empty() {
+        return true;
+    }
+    let mut hasher = Sha1::default();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize()).eq_ignore_ascii_case(sha1)
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct LibraryRule {
     action: String,
@@ -509,6 +586,23 @@ impl Debug for LibraryDownloadArtifact {
 }
 
 impl LibraryDownloadArtifact {
+    /// Size of the file in bytes, or `0` if unknown.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.size.as_u64().unwrap_or_default()
+    }
+
+    /// Checks whether the downloaded `bytes` match the
+    /// SHA1 hash and size listed in the version JSON.
+    ///
+    /// Artifacts without a known hash/size (for example,
+    /// ones built from Fabric-style `name` + `url` libraries)
+    /// skip that part of the check.
+    #[must_use]
+    pub fn verify(&self, bytes: &[u8]) -> bool {
+        verify_sha1_and_size(bytes, &self.sha1, &self.size)
+    }
+
     #[must_use]
     pub fn get_path(&self) -> String {
         self.path.clone().unwrap_or_else(|| {
@@ -538,7 +632,9 @@ pub struct Logging {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LoggingClient {
-    argument: String,
+    /// JVM argument to pass the config with,
+    /// like `-Dlog4j.configurationFile=${path}`
+    pub argument: String,
     pub file: LoggingClientFile,
     r#type: String,
 }
@@ -0,0 +1,95 @@
+use std::{fs::File, path::Path};
+
+use thiserror::Error;
+
+use crate::{IntoIoError, IoError, LAUNCHER_DIR};
+
+/// Makes sure only one launcher window is running at a time,
+/// so two launchers don't overwrite each other's config,
+/// accounts, and so on.
+///
+/// The lock is released when this is dropped
+/// (or the launcher exits/crashes, the OS takes care of that).
+pub struct LauncherLock {
+    _file: File,
+}
+
+impl LauncherLock {
+    /// Takes the launcher-wide lock (`QuantumLauncher/.launcher.lock`).
+    ///
+    /// # Errors
+    /// - [`LockError::AlreadyRunning`] if another launcher holds the lock
+    /// - The lock file couldn't be opened
+    pub fn acquire() -> Result<Self, LockError> {
+        Self::acquire_at(&LAUNCHER_DIR.join(".launcher.lock"))
+    }
+
+    fn acquire_at(path: &Path) -> Result<Self, LockError> {
+        let mut options = std::fs::OpenOptions::new();
+        options.read(true).write(true).create(true).truncate(false);
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::fs::OpenOptionsExt;
+            // No sharing: nobody else can open the file while we have it
+            options.share_mode(0);
+        }
+
+        let file = match options.open(path) {
+            Ok(file) => file,
+            // ERROR_SHARING_VIOLATION
+            #[cfg(windows)]
+            Err(err) if err.raw_os_error() == Some(32) => return Err(LockError::AlreadyRunning),
+            Err(err) => return Err(err.path(path).into()),
+        };
+
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsRawFd;
+
+            // SAFETY: `file` is a valid open file descriptor
+            // for the duration of this call.
+            let result = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+            if result != 0 {
+                let err = std::io::Error::last_os_error();
+                return Err(if err.kind() == std::io::ErrorKind::WouldBlock {
+                    LockError::AlreadyRunning
+                } else {
+                    err.path(path).into()
+                });
+            }
+        }
+
+        Ok(Self { _file: file })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error(
+        "another QuantumLauncher window is already running!\nClose it first, or use --force to open anyway (not recommended)"
+    )]
+    AlreadyRunning,
+    #[error("couldn't lock launcher folder: {0}")]
+    Io(#[from] IoError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_lock_fails_until_released() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(".launcher.lock");
+
+        let first = LauncherLock::acquire_at(&path).unwrap();
+        assert!(matches!(
+            LauncherLock::acquire_at(&path),
+            Err(LockError::AlreadyRunning)
+        ));
+
+        drop(first);
+        let _second = LauncherLock::acquire_at(&path).unwrap();
+    }
+}
@@ -32,33 +32,57 @@ use std::{
 };
 use tokio::process::Child;
 
+mod args;
+mod backoff;
+mod cancel;
 pub mod clean;
 pub mod constants;
+mod disk_usage;
 mod error;
 /// Common utilities for working with files.
 pub mod file_utils;
+mod instance_list;
+mod instance_logs;
+mod instance_name;
 pub mod jarmod;
 /// JSON structs for version, instance config, Fabric, Forge, Optifine, Quilt, Neoforge, etc.
 pub mod json;
+mod launcher_lock;
+mod open_folder;
 /// Logging macros.
 pub mod print;
 mod progress;
 pub mod read_log;
 pub mod request;
+mod running;
 mod structs;
+mod terminate;
+#[cfg(any(test, feature = "test_utils"))]
+pub mod test_utils;
 pub mod urlcache;
 
 pub use crate::json::InstanceConfigJson;
+pub use args::split_args;
+pub use backoff::{retry_with_backoff, should_retry_response};
+pub use cancel::{CancellationToken, Cancelled};
 pub use constants::*;
+pub use disk_usage::{DiskUsage, instance_disk_usage};
 pub use error::{
     DownloadFileError, IntoIoError, IntoJsonError, IntoStringError, IoError, JsonDownloadError,
     JsonError, JsonFileError,
 };
 pub use file_utils::{LAUNCHER_DIR, RequestError};
+pub use instance_list::{InstanceSummary, get_entries, list_instances};
+pub use instance_logs::{LogFileInfo, clear_old_instance_logs, list_instance_logs};
+pub use instance_name::{NameError, sanitize_instance_name, validate_instance_name};
+pub use launcher_lock::{LauncherLock, LockError};
+pub use open_folder::{InstanceFolder, open_instance_folder, open_instance_subfolder};
 pub use print::{LOGGER, LogType, LoggingState, logger_finish};
-pub use progress::{DownloadProgress, GenericProgress, Progress};
-pub use request::download;
+pub use progress::{DownloadProgress, GenericProgress, Progress, ProgressAggregator};
+pub use request::{download, set_user_agent};
+pub use running::RunningProcess;
 pub use structs::{JavaVersion, Loader};
+pub use terminate::terminate_client;
 
 pub const LAUNCHER_VERSION_NAME: &str = "0.5.1";
 
@@ -99,7 +123,9 @@ macro_rules! no_window {
     };
 }
 
-pub static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+/// The HTTP client for all requests, with the
+/// launcher's user agent (see [`set_user_agent`]).
+pub static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(request::build_client);
 
 /// Executes multiple async tasks concurrently (e.g., downloading files).
 ///
@@ -197,6 +223,9 @@ pub async fn do_jobs_with_limit<T, E>(
 
 /// Retries a non-deterministic function up to 5 times if it fails.
 ///
+/// Retries immediately, see [`retry_with_backoff`]
+/// for waiting between attempts.
+///
 /// Useful for inherently unreliable operations (e.g., network requests) that may
 /// fail intermittently, reducing the overall failure rate by retrying.
 /// Maybe we might get lucky and get it working the second time, or the third...
@@ -245,6 +274,13 @@ where
     result
 }
 
+/// Where the `mods` folder is moved to while an instance
+/// is running in safe mode (inside its game folder).
+///
+/// While this exists, the `mods` folder is a temporary empty one,
+/// so it shouldn't be used to tell which mods are installed.
+pub const SAFE_MODE_MODS_DIR: &str = "mods.safe_mode";
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Instance {
     pub name: Arc<str>,
@@ -288,6 +324,9 @@ impl Instance {
     ///
     /// - Instances: `QuantumLauncher/instances/<NAME>/.minecraft/`
     /// - Servers: `QuantumLauncher/servers/<NAME>/` (identical to `instance_path`)
+    ///
+    /// This ignores the user's custom game folder, if any;
+    /// see [`Instance::get_game_dir`].
     #[must_use]
     pub fn get_dot_minecraft_path(&self) -> PathBuf {
         let name = &*self.name;
@@ -297,6 +336,17 @@ impl Instance {
         }
     }
 
+    /// Gets the folder the game actually runs in: the
+    /// [`Instance::get_dot_minecraft_path`], unless the user
+    /// moved it elsewhere ([`InstanceConfigJson::game_dir_override`]).
+    ///
+    /// # Errors
+    /// If `config.json` couldn't be read or parsed (guessing
+    /// the regular path then might mean writing to the wrong place).
+    pub async fn get_game_dir(&self) -> Result<PathBuf, JsonFileError> {
+        Ok(InstanceConfigJson::read(self).await?.get_game_dir(self))
+    }
+
     #[must_use]
     pub fn get_name(&self) -> &str {
         &self.name
@@ -323,11 +373,17 @@ impl InstanceKind {
     }
 
     pub fn get_root_directory(&self) -> PathBuf {
-        let name = match self {
+        LAUNCHER_DIR.join(self.get_dir_name())
+    }
+
+    /// Name of the folder (in the launcher dir)
+    /// holding all instances of this kind.
+    #[must_use]
+    pub const fn get_dir_name(self) -> &'static str {
+        match self {
             InstanceKind::Client => "instances",
             InstanceKind::Server => "servers",
-        };
-        LAUNCHER_DIR.join(name)
+        }
     }
 }
 
@@ -642,6 +698,8 @@ impl LaunchedProcess {
     /// Runs until the process exits, then returns exit status
     /// and returns an optional [`Diagnostic`] (for troubleshooting common issues)
     ///
+    /// Once a client exits, its [`RunningProcess`] record is removed.
+    ///
     /// # Arguments
     /// - `censors`: Any strings to censor (like session id, password, etc.).
     ///   Leave blank if not needed
@@ -659,16 +717,10 @@ impl LaunchedProcess {
         censors: Vec<String>,
         sender: Option<Sender<LogLine>>,
     ) -> Option<ReadLogOut> {
-        Some(read_logs(self.child.clone(), sender, self.instance.clone(), censors).await)
+        let result = read_logs(self.child.clone(), sender, self.instance.clone(), censors).await;
+        if !self.instance.is_server() {
+            RunningProcess::remove(&self.instance).await;
+        }
+        Some(result)
     }
 }
-
-#[must_use]
-pub fn sanitize_instance_name(mut name: String) -> String {
-    let mut disallowed = vec![
-        '/', '\\', ':', '*', '?', '"', '<', '>', '|', '\'', '\0', '\u{7F}',
-    ];
-    disallowed.extend('\u{1}'..='\u{1F}');
-    name.retain(|c| !disallowed.contains(&c));
-    name.trim().to_owned()
-}
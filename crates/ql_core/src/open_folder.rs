@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use crate::{Instance, InstanceConfigJson, IntoIoError, IoError, open_file_explorer};
+
+/// A folder of an instance that the user might
+/// want to browse in their file manager.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceFolder {
+    /// The instance folder itself
+    /// (`QuantumLauncher/instances/<NAME>/` or `QuantumLauncher/servers/<NAME>/`)
+    Root,
+    Mods,
+    /// Worlds. Servers keep these directly
+    /// in the server folder, so that's opened instead.
+    Saves,
+    /// Only clients take screenshots.
+    Screenshots,
+}
+
+impl InstanceFolder {
+    /// Gets the folder's path, ignoring the instance's
+    /// custom game folder (if any; see [`Instance::get_game_dir`]).
+    #[must_use]
+    pub fn get_path(self, instance: &Instance) -> PathBuf {
+        self.get_path_in(instance, instance.get_dot_minecraft_path())
+    }
+
+    fn get_path_in(self, instance: &Instance, dot_minecraft: PathBuf) -> PathBuf {
+        match self {
+            InstanceFolder::Root => instance.get_instance_path(),
+            InstanceFolder::Mods => dot_minecraft.join("mods"),
+            InstanceFolder::Saves if instance.is_server() => dot_minecraft,
+            InstanceFolder::Saves => dot_minecraft.join("saves"),
+            InstanceFolder::Screenshots => dot_minecraft.join("screenshots"),
+        }
+    }
+}
+
+/// Opens the instance folder in the system's file manager
+/// (see [`open_file_explorer`]).
+///
+/// # Errors
+/// If the instance doesn't exist.
+pub fn open_instance_folder(instance: &Instance) -> Result<(), IoError> {
+    open_instance_subfolder(instance, InstanceFolder::Root)
+}
+
+/// Opens one of the instance's folders in the system's file manager
+/// (see [`open_file_explorer`]).
+///
+/// Folders like `screenshots` only appear once the game
+/// first needs them, so it's created if it doesn't exist yet.
+/// The instance itself must exist though.
+///
+/// # Errors
+/// - The instance doesn't exist
+/// - The folder didn't exist and couldn't be created
+pub fn open_instance_subfolder(instance: &Instance, folder: InstanceFolder) -> Result<(), IoError> {
+    let instance_dir = instance.get_instance_path();
+    if !instance_dir.is_dir() {
+        return Err(IoError::Io {
+            error: std::io::ErrorKind::NotFound.into(),
+            path: instance_dir,
+        });
+    }
+    // Not async, so `Instance::get_game_dir` can't be used
+    let game_dir = std::fs::read_to_string(instance_dir.join("config.json"))
+        .ok()
+        .and_then(|n| serde_json::from_str::<InstanceConfigJson>(&n).ok())
+        .map_or_else(
+            || instance.get_dot_minecraft_path(),
+            |n| n.get_game_dir(instance),
+        );
+    let path = folder.get_path_in(instance, game_dir);
+    std::fs::create_dir_all(&path).dir(&path)?;
+    open_file_explorer(&path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LAUNCHER_DIR;
+
+    #[test]
+    fn folder_paths() {
+        let client = Instance::client("Test");
+        let dir = LAUNCHER_DIR.join("instances/Test");
+        assert_eq!(InstanceFolder::Root.get_path(&client), dir);
+        assert_eq!(
+            InstanceFolder::Mods.get_path(&client),
+            dir.join(".minecraft/mods")
+        );
+        assert_eq!(
+            InstanceFolder::Saves.get_path(&client),
+            dir.join(".minecraft/saves")
+        );
+        assert_eq!(
+            InstanceFolder::Screenshots.get_path(&client),
+            dir.join(".minecraft/screenshots")
+        );
+
+        let server = Instance::server("Test");
+        let dir = LAUNCHER_DIR.join("servers/Test");
+        assert_eq!(InstanceFolder::Root.get_path(&server), dir);
+        assert_eq!(InstanceFolder::Mods.get_path(&server), dir.join("mods"));
+        assert_eq!(InstanceFolder::Saves.get_path(&server), dir);
+    }
+
+    #[test]
+    fn missing_instance_not_created() {
+        let instance = Instance::client("ql_test_missing_instance");
+        for folder in [InstanceFolder::Root, InstanceFolder::Mods] {
+            assert!(open_instance_subfolder(&instance, folder).is_err());
+        }
+        assert!(!instance.get_instance_path().exists());
+    }
+}
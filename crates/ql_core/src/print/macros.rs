@@ -16,7 +16,7 @@ macro_rules! info {
         let msg = format!("{}", format_args!($($arg)*));
         let redacted = $crate::print::auto_redact(&msg);
         if $crate::print::is_print() {
-            println!("{} {}", owo_colors::OwoColorize::yellow(&"[info]"), redacted);
+            println!("{} {}", $crate::print::terminal_prefix($crate::print::LogType::Info), redacted);
         }
         $crate::print::print_to_memory(&redacted, $crate::print::LogType::Info);
     }};
@@ -25,7 +25,7 @@ macro_rules! info {
         let msg = format!("{}", format_args!($($arg)*));
         let redacted = $crate::print::auto_redact(&msg);
         if $crate::print::is_print() {
-            println!("{} {}", owo_colors::OwoColorize::yellow(&"[info]"), redacted);
+            println!("{} {}", $crate::print::terminal_prefix($crate::print::LogType::Info), redacted);
         }
         $crate::print::print_to_file(&redacted, $crate::print::LogType::Info);
     }};
@@ -38,7 +38,7 @@ macro_rules! err {
         let msg = format!("{}", format_args!($($arg)*));
         let redacted = $crate::print::auto_redact(&msg);
         if $crate::print::is_print() {
-            $crate::eeprintln!("{} {}", owo_colors::OwoColorize::red(&"[error]"), redacted);
+            $crate::eeprintln!("{} {}", $crate::print::terminal_prefix($crate::print::LogType::Error), redacted);
         }
         $crate::print::print_to_memory(&redacted, $crate::print::LogType::Error);
     }};
@@ -47,7 +47,7 @@ macro_rules! err {
         let msg = format!("{}", format_args!($($arg)*));
         let redacted = $crate::print::auto_redact(&msg);
         if $crate::print::is_print() {
-            $crate::eeprintln!("{} {}", owo_colors::OwoColorize::red(&"[error]"), redacted);
+            $crate::eeprintln!("{} {}", $crate::print::terminal_prefix($crate::print::LogType::Error), redacted);
         }
         $crate::print::print_to_file(&redacted, $crate::print::LogType::Error);
     }};
@@ -60,7 +60,7 @@ macro_rules! pt {
         let msg = format!("{}", format_args!($($arg)*));
         let redacted = $crate::print::auto_redact(&msg);
         if $crate::print::is_print() {
-            println!("{} {}", owo_colors::OwoColorize::bold(&"-"), redacted);
+            println!("{} {}", $crate::print::terminal_prefix($crate::print::LogType::Point), redacted);
         }
         $crate::print::print_to_memory(&redacted, $crate::print::LogType::Point);
     }};
@@ -69,7 +69,7 @@ macro_rules! pt {
         let msg = format!("{}", format_args!($($arg)*));
         let redacted = $crate::print::auto_redact(&msg);
         if $crate::print::is_print() {
-            println!("{} {}", owo_colors::OwoColorize::bold(&"-"), redacted);
+            println!("{} {}", $crate::print::terminal_prefix($crate::print::LogType::Point), redacted);
         }
         $crate::print::print_to_file(&redacted, $crate::print::LogType::Point);
     }};
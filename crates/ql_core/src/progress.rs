@@ -1,4 +1,7 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex, mpsc::Sender},
+};
 
 /// An enum representing the progress in downloading
 /// a Minecraft instance.
@@ -10,6 +13,8 @@ use std::fmt::Display;
 /// 4) Jar
 /// 5) Libraries
 /// 6) Assets
+/// 7) Java (only if it's being installed alongside,
+///    and is still going after the game's done)
 #[derive(Debug, Clone, Copy, Default)]
 pub enum DownloadProgress {
     #[default]
@@ -23,7 +28,17 @@ pub enum DownloadProgress {
         progress: usize,
         out_of: usize,
     },
-    DownloadingJar,
+    /// In bytes (`out_of` is `0` if the size is unknown).
+    DownloadingJar {
+        progress: usize,
+        out_of: usize,
+    },
+    /// The game's downloaded, still waiting on the Java install
+    /// (see `ql_instances::create_instance`).
+    InstallingJava {
+        progress: usize,
+        out_of: usize,
+    },
 }
 
 impl Display for DownloadProgress {
@@ -37,7 +52,40 @@ impl Display for DownloadProgress {
             DownloadProgress::DownloadingLibraries { progress, out_of } => {
                 write!(f, "Downloading library {progress} / {out_of}")
             }
-            DownloadProgress::DownloadingJar => write!(f, "Downloading Game Jar file"),
+            DownloadProgress::DownloadingJar { progress, out_of } if *out_of > 0 => {
+                write!(
+                    f,
+                    "Downloading Game Jar file ({:.1} / {:.1} MB)",
+                    *progress as f32 / 1_048_576.0,
+                    *out_of as f32 / 1_048_576.0
+                )
+            }
+            DownloadProgress::DownloadingJar { .. } => write!(f, "Downloading Game Jar file"),
+            DownloadProgress::InstallingJava { progress, out_of } => {
+                write!(f, "Installing Java ({progress} / {out_of})")
+            }
+        }
+    }
+}
+
+impl DownloadProgress {
+    /// How far along the download is, from `0.0` to `100.0`.
+    #[must_use]
+    pub fn percent(&self) -> f32 {
+        f32::from(self) * 100.0 / <Self as Progress>::total()
+    }
+
+    /// A short name for the current stage, without any counts
+    /// (use [`Display`] for the full message).
+    #[must_use]
+    pub fn stage_label(&self) -> &'static str {
+        match self {
+            DownloadProgress::DownloadingJsonManifest => "Manifest JSON",
+            DownloadProgress::DownloadingVersionJson => "Version JSON",
+            DownloadProgress::DownloadingAssets { .. } => "Assets",
+            DownloadProgress::DownloadingLibraries { .. } => "Libraries",
+            DownloadProgress::DownloadingJar { .. } => "Game Jar",
+            DownloadProgress::InstallingJava { .. } => "Java",
         }
     }
 }
@@ -47,17 +95,29 @@ impl From<&DownloadProgress> for f32 {
         match val {
             DownloadProgress::DownloadingJsonManifest => 0.1,
             DownloadProgress::DownloadingVersionJson => 0.2,
-            DownloadProgress::DownloadingJar => 0.3,
+            DownloadProgress::DownloadingJar { progress, out_of } => {
+                fraction(*progress, *out_of) * 0.7 + 0.3
+            }
             DownloadProgress::DownloadingLibraries { progress, out_of } => {
-                (*progress as f32 / *out_of as f32) + 1.0
+                fraction(*progress, *out_of) + 1.0
             }
             DownloadProgress::DownloadingAssets { progress, out_of } => {
-                (*progress as f32 * 8.0 / *out_of as f32) + 2.0
+                fraction(*progress, *out_of) * 8.0 + 2.0
             }
+            // The game's done, only Java is left
+            DownloadProgress::InstallingJava { .. } => 10.0,
         }
     }
 }
 
+fn fraction(progress: usize, out_of: usize) -> f32 {
+    if out_of == 0 {
+        0.0
+    } else {
+        progress as f32 / out_of as f32
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GenericProgress {
     pub done: usize,
@@ -138,3 +198,215 @@ impl Progress for GenericProgress {
         1.0
     }
 }
+
+/// Combines the progress of multiple stages
+/// (like "install loader" + "download assets")
+/// into one [`GenericProgress`] stream, for a single progress bar.
+///
+/// Each stage gets its own sender (see [`ProgressAggregator::stage`]),
+/// and a weight for how much of the whole operation it makes up.
+///
+/// # Example
+/// ```no_run
+/// # use ql_core::{GenericProgress, ProgressAggregator};
+/// # fn f(sender: std::sync::mpsc::Sender<GenericProgress>) {
+/// let progress = ProgressAggregator::new(sender);
+/// // Downloading is slow, so it makes up most of the bar
+/// let loader = progress.stage(1.0);
+/// let assets = progress.stage(4.0);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ProgressAggregator {
+    sender: Sender<GenericProgress>,
+    stages: Arc<Mutex<Vec<Stage>>>,
+}
+
+struct Stage {
+    weight: f32,
+    done: f32,
+    has_finished: bool,
+}
+
+impl ProgressAggregator {
+    /// How many steps the combined progress is split into
+    /// ([`GenericProgress::total`]).
+    pub const TOTAL: usize = 1000;
+
+    #[must_use]
+    pub fn new(sender: Sender<GenericProgress>) -> Self {
+        Self {
+            sender,
+            stages: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Adds a stage making up `weight` of the operation
+    /// (relative to the other stages), returning a sender
+    /// for its progress.
+    ///
+    /// Add all stages before sending any progress,
+    /// otherwise the combined progress may go backwards.
+    #[must_use]
+    pub fn stage(&self, weight: f32) -> Sender<GenericProgress> {
+        let index = {
+            let Ok(mut stages) = self.stages.lock() else {
+                return std::sync::mpsc::channel().0;
+            };
+            stages.push(Stage {
+                weight: weight.max(0.0),
+                done: 0.0,
+                has_finished: false,
+            });
+            stages.len() - 1
+        };
+
+        let (send, recv) = std::sync::mpsc::channel::<GenericProgress>();
+        let this = self.clone();
+        std::thread::spawn(move || {
+            for item in recv {
+                this.update(index, item);
+            }
+        });
+        send
+    }
+
+    fn update(&self, index: usize, progress: GenericProgress) {
+        let Ok(mut stages) = self.stages.lock() else {
+            return;
+        };
+        let stage = &mut stages[index];
+        stage.has_finished = progress.has_finished;
+        stage.done = if progress.has_finished {
+            1.0
+        } else {
+            progress.get_num().clamp(0.0, 1.0)
+        };
+
+        let total_weight: f32 = stages.iter().map(|n| n.weight).sum();
+        let done = if total_weight > 0.0 {
+            stages.iter().map(|n| n.done * n.weight).sum::<f32>() / total_weight
+        } else {
+            0.0
+        };
+
+        _ = self.sender.send(GenericProgress {
+            done: (done * Self::TOTAL as f32).round() as usize,
+            total: Self::TOTAL,
+            message: progress.message,
+            has_finished: stages.iter().all(|n| n.has_finished),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(done: usize, total: usize) -> GenericProgress {
+        GenericProgress {
+            done,
+            total,
+            message: Some(format!("{done}/{total}")),
+            has_finished: false,
+        }
+    }
+
+    #[test]
+    fn aggregates_weighted_stages() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let aggregator = ProgressAggregator::new(sender);
+        let first = aggregator.stage(1.0);
+        let second = aggregator.stage(3.0);
+
+        first.send(progress(1, 2)).unwrap();
+        let merged = receiver.recv().unwrap();
+        assert_eq!(merged.done, 125);
+        assert_eq!(merged.total, ProgressAggregator::TOTAL);
+        assert_eq!(merged.message.as_deref(), Some("1/2"));
+
+        first.send(GenericProgress::finished()).unwrap();
+        assert_eq!(receiver.recv().unwrap().done, 250);
+
+        second.send(progress(5, 10)).unwrap();
+        let merged = receiver.recv().unwrap();
+        assert_eq!(merged.done, 625);
+        assert!(!merged.has_finished);
+
+        second.send(GenericProgress::finished()).unwrap();
+        let merged = receiver.recv().unwrap();
+        assert_eq!(merged.done, 1000);
+        assert!(merged.has_finished);
+    }
+
+    #[test]
+    fn download_percent() {
+        let cases = [
+            (DownloadProgress::DownloadingJsonManifest, 1.0),
+            (DownloadProgress::DownloadingVersionJson, 2.0),
+            (
+                DownloadProgress::DownloadingJar {
+                    progress: 0,
+                    out_of: 0,
+                },
+                3.0,
+            ),
+            (
+                DownloadProgress::DownloadingJar {
+                    progress: 50,
+                    out_of: 100,
+                },
+                6.5,
+            ),
+            (
+                DownloadProgress::DownloadingLibraries {
+                    progress: 5,
+                    out_of: 10,
+                },
+                15.0,
+            ),
+            (
+                DownloadProgress::DownloadingAssets {
+                    progress: 3,
+                    out_of: 10,
+                },
+                44.0,
+            ),
+            (
+                DownloadProgress::DownloadingAssets {
+                    progress: 10,
+                    out_of: 10,
+                },
+                100.0,
+            ),
+            (
+                DownloadProgress::DownloadingAssets {
+                    progress: 0,
+                    out_of: 0,
+                },
+                20.0,
+            ),
+        ];
+        for (progress, expected) in cases {
+            assert!(
+                (progress.percent() - expected).abs() < 0.001,
+                "{progress:?}: {} != {expected}",
+                progress.percent()
+            );
+        }
+    }
+
+    #[test]
+    fn download_stage_label() {
+        let progress = DownloadProgress::DownloadingAssets {
+            progress: 3,
+            out_of: 10,
+        };
+        assert_eq!(progress.stage_label(), "Assets");
+        let progress = DownloadProgress::DownloadingJar {
+            progress: 0,
+            out_of: 0,
+        };
+        assert_eq!(progress.stage_label(), "Game Jar");
+    }
+}
@@ -1,18 +1,62 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
 use futures::StreamExt;
-use reqwest::Response;
-use tokio_util::io::StreamReader;
+use reqwest::{
+    Response, StatusCode,
+    header::{CONTENT_RANGE, ETAG, HeaderName, IF_RANGE, LAST_MODIFIED, RANGE},
+};
+use tokio::io::AsyncWriteExt;
 
 use crate::{
-    CLIENT, DownloadFileError, IntoIoError, IntoJsonError, JsonDownloadError, RequestError, retry,
+    ARCH, CLIENT, DownloadFileError, IntoIoError, IntoJsonError, JsonDownloadError,
+    LAUNCHER_VERSION_NAME, OS_NAME, RequestError, err, pt, retry,
 };
 
+static USER_AGENT: OnceLock<String> = OnceLock::new();
+
+/// Sets the `User-Agent` sent with all requests made through
+/// [`CLIENT`] (unless a request overrides it, like
+/// [`DownloadRequest::user_agent_spoof`]).
+///
+/// Defaults to `QuantumLauncher/<version> (<os> <arch>)`.
+///
+/// This must be called at startup, before any request is made,
+/// since the client is only built once. Later calls are ignored.
+pub fn set_user_agent(ua: String) {
+    if USER_AGENT.set(ua).is_err() {
+        err!("User agent can only be set once, at startup (ignoring)");
+    }
+}
+
+/// The configured user agent (see [`set_user_agent`]).
+pub fn user_agent() -> &'static str {
+    USER_AGENT.get_or_init(|| format!("QuantumLauncher/{LAUNCHER_VERSION_NAME} ({OS_NAME} {ARCH})"))
+}
+
+/// Builds the shared [`CLIENT`], with the configured [`user_agent`].
+pub(crate) fn build_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(user_agent())
+        .build()
+        .expect("should be able to build HTTP client")
+}
+
+/// Called with `(downloaded_bytes, total_bytes)`.
+/// `total_bytes` is from the `Content-Length` header, if any.
+pub type ProgressFn<'a> = &'a (dyn Fn(u64, Option<u64>) + Sync);
+
 #[must_use]
 pub struct DownloadRequest<'a> {
     url: &'a str,
     user_agent: UserAgentKind,
+    on_progress: Option<ProgressFn<'a>>,
+    resume: bool,
 }
 
-impl DownloadRequest<'_> {
+impl<'a> DownloadRequest<'a> {
     pub fn user_agent_spoof(mut self) -> Self {
         self.user_agent = UserAgentKind::Spoofed;
         self
@@ -23,15 +67,69 @@ impl DownloadRequest<'_> {
         self
     }
 
+    /// Reports progress while downloading
+    /// with [`Self::path`] (see [`ProgressFn`]).
+    pub fn on_progress(mut self, f: ProgressFn<'a>) -> Self {
+        self.on_progress = Some(f);
+        self
+    }
+
+    /// With [`Self::path`], continues a partially downloaded
+    /// file (like from an earlier attempt that lost connection)
+    /// using an HTTP `Range` request, instead of starting over.
+    ///
+    /// The partial file's `ETag` (or `Last-Modified`) is kept
+    /// next to it (`<file>.etag`) and sent as `If-Range`,
+    /// so a file that changed on the server isn't appended to
+    /// the old one. If there's no such tag, or the server
+    /// doesn't support ranges, the file is downloaded from the start.
+    pub fn resume(mut self) -> Self {
+        self.resume = true;
+        self
+    }
+
     async fn send(&self) -> Result<reqwest::Response, RequestError> {
+        let response = self.request().send().await?;
+        check_for_success(&response)?;
+        Ok(response)
+    }
+
+    /// Sends a request for the rest of the file after `existing` bytes,
+    /// if it's still the version tagged `validator`.
+    /// Returns the response and the byte it starts from
+    /// (`0` if the server sent the whole file instead).
+    async fn send_resuming(
+        &self,
+        existing: u64,
+        validator: Option<&str>,
+    ) -> Result<(Response, u64), RequestError> {
+        if let (1.., Some(validator)) = (existing, validator) {
+            let response = self
+                .request()
+                .header(RANGE, format!("bytes={existing}-"))
+                .header(IF_RANGE, validator)
+                .send()
+                .await?;
+            if response.status() == StatusCode::PARTIAL_CONTENT
+                && range_start(&response) == Some(existing)
+            {
+                return Ok((response, existing));
+            }
+            if response.status() == StatusCode::OK {
+                // Range ignored (or the file changed), this is the whole file
+                return Ok((response, 0));
+            }
+            // Range not supported (or the file is already complete), start over
+        }
+        Ok((self.send().await?, 0))
+    }
+
+    fn request(&self) -> reqwest::RequestBuilder {
         let mut get = CLIENT.get(self.url);
         match self.user_agent {
             UserAgentKind::None => {}
             UserAgentKind::Ql => {
-                get = get.header(
-                    "User-Agent",
-                    "Mrmayman/quantumlauncher (https://mrmayman.github.io/quantumlauncher)",
-                );
+                get = get.header("User-Agent", user_agent());
             }
             UserAgentKind::Spoofed => {
                 get = get.header(
@@ -40,9 +138,7 @@ impl DownloadRequest<'_> {
                 );
             }
         }
-        let response = get.send().await?;
-        check_for_success(&response)?;
-        Ok(response)
+        get
     }
 
     pub async fn bytes(&self) -> Result<Vec<u8>, RequestError> {
@@ -78,13 +174,6 @@ impl DownloadRequest<'_> {
     /// - Redirect limit exhausted.
     pub async fn path(&self, path: impl AsRef<std::path::Path>) -> Result<(), DownloadFileError> {
         retry(|| async {
-            let response = self.send().await?;
-
-            let stream = response
-                .bytes_stream()
-                .map(|n| n.map_err(std::io::Error::other));
-            let mut stream = StreamReader::new(stream);
-
             let path = path.as_ref();
             if let Some(parent) = path.parent() {
                 if !parent.is_dir() {
@@ -92,18 +181,78 @@ impl DownloadRequest<'_> {
                 }
             }
 
-            let mut file = tokio::fs::File::create(&path).await.path(path)?;
-            tokio::io::copy(&mut stream, &mut file)
-                .await
-                .map_err(|error| crate::IoError::FromUrl {
-                    error,
-                    path: path.to_owned(),
-                    url: self.url.to_owned(),
-                })?;
+            let validator_path = validator_path(path);
+            let (existing, validator) = if self.resume {
+                (
+                    tokio::fs::metadata(path).await.map_or(0, |n| n.len()),
+                    tokio::fs::read_to_string(&validator_path).await.ok(),
+                )
+            } else {
+                (0, None)
+            };
+            let (response, start) = self.send_resuming(existing, validator.as_deref()).await?;
+            if self.resume && start == 0 {
+                // A new partial file, remember which version it's of
+                if let Some(validator) = response_validator(&response) {
+                    tokio::fs::write(&validator_path, validator)
+                        .await
+                        .path(&validator_path)?;
+                } else {
+                    _ = tokio::fs::remove_file(&validator_path).await;
+                }
+            }
+
+            let total = response.content_length().map(|n| n + start);
+            let mut stream = response.bytes_stream();
+
+            let io_err = |error| crate::IoError::FromUrl {
+                error,
+                path: path.to_owned(),
+                url: self.url.to_owned(),
+            };
+            let mut file = if start > 0 {
+                pt!("Resuming download from {start} bytes: {}", self.url);
+                tokio::fs::OpenOptions::new()
+                    .append(true)
+                    .open(path)
+                    .await
+                    .path(path)?
+            } else {
+                tokio::fs::File::create(&path).await.path(path)?
+            };
+            let mut downloaded = start;
+            let mut last_reported = None;
+            self.report_progress(downloaded, total, &mut last_reported);
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(RequestError::from)?;
+                file.write_all(&chunk).await.map_err(io_err)?;
+                downloaded += chunk.len() as u64;
+                self.report_progress(downloaded, total, &mut last_reported);
+            }
+            file.flush().await.map_err(io_err)?;
+            if self.resume {
+                _ = tokio::fs::remove_file(&validator_path).await;
+            }
             Ok(())
         })
         .await
     }
+
+    /// Reports progress once per percent (or MB, if the size is unknown),
+    /// to not flood the receiver with a message per chunk.
+    fn report_progress(&self, downloaded: u64, total: Option<u64>, last: &mut Option<u64>) {
+        let Some(on_progress) = self.on_progress else {
+            return;
+        };
+        let step = match total {
+            Some(total) if total > 0 => downloaded * 100 / total,
+            _ => downloaded / (1024 * 1024),
+        };
+        if *last != Some(step) || Some(downloaded) == total {
+            *last = Some(step);
+            on_progress(downloaded, total);
+        }
+    }
 }
 
 enum UserAgentKind {
@@ -116,9 +265,42 @@ pub fn download(url: &str) -> DownloadRequest<'_> {
     DownloadRequest {
         url,
         user_agent: UserAgentKind::None,
+        on_progress: None,
+        resume: false,
     }
 }
 
+/// Where [`DownloadRequest::path`] keeps the tag of a
+/// partially downloaded file at `path`, for resuming.
+fn validator_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".etag");
+    path.with_file_name(name)
+}
+
+/// The response's `ETag` (or `Last-Modified`, if there isn't one),
+/// for use with `If-Range`.
+///
+/// Weak `ETag`s (`W/"..."`) can't be used for ranges, so they're skipped.
+fn response_validator(response: &Response) -> Option<&str> {
+    let header = |name: HeaderName| response.headers().get(name)?.to_str().ok();
+    header(ETAG)
+        .filter(|n| !n.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+}
+
+/// The first byte of a `206 Partial Content` response,
+/// from `Content-Range: bytes START-END/TOTAL`.
+fn range_start(response: &Response) -> Option<u64> {
+    let range = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    range
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+}
+
 pub fn check_for_success(response: &Response) -> Result<(), RequestError> {
     if response.status().is_success() {
         Ok(())
@@ -129,3 +311,201 @@ pub fn check_for_success(response: &Response) -> Result<(), RequestError> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::{GenericProgress, file_utils};
+
+    /// Serves `body` once over HTTP, with a `Content-Length`.
+    async fn mock_server(body: Vec<u8>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/server.jar", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 4096];
+            _ = stream.read(&mut buf).await.unwrap();
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).await.unwrap();
+            // In pieces, like a real download
+            for chunk in body.chunks(64 * 1024) {
+                stream.write_all(chunk).await.unwrap();
+                stream.flush().await.unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn streams_to_file_with_progress() {
+        let body: Vec<u8> = (0..1_000_000u32).map(|n| (n % 251) as u8).collect();
+        let url = mock_server(body.clone()).await;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("download/server.jar");
+        let (sender, receiver) = std::sync::mpsc::channel();
+        file_utils::download_file_to_path(&url, &dest, false, Some(&sender))
+            .await
+            .unwrap();
+
+        assert_eq!(tokio::fs::read(&dest).await.unwrap(), body);
+
+        let events: Vec<GenericProgress> = receiver.try_iter().collect();
+        assert!(events.len() > 2, "{events:?}");
+        assert!(events.iter().all(|n| n.total == body.len()));
+        assert!(events.windows(2).all(|n| n[0].done <= n[1].done));
+        assert_eq!(events.first().unwrap().done, 0);
+        assert_eq!(events.last().unwrap().done, body.len());
+    }
+
+    #[tokio::test]
+    async fn sends_configured_user_agent() {
+        set_user_agent("QuantumLauncher-Test/1.0 (mock)".to_owned());
+        // Another test may have made a request (and set the default) first
+        let expected = user_agent().to_owned();
+        assert!(
+            expected == "QuantumLauncher-Test/1.0 (mock)"
+                || expected.starts_with(&format!("QuantumLauncher/{LAUNCHER_VERSION_NAME} (")),
+            "{expected}"
+        );
+
+        let expected = format!("user-agent: {}\r\n", expected.to_lowercase());
+
+        let (url, server) = capture_request().await;
+        assert_eq!(download(&url).string().await.unwrap(), "ok");
+        let request = server.await.unwrap();
+        assert!(request.contains(&expected), "{request}");
+
+        // Explicitly asking for the launcher's user agent
+        let (url, server) = capture_request().await;
+        assert_eq!(download(&url).user_agent_ql().string().await.unwrap(), "ok");
+        let request = server.await.unwrap();
+        assert!(request.contains(&expected), "{request}");
+        assert_eq!(request.matches("user-agent:").count(), 1, "{request}");
+    }
+
+    /// Answers one request with `ok`, returning
+    /// the (lowercase) request it got.
+    async fn capture_request() -> (String, tokio::task::JoinHandle<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 4096];
+            let len = stream.read(&mut buf).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buf[..len]).to_lowercase()
+        });
+        (url, server)
+    }
+
+    /// Serves `body` (tagged `ETag: "v1"`) once. If asked for
+    /// `Range: bytes=N-` with `If-Range: "v1"`, only the rest is sent,
+    /// with `206 Partial Content`.
+    async fn mock_range_server(body: Vec<u8>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/client.jar", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 4096];
+            let len = stream.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..len]).to_lowercase();
+            let start = request
+                .lines()
+                .find_map(|n| n.strip_prefix("range: bytes="))
+                .and_then(|n| n.trim().trim_end_matches('-').parse().ok())
+                .filter(|_| request.contains("\r\nif-range: \"v1\"\r\n"));
+            let header = match start {
+                Some(start) => format!(
+                    "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\n\
+                    Content-Range: bytes {start}-{}/{}\r\n",
+                    body.len() - start,
+                    body.len() - 1,
+                    body.len()
+                ),
+                None => format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n", body.len()),
+            };
+            let header = format!("{header}ETag: \"v1\"\r\nConnection: close\r\n\r\n");
+            stream.write_all(header.as_bytes()).await.unwrap();
+            stream
+                .write_all(&body[start.unwrap_or_default()..])
+                .await
+                .unwrap();
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn resumes_partial_download() {
+        let body: Vec<u8> = (0..1_000_000u32).map(|n| (n % 251) as u8).collect();
+        let url = mock_range_server(body.clone()).await;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("client.jar");
+        // Left over from an interrupted download
+        tokio::fs::write(&dest, &body[..300_000]).await.unwrap();
+        tokio::fs::write(temp.path().join("client.jar.etag"), "\"v1\"")
+            .await
+            .unwrap();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        file_utils::download_file_to_path(&url, &dest, true, Some(&sender))
+            .await
+            .unwrap();
+
+        assert_eq!(tokio::fs::read(&dest).await.unwrap(), body);
+        let events: Vec<GenericProgress> = receiver.try_iter().collect();
+        assert_eq!(events.first().unwrap().done, 300_000);
+        assert_eq!(events.last().unwrap().done, body.len());
+        assert!(events.iter().all(|n| n.total == body.len()));
+        assert!(!temp.path().join("client.jar.etag").exists());
+    }
+
+    #[tokio::test]
+    async fn restarts_if_file_changed() {
+        let body: Vec<u8> = (0..200_000u32).map(|n| (n % 17) as u8).collect();
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("client.jar");
+        let tag = temp.path().join("client.jar.etag");
+
+        // Partial of an older version, and of an unknown one
+        for old_tag in [Some("\"v0\""), None] {
+            tokio::fs::write(&dest, b"old partial data").await.unwrap();
+            if let Some(old_tag) = old_tag {
+                tokio::fs::write(&tag, old_tag).await.unwrap();
+            }
+            let url = mock_range_server(body.clone()).await;
+            file_utils::download_file_to_path(&url, &dest, true, None)
+                .await
+                .unwrap();
+            assert_eq!(tokio::fs::read(&dest).await.unwrap(), body);
+            assert!(!tag.exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn restarts_without_range_support() {
+        let body: Vec<u8> = (0..200_000u32).map(|n| (n % 13) as u8).collect();
+        // Ignores the `Range` header
+        let url = mock_server(body.clone()).await;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("server.jar");
+        tokio::fs::write(&dest, b"stale partial data")
+            .await
+            .unwrap();
+
+        file_utils::download_file_to_path(&url, &dest, true, None)
+            .await
+            .unwrap();
+        assert_eq!(tokio::fs::read(&dest).await.unwrap(), body);
+    }
+}
@@ -0,0 +1,222 @@
+use std::{path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tokio::{fs, process::Command};
+
+use crate::{Instance, IntoIoError, IntoJsonError, IoError, JsonFileError, err, no_window};
+
+/// Stored in the instance folder while the game is running.
+const PID_FILE: &str = "running.json";
+
+/// A game process started by the launcher, recorded on disk
+/// so it can still be found after the launcher is restarted.
+///
+/// Write it with [`RunningProcess::save`] after launching, and
+/// look for it later with [`RunningProcess::find`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunningProcess {
+    pub pid: u32,
+    /// The JVM command line: Java path, JVM arguments and main class.
+    ///
+    /// Game arguments are left out on purpose,
+    /// as they contain the account's access token.
+    pub command: Vec<String>,
+}
+
+impl RunningProcess {
+    /// Records this process in the instance folder.
+    ///
+    /// # Errors
+    /// If the file couldn't be written.
+    pub async fn save(&self, instance: &Instance) -> Result<(), JsonFileError> {
+        let path = instance.get_instance_path().join(PID_FILE);
+        let json = serde_json::to_string(self).json_to()?;
+        fs::write(&path, json).await.path(&path)?;
+        Ok(())
+    }
+
+    /// Checks if the instance still has a running game process
+    /// from a previous launch (even by an earlier launcher session).
+    ///
+    /// If the recorded process has exited (or its PID now belongs
+    /// to some unrelated program), the stale file is deleted
+    /// and `None` is returned.
+    pub async fn find(instance: &Instance) -> Option<Self> {
+        find_in(&instance.get_instance_path()).await
+    }
+
+    /// Deletes the record, once the game has exited.
+    pub async fn remove(instance: &Instance) {
+        remove_in(&instance.get_instance_path()).await;
+    }
+
+    /// Stops a process found through [`RunningProcess::find`],
+    /// giving it `grace` time to save and exit on its own
+    /// (like [`crate::terminate_client`]).
+    ///
+    /// # Errors
+    /// If the OS refused to signal or kill the process.
+    pub async fn kill(&self, grace: Duration) -> Result<(), IoError> {
+        #[cfg(unix)]
+        crate::terminate::send_sigterm(self.pid)?;
+
+        let deadline = tokio::time::Instant::now() + grace;
+        while tokio::time::Instant::now() < deadline {
+            if !self.is_alive().await {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        if !self.is_alive().await {
+            return Ok(());
+        }
+
+        #[cfg(unix)]
+        let mut command = {
+            let mut command = Command::new("kill");
+            command.args(["-9", &self.pid.to_string()]);
+            command
+        };
+        #[cfg(windows)]
+        let mut command = {
+            let mut command = Command::new("taskkill");
+            command.args(["/F", "/PID", &self.pid.to_string()]);
+            command
+        };
+        no_window!(command);
+        let status = command.status().await.map_err(IoError::Process)?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(IoError::Process(std::io::Error::other(format!(
+                "couldn't kill process {} ({status})",
+                self.pid
+            ))))
+        }
+    }
+
+    /// Whether the process is still alive *and* is still
+    /// the game, not something else that reused the PID.
+    async fn is_alive(&self) -> bool {
+        let Some(cmdline) = command_line_of(self.pid).await else {
+            return false;
+        };
+        // The main class is the most telling part.
+        // Note: with a launch prefix (like `gamemoderun`) the PID
+        // belongs to the wrapper, which still has it in its arguments.
+        self.command
+            .last()
+            .is_none_or(|main_class| cmdline.contains(main_class.as_str()))
+    }
+}
+
+async fn find_in(instance_dir: &Path) -> Option<RunningProcess> {
+    let path = instance_dir.join(PID_FILE);
+    let json = fs::read_to_string(&path).await.ok()?;
+
+    let process = match serde_json::from_str::<RunningProcess>(&json) {
+        Ok(n) => Some(n),
+        Err(error) => {
+            err!("Invalid {PID_FILE} in {instance_dir:?}: {error}");
+            None
+        }
+    };
+    match process {
+        Some(process) if process.is_alive().await => Some(process),
+        _ => {
+            remove_in(instance_dir).await;
+            None
+        }
+    }
+}
+
+async fn remove_in(instance_dir: &Path) {
+    let path = instance_dir.join(PID_FILE);
+    if let Err(error) = fs::remove_file(&path).await {
+        if error.kind() != std::io::ErrorKind::NotFound {
+            err!("Couldn't remove {path:?}: {error}");
+        }
+    }
+}
+
+/// The full command line of a process,
+/// or `None` if it isn't running.
+async fn command_line_of(pid: u32) -> Option<String> {
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("ps");
+        // `-ww`: don't cut off long command lines
+        command.args(["-ww", "-o", "command=", "-p", &pid.to_string()]);
+        command
+    };
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-Command",
+            &format!("(Get-CimInstance Win32_Process -Filter 'ProcessId={pid}').CommandLine"),
+        ]);
+        command
+    };
+    no_window!(command);
+
+    let output = command.output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let cmdline = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+    (!cmdline.is_empty()).then_some(cmdline)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    async fn write_record(dir: &Path, process: &RunningProcess) {
+        let json = serde_json::to_string(process).unwrap();
+        fs::write(dir.join(PID_FILE), json).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn detects_live_and_prunes_dead() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let process = RunningProcess {
+            pid: child.id().unwrap(),
+            command: vec!["sleep".to_owned(), "30".to_owned()],
+        };
+        write_record(dir, &process).await;
+        assert_eq!(find_in(dir).await, Some(process.clone()));
+        assert!(dir.join(PID_FILE).exists());
+
+        child.kill().await.unwrap();
+        assert_eq!(find_in(dir).await, None);
+        assert!(!dir.join(PID_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn reused_pid_is_not_the_game() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        write_record(
+            dir,
+            &RunningProcess {
+                pid: child.id().unwrap(),
+                command: vec![
+                    "java".to_owned(),
+                    "net.minecraft.client.main.Main".to_owned(),
+                ],
+            },
+        )
+        .await;
+        assert_eq!(find_in(dir).await, None);
+        assert!(!dir.join(PID_FILE).exists());
+
+        child.kill().await.unwrap();
+    }
+}
@@ -128,6 +128,15 @@ impl JavaVersion {
         Self::Java25,
     ];
 
+    /// The launcher-installable Java with this major version, if any.
+    ///
+    /// Unlike `From<usize>`, unknown versions
+    /// aren't rounded up to the newest one.
+    #[must_use]
+    pub fn from_major(major: usize) -> Option<Self> {
+        Self::ALL.iter().copied().find(|n| *n as usize == major)
+    }
+
     #[must_use]
     pub const fn next(self) -> Option<Self> {
         match self {
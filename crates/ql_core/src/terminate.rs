@@ -0,0 +1,96 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{process::Child, sync::Mutex, time::Instant};
+
+use crate::IoError;
+
+/// How often to check if the process has exited
+/// during the grace period.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Stops a game process, giving it `grace` time to
+/// shut down cleanly (and save the world) before killing it.
+///
+/// - Unix: Sends `SIGTERM`, then `SIGKILL` if it's still
+///   running after `grace`.
+/// - Windows: There's no equivalent graceful signal for
+///   a windowed app, so it's only killed once `grace` is over
+///   (if the user closed the game by then, nothing happens).
+///
+/// The lock on `child` is only held briefly, so log reading
+/// ([`crate::LaunchedProcess::read_logs`]) isn't blocked.
+///
+/// # Errors
+/// If the OS refused to signal or kill the process.
+pub async fn terminate_client(child: Arc<Mutex<Child>>, grace: Duration) -> Result<(), IoError> {
+    {
+        let mut child = child.lock().await;
+        if child.try_wait().map_err(IoError::Process)?.is_some() {
+            return Ok(());
+        }
+        #[cfg(unix)]
+        if let Some(pid) = child.id() {
+            send_sigterm(pid)?;
+        }
+    }
+
+    let deadline = Instant::now() + grace;
+    loop {
+        {
+            let mut child = child.lock().await;
+            if child.try_wait().map_err(IoError::Process)?.is_some() {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return child.kill().await.map_err(IoError::Process);
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn send_sigterm(pid: u32) -> Result<(), IoError> {
+    let pid = libc::pid_t::try_from(pid)
+        .map_err(|_| IoError::Process(std::io::Error::other("invalid pid")))?;
+    // SAFETY: `kill` doesn't touch any memory, the worst case
+    // is signalling the wrong process if the pid was reused,
+    // but the process hasn't been reaped yet (checked above).
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(IoError::Process(std::io::Error::last_os_error()))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{os::unix::process::ExitStatusExt, process::Stdio};
+
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn ignored_sigterm_escalates_to_kill() {
+        let mut child = tokio::process::Command::new("sh")
+            .args(["-c", "trap '' TERM; echo ready; exec sleep 30"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        // Make sure the trap is set up before signalling
+        let stdout = child.stdout.take().unwrap();
+        let mut line = String::new();
+        BufReader::new(stdout).read_line(&mut line).await.unwrap();
+        assert_eq!(line.trim(), "ready");
+
+        let child = Arc::new(Mutex::new(child));
+        let grace = Duration::from_millis(500);
+        let start = Instant::now();
+        terminate_client(child.clone(), grace).await.unwrap();
+        assert!(start.elapsed() >= grace);
+
+        let status = child.lock().await.wait().await.unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+}
@@ -0,0 +1,35 @@
+//! Fixtures shared by the tests of the launcher's crates.
+//!
+//! Enabled for other crates with the `test_utils` feature,
+//! which should only be turned on in `[dev-dependencies]`.
+
+use crate::json::VersionDetails;
+
+/// A minimal `details.json` for version `id`,
+/// with no libraries, downloads or Java version.
+///
+/// Fields can be added or changed (`json["javaVersion"] = ...`)
+/// before turning it into a [`VersionDetails`].
+#[must_use]
+pub fn version_json(id: &str, release_time: &str) -> serde_json::Value {
+    serde_json::json!({
+        "assetIndex": { "id": "17", "sha1": "", "size": 0, "totalSize": 0, "url": "" },
+        "assets": "17",
+        "downloads": { "client": { "sha1": "", "size": 0, "url": "" } },
+        "id": id,
+        "libraries": [],
+        "mainClass": "net.minecraft.client.main.Main",
+        "releaseTime": release_time,
+        "time": release_time,
+        "type": "release"
+    })
+}
+
+/// [`version_json`], parsed.
+///
+/// # Panics
+/// Never, the JSON is always valid.
+#[must_use]
+pub fn version_details(id: &str, release_time: &str) -> VersionDetails {
+    serde_json::from_value(version_json(id, release_time)).unwrap()
+}
@@ -0,0 +1,702 @@
+//! Reading and switching the logged-in accounts
+//! stored in the launcher config (`QuantumLauncher/config.json`),
+//! for frontends other than the GUI (like the CLI).
+
+use std::path::Path;
+
+use ql_core::{IntoIoError, IntoJsonError, JsonFileError, LAUNCHER_DIR, RequestError, err};
+use reqwest::StatusCode;
+use serde_json::Value;
+use thiserror::Error;
+
+use super::{AccountData, AccountType, KeyringError, ms, read_refresh_token, yggdrasil};
+
+/// An account from the launcher config,
+/// as listed by [`list_accounts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountSummary {
+    /// The key of the account in the config.
+    /// Pass this to [`set_active_account`].
+    ///
+    /// Usually the username, with a suffix like
+    /// ` (elyby)` for non-Microsoft accounts.
+    pub key: String,
+    /// The in-game username.
+    pub nice_username: String,
+    /// A name the user gave this account, see [`set_account_alias`].
+    pub alias: Option<String>,
+    pub uuid: String,
+    pub account_type: AccountType,
+    /// Whether this is the currently selected account.
+    pub is_selected: bool,
+}
+
+impl AccountSummary {
+    /// The alias if set, otherwise the in-game username.
+    #[must_use]
+    pub fn display_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.nice_username)
+    }
+}
+
+const ACCOUNT_ERR_PREFIX: &str = "while switching account:\n";
+
+#[derive(Debug, Error)]
+pub enum AccountSwitchError {
+    #[error("{ACCOUNT_ERR_PREFIX}no account named {0:?} is logged in")]
+    NotFound(String),
+    #[error("{ACCOUNT_ERR_PREFIX}{0}")]
+    Config(#[from] JsonFileError),
+}
+
+/// Whether a logged-in account's saved session
+/// still works, as checked by [`validate_accounts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountStatus {
+    Valid,
+    /// The server rejected the saved token (revoked,
+    /// or expired and couldn't be refreshed),
+    /// so the user needs to log in again.
+    NeedsReauth,
+    /// The saved token couldn't be read at all
+    /// (keyring error, or it was deleted).
+    Invalid(String),
+    /// Couldn't reach the server (offline?),
+    /// so the account wasn't checked.
+    Unreachable(String),
+}
+
+/// Checks every logged-in account's saved token against its server,
+/// so the UI can warn about accounts that need logging in again
+/// (instead of only finding out when launching).
+///
+/// Returns the account's config key (see [`AccountSummary::key`])
+/// and status. Nothing is removed from the config, that's
+/// up to the user.
+///
+/// This is done with a lightweight check, not a full login:
+/// - ely.by/littleskin: the yggdrasil `validate` endpoint, and
+///   if the token expired, `refresh` (saving the renewed token)
+/// - Microsoft: only the Microsoft token refresh, skipping the
+///   Xbox/Minecraft steps (the new token isn't saved,
+///   the saved one keeps working)
+pub async fn validate_accounts() -> Vec<(String, AccountStatus)> {
+    let config = match read_config(&LAUNCHER_DIR.join("config.json")) {
+        Ok(n) => n,
+        Err(error) => {
+            err!("Couldn't read accounts from config: {error}");
+            return Vec::new();
+        }
+    };
+    let Some(accounts) = config.get("accounts").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    let mut statuses = Vec::new();
+    for (key, account) in accounts {
+        let account_type = account_type(key, account);
+        let keyring_username = keyring_username(key, account_type, account);
+
+        let status = match read_refresh_token(keyring_username, account_type) {
+            Ok(token) => match account_type {
+                AccountType::Microsoft => to_status(ms::validate_token(&token).await),
+                AccountType::ElyBy | AccountType::LittleSkin => {
+                    check_yggdrasil(account_type.yggdrasil_validate(), &token, || {
+                        yggdrasil::login_refresh(
+                            keyring_username.to_owned(),
+                            token.clone(),
+                            account_type,
+                        )
+                    })
+                    .await
+                }
+            },
+            Err(error) => AccountStatus::Invalid(error.to_string()),
+        };
+        statuses.push((key.clone(), status));
+    }
+    statuses
+}
+
+const REAUTH_ERR_PREFIX: &str = "while refreshing account:\n";
+
+/// Why an account couldn't be refreshed by [`reauthenticate_all`].
+#[derive(Debug, Error)]
+pub enum ReauthError {
+    /// The saved login expired, was revoked or is missing
+    /// (or ely.by wants a 2FA code), so the user has to log in again.
+    /// Has what the UI needs to show the login screen for it.
+    #[error("{REAUTH_ERR_PREFIX}{} needs to log in again", .0.nice_username)]
+    NeedsLogin(AccountSummary),
+    #[error("{REAUTH_ERR_PREFIX}{0}")]
+    Keyring(#[from] KeyringError),
+    #[error("{REAUTH_ERR_PREFIX}{0}")]
+    Microsoft(#[from] ms::Error),
+    #[error("{REAUTH_ERR_PREFIX}{0}")]
+    Yggdrasil(#[from] yggdrasil::Error),
+}
+
+/// Refreshes the session of every logged-in account,
+/// for example after their tokens all expired at once
+/// (like after a password change).
+///
+/// Returns the account's config key (see [`AccountSummary::key`]),
+/// and either its refreshed session, or why it couldn't be refreshed.
+/// Accounts that need an interactive login fail with
+/// [`ReauthError::NeedsLogin`], so the UI can queue them up.
+///
+/// New tokens are saved to the keyring. The accounts are refreshed
+/// one at a time, to not get rate-limited.
+pub async fn reauthenticate_all() -> Vec<(String, Result<AccountData, ReauthError>)> {
+    let config = match read_config(&LAUNCHER_DIR.join("config.json")) {
+        Ok(n) => n,
+        Err(error) => {
+            err!("Couldn't read accounts from config: {error}");
+            return Vec::new();
+        }
+    };
+    let Some(accounts) = config.get("accounts").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let accounts: Vec<(AccountSummary, String)> = summaries(&config)
+        .into_iter()
+        .map(|summary| {
+            let keyring_username = accounts.get(&summary.key).map_or_else(
+                || summary.account_type.strip_name(&summary.key),
+                |n| keyring_username(&summary.key, summary.account_type, n),
+            );
+            let keyring_username = keyring_username.to_owned();
+            (summary, keyring_username)
+        })
+        .collect();
+    reauthenticate_each(accounts, refresh_account).await
+}
+
+/// `refresh` returns `None` if the account needs an interactive login.
+async fn reauthenticate_each<F, Fut>(
+    accounts: Vec<(AccountSummary, String)>,
+    refresh: F,
+) -> Vec<(String, Result<AccountData, ReauthError>)>
+where
+    F: Fn(AccountType, String) -> Fut,
+    Fut: Future<Output = Result<Option<AccountData>, ReauthError>>,
+{
+    let mut results = Vec::new();
+    for (account, keyring_username) in accounts {
+        let result = match refresh(account.account_type, keyring_username).await {
+            Ok(Some(mut data)) => {
+                data.alias.clone_from(&account.alias);
+                Ok(data)
+            }
+            Ok(None) => Err(ReauthError::NeedsLogin(account.clone())),
+            Err(error) => Err(error),
+        };
+        if let Err(error) = &result {
+            err!("{error}");
+        }
+        results.push((account.key, result));
+    }
+    results
+}
+
+async fn refresh_account(
+    account_type: AccountType,
+    keyring_username: String,
+) -> Result<Option<AccountData>, ReauthError> {
+    // Checked first, as failed full logins
+    // don't tell apart rejected tokens from other errors
+    let token = match read_refresh_token(&keyring_username, account_type) {
+        Ok(n) => n,
+        Err(KeyringError(keyring::Error::NoEntry)) => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    // Not validated first: expired tokens fail validation,
+    // but refreshing them is the whole point
+    match account_type {
+        AccountType::Microsoft => match ms::login_refresh(keyring_username, token, None).await {
+            Ok(data) => Ok(Some(data)),
+            Err(ms::Error::RefreshTokenRejected) => Ok(None),
+            Err(error) => Err(error.into()),
+        },
+        AccountType::ElyBy | AccountType::LittleSkin => {
+            match yggdrasil::login_refresh(keyring_username, token, account_type).await {
+                Ok(yggdrasil::Account::Account(data)) => Ok(Some(data)),
+                Ok(yggdrasil::Account::NeedsOTP) => Ok(None),
+                Err(error) if is_rejected(&error) => Ok(None),
+                Err(error) => Err(error.into()),
+            }
+        }
+    }
+}
+
+/// Whether the yggdrasil server refused to refresh the
+/// session (revoked/too old), rather than failing some other way.
+fn is_rejected(error: &yggdrasil::Error) -> bool {
+    matches!(
+        error,
+        yggdrasil::Error::Request(RequestError::DownloadError { code, .. })
+            if *code == StatusCode::UNAUTHORIZED || *code == StatusCode::FORBIDDEN
+    )
+}
+
+/// Validates `token` at `url`. Expired tokens fail
+/// validation but can still be renewed with `refresh`,
+/// so they only need a login if that's rejected too.
+async fn check_yggdrasil<F: Future<Output = Result<yggdrasil::Account, yggdrasil::Error>>>(
+    url: &str,
+    token: &str,
+    refresh: impl FnOnce() -> F,
+) -> AccountStatus {
+    match yggdrasil::validate_token(url, token).await {
+        Ok(false) => match refresh().await {
+            Ok(yggdrasil::Account::Account(_)) => AccountStatus::Valid,
+            Ok(yggdrasil::Account::NeedsOTP) => AccountStatus::NeedsReauth,
+            Err(error) if is_rejected(&error) => AccountStatus::NeedsReauth,
+            Err(error) => AccountStatus::Unreachable(error.to_string()),
+        },
+        result => to_status(result),
+    }
+}
+
+fn to_status<E: std::fmt::Display>(result: Result<bool, E>) -> AccountStatus {
+    match result {
+        Ok(true) => AccountStatus::Valid,
+        Ok(false) => AccountStatus::NeedsReauth,
+        Err(error) => AccountStatus::Unreachable(error.to_string()),
+    }
+}
+
+/// Lists all accounts logged into the launcher
+/// (sorted by key), not including the offline account.
+///
+/// Returns an empty list if the config couldn't be read.
+#[must_use]
+pub fn list_accounts() -> Vec<AccountSummary> {
+    match list_accounts_in(&LAUNCHER_DIR.join("config.json")) {
+        Ok(accounts) => accounts,
+        Err(error) => {
+            err!("Couldn't read accounts from config: {error}");
+            Vec::new()
+        }
+    }
+}
+
+/// Selects the account with this `key` (see [`AccountSummary::key`]),
+/// saving it to the config so it's used for the next launch.
+///
+/// # Errors
+/// - [`AccountSwitchError::NotFound`] if there's no such account
+/// - The config couldn't be read, parsed, or saved
+pub fn set_active_account(key: &str) -> Result<(), AccountSwitchError> {
+    set_active_account_in(&LAUNCHER_DIR.join("config.json"), key)
+}
+
+/// Gives the account with this `key` (see [`AccountSummary::key`])
+/// a friendly name (eg: "Main", "Alt") to show instead of its username,
+/// or removes it with `None` (or an empty name).
+///
+/// Only for showing, the account still logs in with its real username.
+///
+/// # Errors
+/// - [`AccountSwitchError::NotFound`] if there's no such account
+/// - The config couldn't be read, parsed, or saved
+pub fn set_account_alias(key: &str, alias: Option<&str>) -> Result<(), AccountSwitchError> {
+    set_account_alias_in(&LAUNCHER_DIR.join("config.json"), key, alias)
+}
+
+/// Picks the account to launch an instance with:
+///
+/// 1. The instance's default account
+///    (`default_account` in the instance config, a UUID),
+///    if it's still logged in
+/// 2. Otherwise, the launcher-wide `selected` account
+///    (`None` for offline mode)
+#[must_use]
+pub fn pick_launch_account<'a>(
+    accounts: impl IntoIterator<Item = &'a AccountData>,
+    instance_default: Option<&str>,
+    selected: Option<&'a AccountData>,
+) -> Option<&'a AccountData> {
+    let normalize = |uuid: &str| uuid.replace('-', "").to_lowercase();
+    instance_default
+        .map(normalize)
+        .and_then(|default| {
+            accounts
+                .into_iter()
+                .find(|account| normalize(&account.uuid) == default)
+        })
+        .or(selected)
+}
+
+fn read_config(path: &Path) -> Result<Value, JsonFileError> {
+    let config = std::fs::read_to_string(path).path(path)?;
+    Ok(serde_json::from_str(&config).json(config)?)
+}
+
+fn write_config(path: &Path, config: &Value) -> Result<(), JsonFileError> {
+    let config = serde_json::to_string(config).json_to()?;
+    std::fs::write(path, config).path(path)?;
+    Ok(())
+}
+
+fn list_accounts_in(config_path: &Path) -> Result<Vec<AccountSummary>, JsonFileError> {
+    Ok(summaries(&read_config(config_path)?))
+}
+
+fn summaries(config: &Value) -> Vec<AccountSummary> {
+    let selected = config.get("account_selected").and_then(Value::as_str);
+    let Some(accounts) = config.get("accounts").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    let mut list: Vec<AccountSummary> = accounts
+        .iter()
+        .map(|(key, account)| {
+            let account_type = account_type(key, account);
+            let field = |name: &str| account.get(name).and_then(Value::as_str);
+            AccountSummary {
+                nice_username: field("username_nice")
+                    .unwrap_or_else(|| account_type.strip_name(key))
+                    .to_owned(),
+                alias: field("alias").map(str::to_owned),
+                uuid: field("uuid").unwrap_or_default().to_owned(),
+                account_type,
+                is_selected: selected == Some(key.as_str()),
+                key: key.clone(),
+            }
+        })
+        .collect();
+    list.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+    list
+}
+
+fn keyring_username<'a>(key: &'a str, account_type: AccountType, account: &'a Value) -> &'a str {
+    account
+        .get("keyring_identifier")
+        .and_then(Value::as_str)
+        .unwrap_or_else(|| account_type.strip_name(key))
+}
+
+/// Same logic as the GUI's account loading: old configs
+/// only marked non-Microsoft accounts with a suffix.
+fn account_type(key: &str, account: &Value) -> AccountType {
+    if key.ends_with(" (elyby)") {
+        AccountType::ElyBy
+    } else if key.ends_with(" (littleskin)") {
+        AccountType::LittleSkin
+    } else {
+        account
+            .get("account_type")
+            .and_then(|n| serde_json::from_value(n.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+fn set_active_account_in(config_path: &Path, key: &str) -> Result<(), AccountSwitchError> {
+    // Edited as raw JSON so settings this crate
+    // doesn't know about are kept untouched
+    let mut config = read_config(config_path)?;
+
+    let exists = config
+        .get("accounts")
+        .and_then(Value::as_object)
+        .is_some_and(|n| n.contains_key(key));
+    let Some(config_map) = config.as_object_mut().filter(|_| exists) else {
+        return Err(AccountSwitchError::NotFound(key.to_owned()));
+    };
+    config_map.insert("account_selected".to_owned(), Value::String(key.to_owned()));
+
+    write_config(config_path, &config)?;
+    Ok(())
+}
+
+fn set_account_alias_in(
+    config_path: &Path,
+    key: &str,
+    alias: Option<&str>,
+) -> Result<(), AccountSwitchError> {
+    let mut config = read_config(config_path)?;
+    let Some(account) = config
+        .get_mut("accounts")
+        .and_then(|n| n.get_mut(key))
+        .and_then(Value::as_object_mut)
+    else {
+        return Err(AccountSwitchError::NotFound(key.to_owned()));
+    };
+    match alias.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(alias) => account.insert("alias".to_owned(), Value::String(alias.to_owned())),
+        None => account.remove("alias"),
+    };
+    write_config(config_path, &config)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(name: &str, uuid: &str) -> AccountData {
+        AccountData {
+            access_token: None,
+            uuid: uuid.to_owned(),
+            refresh_token: String::new(),
+            needs_refresh: true,
+            username: name.to_owned(),
+            nice_username: name.to_owned(),
+            alias: None,
+            account_type: AccountType::Microsoft,
+        }
+    }
+
+    #[test]
+    fn launch_uses_instance_default_account() {
+        let steve = account("Steve", "2553495fc9094d40a82646cfc92cd7a5");
+        let alex = account("Alex", "6ab43178-89fd-4905-a2b4-3e2a1b6e57b9");
+        let accounts = [&steve, &alex];
+
+        let picked = pick_launch_account(
+            accounts,
+            Some("6ab4317889fd4905a2b43e2a1b6e57b9"),
+            Some(&steve),
+        );
+        assert_eq!(picked.map(|n| n.username.as_str()), Some("Alex"));
+
+        // Works in offline mode too
+        let picked = pick_launch_account(accounts, Some(&steve.uuid), None);
+        assert_eq!(picked.map(|n| n.username.as_str()), Some("Steve"));
+
+        // Not set, or logged out: use the selected one
+        let picked = pick_launch_account(accounts, None, Some(&steve));
+        assert_eq!(picked.map(|n| n.username.as_str()), Some("Steve"));
+        let picked = pick_launch_account(accounts, Some("deadbeef"), None);
+        assert!(picked.is_none());
+    }
+
+    /// Serves a single HTTP request with `response`.
+    async fn mock_server(response: String) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/validate", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 4096];
+            _ = stream.read(&mut buf).await.unwrap();
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn invalid_token_needs_reauth() {
+        let body = r#"{"error":"ForbiddenOperationException","errorMessage":"Invalid token."}"#;
+        let response = format!(
+            "HTTP/1.1 403 Forbidden\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let refresh_status = |code| async move {
+            Err(yggdrasil::Error::Request(RequestError::DownloadError {
+                code,
+                url: "https://authserver.ely.by/auth/refresh".parse().unwrap(),
+            }))
+        };
+        let not_refreshed = || async { unreachable!("valid tokens aren't refreshed") };
+
+        // Revoked: refresh is rejected too
+        let url = mock_server(response.clone()).await;
+        assert_eq!(
+            check_yggdrasil(&url, "revoked", || refresh_status(StatusCode::FORBIDDEN)).await,
+            AccountStatus::NeedsReauth
+        );
+
+        // Expired, but refreshed
+        let url = mock_server(response.clone()).await;
+        let refreshed = || async {
+            Ok(yggdrasil::Account::Account(account(
+                "Alex",
+                "6ab4317889fd4905a2b43e2a1b6e57b9",
+            )))
+        };
+        assert_eq!(
+            check_yggdrasil(&url, "expired", refreshed).await,
+            AccountStatus::Valid
+        );
+
+        // Couldn't refresh for some other reason
+        let url = mock_server(response).await;
+        assert!(matches!(
+            check_yggdrasil(&url, "expired", || refresh_status(
+                StatusCode::SERVICE_UNAVAILABLE
+            ))
+            .await,
+            AccountStatus::Unreachable(_)
+        ));
+
+        let url =
+            mock_server("HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n".to_owned()).await;
+        assert_eq!(
+            check_yggdrasil(&url, "good", not_refreshed).await,
+            AccountStatus::Valid
+        );
+
+        let url = mock_server(
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_owned(),
+        )
+        .await;
+        assert!(matches!(
+            check_yggdrasil(&url, "good", not_refreshed).await,
+            AccountStatus::Unreachable(_)
+        ));
+    }
+
+    #[test]
+    fn rejected_refresh_needs_login() {
+        let status = |code| {
+            yggdrasil::Error::Request(RequestError::DownloadError {
+                code,
+                url: "https://authserver.ely.by/auth/refresh".parse().unwrap(),
+            })
+        };
+        assert!(is_rejected(&status(StatusCode::FORBIDDEN)));
+        assert!(is_rejected(&status(StatusCode::UNAUTHORIZED)));
+        // Server trouble, try again later
+        assert!(!is_rejected(&status(StatusCode::SERVICE_UNAVAILABLE)));
+        assert!(!is_rejected(&yggdrasil::Error::IncorrectPassword));
+    }
+
+    #[tokio::test]
+    async fn reauth_reports_accounts_needing_login() {
+        let summary = |key: &str, account_type| AccountSummary {
+            key: key.to_owned(),
+            nice_username: key.to_owned(),
+            alias: None,
+            uuid: String::new(),
+            account_type,
+            is_selected: false,
+        };
+        let accounts = vec![
+            (summary("Steve", AccountType::Microsoft), "Steve".to_owned()),
+            (
+                summary("Alex (elyby)", AccountType::ElyBy),
+                "Alex".to_owned(),
+            ),
+            (
+                summary("Herobrine (littleskin)", AccountType::LittleSkin),
+                "Herobrine".to_owned(),
+            ),
+        ];
+
+        let results = reauthenticate_each(accounts, |account_type, name| async move {
+            match name.as_str() {
+                "Steve" => Ok(Some(AccountData {
+                    account_type,
+                    ..account(&name, "1234")
+                })),
+                "Alex" => Ok(None),
+                _ => Err(ReauthError::Keyring(KeyringError(
+                    keyring::Error::NoStorageAccess("locked".into()),
+                ))),
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, "Steve");
+        assert!(results[0].1.as_ref().is_ok_and(|n| n.username == "Steve"));
+        assert!(matches!(
+            &results[1].1,
+            Err(ReauthError::NeedsLogin(n))
+                if n.key == "Alex (elyby)" && n.account_type == AccountType::ElyBy
+        ));
+        assert!(matches!(&results[2].1, Err(ReauthError::Keyring(_))));
+    }
+
+    #[test]
+    fn switch_accounts() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let config_path = dir.join("config.json");
+        std::fs::write(
+            &config_path,
+            r#"{
+                "accounts": {
+                    "Steve": { "uuid": "1234", "account_type": "Microsoft" },
+                    "alex@mail.com (elyby)": { "uuid": "5678", "username_nice": "Alex" }
+                },
+                "account_selected": "Steve",
+                "ui_scale": 1.5
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            set_active_account_in(&config_path, "Herobrine"),
+            Err(AccountSwitchError::NotFound(_))
+        ));
+
+        set_active_account_in(&config_path, "alex@mail.com (elyby)").unwrap();
+        let accounts = list_accounts_in(&config_path).unwrap();
+        assert_eq!(
+            accounts,
+            [
+                AccountSummary {
+                    key: "Steve".to_owned(),
+                    nice_username: "Steve".to_owned(),
+                    alias: None,
+                    uuid: "1234".to_owned(),
+                    account_type: AccountType::Microsoft,
+                    is_selected: false,
+                },
+                AccountSummary {
+                    key: "alex@mail.com (elyby)".to_owned(),
+                    nice_username: "Alex".to_owned(),
+                    alias: None,
+                    uuid: "5678".to_owned(),
+                    account_type: AccountType::ElyBy,
+                    is_selected: true,
+                },
+            ]
+        );
+
+        // Other settings are kept
+        let config = read_config(&config_path).unwrap();
+        assert_eq!(config["ui_scale"], 1.5);
+    }
+
+    #[test]
+    fn alias_is_only_for_display() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let config_path = dir.join("config.json");
+        std::fs::write(
+            &config_path,
+            r#"{"accounts": {
+                "alex@mail.com (elyby)": { "uuid": "5678", "username_nice": "Alex" }
+            }}"#,
+        )
+        .unwrap();
+
+        set_account_alias_in(&config_path, "alex@mail.com (elyby)", Some(" Main ")).unwrap();
+        let accounts = list_accounts_in(&config_path).unwrap();
+        assert_eq!(accounts[0].display_name(), "Main");
+        assert_eq!(accounts[0].nice_username, "Alex");
+
+        let config = read_config(&config_path).unwrap();
+        let account = &config["accounts"]["alex@mail.com (elyby)"];
+        assert_eq!(
+            keyring_username("alex@mail.com (elyby)", AccountType::ElyBy, account),
+            "alex@mail.com"
+        );
+
+        set_account_alias_in(&config_path, "alex@mail.com (elyby)", None).unwrap();
+        assert_eq!(
+            list_accounts_in(&config_path).unwrap()[0].display_name(),
+            "Alex"
+        );
+        assert!(matches!(
+            set_account_alias_in(&config_path, "Steve", Some("Alt")),
+            Err(AccountSwitchError::NotFound(_))
+        ));
+    }
+}
@@ -2,11 +2,19 @@ use ql_core::{IntoStringError, err};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
+mod accounts;
 mod alt;
 pub mod authlib;
 pub mod ms;
+mod skin;
 pub mod yggdrasil;
+pub use accounts::{
+    AccountStatus, AccountSummary, AccountSwitchError, ReauthError, list_accounts,
+    pick_launch_account, reauthenticate_all, set_account_alias, set_active_account,
+    validate_accounts,
+};
 pub use authlib::get_authlib_injector;
+pub use skin::{SkinUploadError, upload_skin};
 
 #[derive(Debug, Clone)]
 pub struct AccountData {
@@ -17,11 +25,21 @@ pub struct AccountData {
 
     pub username: String,
     pub nice_username: String,
+    /// A name the user gave this account (eg: "Main", "Alt")
+    /// to tell it apart from others, only for showing.
+    /// Logging in still uses [`Self::username`].
+    pub alias: Option<String>,
 
     pub account_type: AccountType,
 }
 
 impl AccountData {
+    /// The alias if set, otherwise the in-game username.
+    #[must_use]
+    pub fn display_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.nice_username)
+    }
+
     #[must_use]
     pub fn get_username_modified(&self) -> String {
         self.account_type.add_suffix_to_name(&self.nice_username)
@@ -37,7 +55,7 @@ impl AccountData {
     }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AccountType {
     ElyBy,
     LittleSkin,
@@ -77,6 +95,15 @@ impl AccountType {
         }
     }
 
+    #[must_use]
+    fn yggdrasil_validate(self) -> &'static str {
+        match self {
+            AccountType::Microsoft => unreachable!(),
+            AccountType::ElyBy => "https://authserver.ely.by/auth/validate",
+            AccountType::LittleSkin => "https://littleskin.cn/api/yggdrasil/authserver/validate",
+        }
+    }
+
     #[must_use]
     fn yggdrasil_needs_agent_field(self) -> bool {
         match self {
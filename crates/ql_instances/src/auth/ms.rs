@@ -65,7 +65,10 @@
 //! # Ok(()) }
 //! ```
 
-use ql_core::{CLIENT, GenericProgress, IntoJsonError, JsonError, RequestError, info, pt, retry};
+use ql_core::{
+    CLIENT, GenericProgress, IntoJsonError, JsonError, RequestError, info, pt,
+    request::check_for_success, retry,
+};
 use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 use serde_json::json;
@@ -162,6 +165,60 @@ struct MinecraftFinalDetails {
     name: String,
 }
 
+/// Known Xbox Live (XSTS) errors,
+/// returned as `XErr` codes when logging in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum XboxError {
+    #[error(
+        "This Microsoft account doesn't have an Xbox profile.\nLog in once at xbox.com to create one, then try again."
+    )]
+    NoXboxProfile,
+    #[error("This Microsoft account is banned from Xbox Live.")]
+    Banned,
+    #[error(
+        "This Microsoft account needs permission from a parent to play online.\nA parent can allow it in the Xbox Family settings."
+    )]
+    NeedsParentalPermission,
+    #[error(
+        "This Microsoft account hasn't accepted the Xbox Live terms yet.\nLog in once at xbox.com to accept them, then try again."
+    )]
+    TermsNotAccepted,
+    #[error("Xbox Live isn't available in this Microsoft account's country/region.")]
+    RegionUnavailable,
+    #[error(
+        "This Microsoft account needs adult verification.\nComplete it at xbox.com, then try again."
+    )]
+    NeedsAdultVerification,
+    #[error(
+        "This is a child account (under 18).\nAn adult needs to add it to a Microsoft Family before it can play."
+    )]
+    ChildAccount,
+    #[error("Xbox Live error (code {0})")]
+    Unknown(u64),
+}
+
+impl XboxError {
+    #[must_use]
+    pub fn from_code(code: u64) -> Self {
+        match code {
+            2_148_916_227 => Self::Banned,
+            2_148_916_229 => Self::NeedsParentalPermission,
+            2_148_916_233 => Self::NoXboxProfile,
+            2_148_916_234 => Self::TermsNotAccepted,
+            2_148_916_235 => Self::RegionUnavailable,
+            2_148_916_236 | 2_148_916_237 => Self::NeedsAdultVerification,
+            2_148_916_238 => Self::ChildAccount,
+            code => Self::Unknown(code),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct XboxErrorResponse {
+    #[serde(rename = "XErr")]
+    x_err: u64,
+}
+
 const AUTH_ERR_PREFIX: &str = "while managing Microsoft account:\n";
 
 #[derive(Debug, thiserror::Error)]
@@ -172,6 +229,8 @@ pub enum Error {
     Json(#[from] JsonError),
     #[error("{AUTH_ERR_PREFIX}Invalid account access token!")]
     InvalidAccessToken,
+    #[error("{AUTH_ERR_PREFIX}the saved login expired or was revoked, log in again")]
+    RefreshTokenRejected,
     #[error(
         "{AUTH_ERR_PREFIX}An unknown error has occurred (code: {0})\n\nThis is a major bug! Please report in discord."
     )]
@@ -184,6 +243,8 @@ pub enum Error {
     KeyringError(#[from] KeyringError),
     #[error("{AUTH_ERR_PREFIX}{0}")]
     Response(MsaResponseError),
+    #[error("{AUTH_ERR_PREFIX}{0}")]
+    Xbox(#[from] XboxError),
 
     #[error(
         "Your Microsoft account doesn't own Minecraft!\nJust enter the username in the text box instead of logging in."
@@ -220,24 +281,7 @@ pub async fn login_refresh(
 ) -> Result<AccountData, Error> {
     send_progress(sender.as_ref(), 0, 4, "Refreshing account token...");
 
-    let response: String = retry(|| async {
-        CLIENT
-            .post("https://login.live.com/oauth20_token.srf")
-            .form(&[
-                ("client_id", CLIENT_ID),
-                ("refresh_token", &refresh_token),
-                ("grant_type", "refresh_token"),
-                ("redirect_uri", "https://login.live.com/oauth20_desktop.srf"),
-                ("scope", "XboxLive.signin offline_access"),
-            ])
-            .send()
-            .await?
-            .text()
-            .await
-    })
-    .await?;
-
-    let data: RefreshResponse = serde_json::from_str(&response).json(response)?;
+    let data = refresh_ms_token(&refresh_token).await?;
 
     let entry = keyring::Entry::new("QuantumLauncher", &username)?;
     entry.set_password(&data.refresh_token)?;
@@ -255,6 +299,49 @@ pub async fn login_refresh(
     Ok(data)
 }
 
+/// Checks if a saved refresh token still works, by exchanging
+/// it for a new one (skipping the rest of the login).
+///
+/// Nothing is saved: the new token is thrown away,
+/// and the saved one keeps working.
+///
+/// Returns `false` if Microsoft rejected the token.
+pub(crate) async fn validate_token(refresh_token: &str) -> Result<bool, Error> {
+    match refresh_ms_token(refresh_token).await {
+        Ok(_) => Ok(true),
+        Err(Error::RefreshTokenRejected) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// Exchanges a refresh token for a new Microsoft token
+/// (and refresh token), the first step of [`login_refresh`].
+///
+/// Fails with [`Error::RefreshTokenRejected`] if Microsoft
+/// rejected the token (`invalid_grant`).
+async fn refresh_ms_token(refresh_token: &str) -> Result<RefreshResponse, Error> {
+    let response = retry(|| async {
+        CLIENT
+            .post("https://login.live.com/oauth20_token.srf")
+            .form(&[
+                ("client_id", CLIENT_ID),
+                ("refresh_token", refresh_token),
+                ("grant_type", "refresh_token"),
+                ("redirect_uri", "https://login.live.com/oauth20_desktop.srf"),
+                ("scope", "XboxLive.signin offline_access"),
+            ])
+            .send()
+            .await
+    })
+    .await?;
+    if response.status().is_client_error() {
+        return Err(Error::RefreshTokenRejected);
+    }
+    check_for_success(&response)?;
+    let text = response.text().await?;
+    Ok(serde_json::from_str(&text).json(text)?)
+}
+
 pub async fn login_1_link() -> Result<AuthCodeResponse, Error> {
     info!("Logging into Microsoft Account...");
 
@@ -316,6 +403,7 @@ pub async fn login_3_xbox(
 
         username: final_details.name.clone(),
         nice_username: final_details.name,
+        alias: None,
     };
 
     info!("Finished Microsoft Account login!");
@@ -401,8 +489,20 @@ async fn login_in_xbox_live(
         .text()
         .await?;
 
-    let xbox_res: XboxLiveAuthResponse = serde_json::from_str(&xbox_res).json(xbox_res)?;
-    Ok(xbox_res)
+    parse_xbox_response(xbox_res)
+}
+
+/// Parses an Xbox Live/XSTS auth response, turning
+/// error codes into [`XboxError`]. These can't be fixed
+/// by retrying, the user has to do something first.
+fn parse_xbox_response(text: String) -> Result<XboxLiveAuthResponse, Error> {
+    match serde_json::from_str(&text).json(text.clone()) {
+        Ok(n) => Ok(n),
+        Err(err) => match serde_json::from_str::<XboxErrorResponse>(&text) {
+            Ok(res) => Err(XboxError::from_code(res.x_err).into()),
+            Err(_) => Err(err.into()),
+        },
+    }
 }
 
 async fn login_in_minecraft(
@@ -440,8 +540,7 @@ async fn login_in_minecraft(
         .text()
         .await?;
 
-    let xbox_security_token_res: XboxLiveAuthResponse =
-        serde_json::from_str(&xbox_security_token_res).json(xbox_security_token_res)?;
+    let xbox_security_token_res = parse_xbox_response(xbox_security_token_res)?;
 
     let xbox_security_token = &xbox_security_token_res.token;
 
@@ -498,9 +597,7 @@ async fn check_minecraft_ownership(access_token: &str) -> Result<bool, Error> {
         items: Vec<serde_json::Value>,
     }
 
-    let client = Client::new();
-
-    let response = client
+    let response = CLIENT
         .get("https://api.minecraftservices.com/entitlements/mcstore")
         .bearer_auth(access_token)
         .send()
@@ -511,3 +608,51 @@ async fn check_minecraft_ownership(access_token: &str) -> Result<bool, Error> {
 
     Ok(!response.items.is_empty())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_xbox_error_codes() {
+        let cases = [
+            (2_148_916_227, XboxError::Banned, "banned"),
+            (2_148_916_229, XboxError::NeedsParentalPermission, "parent"),
+            (
+                2_148_916_233,
+                XboxError::NoXboxProfile,
+                "doesn't have an Xbox profile",
+            ),
+            (2_148_916_234, XboxError::TermsNotAccepted, "terms"),
+            (2_148_916_235, XboxError::RegionUnavailable, "country"),
+            (
+                2_148_916_236,
+                XboxError::NeedsAdultVerification,
+                "adult verification",
+            ),
+            (
+                2_148_916_237,
+                XboxError::NeedsAdultVerification,
+                "adult verification",
+            ),
+            (2_148_916_238, XboxError::ChildAccount, "child account"),
+            (1234, XboxError::Unknown(1234), "code 1234"),
+        ];
+        for (code, expected, message) in cases {
+            let error = XboxError::from_code(code);
+            assert_eq!(error, expected);
+            assert!(error.to_string().contains(message), "{error}");
+        }
+
+        let response = r#"{
+            "Identity": "0",
+            "XErr": 2148916233,
+            "Message": "",
+            "Redirect": "https://start.ui.xboxlive.com/CreateAccount"
+        }"#;
+        assert!(matches!(
+            parse_xbox_response(response.to_owned()),
+            Err(Error::Xbox(XboxError::NoXboxProfile))
+        ));
+    }
+}
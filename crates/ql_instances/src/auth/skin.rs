@@ -0,0 +1,189 @@
+use ql_core::{CLIENT, RequestError, info, request::check_for_success};
+use reqwest::multipart::{Form, Part};
+
+use super::{AccountData, AccountType};
+
+const SKIN_ERR_PREFIX: &str = "while uploading skin:\n";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SkinUploadError {
+    #[error("{SKIN_ERR_PREFIX}the file isn't a valid PNG image")]
+    NotPng,
+    #[error("{SKIN_ERR_PREFIX}skin must be 64x64 (or 64x32 for old skins), but it's {0}x{1}")]
+    InvalidSize(u32, u32),
+    #[error("{SKIN_ERR_PREFIX}account isn't logged in (no access token), try logging in again")]
+    NotLoggedIn,
+    #[error("{SKIN_ERR_PREFIX}{0} skins can't be changed from the launcher, use their website")]
+    Unsupported(AccountType),
+    #[error("{SKIN_ERR_PREFIX}{0}")]
+    Request(#[from] RequestError),
+}
+
+impl From<reqwest::Error> for SkinUploadError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Request(RequestError::ReqwestError(value))
+    }
+}
+
+/// Sets the skin of an account.
+///
+/// - `png`: The skin image, must be 64x64 (or 64x32 for old skins)
+/// - `slim`: Whether to use the slim (Alex) arm model
+///
+/// LittleSkin accounts use its
+/// [authlib-injector texture upload](https://github.com/yushijinhun/authlib-injector/wiki/Yggdrasil-%E6%9C%8D%E5%8A%A1%E7%AB%AF%E6%8A%80%E6%9C%AF%E8%A7%84%E8%8C%83#%E6%9D%90%E8%B4%A8%E4%B8%8A%E4%BC%A0)
+/// endpoint, Microsoft accounts use Mojang's API.
+/// Ely.by has no API for it.
+///
+/// # Errors
+/// - [`SkinUploadError::Unsupported`] for Ely.by accounts
+/// - The image isn't a PNG of the right size
+/// - The account has no access token (needs refresh)
+/// - The server rejected the upload (some servers don't allow it),
+///   or couldn't be reached
+pub async fn upload_skin(
+    account: &AccountData,
+    png: &[u8],
+    slim: bool,
+) -> Result<(), SkinUploadError> {
+    let url = skin_endpoint(account).ok_or(SkinUploadError::Unsupported(account.account_type))?;
+    let (width, height) = png_size(png).ok_or(SkinUploadError::NotPng)?;
+    if width != 64 || !(height == 64 || height == 32) {
+        return Err(SkinUploadError::InvalidSize(width, height));
+    }
+    let access_token = account
+        .access_token
+        .as_deref()
+        .ok_or(SkinUploadError::NotLoggedIn)?;
+
+    info!(
+        "Uploading skin for {} ({})",
+        account.nice_username, account.account_type
+    );
+
+    let request = if account.is_microsoft() {
+        CLIENT.post(url)
+    } else {
+        CLIENT.put(url)
+    };
+    let (model_field, model) = model_field(account.account_type, slim);
+    let file = Part::bytes(png.to_vec())
+        .file_name("skin.png")
+        .mime_str("image/png")?;
+    let form = Form::new().text(model_field, model).part("file", file);
+
+    let response = request
+        .bearer_auth(access_token)
+        .multipart(form)
+        .send()
+        .await?;
+    check_for_success(&response)?;
+
+    info!("Uploaded skin");
+    Ok(())
+}
+
+/// Where to upload the skin, or `None` if
+/// the account's server doesn't allow it.
+fn skin_endpoint(account: &AccountData) -> Option<String> {
+    // authlib-injector wants the UUID without dashes
+    let uuid = account.uuid.replace('-', "");
+    match account.account_type {
+        AccountType::Microsoft => {
+            Some("https://api.minecraftservices.com/minecraft/profile/skins".to_owned())
+        }
+        AccountType::ElyBy => None,
+        AccountType::LittleSkin => Some(format!(
+            "https://littleskin.cn/api/yggdrasil/api/user/profile/{uuid}/skin"
+        )),
+    }
+}
+
+/// The form field for the arm model, and its value.
+fn model_field(account_type: AccountType, slim: bool) -> (&'static str, &'static str) {
+    match (account_type, slim) {
+        (AccountType::Microsoft, true) => ("variant", "slim"),
+        (AccountType::Microsoft, false) => ("variant", "classic"),
+        (AccountType::ElyBy | AccountType::LittleSkin, true) => ("model", "slim"),
+        (AccountType::ElyBy | AccountType::LittleSkin, false) => ("model", ""),
+    }
+}
+
+/// Reads the width and height from a PNG's header.
+fn png_size(png: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    // Signature, then the IHDR chunk (length, type, width, height)
+    if png.len() < 24 || !png.starts_with(SIGNATURE) || &png[12..16] != b"IHDR" {
+        return None;
+    }
+    let read = |i: usize| u32::from_be_bytes([png[i], png[i + 1], png[i + 2], png[i + 3]]);
+    Some((read(16), read(20)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&width.to_be_bytes());
+        png.extend_from_slice(&height.to_be_bytes());
+        png
+    }
+
+    fn account(account_type: AccountType) -> AccountData {
+        AccountData {
+            access_token: Some("token".to_owned()),
+            uuid: "6ab43178-89fd-4905-a2b4-3e2a1b6e57b9".to_owned(),
+            refresh_token: String::new(),
+            needs_refresh: false,
+            username: "Alex".to_owned(),
+            nice_username: "Alex".to_owned(),
+            alias: None,
+            account_type,
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_size() {
+        assert_eq!(png_size(&png_header(64, 32)), Some((64, 32)));
+        assert_eq!(png_size(b"GIF89a not a png at all"), None);
+
+        let littleskin = account(AccountType::LittleSkin);
+        for (width, height) in [(128, 128), (64, 48), (32, 64)] {
+            let result = upload_skin(&littleskin, &png_header(width, height), false).await;
+            assert!(
+                matches!(result, Err(SkinUploadError::InvalidSize(w, h)) if (w, h) == (width, height)),
+                "{result:?}"
+            );
+        }
+        let result = upload_skin(&littleskin, b"not a png", false).await;
+        assert!(matches!(result, Err(SkinUploadError::NotPng)));
+
+        let result = upload_skin(&account(AccountType::ElyBy), &png_header(64, 64), false).await;
+        assert!(matches!(
+            result,
+            Err(SkinUploadError::Unsupported(AccountType::ElyBy))
+        ));
+    }
+
+    #[test]
+    fn endpoint_per_account_type() {
+        assert_eq!(
+            skin_endpoint(&account(AccountType::Microsoft)).as_deref(),
+            Some("https://api.minecraftservices.com/minecraft/profile/skins")
+        );
+        assert_eq!(skin_endpoint(&account(AccountType::ElyBy)), None);
+        assert_eq!(
+            skin_endpoint(&account(AccountType::LittleSkin)).as_deref(),
+            Some(
+                "https://littleskin.cn/api/yggdrasil/api/user/profile/6ab4317889fd4905a2b43e2a1b6e57b9/skin"
+            )
+        );
+        assert_eq!(
+            model_field(AccountType::Microsoft, true),
+            ("variant", "slim")
+        );
+        assert_eq!(model_field(AccountType::LittleSkin, false), ("model", ""));
+    }
+}
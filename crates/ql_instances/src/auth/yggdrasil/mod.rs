@@ -1,16 +1,23 @@
+use std::time::Duration;
+
 use crate::auth::alt::AccountResponse;
 
 use super::{AccountData, AccountType};
-use ql_core::{CLIENT, IntoJsonError, info, pt};
+use ql_core::{CLIENT, IntoJsonError, info, pt, retry_with_backoff, should_retry_response};
 
 pub use super::alt::{Account, AccountResponseError, Error};
-use ql_core::request::check_for_success;
+use ql_core::{RequestError, request::check_for_success};
 use serde::Serialize;
 
 pub mod oauth;
 
 const CLIENT_ID: &str = "1160";
 
+/// For connection errors (the auth servers can be flaky),
+/// rejected logins aren't retried.
+const RETRIES: usize = 3;
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
 #[derive(Serialize)]
 struct Agent {
     name: &'static str,
@@ -34,29 +41,19 @@ pub async fn login_new(
     });
     insert_agent_field(account_type, &mut value);
 
-    let response = CLIENT
-        .post(account_type.yggdrasil_authenticate())
-        .json(&value)
-        .send()
-        .await?;
+    let url = account_type.yggdrasil_authenticate();
+    let response = retry_with_backoff(RETRIES, RETRY_DELAY, should_retry_response, || {
+        CLIENT.post(url).json(&value).send()
+    })
+    .await?;
     if response.status().as_u16() == 401 {
         return Err(Error::IncorrectPassword);
     }
     check_for_success(&response)?;
     let text = response.text().await?;
 
-    let account_response = match serde_json::from_str::<AccountResponse>(&text).json(text.clone()) {
-        Ok(n) => n,
-        Err(err) => {
-            if let Ok(res_err) = serde_json::from_str::<AccountResponseError>(&text).json(text) {
-                if res_err.error == "ForbiddenOperationException"
-                    && res_err.errorMessage == "Account protected with two factor auth."
-                {
-                    return Ok(Account::NeedsOTP);
-                }
-            }
-            return Err(err.into());
-        }
+    let Some(account_response) = parse_response(&text)? else {
+        return Ok(Account::NeedsOTP);
     };
 
     let entry = account_type.get_keyring_entry(&email_or_username)?;
@@ -68,6 +65,7 @@ pub async fn login_new(
 
         username: email_or_username,
         nice_username: account_response.selectedProfile.name,
+        alias: None,
 
         refresh_token: account_response.accessToken,
         needs_refresh: false,
@@ -87,7 +85,7 @@ pub async fn login_refresh(
     email_or_username: String,
     refresh_token: String,
     account_type: AccountType,
-) -> Result<AccountData, Error> {
+) -> Result<Account, Error> {
     pt!("Refreshing {account_type} account...");
     let entry = account_type.get_keyring_entry(&email_or_username)?;
 
@@ -96,26 +94,120 @@ pub async fn login_refresh(
         "clientToken": account_type.get_client_id()
     });
     insert_agent_field(account_type, &mut value);
-    let response = CLIENT
-        .post(account_type.yggdrasil_refresh())
-        .json(&value)
-        .send()
-        .await?;
-    check_for_success(&response)?;
+    let url = account_type.yggdrasil_refresh();
+    let response = retry_with_backoff(RETRIES, RETRY_DELAY, should_retry_response, || {
+        CLIENT.post(url).json(&value).send()
+    })
+    .await?;
+    let status = check_for_success(&response);
     let text = response.text().await?;
 
-    let account_response = serde_json::from_str::<AccountResponse>(&text).json(text.clone())?;
+    let Some(account_response) = parse_refresh_response(status, &text)? else {
+        return Ok(Account::NeedsOTP);
+    };
     entry.set_password(&account_response.accessToken)?;
 
-    Ok(AccountData {
+    Ok(Account::Account(AccountData {
         access_token: Some(account_response.accessToken.clone()),
         uuid: account_response.selectedProfile.id,
 
         username: email_or_username,
         nice_username: account_response.selectedProfile.name,
+        alias: None,
 
         refresh_token: account_response.accessToken,
         needs_refresh: false,
         account_type,
+    }))
+}
+
+/// Checks if a saved token still works (at the
+/// `validate` endpoint `url`), without refreshing it.
+///
+/// Returns `false` if the server rejected the token.
+pub(crate) async fn validate_token(url: &str, access_token: &str) -> Result<bool, Error> {
+    let body = serde_json::json!({ "accessToken": access_token });
+    let response = retry_with_backoff(RETRIES, RETRY_DELAY, should_retry_response, || {
+        CLIENT.post(url).json(&body).send()
+    })
+    .await?;
+    if response.status().is_client_error() {
+        return Ok(false);
+    }
+    check_for_success(&response)?;
+    Ok(true)
+}
+
+/// Parses an authenticate/refresh response,
+/// returning `None` if the account needs a 2FA code.
+fn parse_response(text: &str) -> Result<Option<AccountResponse>, Error> {
+    match serde_json::from_str::<AccountResponse>(text).json(text.to_owned()) {
+        Ok(n) => Ok(Some(n)),
+        Err(_) if needs_otp(text) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Like [`parse_response`], but the 2FA error can also
+/// come with an error status code (when the saved session
+/// was revoked after 2FA was enabled on the account).
+fn parse_refresh_response(
+    status: Result<(), RequestError>,
+    text: &str,
+) -> Result<Option<AccountResponse>, Error> {
+    match status {
+        Ok(()) => parse_response(text),
+        Err(_) if needs_otp(text) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn needs_otp(text: &str) -> bool {
+    serde_json::from_str::<AccountResponseError>(text).is_ok_and(|n| {
+        n.error == "ForbiddenOperationException"
+            && n.errorMessage == "Account protected with two factor auth."
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OTP_RESPONSE: &str = r#"{
+        "error": "ForbiddenOperationException",
+        "errorMessage": "Account protected with two factor auth."
+    }"#;
+
+    fn status_401() -> Result<(), RequestError> {
+        Err(RequestError::DownloadError {
+            code: reqwest::StatusCode::UNAUTHORIZED,
+            url: "https://authserver.ely.by/auth/refresh".parse().unwrap(),
+        })
+    }
+
+    #[test]
+    fn refresh_needs_otp() {
+        assert!(matches!(
+            parse_refresh_response(Ok(()), OTP_RESPONSE),
+            Ok(None)
+        ));
+        assert!(matches!(
+            parse_refresh_response(status_401(), OTP_RESPONSE),
+            Ok(None)
+        ));
+
+        // Other errors are still errors
+        let expired = r#"{
+            "error": "ForbiddenOperationException",
+            "errorMessage": "Token expired."
+        }"#;
+        assert!(parse_refresh_response(status_401(), expired).is_err());
+
+        let ok = r#"{
+            "accessToken": "abcd",
+            "selectedProfile": { "id": "1234", "name": "Steve" }
+        }"#;
+        let response = parse_refresh_response(Ok(()), ok).unwrap().unwrap();
+        assert_eq!(response.selectedProfile.name, "Steve");
+    }
+}
@@ -87,14 +87,7 @@ pub async fn poll_device_token(
     // Step C: exchange OAuth token for a Yggdrasil/Minecraft token (needed for actual game login)
     // Sub step get UUID
     let profile = get_minecraft_profile(&oauth_access_token).await?;
-    let uuid = profile.id;
-    let mut mc_token_resp = create_minecraft_token(&oauth_access_token, &uuid).await?;
-    // If server didn't include selectedProfile, fetch via sessionserver
-    if mc_token_resp.selected_profile.is_none() {
-        if let Ok(profile) = get_minecraft_profile(&oauth_access_token).await {
-            mc_token_resp.selected_profile = Some(profile);
-        }
-    }
+    let mc_token_resp = create_minecraft_token(&oauth_access_token, &profile.id).await?;
 
     // Store Minecraft token in keyring (same convention as password flow)
     keyring::Entry::new(
@@ -103,23 +96,36 @@ pub async fn poll_device_token(
     )
     .and_then(|e| e.set_password(&mc_token_resp.access_token))?;
 
-    // Build account data compatible with existing flows
-    Ok(super::Account::Account(super::AccountData {
+    Ok(super::Account::Account(build_account(
+        user_info,
+        mc_token_resp,
+        profile,
+    )))
+}
+
+/// Builds account data compatible with existing flows.
+///
+/// The UUID always comes from the Minecraft profile
+/// (the token's `selectedProfile`, or else `profile` from the
+/// sessionserver). BlessingSkin's `uid` is just the site's
+/// user number, not a valid Minecraft UUID, so using it
+/// would break skins and per-player world data.
+fn build_account(
+    user_info: UserInfo,
+    mc_token_resp: MinecraftTokenResponse,
+    profile: MinecraftProfile,
+) -> super::AccountData {
+    let profile = mc_token_resp.selected_profile.unwrap_or(profile);
+    super::AccountData {
         access_token: Some(mc_token_resp.access_token.clone()),
-        uuid: mc_token_resp
-            .selected_profile
-            .as_ref()
-            .map(|p| p.id.clone())
-            .unwrap_or_default(),
-        username: user_info.username.clone(),
-        nice_username: mc_token_resp
-            .selected_profile
-            .as_ref()
-            .map_or_else(|| user_info.username.clone(), |p| p.name.clone()),
+        uuid: profile.id,
+        username: user_info.username,
+        nice_username: profile.name,
+        alias: None,
         refresh_token: mc_token_resp.access_token,
         needs_refresh: false,
         account_type: crate::auth::AccountType::LittleSkin,
-    }))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -233,3 +239,27 @@ async fn get_device_token(
         sleep(Duration::from_secs(interval)).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_minecraft_uuid() {
+        let user_info: UserInfo =
+            serde_json::from_str(r#"{"uid": 12345, "nickname": "steve_site", "email": null}"#)
+                .unwrap();
+        let mut profiles: Vec<MinecraftProfile> = serde_json::from_str(
+            r#"[{"id": "6ab4317889fd4905a2b43e2a1b6e57b9", "name": "Steve", "properties": []}]"#,
+        )
+        .unwrap();
+        // No `selectedProfile` in the token response
+        let token: MinecraftTokenResponse =
+            serde_json::from_str(r#"{"accessToken": "abcd"}"#).unwrap();
+
+        let account = build_account(user_info, token, profiles.pop().unwrap());
+        assert_eq!(account.uuid, "6ab4317889fd4905a2b43e2a1b6e57b9");
+        assert_eq!(account.nice_username, "Steve");
+        assert_eq!(account.username, "steve_site");
+    }
+}
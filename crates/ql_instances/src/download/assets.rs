@@ -0,0 +1,95 @@
+//! Adding assets (sounds, languages, ...) to instances
+//! that were created without them
+//! (see `download_assets` in [`crate::create_instance`]).
+
+use std::{path::Path, sync::mpsc::Sender};
+
+use ql_core::{
+    DownloadProgress, Instance, LAUNCHER_DIR, file_utils::exists, info, json::VersionDetails,
+};
+
+use super::{DownloadError, GameDownloader};
+
+/// Whether the assets (sounds, languages, ...) an instance
+/// needs are downloaded. If not, the game runs silent.
+///
+/// Assets are shared between instances, so an instance created
+/// without them may still have them (if another instance of a
+/// similar version downloaded them).
+///
+/// Servers don't use assets, so this is always `true` for them.
+/// Also `true` if `details.json` can't be read (broken instance,
+/// nothing that downloading assets would fix).
+pub async fn has_assets(instance: &Instance) -> bool {
+    if instance.is_server() {
+        return true;
+    }
+    let instance_dir = instance.get_instance_path();
+    let Ok(version_json) = VersionDetails::load_from_path(&instance_dir).await else {
+        return true;
+    };
+    has_assets_in(&LAUNCHER_DIR, &instance_dir, &version_json.assetIndex.id).await
+}
+
+async fn has_assets_in(launcher_dir: &Path, instance_dir: &Path, index_id: &str) -> bool {
+    let index = Path::new("indexes").join(format!("{index_id}.json"));
+    // The asset index is saved after all the assets are downloaded
+    exists(launcher_dir.join("assets/dir").join(&index)).await
+        || exists(instance_dir.join("private_assets").join(&index)).await
+        // Old layouts, migrated when launching
+        || exists(launcher_dir.join("assets").join(index_id)).await
+        || exists(instance_dir.join("assets")).await
+}
+
+/// Downloads the assets (sounds, languages, ...) of an instance
+/// created without them (see [`has_assets`]).
+///
+/// Does nothing if it already has them, or is a server.
+///
+/// # Errors
+/// - `details.json` couldn't be read
+/// - The assets couldn't be downloaded
+pub async fn download_missing_assets(
+    instance: &Instance,
+    sender: Option<Sender<DownloadProgress>>,
+) -> Result<(), DownloadError> {
+    if has_assets(instance).await {
+        return Ok(());
+    }
+    info!("Adding assets to instance: {}", instance.get_name());
+    let instance_dir = instance.get_instance_path();
+    let version_json = VersionDetails::load_from_path(&instance_dir).await?;
+    GameDownloader::with_existing_instance(version_json, instance_dir, sender)
+        .download_assets()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn detects_missing_assets() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let instance_dir = dir.join("instances/Silent");
+        tokio::fs::create_dir_all(&instance_dir).await.unwrap();
+        // Created without assets, but another version's are there
+        let other_index = dir.join("assets/dir/indexes/16.json");
+        tokio::fs::create_dir_all(other_index.parent().unwrap())
+            .await
+            .unwrap();
+        tokio::fs::write(&other_index, "{}").await.unwrap();
+        tokio::fs::create_dir_all(dir.join("assets/null"))
+            .await
+            .unwrap();
+
+        assert!(!has_assets_in(dir, &instance_dir, "17").await);
+
+        // Same as what `GameDownloader::download_assets` writes last
+        tokio::fs::write(dir.join("assets/dir/indexes/17.json"), "{}")
+            .await
+            .unwrap();
+        assert!(has_assets_in(dir, &instance_dir, "17").await);
+    }
+}
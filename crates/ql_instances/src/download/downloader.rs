@@ -6,13 +6,11 @@ use std::{
 
 use crate::json_profiles::ProfileJson;
 use ql_core::{
-    DownloadFileError, DownloadProgress, IntoIoError, IntoJsonError, IoError, JsonError, ListEntry,
-    RequestError, do_jobs, download,
+    CancellationToken, Cancelled, DownloadProgress, IntoIoError, IntoJsonError, IoError, JsonError,
+    ListEntry, NameError, RequestError, do_jobs, download,
     file_utils::{self, LAUNCHER_DIR, exists},
     impl_3_errs_jri, info,
-    json::{
-        AssetIndex, InstanceConfigJson, Manifest, VersionDetails, instance_config::VersionInfo,
-    },
+    json::{AssetIndex, Manifest, VersionDetails},
     pt,
 };
 use thiserror::Error;
@@ -29,10 +27,8 @@ pub enum DownloadError {
     #[error("{DOWNLOAD_ERR_PREFIX}{0}")]
     Io(#[from] IoError),
 
-    #[error("instance name is invalid (empty/disallowed characters)")]
-    InvalidName,
-    #[error("an instance with that name already exists: {0}")]
-    InstanceAlreadyExists(String),
+    #[error("{0}")]
+    InvalidName(#[from] NameError),
     #[error("{DOWNLOAD_ERR_PREFIX}version not found in manifest.json: {0}")]
     VersionNotFoundInManifest(String),
     #[error("{DOWNLOAD_ERR_PREFIX}in assets JSON, field not found: \"{0}\"")]
@@ -40,9 +36,11 @@ pub enum DownloadError {
     #[error("{DOWNLOAD_ERR_PREFIX}could not extract native libraries:\n{0}")]
     NativesExtractError(zip::result::ZipError),
     #[error(
-        "{DOWNLOAD_ERR_PREFIX}tried to remove natives outside folder. POTENTIAL SECURITY RISK AVOIDED"
+        "{DOWNLOAD_ERR_PREFIX}library {name} is corrupted (SHA1/size doesn't match version JSON)\nUrl: {url}"
     )]
-    NativesOutsideDirRemove,
+    LibraryChecksumMismatch { name: String, url: String },
+    #[error("{0}")]
+    Cancelled(#[from] Cancelled),
 }
 
 impl_3_errs_jri!(DownloadError, Json, Request, Io);
@@ -59,6 +57,8 @@ pub(crate) struct GameDownloader {
     pub instance_dir: PathBuf,
     pub version_json: VersionDetails,
     sender: Option<Sender<DownloadProgress>>,
+    /// Checked between files, see [`crate::create_instance`].
+    pub cancel: CancellationToken,
     pub(crate) already_downloaded_natives: Mutex<HashSet<String>>,
 }
 
@@ -78,9 +78,7 @@ impl GameDownloader {
         sender: Option<Sender<DownloadProgress>>,
     ) -> Result<GameDownloader, DownloadError> {
         let Some(instance_dir) = GameDownloader::new_get_instance_dir(instance_name).await? else {
-            return Err(DownloadError::InstanceAlreadyExists(
-                instance_name.to_owned(),
-            ));
+            return Err(NameError::AlreadyExists(instance_name.to_owned()).into());
         };
         let version_json =
             match GameDownloader::new_download_version_json(version, sender.as_ref()).await {
@@ -98,6 +96,7 @@ impl GameDownloader {
             instance_dir,
             version_json,
             sender,
+            cancel: CancellationToken::default(),
             already_downloaded_natives: already_downloaded_natives(),
         })
     }
@@ -112,13 +111,20 @@ impl GameDownloader {
             instance_dir,
             version_json,
             sender,
+            cancel: CancellationToken::default(),
             already_downloaded_natives: already_downloaded_natives(),
         }
     }
 
     pub async fn download_jar(&self) -> Result<(), DownloadError> {
         info!("Downloading game jar file.");
-        self.send_progress(DownloadProgress::DownloadingJar, false);
+        self.send_progress(
+            DownloadProgress::DownloadingJar {
+                progress: 0,
+                out_of: 0,
+            },
+            false,
+        );
 
         let version_dir = self
             .instance_dir
@@ -131,9 +137,22 @@ impl GameDownloader {
 
         let jar_path = version_dir.join(format!("{}.jar", self.version_json.get_id()));
 
-        download(&self.version_json.downloads.client.url)
-            .path(&jar_path)
-            .await?;
+        let on_progress = |progress: u64, out_of: Option<u64>| {
+            self.send_progress(
+                DownloadProgress::DownloadingJar {
+                    progress: progress as usize,
+                    out_of: out_of.unwrap_or_default() as usize,
+                },
+                false,
+            );
+        };
+        file_utils::download_file_to_path_with(
+            &self.version_json.downloads.client.url,
+            &jar_path,
+            false,
+            &on_progress,
+        )
+        .await?;
 
         Ok(())
     }
@@ -169,9 +188,6 @@ impl GameDownloader {
             .await
             .path(&current_assets_dir)?;
 
-        self.save_asset_index(&asset_index, &current_assets_dir)
-            .await?;
-
         let assets_objects_path = &current_assets_dir.join("objects");
         tokio::fs::create_dir_all(&assets_objects_path)
             .await
@@ -182,6 +198,7 @@ impl GameDownloader {
         let progress_num = &Mutex::new(0);
 
         let results = asset_index.objects.values().map(|asset| async move {
+            self.cancel.check()?;
             asset.download(assets_objects_path).await?;
 
             let mut progress = progress_num.lock().await;
@@ -197,10 +214,15 @@ impl GameDownloader {
 
             bar.inc(1);
 
-            Ok::<(), DownloadFileError>(())
+            Ok::<(), DownloadError>(())
         });
 
         _ = do_jobs(results).await?;
+
+        // Saved last, so an interrupted download
+        // isn't mistaken for a complete one (see `crate::has_assets`)
+        self.save_asset_index(&asset_index, &current_assets_dir)
+            .await?;
         Ok(())
     }
 
@@ -311,22 +333,15 @@ impl GameDownloader {
     }
 
     pub async fn create_config_json(&self) -> Result<(), DownloadError> {
-        let config_json = InstanceConfigJson::new(
-            ql_core::InstanceKind::Client,
-            false,
-            VersionInfo::new(&self.version_json.id),
-        );
-        let config_json = serde_json::to_string(&config_json).json_to()?;
-
-        let config_json_path = self.instance_dir.join("config.json");
-        tokio::fs::write(&config_json_path, config_json)
-            .await
-            .path(config_json_path)?;
-
-        Ok(())
+        super::template::write_initial_config(
+            &self.instance_dir,
+            &self.version_json.id,
+            &LAUNCHER_DIR.join("config.json"),
+        )
+        .await
     }
 
-    async fn new_download_version_json(
+    pub(crate) async fn new_download_version_json(
         version: &ListEntry,
         sender: Option<&Sender<DownloadProgress>>,
     ) -> Result<VersionDetails, DownloadError> {
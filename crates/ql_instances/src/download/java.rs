@@ -0,0 +1,197 @@
+//! Installing the Java that a new instance needs
+//! while it's being downloaded, so new users don't have
+//! to wait for it separately on first launch.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+    mpsc::Sender,
+};
+
+use ql_core::{CancellationToken, DownloadProgress, GenericProgress, JavaVersion, err, info};
+use ql_java_handler::{install_java, is_java_installed};
+
+use super::{DownloadError, GameDownloader, download_instance};
+
+/// Downloads the instance, along with the Java it needs
+/// (from the version JSON's `javaVersion`) if that isn't installed yet.
+///
+/// `sender` gets the game's progress, followed by
+/// [`DownloadProgress::InstallingJava`] if Java is still going.
+pub(super) async fn download_with_java(
+    game_downloader: &mut GameDownloader,
+    instance_name: &str,
+    download_assets: bool,
+    sender: Option<Sender<DownloadProgress>>,
+) -> Result<(), DownloadError> {
+    let version = game_downloader
+        .version_json
+        .javaVersion
+        .clone()
+        .map_or(JavaVersion::Java8, Into::into);
+    if is_java_installed(version).await {
+        return download_instance(game_downloader, instance_name, download_assets).await;
+    }
+    info!("Installing {version} alongside the instance");
+
+    let cancel = game_downloader.cancel.clone();
+    let java_cancel = &cancel;
+    with_java(
+        download_instance(game_downloader, instance_name, download_assets),
+        |java_sender| async move {
+            install_java(version, java_sender.as_ref(), Some(java_cancel)).await
+        },
+        sender,
+        &cancel,
+    )
+    .await
+}
+
+/// Runs the download and the Java install (from `install`,
+/// given where to send its progress) side by side.
+///
+/// If the download fails, `cancel` is triggered so the
+/// Java install stops early instead of being waited on.
+/// Java failing doesn't fail the instance,
+/// it's installed again when launching anyway.
+async fn with_java<E: std::fmt::Display, J: Future<Output = Result<(), E>>>(
+    download: impl Future<Output = Result<(), DownloadError>>,
+    install: impl FnOnce(Option<Sender<GenericProgress>>) -> J,
+    sender: Option<Sender<DownloadProgress>>,
+    cancel: &CancellationToken,
+) -> Result<(), DownloadError> {
+    let game_done = Arc::new(AtomicBool::new(false));
+    let java_sender = sender.map(|n| forward_java_progress(n, game_done.clone()));
+
+    let download = async {
+        let result = download.await;
+        game_done.store(true, Ordering::Relaxed);
+        if result.is_err() {
+            cancel.cancel();
+        }
+        result
+    };
+    let (download, java) = tokio::join!(download, install(java_sender));
+    if let Err(error) = java {
+        err!("Couldn't install Java (will be retried on launch): {error}");
+    }
+    download
+}
+
+/// Until the game's downloaded its progress is shown,
+/// so Java's is only forwarded after that.
+fn forward_java_progress(
+    sender: Sender<DownloadProgress>,
+    game_done: Arc<AtomicBool>,
+) -> Sender<GenericProgress> {
+    let (send, recv) = std::sync::mpsc::channel::<GenericProgress>();
+    std::thread::spawn(move || {
+        for progress in recv {
+            if game_done.load(Ordering::Relaxed) && !progress.has_finished {
+                _ = sender.send(DownloadProgress::InstallingJava {
+                    progress: progress.done,
+                    out_of: progress.total,
+                });
+            }
+        }
+    });
+    send
+}
+
+#[cfg(test)]
+mod tests {
+    use ql_core::Cancelled;
+
+    use super::*;
+
+    /// A Java install that keeps going (checking `cancel`)
+    /// until `until` is set, then reports some progress.
+    async fn fake_java(
+        sender: Option<Sender<GenericProgress>>,
+        cancel: &CancellationToken,
+        until: &AtomicBool,
+    ) -> Result<(), Cancelled> {
+        while !until.load(Ordering::Relaxed) {
+            cancel.check()?;
+            tokio::task::yield_now().await;
+        }
+        if let Some(sender) = sender {
+            _ = sender.send(GenericProgress {
+                done: 1,
+                total: 2,
+                message: None,
+                has_finished: false,
+            });
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn java_progress_follows_game() {
+        let cancel = CancellationToken::new();
+        let game_done = AtomicBool::new(false);
+        let (send, recv) = std::sync::mpsc::channel();
+
+        let result = with_java(
+            async {
+                tokio::task::yield_now().await;
+                game_done.store(true, Ordering::Relaxed);
+                Ok(())
+            },
+            |java_sender| fake_java(java_sender, &cancel, &game_done),
+            Some(send),
+            &cancel,
+        )
+        .await;
+        assert!(result.is_ok());
+        assert!(!cancel.is_cancelled());
+
+        // Ends once the forwarding thread is done
+        let progress: Vec<_> = recv.iter().collect();
+        assert!(matches!(
+            progress.as_slice(),
+            [DownloadProgress::InstallingJava {
+                progress: 1,
+                out_of: 2
+            }]
+        ));
+    }
+
+    #[tokio::test]
+    async fn failed_download_stops_java() {
+        let cancel = CancellationToken::new();
+        let never = AtomicBool::new(false);
+        let java_stopped = AtomicBool::new(false);
+
+        let (java_cancel, never, stopped) = (&cancel, &never, &java_stopped);
+        let result = with_java(
+            async { Err(DownloadError::VersionNotFoundInManifest("b9.9".to_owned())) },
+            |java_sender| async move {
+                let result = fake_java(java_sender, java_cancel, never).await;
+                stopped.store(result.is_err(), Ordering::Relaxed);
+                result
+            },
+            None,
+            &cancel,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(DownloadError::VersionNotFoundInManifest(_))
+        ));
+        assert!(java_stopped.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn failed_java_keeps_instance() {
+        let cancel = CancellationToken::new();
+        let result = with_java(
+            async { Ok(()) },
+            |_| async { Err("no internet") },
+            None,
+            &cancel,
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+}
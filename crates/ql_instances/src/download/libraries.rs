@@ -26,9 +26,90 @@ use super::{DownloadError, GameDownloader};
 const MACOS_X64_LWJGL_294: &str = "https://libraries.minecraft.net/org/lwjgl/lwjgl/lwjgl-platform/2.9.4-nightly-20150209/lwjgl-platform-2.9.4-nightly-20150209-natives-osx.jar";
 const MACOS_ARM_LWJGL_294: &str = "https://github.com/Dungeons-Guide/lwjgl/releases/download/2.9.4-20150209-mmachina.2-syeyoung.1/lwjgl-platform-2.9.4-nightly-20150209-natives-osx-arm64.jar";
 
+/// How a [`NativeSubstitution`] matches a natives URL.
+enum NativeMatch {
+    /// The whole URL must be equal.
+    Exact(&'static str),
+    /// Only the end of the URL (file name) must be equal,
+    /// and only that part is replaced.
+    Suffix(&'static str),
+}
+
+/// An alternative natives jar to use on a platform
+/// that Mojang doesn't provide natives for (mostly ARM).
+struct NativeSubstitution {
+    /// [`OS_NAME`] this applies to (see [`native_os`]), or `"*"` for any.
+    os: &'static str,
+    /// [`ARCH`] this applies to, or `"*"` for any.
+    arch: &'static str,
+    from: NativeMatch,
+    to: &'static str,
+}
+
+const NATIVE_SUBSTITUTIONS: &[NativeSubstitution] = &[
+    NativeSubstitution {
+        os: "linux",
+        arch: "arm64",
+        from: NativeMatch::Exact(
+            "https://github.com/theofficialgman/lwjgl3-binaries-arm64/raw/lwjgl-3.1.6/lwjgl-jemalloc-natives-linux.jar",
+        ),
+        to: "https://github.com/theofficialgman/lwjgl3-binaries-arm64/raw/lwjgl-3.1.6/lwjgl-jemalloc-patched-natives-linux-arm64.jar",
+    },
+    NativeSubstitution {
+        os: "linux",
+        arch: "arm64",
+        from: NativeMatch::Suffix("lwjgl-core-natives-linux.jar"),
+        to: "lwjgl-natives-linux-arm64.jar",
+    },
+    NativeSubstitution {
+        os: "*",
+        arch: "arm64",
+        from: NativeMatch::Exact(MACOS_X64_LWJGL_294),
+        to: MACOS_ARM_LWJGL_294,
+    },
+    NativeSubstitution {
+        os: "*",
+        arch: "*",
+        from: NativeMatch::Exact(
+            "https://github.com/MinecraftMachina/lwjgl/releases/download/2.9.4-20150209-mmachina.2/lwjgl-platform-2.9.4-nightly-20150209-natives-osx.jar",
+        ),
+        to: MACOS_ARM_LWJGL_294,
+    },
+];
+
+/// [`OS_NAME`], but `"linux-musl"` on MUSL, so substitutions
+/// built for glibc (most ARM ones) aren't used there.
+fn native_os() -> &'static str {
+    if IS_MUSL && OS_NAME == "linux" {
+        "linux-musl"
+    } else {
+        OS_NAME
+    }
+}
+
+/// Returns the replacement for a natives `url` on the given
+/// `os`/`arch` (see [`NATIVE_SUBSTITUTIONS`]), or `None`
+/// if the original (Mojang) artifact should be used.
+fn substitute_native_url(url: &str, os: &str, arch: &str) -> Option<String> {
+    NATIVE_SUBSTITUTIONS
+        .iter()
+        .filter(|n| (n.os == "*" || n.os == os) && (n.arch == "*" || n.arch == arch))
+        .find_map(|n| match n.from {
+            NativeMatch::Exact(from) => (url == from).then(|| n.to.to_owned()),
+            NativeMatch::Suffix(from) => url
+                .strip_suffix(from)
+                .map(|prefix| format!("{prefix}{}", n.to)),
+        })
+}
+
 impl GameDownloader {
     pub async fn download_libraries(&mut self) -> Result<(), DownloadError> {
         info!("Downloading libraries");
+        if IS_MUSL {
+            err!(
+                "Minecraft's natives are built for glibc, and might not load on MUSL.\n    If the game crashes, try a glibc (non-MUSL) build of the launcher"
+            );
+        }
         self.prepare_library_directories().await?;
 
         let total_libraries = self.version_json.libraries.len();
@@ -79,6 +160,7 @@ impl GameDownloader {
             return Ok(());
         }
 
+        self.cancel.check()?;
         self.download_library(library, None).await?;
 
         {
@@ -129,7 +211,7 @@ impl GameDownloader {
                 .await?;
             }
             if let Some(classifiers) = classifiers {
-                self.download_library_native(classifiers, &libraries_dir, library.extract.as_ref())
+                self.download_library_native(classifiers, library.extract.as_ref())
                     .await?;
             }
         } else if let Some(artifact) = artifact_fallback {
@@ -154,7 +236,7 @@ impl GameDownloader {
             artifact.url.bright_black()
         );
         let jar_file = self
-            .download_library_normal(artifact, libraries_dir)
+            .download_library_normal(library, artifact, libraries_dir)
             .await?;
 
         let natives_path = self.instance_dir.join("libraries/natives");
@@ -208,6 +290,7 @@ impl GameDownloader {
 
     async fn download_library_normal(
         &self,
+        library: &Library,
         artifact: &LibraryDownloadArtifact,
         libraries_dir: &Path,
     ) -> Result<Vec<u8>, DownloadError> {
@@ -221,7 +304,18 @@ impl GameDownloader {
             .to_path_buf();
 
         fs::create_dir_all(&lib_dir_path).await.path(lib_dir_path)?;
-        let library_downloaded = file_utils::download_file_to_bytes(&artifact.url, false).await?;
+        let mut library_downloaded =
+            file_utils::download_file_to_bytes(&artifact.url, false).await?;
+        if !artifact.verify(&library_downloaded) {
+            // Might be a flaky connection or a bad mirror, give it one more try
+            pt!(
+                "{} {}, retrying...",
+                "Checksum mismatch".yellow(),
+                library.name.as_deref().unwrap_or(&artifact.url)
+            );
+            library_downloaded = file_utils::download_file_to_bytes(&artifact.url, false).await?;
+        }
+        check_library_checksum(library, artifact, &library_downloaded)?;
 
         fs::write(&lib_file_path, &library_downloaded)
             .await
@@ -233,11 +327,8 @@ impl GameDownloader {
     async fn download_library_native(
         &self,
         classifiers: &BTreeMap<String, LibraryClassifier>,
-        libraries_dir: &Path,
         extract: Option<&LibraryExtract>,
     ) -> Result<(), DownloadError> {
-        let natives_dir = libraries_dir.join("natives");
-
         for (os, download) in classifiers {
             if os == "sources" {
                 continue;
@@ -290,52 +381,32 @@ impl GameDownloader {
                 "4: classifiers".blue(),
                 download.url.bright_black()
             );
-            self.extract_file(download.url.clone()).await?;
-        }
-
-        if let Some(extract) = extract {
-            for exclusion in &extract.exclude {
-                let path = natives_dir.join(exclusion);
-
-                if !path.starts_with(&natives_dir) {
-                    return Err(DownloadError::NativesOutsideDirRemove);
-                }
-
-                if let Ok(meta) = fs::metadata(&path).await {
-                    if meta.is_dir() {
-                        fs::remove_dir_all(&path).await.path(path)?;
-                    } else {
-                        fs::remove_file(&path).await.path(path)?;
-                    }
-                }
-            }
+            self.extract_file(download.url.clone(), extract, Some(download))
+                .await?;
         }
 
         Ok(())
     }
 
-    async fn extract_file(&self, mut url: String) -> Result<(), DownloadError> {
-        if url
-            == "https://github.com/theofficialgman/lwjgl3-binaries-arm64/raw/lwjgl-3.1.6/lwjgl-jemalloc-natives-linux.jar"
-        {
-            "https://github.com/theofficialgman/lwjgl3-binaries-arm64/raw/lwjgl-3.1.6/lwjgl-jemalloc-patched-natives-linux-arm64.jar".clone_into(&mut url);
-        }
-        if (cfg!(target_arch = "aarch64") && url == MACOS_X64_LWJGL_294)
-            || url
-                == "https://github.com/MinecraftMachina/lwjgl/releases/download/2.9.4-20150209-mmachina.2/lwjgl-platform-2.9.4-nightly-20150209-natives-osx.jar"
-        {
-            MACOS_ARM_LWJGL_294.clone_into(&mut url);
-        }
-
-        #[cfg(any(
-            feature = "simulate_linux_arm64",
-            all(target_os = "linux", target_arch = "aarch64")
-        ))]
-        if url.ends_with("lwjgl-core-natives-linux.jar") {
-            url = url.replace(
-                "lwjgl-core-natives-linux.jar",
-                "lwjgl-natives-linux-arm64.jar",
+    /// Downloads and extracts a natives jar.
+    ///
+    /// If `expected` is given, the jar is checked against it
+    /// first (unless it was swapped out for a substitute,
+    /// which has its own hash).
+    async fn extract_file(
+        &self,
+        mut url: String,
+        extract: Option<&LibraryExtract>,
+        mut expected: Option<&LibraryClassifier>,
+    ) -> Result<(), DownloadError> {
+        if let Some(substitute) = substitute_native_url(&url, native_os(), ARCH) {
+            pt!(
+                "    {} {}",
+                "Substituted:".bright_black(),
+                substitute.bright_black()
             );
+            url = substitute;
+            expected = None;
         }
 
         if !self
@@ -346,13 +417,14 @@ impl GameDownloader {
         {
             return Ok(());
         }
-        let file_bytes = match file_utils::download_file_to_bytes(&url, false).await {
+        let mut file_bytes = match file_utils::download_file_to_bytes(&url, false).await {
             Ok(n) => n,
             #[cfg(any(
                 all(target_os = "linux", target_arch = "aarch64"),
                 feature = "simulate_linux_arm64"
             ))]
             Err(ql_core::RequestError::DownloadError { code, .. }) if code.as_u16() == 404 => {
+                expected = None;
                 file_utils::download_file_to_bytes(
                     &url.replace("linux.jar", "linux-arm64.jar"),
                     false,
@@ -361,12 +433,16 @@ impl GameDownloader {
             }
             Err(err) => Err(err)?,
         };
+        if let Some(expected) = expected {
+            if !expected.verify(&file_bytes) {
+                pt!("    {}, retrying...", "Checksum mismatch".yellow());
+                file_bytes = file_utils::download_file_to_bytes(&url, false).await?;
+            }
+            check_native_checksum(expected, &file_bytes)?;
+        }
 
         let extract_path = self.instance_dir.join("libraries/natives");
-        file_utils::extract_zip_archive(Cursor::new(file_bytes), &extract_path, true)
-            .await
-            .map_err(DownloadError::NativesExtractError)?;
-        Ok(())
+        extract_natives_jar(file_bytes, &extract_path, extract).await
     }
 
     async fn extractlib_natives_field(
@@ -416,13 +492,14 @@ impl GameDownloader {
             );
 
             if let Err(err) =
-                file_utils::extract_zip_archive(Cursor::new(jar_file), natives_path, true).await
+                extract_natives_jar(jar_file, natives_path, library.extract.as_ref()).await
             {
                 err!("Couldn't extract main jar: {err}");
             }
         }
 
-        let natives_url = if let Some(natives) = classifiers.and_then(|n| n.get(natives_name)) {
+        let classifier = classifiers.and_then(|n| n.get(natives_name));
+        let natives_url = if let Some(natives) = classifier {
             natives.url.clone()
         } else {
             let url = &artifact.url[..artifact.url.len() - 4];
@@ -435,7 +512,8 @@ impl GameDownloader {
             name.bright_black(),
             natives_url.bright_black()
         );
-        self.extract_file(natives_url).await?;
+        self.extract_file(natives_url, library.extract.as_ref(), classifier)
+            .await?;
 
         Ok(())
     }
@@ -479,13 +557,70 @@ impl GameDownloader {
                 "3: based on name".yellow(),
                 name.bright_black()
             );
-            self.extract_file(artifact.url.clone()).await?;
+            self.extract_file(artifact.url.clone(), library.extract.as_ref(), None)
+                .await?;
         }
 
         Ok(())
     }
 }
 
+/// Extracts a natives jar into the `natives` folder,
+/// skipping anything listed in the library's `extract.exclude`
+/// rules (usually `META-INF/`).
+async fn extract_natives_jar(
+    bytes: Vec<u8>,
+    natives_dir: &Path,
+    extract: Option<&LibraryExtract>,
+) -> Result<(), DownloadError> {
+    match extract.filter(|n| !n.exclude.is_empty()) {
+        Some(extract) => {
+            file_utils::extract_zip_archive_excluding(
+                Cursor::new(bytes),
+                natives_dir,
+                extract.exclude.clone(),
+            )
+            .await
+        }
+        None => file_utils::extract_zip_archive(Cursor::new(bytes), natives_dir, true).await,
+    }
+    .map_err(DownloadError::NativesExtractError)
+}
+
+fn check_library_checksum(
+    library: &Library,
+    artifact: &LibraryDownloadArtifact,
+    bytes: &[u8],
+) -> Result<(), DownloadError> {
+    if artifact.verify(bytes) {
+        Ok(())
+    } else {
+        Err(DownloadError::LibraryChecksumMismatch {
+            name: library.name.clone().unwrap_or_else(|| artifact.get_path()),
+            url: artifact.url.clone(),
+        })
+    }
+}
+
+fn check_native_checksum(
+    classifier: &LibraryClassifier,
+    bytes: &[u8],
+) -> Result<(), DownloadError> {
+    if classifier.verify(bytes) {
+        Ok(())
+    } else {
+        Err(DownloadError::LibraryChecksumMismatch {
+            name: classifier
+                .url
+                .rsplit('/')
+                .next()
+                .unwrap_or(&classifier.url)
+                .to_owned(),
+            url: classifier.url.clone(),
+        })
+    }
+}
+
 async fn finalize_natives_directory(dir: &Path, root: &Path) -> Result<(), IoError> {
     async fn is_dir_empty(dir: &Path) -> Result<bool, IoError> {
         let mut entries = fs::read_dir(dir).await.path(dir)?;
@@ -534,3 +669,110 @@ async fn finalize_natives_directory(dir: &Path, root: &Path) -> Result<(), IoErr
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(sha1: &str, size: usize) -> Library {
+        serde_json::from_value(serde_json::json!({
+            "name": "org.example:broken:1.0",
+            "downloads": {
+                "artifact": {
+                    "path": "org/example/broken/1.0/broken-1.0.jar",
+                    "sha1": sha1,
+                    "size": size,
+                    "url": "https://libraries.minecraft.net/org/example/broken/1.0/broken-1.0.jar"
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn natives_exclude_rules() {
+        use std::io::Write;
+
+        let mut jar = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut jar);
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("META-INF/MANIFEST.MF", options).unwrap();
+            zip.write_all(b"Manifest-Version: 1.0").unwrap();
+            zip.start_file("liblwjgl.so", options).unwrap();
+            zip.write_all(b"not really a native").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let extract: LibraryExtract =
+            serde_json::from_value(serde_json::json!({ "exclude": ["META-INF/"] })).unwrap();
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        extract_natives_jar(jar.into_inner(), dir, Some(&extract))
+            .await
+            .unwrap();
+
+        assert!(dir.join("liblwjgl.so").is_file());
+        assert!(!dir.join("META-INF").exists());
+    }
+
+    #[test]
+    fn native_substitution_arm64() {
+        let url = "https://build.lwjgl.org/release/3.1.6/lwjgl-core-natives-linux.jar";
+        assert_eq!(
+            substitute_native_url(url, "linux", "arm64").as_deref(),
+            Some("https://build.lwjgl.org/release/3.1.6/lwjgl-natives-linux-arm64.jar")
+        );
+        assert_eq!(
+            substitute_native_url(MACOS_X64_LWJGL_294, "osx", "arm64").as_deref(),
+            Some(MACOS_ARM_LWJGL_294)
+        );
+
+        // Falls back to the original artifact elsewhere
+        assert_eq!(substitute_native_url(url, "linux", "x86_64"), None);
+        // The ARM Linux ones are built for glibc
+        assert_eq!(substitute_native_url(url, "linux-musl", "arm64"), None);
+        assert_eq!(
+            substitute_native_url(MACOS_X64_LWJGL_294, "osx", "x86_64"),
+            None
+        );
+    }
+
+    #[test]
+    fn checksum_matches() {
+        // SHA1 of "hello"
+        let lib = library("aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d", 5);
+        let artifact = lib.get_artifact().unwrap();
+        assert!(check_library_checksum(&lib, &artifact, b"hello").is_ok());
+    }
+
+    #[test]
+    fn checksum_mismatch_names_library() {
+        let lib = library("aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d", 5);
+        let artifact = lib.get_artifact().unwrap();
+
+        let err = check_library_checksum(&lib, &artifact, b"world").unwrap_err();
+        assert!(matches!(err, DownloadError::LibraryChecksumMismatch { .. }));
+        assert!(err.to_string().contains("org.example:broken:1.0"));
+
+        // Size mismatch alone should also be caught
+        let err = check_library_checksum(&lib, &artifact, b"hello!").unwrap_err();
+        assert!(matches!(err, DownloadError::LibraryChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn native_checksum() {
+        let classifier: LibraryClassifier = serde_json::from_value(serde_json::json!({
+            "sha1": "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d",
+            "size": 5,
+            "url": "https://libraries.minecraft.net/org/lwjgl/lwjgl-platform-natives-linux.jar"
+        }))
+        .unwrap();
+        assert!(check_native_checksum(&classifier, b"hello").is_ok());
+
+        let err = check_native_checksum(&classifier, b"world").unwrap_err();
+        assert!(err.to_string().contains("lwjgl-platform-natives-linux.jar"));
+    }
+}
@@ -1,15 +1,47 @@
 use std::sync::mpsc::Sender;
 
 use ql_core::{
-    DownloadProgress, Instance, IntoIoError, IntoStringError, LAUNCHER_DIR, LAUNCHER_VERSION_NAME,
-    ListEntry, info, json::VersionDetails, sanitize_instance_name,
+    CancellationToken, DownloadProgress, Instance, InstanceKind, IntoIoError, IntoStringError,
+    LAUNCHER_DIR, LAUNCHER_VERSION_NAME, ListEntry, OS_NAME, get_entries, info,
+    json::{VersionDetails, version::Library},
+    sanitize_instance_name, validate_instance_name,
 };
 
+mod assets;
 mod downloader;
+mod java;
 mod libraries;
+mod template;
 
+pub use assets::{download_missing_assets, has_assets};
 pub use downloader::DownloadError;
 pub(crate) use downloader::GameDownloader;
+pub use template::NewInstanceTemplate;
+
+/// Optional parts of creating an instance with [`create_instance`].
+#[derive(Debug, Clone, Copy)]
+pub struct CreateOptions {
+    /// Whether to download the assets. Default: true. Disable this if you want to speed
+    /// up the download or reduce file size. *Disabling this will make the game completely silent;
+    /// No sounds or music will play*
+    pub download_assets: bool,
+    /// Whether to install the Java version the game needs
+    /// (unless it's already installed) while downloading, instead of on first launch.
+    /// Default: false.
+    ///
+    /// Progress for it is sent (as [`DownloadProgress::InstallingJava`]) once the game
+    /// is downloaded. *Failing to install Java doesn't fail the instance*
+    pub install_java: bool,
+}
+
+impl Default for CreateOptions {
+    fn default() -> Self {
+        Self {
+            download_assets: true,
+            install_java: false,
+        }
+    }
+}
 
 /// Creates a Minecraft instance.
 ///
@@ -18,9 +50,10 @@ pub(crate) use downloader::GameDownloader;
 /// - `version` : Version of the game to download (for example: "1.21.1", "1.12.2", "b1.7.3", etc.)
 /// - `progress_sender` : If you want, you can create an `mpsc::channel()` of [`DownloadProgress`],
 ///   provide the receiver and keep polling the sender for progress updates. *If not needed, leave as `None`*
-/// - `download_assets` : Whether to download the assets. Default: true. Disable this if you want to speed
-///   up the download or reduce file size. *Disabling this will make the game completely silent;
-///   No sounds or music will play*
+/// - `options` : Whether to download assets and install Java (see [`CreateOptions`])
+/// - `cancel` : Lets you stop the download (see [`CancellationToken`]).
+///   The partially created instance is deleted, and
+///   [`DownloadError::Cancelled`] is returned. *If not needed, leave as `None`*
 ///
 /// # Returns
 /// The instance name that you passed in.
@@ -32,12 +65,15 @@ pub async fn create_instance(
     instance_name: String,
     version: ListEntry,
     progress_sender: Option<Sender<DownloadProgress>>,
-    download_assets: bool,
+    options: CreateOptions,
+    cancel: Option<CancellationToken>,
 ) -> Result<String, DownloadError> {
+    let CreateOptions {
+        download_assets,
+        install_java,
+    } = options;
     let instance_name = sanitize_instance_name(instance_name);
-    if instance_name.is_empty() {
-        return Err(DownloadError::InvalidName);
-    }
+    validate_instance_name(&instance_name, &get_entries(InstanceKind::Client).await?)?;
 
     info!(
         "Started creating instance: {instance_name} (version: {}, kind: {})",
@@ -52,17 +88,59 @@ pub async fn create_instance(
             .path(assets_dir)?;
     }
 
+    let java_sender = if install_java {
+        progress_sender.clone()
+    } else {
+        None
+    };
     let mut game_downloader =
         GameDownloader::new(&instance_name, &version, progress_sender).await?;
+    if let Some(cancel) = cancel {
+        game_downloader.cancel = cancel;
+    }
+
+    let result = if install_java {
+        java::download_with_java(
+            &mut game_downloader,
+            &instance_name,
+            download_assets,
+            java_sender,
+        )
+        .await
+    } else {
+        download_instance(&mut game_downloader, &instance_name, download_assets).await
+    };
+    match result {
+        Ok(()) => {}
+        Err(DownloadError::Cancelled(n)) => {
+            info!("Cancelled creating instance, cleaning up: {instance_name}");
+            let dir = &game_downloader.instance_dir;
+            tokio::fs::remove_dir_all(dir).await.path(dir)?;
+            return Err(DownloadError::Cancelled(n));
+        }
+        Err(err) => return Err(err),
+    }
+
+    info!("Finished creating instance: {instance_name}");
+
+    Ok(instance_name)
+}
 
+async fn download_instance(
+    game_downloader: &mut GameDownloader,
+    instance_name: &str,
+    download_assets: bool,
+) -> Result<(), DownloadError> {
     tokio::try_join!(
         game_downloader.download_logging_config(),
         game_downloader.download_jar()
     )?;
+    game_downloader.cancel.check()?;
     game_downloader.download_libraries().await?;
     game_downloader.library_extras().await?;
 
     if download_assets {
+        game_downloader.cancel.check()?;
         game_downloader.download_assets().await?;
     }
 
@@ -75,7 +153,7 @@ pub async fn create_instance(
 
     let version_file_path = LAUNCHER_DIR
         .join("instances")
-        .join(&instance_name)
+        .join(instance_name)
         .join("launcher_version.txt");
     tokio::fs::write(&version_file_path, LAUNCHER_VERSION_NAME)
         .await
@@ -83,13 +161,69 @@ pub async fn create_instance(
 
     let mods_dir = LAUNCHER_DIR
         .join("instances")
-        .join(&instance_name)
+        .join(instance_name)
         .join(".minecraft/mods");
     tokio::fs::create_dir_all(&mods_dir).await.path(mods_dir)?;
 
-    info!("Finished creating instance: {instance_name}");
+    Ok(())
+}
 
-    Ok(instance_name)
+/// Estimates how much (in bytes) will be downloaded when creating
+/// an instance of `version` with [`create_instance`].
+///
+/// Only the (small) version JSON is downloaded for this.
+/// It's an estimate: it doesn't account for assets that are
+/// already downloaded (shared between instances) or
+/// replacement natives on some platforms.
+///
+/// # Errors
+/// If the version manifest or version JSON couldn't be downloaded/parsed,
+/// or `version` doesn't exist.
+pub async fn estimate_instance_size(
+    version: &ListEntry,
+    download_assets: bool,
+) -> Result<u64, DownloadError> {
+    let version_json = GameDownloader::new_download_version_json(version, None).await?;
+    Ok(download_size(&version_json, download_assets))
+}
+
+fn download_size(version_json: &VersionDetails, download_assets: bool) -> u64 {
+    let libraries: u64 = version_json
+        .libraries
+        .iter()
+        .filter(|n| n.is_allowed())
+        .map(library_size)
+        .sum();
+    let assets = if download_assets {
+        version_json.assetIndex.size() + version_json.assetIndex.total_size()
+    } else {
+        0
+    };
+    version_json.downloads.client.size() + libraries + assets
+}
+
+fn library_size(library: &Library) -> u64 {
+    let Some(downloads) = &library.downloads else {
+        return 0;
+    };
+    let artifact = downloads.artifact.as_ref().map_or(0, |n| n.size());
+    let natives = library
+        .natives
+        .as_ref()
+        .and_then(|n| n.get(OS_NAME))
+        .and_then(|classifier| {
+            let arch = if cfg!(target_pointer_width = "64") {
+                "64"
+            } else {
+                "32"
+            };
+            downloads
+                .classifiers
+                .as_ref()?
+                .get(&classifier.replace("${arch}", arch))
+        })
+        .map_or(0, |n| n.size());
+    artifact + natives
 }
 
 pub async fn repeat_stage(
@@ -120,14 +254,75 @@ pub async fn repeat_stage(
         DownloadProgress::DownloadingAssets { .. } => {
             downloader.download_assets().await.strerr()?;
         }
-        DownloadProgress::DownloadingJar => {
+        DownloadProgress::DownloadingJar { .. } => {
             downloader.download_jar().await.strerr()?;
         }
         DownloadProgress::DownloadingJsonManifest | DownloadProgress::DownloadingVersionJson => {
             // Can't do anything about that :/
         }
+        // Not part of the instance
+        DownloadProgress::InstallingJava { .. } => {}
     }
     info!("Finished redownloading");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_download_size() {
+        let version_json: VersionDetails = serde_json::from_value(serde_json::json!({
+            "assetIndex": {
+                "id": "1.12",
+                "sha1": "",
+                "size": 100,
+                "totalSize": 10000,
+                "url": "https://example.com/1.12.json"
+            },
+            "assets": "1.12",
+            "downloads": {
+                "client": { "sha1": "", "size": 1000, "url": "https://example.com/client.jar" }
+            },
+            "id": "1.12.2",
+            "libraries": [
+                {
+                    "name": "com.example:lib:1.0",
+                    "downloads": {
+                        "artifact": { "sha1": "", "size": 20, "url": "https://example.com/lib.jar" }
+                    }
+                },
+                {
+                    "name": "com.example:natives:1.0",
+                    "downloads": {
+                        "classifiers": {
+                            format!("natives-{OS_NAME}"): { "sha1": "", "size": 3, "url": "https://example.com/n.jar" },
+                            "natives-other": { "sha1": "", "size": 5000, "url": "https://example.com/o.jar" }
+                        }
+                    },
+                    "natives": { OS_NAME: format!("natives-{OS_NAME}") }
+                },
+                {
+                    "name": "com.example:disallowed:1.0",
+                    "downloads": {
+                        "artifact": { "sha1": "", "size": 5000, "url": "https://example.com/no.jar" }
+                    },
+                    "rules": [{ "action": "allow", "os": { "name": "some-other-os" } }]
+                }
+            ],
+            "mainClass": "net.minecraft.client.main.Main",
+            "releaseTime": "2017-09-18T08:39:46+00:00",
+            "time": "2017-09-18T08:39:46+00:00",
+            "type": "release"
+        }))
+        .unwrap();
+
+        assert_eq!(download_size(&version_json, false), 1000 + 20 + 3);
+        assert_eq!(
+            download_size(&version_json, true),
+            1000 + 20 + 3 + 100 + 10000
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Settings that new instances start with, so users who
+//! always want (for example) 4 GB of memory don't have to
+//! set it on every instance they create.
+
+use std::path::Path;
+
+use ql_core::{
+    InstanceConfigJson, InstanceKind, IntoIoError, IntoJsonError, LAUNCHER_DIR,
+    MIN_RAM_MB_FOR_INSTANCE, err, json::instance_config::VersionInfo,
+};
+use serde::{Deserialize, Serialize};
+
+use super::DownloadError;
+
+/// Defaults for new instances, stored in the launcher config
+/// (`QuantumLauncher/config.json`) as `new_instance_template`.
+///
+/// Unset fields use the usual defaults.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NewInstanceTemplate {
+    /// Memory allocation in MB, raised to
+    /// [`MIN_RAM_MB_FOR_INSTANCE`] if lower
+    pub ram_in_mb: Option<usize>,
+    pub java_args: Option<Vec<String>>,
+    /// Default of the "Download assets?" option when
+    /// creating an instance (passed to [`crate::create_instance`]).
+    pub download_assets: Option<bool>,
+}
+
+impl NewInstanceTemplate {
+    /// Reads the template from the launcher config.
+    ///
+    /// Falls back to the defaults if there's no
+    /// launcher config (or template) yet, or it's invalid.
+    pub async fn load() -> Self {
+        Self::load_from(&LAUNCHER_DIR.join("config.json")).await
+    }
+
+    async fn load_from(config_path: &Path) -> Self {
+        let Ok(config) = tokio::fs::read_to_string(config_path).await else {
+            return Self::default();
+        };
+        let template = serde_json::from_str::<serde_json::Value>(&config)
+            .ok()
+            .and_then(|mut n| {
+                n.get_mut("new_instance_template")
+                    .map(serde_json::Value::take)
+            })
+            .filter(|n| !n.is_null());
+        let Some(template) = template else {
+            return Self::default();
+        };
+        serde_json::from_value(template).unwrap_or_else(|error| {
+            err!("Invalid new instance template in launcher config, ignoring: {error}");
+            Self::default()
+        })
+    }
+
+    /// Applies the template to a freshly made instance config.
+    pub fn apply(&self, config: &mut InstanceConfigJson) {
+        if let Some(ram) = self.ram_in_mb {
+            config.ram_in_mb = ram.max(MIN_RAM_MB_FOR_INSTANCE);
+        }
+        if let Some(args) = self.java_args.as_ref().filter(|n| !n.is_empty()) {
+            config.java_args = Some(args.clone());
+        }
+    }
+}
+
+/// Writes the initial `config.json` of a new client instance,
+/// with the [`NewInstanceTemplate`] from `launcher_config` applied.
+pub(crate) async fn write_initial_config(
+    instance_dir: &Path,
+    version: &str,
+    launcher_config: &Path,
+) -> Result<(), DownloadError> {
+    let mut config =
+        InstanceConfigJson::new(InstanceKind::Client, false, VersionInfo::new(version));
+    NewInstanceTemplate::load_from(launcher_config)
+        .await
+        .apply(&mut config);
+
+    let config = serde_json::to_string(&config).json_to()?;
+    let config_path = instance_dir.join("config.json");
+    tokio::fs::write(&config_path, config)
+        .await
+        .path(config_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn new_instance_uses_template() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let instance_dir = dir.join("instances/Templated");
+        tokio::fs::create_dir_all(&instance_dir).await.unwrap();
+
+        let launcher_config = dir.join("config.json");
+        tokio::fs::write(
+            &launcher_config,
+            r#"{
+                "username": "test",
+                "new_instance_template": { "ram_in_mb": 4096, "java_args": ["-Xss2M"] }
+            }"#,
+        )
+        .await
+        .unwrap();
+
+        write_initial_config(&instance_dir, "1.21.4", &launcher_config)
+            .await
+            .unwrap();
+        let config = InstanceConfigJson::read_from_dir(&instance_dir)
+            .await
+            .unwrap();
+        assert_eq!(config.ram_in_mb, 4096);
+        assert_eq!(config.java_args, Some(vec!["-Xss2M".to_owned()]));
+
+        // No template, usual defaults
+        tokio::fs::write(&launcher_config, r#"{"username": "test"}"#)
+            .await
+            .unwrap();
+        write_initial_config(&instance_dir, "1.21.4", &launcher_config)
+            .await
+            .unwrap();
+        let config = InstanceConfigJson::read_from_dir(&instance_dir)
+            .await
+            .unwrap();
+        assert_eq!(config.ram_in_mb, ql_core::DEFAULT_RAM_MB_FOR_INSTANCE);
+        assert_eq!(config.java_args, None);
+    }
+
+    #[test]
+    fn template_ram_has_minimum() {
+        let mut config =
+            InstanceConfigJson::new(InstanceKind::Client, false, VersionInfo::new("1.21.4"));
+        let template = NewInstanceTemplate {
+            ram_in_mb: Some(4),
+            ..Default::default()
+        };
+        template.apply(&mut config);
+        assert_eq!(config.ram_in_mb, MIN_RAM_MB_FOR_INSTANCE);
+    }
+}
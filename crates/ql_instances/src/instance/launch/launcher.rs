@@ -5,12 +5,13 @@ use crate::{
 };
 use ql_core::{
     CLASSPATH_SEPARATOR, GenericProgress, Instance, IntoIoError, IntoJsonError, IoError,
-    JsonFileError, LAUNCHER_DIR, Loader, err,
+    JsonFileError, LAUNCHER_DIR, Loader, download, err,
     file_utils::{self, exists},
     info,
     json::{
         FabricJSON, GlobalSettings, InstanceConfigJson, JsonOptifine, V_1_5_2, V_1_12_2,
-        V_PAULSCODE_LAST, V_PRECLASSIC_LAST, VersionDetails, forge, version::Library,
+        V_PAULSCODE_LAST, V_PRECLASSIC_LAST, VersionDetails, forge,
+        version::{Library, Logging},
     },
     pt,
 };
@@ -24,7 +25,7 @@ use std::{
 };
 use tokio::process::Command;
 
-use super::{error::GameLaunchError, replace_var};
+use super::{QuickPlay, error::GameLaunchError, private_assets, replace_var};
 
 pub struct GameLauncher {
     username: String,
@@ -38,9 +39,10 @@ pub struct GameLauncher {
     /// Client: `QuantumLauncher/instances/NAME/`
     /// Server: `QuantumLauncher/servers/NAME/`
     pub instance_dir: PathBuf,
-    /// Client: `QuantumLauncher/instances/NAME/.minecraft/`
+    /// Client: `QuantumLauncher/instances/NAME/.minecraft/`,
+    /// or the [`InstanceConfigJson::game_dir_override`]
     /// Server: `QuantumLauncher/servers/NAME/`
-    minecraft_dir: PathBuf,
+    pub minecraft_dir: PathBuf,
 
     config: InstanceConfigJson,
     pub version_json: VersionDetails,
@@ -48,6 +50,11 @@ pub struct GameLauncher {
     /// can be overridden by `config_json.global_settings`.
     global_settings: Option<GlobalSettings>,
     extra_java_args: Vec<String>,
+    /// Launch without any mods or jar mods (for this launch only),
+    /// to check if they're causing a crash.
+    pub safe_mode: bool,
+    /// Launch straight into a world or server (1.20+).
+    pub quick_play: Option<QuickPlay>,
 }
 
 impl GameLauncher {
@@ -60,11 +67,6 @@ impl GameLauncher {
     ) -> Result<Self, GameLaunchError> {
         let instance_dir = get_instance_dir(&instance_name).await?;
 
-        let minecraft_dir = instance_dir.join(".minecraft");
-        tokio::fs::create_dir_all(&minecraft_dir)
-            .await
-            .path(&minecraft_dir)?;
-
         let config = match InstanceConfigJson::read_from_dir(&instance_dir).await {
             Err(JsonFileError::Io(IoError::Io { error, .. }))
                 if error.kind() == ErrorKind::NotFound =>
@@ -74,6 +76,14 @@ impl GameLauncher {
             c => c?,
         };
 
+        let minecraft_dir = config
+            .game_dir_override
+            .clone()
+            .unwrap_or_else(|| instance_dir.join(".minecraft"));
+        tokio::fs::create_dir_all(&minecraft_dir)
+            .await
+            .path(&minecraft_dir)?;
+
         let instance = Instance::client(&instance_name);
         let mut version_json = VersionDetails::load(&instance).await?;
         version_json.apply_tweaks(&instance).await?;
@@ -88,6 +98,8 @@ impl GameLauncher {
             version_json,
             global_settings,
             extra_java_args,
+            safe_mode: false,
+            quick_play: None,
         })
     }
 
@@ -135,6 +147,12 @@ impl GameLauncher {
             game_arguments.push(height.to_string());
         }
 
+        super::quick_play::add_args(
+            &mut game_arguments,
+            self.quick_play.as_ref(),
+            &self.version_json,
+        );
+
         game_arguments.extend(self.config.game_args.clone().unwrap_or_default());
 
         Ok(game_arguments)
@@ -145,6 +163,11 @@ impl GameLauncher {
         game_arguments: &mut [String],
         account_details: Option<&AccountData>,
     ) -> Result<(), GameLaunchError> {
+        let assets_dir = self.get_assets_dir().await?;
+        let Some(assets_path) = assets_dir.to_str() else {
+            return Err(GameLaunchError::PathBufToString(assets_dir));
+        };
+
         for arg in game_arguments.iter_mut() {
             replace_var(arg, "auth_player_name", &self.username);
             replace_var(arg, "version_name", self.version_json.get_id());
@@ -153,7 +176,8 @@ impl GameLauncher {
             };
             replace_var(arg, "game_directory", minecraft_dir_path);
 
-            self.set_assets_argument(arg).await?;
+            replace_var(arg, "assets_root", assets_path);
+            replace_var(arg, "game_assets", assets_path);
             replace_var(arg, "auth_xuid", "0");
 
             let uuid = if let Some(account_details) = account_details {
@@ -193,7 +217,10 @@ impl GameLauncher {
         Ok(())
     }
 
-    async fn set_assets_argument(&self, argument: &mut String) -> Result<(), GameLaunchError> {
+    /// The assets root folder to pass to the game: the shared
+    /// store (migrating old layouts into it), or the instance's
+    /// own copy if [`InstanceConfigJson::private_assets`] is on.
+    async fn get_assets_dir(&self) -> Result<PathBuf, GameLaunchError> {
         let launcher_dir = &*LAUNCHER_DIR;
 
         let old_assets_path_v2 = launcher_dir
@@ -215,17 +242,20 @@ impl GameLauncher {
             migrate_to_new_assets_path(&old_assets_path_v1, &assets_path).await?;
         }
 
-        let assets_path_fixed = if exists(&assets_path).await {
+        if self.config.private_assets.unwrap_or(false) {
+            return Ok(private_assets::prepare(
+                &self.instance_dir,
+                &assets_path,
+                &self.version_json.assetIndex.id,
+            )
+            .await?);
+        }
+
+        Ok(if exists(&assets_path).await {
             assets_path
         } else {
             launcher_dir.join("assets/null")
-        };
-        let Some(assets_path) = assets_path_fixed.to_str() else {
-            return Err(GameLaunchError::PathBufToString(assets_path_fixed));
-        };
-        replace_var(argument, "assets_root", assets_path);
-        replace_var(argument, "game_assets", assets_path);
-        Ok(())
+        })
     }
 
     pub async fn create_mods_dir(&self) -> Result<(), IoError> {
@@ -435,16 +465,31 @@ impl GameLauncher {
         }
     }
 
-    pub fn setup_logging(&self, java_arguments: &mut Vec<String>) -> Result<(), GameLaunchError> {
-        if let Some(logging) = &self.version_json.logging {
-            let logging_path = self
-                .instance_dir
-                .join(format!("logging-{}", logging.client.file.id));
-            let logging_path = logging_path
-                .to_str()
-                .ok_or(GameLaunchError::PathBufToString(logging_path.clone()))?;
-            java_arguments.push(format!("-Dlog4j.configurationFile={logging_path}"));
+    /// Makes the game use the version's log4j config (if it has one),
+    /// which outputs logs as XML for the launcher's log viewer.
+    ///
+    /// Mojang's configs also disable message lookups,
+    /// fixing Log4Shell in the affected versions (1.7 - 1.18).
+    pub async fn setup_logging(
+        &self,
+        java_arguments: &mut Vec<String>,
+    ) -> Result<(), GameLaunchError> {
+        let Some(logging) = &self.version_json.logging else {
+            return Ok(());
+        };
+
+        // Instances from old launcher versions (or interrupted downloads)
+        // might not have it
+        let config_path = logging_config_path(logging, &self.instance_dir);
+        if !exists(&config_path).await {
+            info!("Downloading missing logging config");
+            if let Err(error) = download(&logging.client.file.url).path(&config_path).await {
+                err!("Couldn't download logging config, using the game's default: {error}");
+                return Ok(());
+            }
         }
+
+        java_arguments.extend(logging_args(Some(logging), &self.instance_dir)?);
         Ok(())
     }
 
@@ -502,7 +547,11 @@ impl GameLauncher {
         // version of a library has already been loaded.
 
         let instance = Instance::client(&self.instance_name);
-        let jar_path = jarmod::build(&instance).await?;
+        let jar_path = if self.safe_mode {
+            jarmod::original_jar(&instance).await?
+        } else {
+            jarmod::build(&instance).await?
+        };
         debug_assert!(
             jar_path.is_file(),
             "Minecraft JAR file should exist\nPath: {}",
@@ -753,6 +802,13 @@ impl GameLauncher {
         Ok(())
     }
 
+    /// The Java version needed by the game
+    /// (unless overridden with a custom Java install).
+    #[must_use]
+    pub fn java_version(&self) -> JavaVersion {
+        self.config.get_java_version(&self.version_json)
+    }
+
     async fn get_java_command(&mut self) -> Result<(Command, PathBuf), GameLaunchError> {
         let which_java = if cfg!(target_os = "windows") && self.config.enable_logger.unwrap_or(true)
         {
@@ -771,16 +827,8 @@ impl GameLauncher {
             ));
         }
 
-        let version = if let Some(version) = self.config.java_override_version {
-            version.into()
-        } else if let Some(version) = self.version_json.javaVersion.clone() {
-            version.into()
-        } else {
-            JavaVersion::Java8
-        };
-
         let program = get_java_binary(
-            version,
+            self.java_version(),
             which_java,
             self.java_install_progress_sender.take().as_ref(),
         )
@@ -1036,3 +1084,65 @@ fn deduplicate_game_args(arr1: &[String], arr2: &[String]) -> Vec<String> {
     // HashMap -> Vec<String> (key, value, key, value, ...)
     result
 }
+
+fn logging_config_path(logging: &Logging, instance_dir: &Path) -> PathBuf {
+    instance_dir.join(format!("logging-{}", logging.client.file.id))
+}
+
+/// The JVM arguments for using the version's log4j config
+/// (none if it doesn't have one, like pre-1.7 versions).
+fn logging_args(
+    logging: Option<&Logging>,
+    instance_dir: &Path,
+) -> Result<Vec<String>, GameLaunchError> {
+    let Some(logging) = logging else {
+        return Ok(Vec::new());
+    };
+    let config_path = logging_config_path(logging, instance_dir);
+    let Some(config_path_str) = config_path.to_str() else {
+        return Err(GameLaunchError::PathBufToString(config_path));
+    };
+
+    let mut argument = logging.client.argument.clone();
+    if !argument.contains("${path}") {
+        // Shouldn't happen, but just in case
+        argument = "-Dlog4j.configurationFile=${path}".to_owned();
+    }
+    replace_var(&mut argument, "path", config_path_str);
+    Ok(vec![argument])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logging_arg_only_with_config() {
+        let logging: Logging = serde_json::from_str(
+            r#"{
+                "client": {
+                    "argument": "-Dlog4j.configurationFile=${path}",
+                    "file": {
+                        "id": "client-1.12.xml",
+                        "sha1": "bd65e7d2e3c237be76cfbef4c2405033d7f91521",
+                        "size": 888,
+                        "url": "https://piston-data.mojang.com/v1/objects/bd65e7d2e3c237be76cfbef4c2405033d7f91521/client-1.12.xml"
+                    },
+                    "type": "log4j2-xml"
+                }
+            }"#,
+        )
+        .unwrap();
+        let instance_dir = Path::new("instances").join("Test");
+
+        let expected = format!(
+            "-Dlog4j.configurationFile={}",
+            instance_dir.join("logging-client-1.12.xml").display()
+        );
+        assert_eq!(
+            logging_args(Some(&logging), &instance_dir).unwrap(),
+            [expected]
+        );
+        assert!(logging_args(None, &instance_dir).unwrap().is_empty());
+    }
+}
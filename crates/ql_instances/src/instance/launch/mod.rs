@@ -1,13 +1,35 @@
 use crate::auth::AccountData;
-use error::GameLaunchError;
-use ql_core::{GenericProgress, Instance, LaunchedProcess, REDACT_SENSITIVE_INFO, err, info};
-use std::sync::{Arc, mpsc::Sender};
+pub use error::GameLaunchError;
+use ql_core::{
+    GenericProgress, Instance, LaunchedProcess, REDACT_SENSITIVE_INFO, RunningProcess, err, info,
+};
+use ql_java_handler::JavaVersion;
+use std::{
+    path::PathBuf,
+    sync::{Arc, mpsc::Sender},
+};
 use tokio::sync::Mutex;
 
 pub(super) mod error;
 mod launcher;
+mod private_assets;
+mod quick_play;
+mod safe_mode;
 pub use launcher::GameLauncher;
 use ql_core::json::GlobalSettings;
+pub use quick_play::QuickPlay;
+
+/// Optional ways to launch an instance, for [`launch`].
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    /// Launches without any mods or jar mods, to check if
+    /// they're causing a crash. This is only for this launch, the mods
+    /// are restored once the game exits (nothing is permanently changed).
+    pub safe_mode: bool,
+    /// Launches directly into a world or server,
+    /// if the version supports it (1.20+). Otherwise it's ignored.
+    pub quick_play: Option<QuickPlay>,
+}
 
 /// Launches a Minecraft instance.
 ///
@@ -22,6 +44,7 @@ use ql_core::json::GlobalSettings;
 /// - `global_settings`: Global launcher-level settings that apply to instance
 ///   like window width/height, etc.
 /// - `extra_java_args`
+/// - `options`: Safe mode and quick play (see [`LaunchOptions`]).
 pub async fn launch(
     instance_name: Arc<str>,
     username: String,
@@ -29,6 +52,7 @@ pub async fn launch(
     auth: Option<AccountData>,
     global_settings: Option<GlobalSettings>,
     extra_java_args: Vec<String>,
+    options: LaunchOptions,
 ) -> Result<LaunchedProcess, GameLaunchError> {
     if username.is_empty() {
         return Err(GameLaunchError::UsernameIsEmpty);
@@ -45,12 +69,125 @@ pub async fn launch(
         extra_java_args,
     )
     .await?;
+    let LaunchOptions {
+        safe_mode,
+        quick_play,
+    } = options;
+    game_launcher.safe_mode = safe_mode;
+    game_launcher.quick_play = quick_play;
+
+    let (mut java_arguments, main_class, mut game_arguments) =
+        build_arguments(&mut game_launcher, auth.as_ref()).await?;
+    java_arguments.push(main_class);
+
+    let minecraft_dir = game_launcher.minecraft_dir.clone();
+    if safe_mode {
+        safe_mode::disable_mods(&minecraft_dir).await?;
+    }
+
+    info!("Java args: {java_arguments:?}\n");
+
+    print_censored_args(auth.as_ref(), &mut game_arguments);
+
+    // Recorded once launched, without the game arguments (access token)
+    let jvm_command = java_arguments.clone();
+    let (mut command, path) = game_launcher
+        .get_command(game_arguments, java_arguments)
+        .await?;
+    let child = match command.spawn() {
+        Ok(n) => n,
+        Err(err) => {
+            if safe_mode {
+                safe_mode::restore_mods(&minecraft_dir).await?;
+            }
+            return Err(GameLaunchError::CommandError(err, path));
+        }
+    };
+    if let Some(id) = child.id() {
+        info!("Launched! PID: {id}");
+        let process = RunningProcess {
+            pid: id,
+            command: std::iter::once(path.to_string_lossy().into_owned())
+                .chain(jvm_command)
+                .collect(),
+        };
+        if let Err(error) = process.save(&Instance::client(&instance_name)).await {
+            err!("Couldn't record running process: {error}");
+        }
+    } else {
+        err!("No ID found!");
+    }
+
+    let child = Arc::new(Mutex::new(child));
+    if safe_mode {
+        safe_mode::restore_on_exit(child.clone(), minecraft_dir);
+    }
+
+    Ok(LaunchedProcess {
+        child,
+        instance: Instance::client(&instance_name),
+        is_classic_server: false,
+    })
+}
+
+/// The arguments to start a game with,
+/// from [`launch_arguments`].
+#[derive(Debug, Clone)]
+pub struct LaunchArguments {
+    /// Java (JVM) arguments, including the classpath
+    /// (`-cp`) but not the main class.
+    pub java: Vec<String>,
+    pub main_class: String,
+    /// Arguments passed to the game itself (after the main class).
+    pub game: Vec<String>,
+    /// The Java version the game needs to run.
+    pub java_version: JavaVersion,
+    /// The folder to run the game in (`.minecraft`).
+    pub working_dir: PathBuf,
+}
 
+/// Builds the arguments that [`launch`] would start the
+/// game with (in offline mode), without actually launching it.
+///
+/// Useful for running the game outside the launcher.
+/// Java isn't installed by this, see
+/// [`LaunchArguments::java_version`] for what's needed.
+///
+/// # Errors
+/// Same as [`launch`] (except ones from starting the process).
+pub async fn launch_arguments(
+    instance_name: Arc<str>,
+    username: String,
+    global_settings: Option<GlobalSettings>,
+) -> Result<LaunchArguments, GameLaunchError> {
+    if username.is_empty() {
+        return Err(GameLaunchError::UsernameIsEmpty);
+    }
+    let mut game_launcher =
+        GameLauncher::new(instance_name, username, None, global_settings, Vec::new()).await?;
+    let (java, main_class, game) = build_arguments(&mut game_launcher, None).await?;
+
+    Ok(LaunchArguments {
+        java,
+        main_class,
+        game,
+        java_version: game_launcher.java_version(),
+        working_dir: game_launcher.minecraft_dir.clone(),
+    })
+}
+
+/// Returns Java arguments (with classpath), main class, and game arguments.
+async fn build_arguments(
+    game_launcher: &mut GameLauncher,
+    auth: Option<&AccountData>,
+) -> Result<(Vec<String>, String, Vec<String>), GameLaunchError> {
     game_launcher.migrate_old_instances().await?;
+    // In case the launcher was closed during a safe mode launch
+    safe_mode::restore_mods(&game_launcher.minecraft_dir).await?;
     game_launcher.create_mods_dir().await?;
 
-    let mut game_arguments = game_launcher.init_game_arguments(auth.as_ref())?;
-    let mut java_arguments = game_launcher.init_java_arguments(auth.as_ref()).await?;
+    let mut game_arguments = game_launcher.init_game_arguments(auth)?;
+    let mut java_arguments = game_launcher.init_java_arguments(auth).await?;
 
     let fabric_json = game_launcher
         .setup_fabric(&mut java_arguments, &mut game_arguments)
@@ -63,10 +200,10 @@ pub async fn launch(
     game_launcher.fill_java_arguments(&mut java_arguments);
 
     game_launcher
-        .fill_game_arguments(&mut game_arguments, auth.as_ref())
+        .fill_game_arguments(&mut game_arguments, auth)
         .await?;
 
-    game_launcher.setup_logging(&mut java_arguments)?;
+    game_launcher.setup_logging(&mut java_arguments).await?;
     let main_class = game_launcher.get_main_class(
         fabric_json.as_ref(),
         forge_json.as_ref(),
@@ -84,29 +221,8 @@ pub async fn launch(
             )
             .await?,
     );
-    java_arguments.push(main_class);
-
-    info!("Java args: {java_arguments:?}\n");
 
-    print_censored_args(auth.as_ref(), &mut game_arguments);
-
-    let (mut command, path) = game_launcher
-        .get_command(game_arguments, java_arguments)
-        .await?;
-    let child = command
-        .spawn()
-        .map_err(|err| GameLaunchError::CommandError(err, path))?;
-    if let Some(id) = child.id() {
-        info!("Launched! PID: {id}");
-    } else {
-        err!("No ID found!");
-    }
-
-    Ok(LaunchedProcess {
-        child: Arc::new(Mutex::new(child)),
-        instance: Instance::client(&instance_name),
-        is_classic_server: false,
-    })
+    Ok((java_arguments, main_class, game_arguments))
 }
 
 fn print_censored_args(auth: Option<&AccountData>, game_arguments: &mut Vec<String>) {
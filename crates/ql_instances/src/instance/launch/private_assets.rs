@@ -0,0 +1,139 @@
+//! Instances with [`InstanceConfigJson::private_assets`] enabled
+//! keep their own copy of the game assets (sounds, languages, ...)
+//! inside the instance folder, instead of using the shared
+//! `QuantumLauncher/assets/dir/` store.
+//!
+//! This makes the instance folder self-contained,
+//! so it can be moved or exported as-is.
+//!
+//! [`InstanceConfigJson::private_assets`]: ql_core::json::InstanceConfigJson::private_assets
+
+use std::path::{Path, PathBuf};
+
+use ql_core::{IntoIoError, IoError, err, file_utils::exists, info};
+use serde::Deserialize;
+
+/// Same layout as the shared store (`indexes/`, `objects/`).
+///
+/// Not just `assets`, that's the pre-v0.2 per-instance
+/// folder that gets migrated to the shared store.
+const DIR_NAME: &str = "private_assets";
+
+#[derive(Deserialize)]
+struct AssetIndex {
+    objects: std::collections::HashMap<String, AssetObject>,
+}
+
+#[derive(Deserialize)]
+struct AssetObject {
+    hash: String,
+}
+
+/// Gets the instance's private assets folder ready,
+/// copying over the assets of `index_id` from the
+/// shared store if it doesn't have them yet.
+///
+/// Returns the folder to use as the game's assets root.
+///
+/// If the shared store doesn't have them either
+/// (instance was made without downloading assets),
+/// the folder is left empty.
+pub(super) async fn prepare(
+    instance_dir: &Path,
+    shared_dir: &Path,
+    index_id: &str,
+) -> Result<PathBuf, IoError> {
+    let private_dir = instance_dir.join(DIR_NAME);
+    let index_path = Path::new("indexes").join(format!("{index_id}.json"));
+    let private_index = private_dir.join(&index_path);
+    if exists(&private_index).await {
+        return Ok(private_dir);
+    }
+    tokio::fs::create_dir_all(&private_dir)
+        .await
+        .path(&private_dir)?;
+
+    let shared_index = shared_dir.join(&index_path);
+    let Ok(index_json) = tokio::fs::read_to_string(&shared_index).await else {
+        err!("Assets ({index_id}) aren't downloaded, the game will have no sounds or languages");
+        return Ok(private_dir);
+    };
+    let index: AssetIndex = match serde_json::from_str(&index_json) {
+        Ok(n) => n,
+        Err(error) => {
+            err!("Invalid asset index {shared_index:?}: {error}");
+            return Ok(private_dir);
+        }
+    };
+
+    info!(
+        "Copying assets into instance ({} files)",
+        index.objects.len()
+    );
+    for object in index.objects.values() {
+        let Some(prefix) = object.hash.get(..2) else {
+            continue;
+        };
+        let relative = Path::new("objects").join(prefix).join(&object.hash);
+        let (src, dest) = (shared_dir.join(&relative), private_dir.join(&relative));
+        if exists(&dest).await || !exists(&src).await {
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await.path(parent)?;
+        }
+        tokio::fs::copy(&src, &dest).await.path(&src)?;
+    }
+
+    // Copied last, so an interrupted copy is resumed next launch
+    if let Some(parent) = private_index.parent() {
+        tokio::fs::create_dir_all(parent).await.path(parent)?;
+    }
+    tokio::fs::write(&private_index, index_json)
+        .await
+        .path(&private_index)?;
+    Ok(private_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn copies_only_needed_assets() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let (shared, instance) = (dir.join("assets/dir"), dir.join("instances/Private"));
+
+        let files = [
+            (
+                "indexes/17.json",
+                r#"{"objects": {"minecraft/lang/en_us.json": {"hash": "ab12", "size": 2}}}"#,
+            ),
+            ("objects/ab/ab12", "{}"),
+            // From another version's index
+            ("objects/cd/cd34", "other"),
+        ];
+        for (path, contents) in files {
+            let path = shared.join(path);
+            tokio::fs::create_dir_all(path.parent().unwrap())
+                .await
+                .unwrap();
+            tokio::fs::write(&path, contents).await.unwrap();
+        }
+
+        let assets_root = prepare(&instance, &shared, "17").await.unwrap();
+        assert_eq!(assets_root, instance.join(DIR_NAME));
+        assert!(assets_root.join("indexes/17.json").is_file());
+        assert!(assets_root.join("objects/ab/ab12").is_file());
+        assert!(!assets_root.join("objects/cd/cd34").exists());
+
+        // Already set up, doesn't need the shared store anymore
+        tokio::fs::remove_dir_all(&shared).await.unwrap();
+        assert_eq!(
+            prepare(&instance, &shared, "17").await.unwrap(),
+            assets_root
+        );
+        assert!(assets_root.join("objects/ab/ab12").is_file());
+    }
+}
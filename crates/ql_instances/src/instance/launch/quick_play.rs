@@ -0,0 +1,88 @@
+use ql_core::{err, json::VersionDetails};
+
+/// Where to put the player once the game starts,
+/// skipping the title screen.
+///
+/// Only supported in Minecraft 1.20 and above,
+/// older versions ignore this (and launch normally).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuickPlay {
+    /// Opens a world, by its folder name
+    /// inside `.minecraft/saves/`.
+    Singleplayer(String),
+    /// Joins a server, by its address
+    /// (like `mc.example.com` or `127.0.0.1:25565`).
+    Multiplayer(String),
+}
+
+impl QuickPlay {
+    fn get_args(&self) -> [String; 2] {
+        match self {
+            QuickPlay::Singleplayer(world) => ["--quickPlaySingleplayer".to_owned(), world.clone()],
+            QuickPlay::Multiplayer(server) => ["--quickPlayMultiplayer".to_owned(), server.clone()],
+        }
+    }
+}
+
+pub(super) fn add_args(
+    game_arguments: &mut Vec<String>,
+    quick_play: Option<&QuickPlay>,
+    version_json: &VersionDetails,
+) {
+    let Some(quick_play) = quick_play else {
+        return;
+    };
+    if version_json.supports_quick_play() {
+        game_arguments.extend(quick_play.get_args());
+    } else {
+        err!(
+            "Minecraft {} doesn't support launching directly into a world/server (needs 1.20+), launching normally",
+            version_json.get_id()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ql_core::test_utils::version_json;
+
+    use super::*;
+
+    fn version(id: &str, game_args: serde_json::Value) -> VersionDetails {
+        let mut json = version_json(id, "2024-01-01T00:00:00+00:00");
+        json["arguments"] = serde_json::json!({ "game": game_args, "jvm": [] });
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn only_for_supported_versions() {
+        let quick_play = QuickPlay::Singleplayer("New World".to_owned());
+
+        let modern = version(
+            "1.20.4",
+            serde_json::json!([
+                "--username",
+                "${auth_player_name}",
+                {
+                    "rules": [{ "action": "allow", "features": { "is_quick_play_singleplayer": true } }],
+                    "value": ["--quickPlaySingleplayer", "${quickPlaySingleplayer}"]
+                }
+            ]),
+        );
+        let mut args = vec!["--username".to_owned()];
+        add_args(&mut args, Some(&quick_play), &modern);
+        assert_eq!(args, ["--username", "--quickPlaySingleplayer", "New World"]);
+
+        let old = version(
+            "1.19.4",
+            serde_json::json!(["--username", "${auth_player_name}"]),
+        );
+        let mut args = vec!["--username".to_owned()];
+        add_args(&mut args, Some(&quick_play), &old);
+        assert_eq!(args, ["--username"]);
+
+        let mut args = Vec::new();
+        add_args(&mut args, None, &modern);
+        assert!(args.is_empty());
+    }
+}
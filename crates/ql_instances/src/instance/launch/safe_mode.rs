@@ -0,0 +1,134 @@
+//! Safe mode: launching a modded instance once with all
+//! mods disabled, to check whether a crash is caused by mods.
+//!
+//! The `mods` folder is moved aside (to `mods.safe_mode`)
+//! while the game runs, and an empty one is left in its place.
+//! Jar mods are skipped by using the original jar
+//! (see [`super::GameLauncher::safe_mode`]).
+//!
+//! Nothing is permanently changed: the folder is moved back
+//! once the game exits (or on the next launch, if the launcher
+//! was closed before that). Meanwhile, the mod manager
+//! leaves `mod_index.json` alone and refuses to install mods
+//! (see [`SAFE_MODE_MODS_DIR`]).
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use ql_core::{IntoIoError, IoError, SAFE_MODE_MODS_DIR as MODS_BACKUP_DIR, err, info, pt};
+use tokio::{process::Child, sync::Mutex};
+
+/// Moves the `mods` folder aside, leaving an empty one.
+pub(super) async fn disable_mods(minecraft_dir: &Path) -> Result<(), IoError> {
+    let mods_dir = minecraft_dir.join("mods");
+    let backup_dir = minecraft_dir.join(MODS_BACKUP_DIR);
+    if mods_dir.is_dir() {
+        tokio::fs::rename(&mods_dir, &backup_dir)
+            .await
+            .path(&mods_dir)?;
+    }
+    tokio::fs::create_dir_all(&mods_dir).await.path(mods_dir)?;
+    info!("Safe mode: mods disabled for this launch");
+    Ok(())
+}
+
+/// Moves the `mods` folder back after a safe mode launch.
+/// Does nothing if there's nothing to restore.
+///
+/// Anything added to the temporary `mods` folder during
+/// safe mode is kept, unless the real one has a file
+/// with the same name.
+pub(super) async fn restore_mods(minecraft_dir: &Path) -> Result<(), IoError> {
+    let backup_dir = minecraft_dir.join(MODS_BACKUP_DIR);
+    if !backup_dir.is_dir() {
+        return Ok(());
+    }
+    let mods_dir = minecraft_dir.join("mods");
+    if mods_dir.is_dir() {
+        let mut entries = tokio::fs::read_dir(&mods_dir).await.path(&mods_dir)?;
+        while let Some(entry) = entries.next_entry().await.path(&mods_dir)? {
+            let dest = backup_dir.join(entry.file_name());
+            if dest.exists() {
+                pt!("Safe mode: {dest:?} already exists, not keeping the new one");
+                continue;
+            }
+            tokio::fs::rename(entry.path(), &dest)
+                .await
+                .path(entry.path())?;
+        }
+        tokio::fs::remove_dir_all(&mods_dir).await.path(&mods_dir)?;
+    }
+    tokio::fs::rename(&backup_dir, &mods_dir)
+        .await
+        .path(&backup_dir)?;
+    info!("Restored mods after safe mode");
+    Ok(())
+}
+
+/// Restores the mods once `child` (the game) exits.
+pub(super) fn restore_on_exit(child: Arc<Mutex<Child>>, minecraft_dir: std::path::PathBuf) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            let mut child = child.lock().await;
+            if !matches!(child.try_wait(), Ok(None)) {
+                break;
+            }
+        }
+        if let Err(error) = restore_mods(&minecraft_dir).await {
+            err!("Couldn't restore mods after safe mode: {error}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn excludes_mods_without_touching_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let minecraft_dir = temp.path().join(".minecraft");
+        let mods_dir = minecraft_dir.join("mods");
+        tokio::fs::create_dir_all(&mods_dir).await.unwrap();
+        tokio::fs::write(mods_dir.join("sodium.jar"), "jar")
+            .await
+            .unwrap();
+        tokio::fs::write(mods_dir.join("lithium.jar.disabled"), "jar")
+            .await
+            .unwrap();
+        let index_path = minecraft_dir.join("mod_index.json");
+        let index = r#"{"mods":{"AANobbMI":{"name":"Sodium","enabled":true}}}"#;
+        tokio::fs::write(&index_path, index).await.unwrap();
+
+        disable_mods(&minecraft_dir).await.unwrap();
+        assert!(ql_core::jarmod::is_dir_empty(&mods_dir).await);
+        // Added by the user during the session
+        tokio::fs::write(mods_dir.join("iris.jar"), "jar")
+            .await
+            .unwrap();
+        // Same name as an existing mod, the original wins
+        tokio::fs::write(mods_dir.join("sodium.jar"), "new")
+            .await
+            .unwrap();
+
+        restore_mods(&minecraft_dir).await.unwrap();
+        let mut files = Vec::new();
+        let mut entries = tokio::fs::read_dir(&mods_dir).await.unwrap();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            files.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        files.sort();
+        assert_eq!(files, ["iris.jar", "lithium.jar.disabled", "sodium.jar"]);
+        assert_eq!(
+            tokio::fs::read_to_string(mods_dir.join("sodium.jar"))
+                .await
+                .unwrap(),
+            "jar"
+        );
+        assert!(!minecraft_dir.join(MODS_BACKUP_DIR).exists());
+        assert_eq!(tokio::fs::read_to_string(&index_path).await.unwrap(), index);
+
+        // Nothing to restore
+        restore_mods(&minecraft_dir).await.unwrap();
+    }
+}
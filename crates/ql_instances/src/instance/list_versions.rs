@@ -1,4 +1,11 @@
-use ql_core::{JsonDownloadError, ListEntry, ListEntryKind, json::Manifest};
+use std::collections::HashSet;
+
+use ql_core::{
+    JsonDownloadError, ListEntry, ListEntryKind,
+    json::{Manifest, manifest::Version},
+};
+
+use crate::DownloadError;
 
 /// Returns a list of every downloadable version of Minecraft.
 /// Sources the list from multiple places (see [`Manifest`]).
@@ -14,15 +21,74 @@ pub async fn list_versions() -> Result<(Vec<ListEntry>, String), JsonDownloadErr
         .unwrap_or_default();
 
     Ok((
-        manifest
-            .versions
-            .into_iter()
-            .map(|n| ListEntry {
-                kind: ListEntryKind::calculate(&n.id, &n.r#type),
-                supports_server: n.supports_server(),
-                name: n.id,
-            })
-            .collect(),
+        manifest.versions.into_iter().map(to_entry).collect(),
         latest,
     ))
 }
+
+/// Returns the versions that aren't in `known`
+/// (the version names from an earlier [`list_versions`]),
+/// newest first.
+///
+/// For telling the user about new releases/snapshots
+/// (eg: "3 new snapshots available").
+///
+/// Uses the same (cached) manifest as [`list_versions`].
+///
+/// # Errors
+/// If [`Manifest`] couldn't be downloaded or parsed into JSON
+pub async fn new_versions_since(known: &[String]) -> Result<Vec<ListEntry>, DownloadError> {
+    let manifest = Manifest::download().await?;
+    Ok(new_versions(manifest.versions, known))
+}
+
+fn new_versions(versions: Vec<Version>, known: &[String]) -> Vec<ListEntry> {
+    let known: HashSet<&str> = known.iter().map(String::as_str).collect();
+    versions
+        .into_iter()
+        .filter(|n| !known.contains(n.id.as_str()))
+        .map(to_entry)
+        .collect()
+}
+
+fn to_entry(version: Version) -> ListEntry {
+    ListEntry {
+        kind: ListEntryKind::calculate(&version.id, &version.r#type),
+        supports_server: version.supports_server(),
+        name: version.id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_new_versions() {
+        let manifest: Manifest = serde_json::from_str(
+            r#"{
+                "latest": { "release": "1.21.5", "snapshot": "25w21a" },
+                "versions": [
+                    { "id": "25w21a", "type": "snapshot", "url": "", "releaseTime": "2025-05-20T12:00:00+00:00" },
+                    { "id": "1.21.5", "type": "release", "url": "", "releaseTime": "2025-03-25T12:00:00+00:00" },
+                    { "id": "25w20a", "type": "snapshot", "url": "", "releaseTime": "2025-05-13T12:00:00+00:00" },
+                    { "id": "1.21.4", "type": "release", "url": "", "releaseTime": "2024-12-03T10:12:57+00:00" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let names = |entries: Vec<ListEntry>| -> Vec<String> {
+            entries.into_iter().map(|n| n.name).collect()
+        };
+
+        // Last checked before 1.21.5 came out
+        let known = vec!["1.21.4".to_owned(), "1.21.3".to_owned()];
+        assert_eq!(
+            names(new_versions(manifest.versions.clone(), &known)),
+            ["25w21a", "1.21.5", "25w20a"]
+        );
+
+        let known: Vec<String> = manifest.versions.iter().map(|n| n.id.clone()).collect();
+        assert!(new_versions(manifest.versions, &known).is_empty());
+    }
+}
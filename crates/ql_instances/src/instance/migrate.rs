@@ -4,8 +4,12 @@ use std::{
 };
 
 use ql_core::{
-    CLASSPATH_SEPARATOR, IntoIoError, LAUNCHER_DIR, LAUNCHER_VERSION, LAUNCHER_VERSION_NAME,
-    file_utils::exists, info, json::version::LibraryDownloads,
+    CLASSPATH_SEPARATOR, IntoIoError, IoError, JavaVersion, LAUNCHER_DIR, LAUNCHER_VERSION,
+    LAUNCHER_VERSION_NAME,
+    file_utils::{self, exists},
+    info,
+    json::version::LibraryDownloads,
+    pt,
 };
 
 use crate::download::GameDownloader;
@@ -262,3 +266,232 @@ const fn ver(major: u64, minor: u64, patch: u64) -> semver::Version {
         build: semver::BuildMetadata::EMPTY,
     }
 }
+
+/// What [`detect_and_migrate`] changed.
+///
+/// Empty if the launcher directory was already up-to-date.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub changes: Vec<MigrationChange>,
+}
+
+impl MigrationReport {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationChange {
+    /// `assets/<INDEX>/` (pre-v0.3 layout) was merged into `assets/dir/`.
+    SharedAssetsMoved(String),
+    /// `instances/<NAME>/assets/` (pre-v0.1 layout) was merged into `assets/dir/`.
+    InstanceAssetsMoved(String),
+    /// `java_installs/<from>/` was renamed to the current naming.
+    JavaInstallRenamed { from: String, to: String },
+    /// `instances/<NAME>/launcher_version.txt` (added in v0.2) was missing.
+    InstanceVersionFileAdded(String),
+}
+
+/// Detects old QuantumLauncher directory layouts and
+/// migrates them to the current structure.
+///
+/// This handles launcher-wide things that would otherwise only be
+/// migrated lazily (when launching an instance). Per-instance migrations
+/// that depend on the old version (natives, Forge classpath) still happen
+/// on launch, using the `launcher_version.txt` this ensures exists.
+///
+/// Running this multiple times is safe; once migrated,
+/// it returns an empty [`MigrationReport`].
+///
+/// # Errors
+/// - User lacks permissions to read/move files in the launcher dir
+pub async fn detect_and_migrate() -> Result<MigrationReport, IoError> {
+    migrate_launcher_dir(&LAUNCHER_DIR).await
+}
+
+async fn migrate_launcher_dir(launcher_dir: &Path) -> Result<MigrationReport, IoError> {
+    let mut report = MigrationReport::default();
+    let assets_dir = launcher_dir.join("assets/dir");
+
+    migrate_shared_assets(launcher_dir, &assets_dir, &mut report).await?;
+    migrate_instances(launcher_dir, &assets_dir, &mut report).await?;
+    migrate_java_installs(launcher_dir, &mut report).await?;
+
+    if !report.is_empty() {
+        info!("Migrated old launcher files:");
+        for change in &report.changes {
+            pt!("{change:?}");
+        }
+    }
+    Ok(report)
+}
+
+async fn migrate_shared_assets(
+    launcher_dir: &Path,
+    assets_dir: &Path,
+    report: &mut MigrationReport,
+) -> Result<(), IoError> {
+    let old_assets_root = launcher_dir.join("assets");
+    if !exists(&old_assets_root).await {
+        return Ok(());
+    }
+
+    let mut entries = tokio::fs::read_dir(&old_assets_root)
+        .await
+        .dir(&old_assets_root)?;
+    while let Some(entry) = entries.next_entry().await.dir(&old_assets_root)? {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        // `dir` is the current location, `null` is for instances without assets
+        if name == "dir" || name == "null" || !path.is_dir() {
+            continue;
+        }
+        if !exists(path.join("objects")).await && !exists(path.join("indexes")).await {
+            continue;
+        }
+
+        file_utils::copy_dir_recursive(&path, assets_dir).await?;
+        tokio::fs::remove_dir_all(&path).await.path(&path)?;
+        report
+            .changes
+            .push(MigrationChange::SharedAssetsMoved(name));
+    }
+    Ok(())
+}
+
+async fn migrate_instances(
+    launcher_dir: &Path,
+    assets_dir: &Path,
+    report: &mut MigrationReport,
+) -> Result<(), IoError> {
+    let instances_dir = launcher_dir.join("instances");
+    if !exists(&instances_dir).await {
+        return Ok(());
+    }
+
+    let mut entries = tokio::fs::read_dir(&instances_dir)
+        .await
+        .dir(&instances_dir)?;
+    while let Some(entry) = entries.next_entry().await.dir(&instances_dir)? {
+        let instance_dir = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Not an instance (or a broken one)
+        if !exists(instance_dir.join("details.json")).await {
+            continue;
+        }
+
+        let old_assets = instance_dir.join("assets");
+        if exists(&old_assets).await {
+            file_utils::copy_dir_recursive(&old_assets, assets_dir).await?;
+            tokio::fs::remove_dir_all(&old_assets)
+                .await
+                .path(&old_assets)?;
+            report
+                .changes
+                .push(MigrationChange::InstanceAssetsMoved(name.clone()));
+        }
+
+        let version_file = instance_dir.join("launcher_version.txt");
+        if !exists(&version_file).await {
+            // Same as `GameLauncher::migrate_get_version`, so the
+            // remaining v0.1 migrations still run on next launch
+            tokio::fs::write(&version_file, "0.1")
+                .await
+                .path(&version_file)?;
+            report
+                .changes
+                .push(MigrationChange::InstanceVersionFileAdded(name));
+        }
+    }
+    Ok(())
+}
+
+async fn migrate_java_installs(
+    launcher_dir: &Path,
+    report: &mut MigrationReport,
+) -> Result<(), IoError> {
+    fn normalize(name: &str) -> String {
+        name.to_lowercase().replace(['_', '-'], "")
+    }
+
+    let java_dir = launcher_dir.join("java_installs");
+    if !exists(&java_dir).await {
+        return Ok(());
+    }
+
+    let mut entries = tokio::fs::read_dir(&java_dir).await.dir(&java_dir)?;
+    while let Some(entry) = entries.next_entry().await.dir(&java_dir)? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // `Java8`, `java8`, `JAVA_8` -> `java_8`
+        let Some(new_name) = JavaVersion::ALL
+            .iter()
+            .map(ToString::to_string)
+            .find(|n| *n != name && normalize(n) == normalize(&name))
+        else {
+            continue;
+        };
+
+        let new_path = java_dir.join(&new_name);
+        if exists(&new_path).await {
+            // Already have a proper install, the old one is just junk
+            continue;
+        }
+        tokio::fs::rename(entry.path(), &new_path)
+            .await
+            .path(&new_path)?;
+        report.changes.push(MigrationChange::JavaInstallRenamed {
+            from: name,
+            to: new_name,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn migrate_old_layout() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        for file in [
+            "assets/1.8/indexes/1.8.json",
+            "assets/1.8/objects/ab/abcd",
+            "instances/Old/details.json",
+            "instances/Old/assets/objects/cd/cdef",
+            "java_installs/Java8/release",
+        ] {
+            let path = dir.join(file);
+            tokio::fs::create_dir_all(path.parent().unwrap())
+                .await
+                .unwrap();
+            tokio::fs::write(&path, "{}").await.unwrap();
+        }
+
+        let report = migrate_launcher_dir(dir).await.unwrap();
+        assert_eq!(
+            report.changes,
+            vec![
+                MigrationChange::SharedAssetsMoved("1.8".to_owned()),
+                MigrationChange::InstanceAssetsMoved("Old".to_owned()),
+                MigrationChange::InstanceVersionFileAdded("Old".to_owned()),
+                MigrationChange::JavaInstallRenamed {
+                    from: "Java8".to_owned(),
+                    to: "java_8".to_owned()
+                },
+            ]
+        );
+        assert!(dir.join("assets/dir/indexes/1.8.json").is_file());
+        assert!(dir.join("assets/dir/objects/ab/abcd").is_file());
+        assert!(dir.join("assets/dir/objects/cd/cdef").is_file());
+        assert!(dir.join("java_installs/java_8/release").is_file());
+        assert!(!dir.join("assets/1.8").exists());
+
+        // Second run is a no-op
+        assert!(migrate_launcher_dir(dir).await.unwrap().is_empty());
+    }
+}
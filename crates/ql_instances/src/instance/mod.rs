@@ -1,6 +1,15 @@
+mod diagnostics;
 pub mod launch;
 pub mod list_versions;
 mod migrate;
+mod nbt;
+mod servers_dat;
+
+pub use diagnostics::{collect_log_for_sharing, export_diagnostics};
+pub use migrate::{MigrationChange, MigrationReport, detect_and_migrate};
+pub use servers_dat::{
+    ServerEntry, add_multiplayer_server, read_multiplayer_servers, remove_multiplayer_server,
+};
 
 pub mod notes {
     use ql_core::{Instance, IntoIoError, IoError};
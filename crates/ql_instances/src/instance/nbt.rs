@@ -0,0 +1,237 @@
+//! A minimal reader/writer for Minecraft's NBT format
+//! (uncompressed, big-endian), enough for small files
+//! like `servers.dat`.
+//!
+//! <https://minecraft.wiki/w/NBT_format>
+
+use std::io::{self, Read, Write};
+
+/// Compound tags keep their entries in file order,
+/// so unknown data is written back unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    /// Element type id, elements
+    List(u8, Vec<Tag>),
+    Compound(Vec<(String, Tag)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+const TAG_END: u8 = 0;
+pub const TAG_COMPOUND: u8 = 10;
+
+impl Tag {
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(_) => 3,
+            Tag::Long(_) => 4,
+            Tag::Float(_) => 5,
+            Tag::Double(_) => 6,
+            Tag::ByteArray(_) => 7,
+            Tag::String(_) => 8,
+            Tag::List(_, _) => 9,
+            Tag::Compound(_) => TAG_COMPOUND,
+            Tag::IntArray(_) => 11,
+            Tag::LongArray(_) => 12,
+        }
+    }
+}
+
+/// Reads a file's root compound tag (its name is ignored).
+pub fn read_root(mut bytes: &[u8]) -> io::Result<Tag> {
+    let id = read_u8(&mut bytes)?;
+    if id != TAG_COMPOUND {
+        return Err(invalid(format!("root tag is {id}, not a compound")));
+    }
+    read_string(&mut bytes)?;
+    read_payload(&mut bytes, id)
+}
+
+/// Writes `tag` (which should be a compound)
+/// as the root of a file, with an empty name.
+pub fn write_root(mut writer: impl Write, tag: &Tag) -> io::Result<()> {
+    writer.write_all(&[tag.id()])?;
+    write_string(&mut writer, "")?;
+    write_payload(&mut writer, tag)
+}
+
+fn read_payload(r: &mut &[u8], id: u8) -> io::Result<Tag> {
+    Ok(match id {
+        1 => Tag::Byte(read_u8(r)? as i8),
+        2 => Tag::Short(i16::from_be_bytes(read_array(r)?)),
+        3 => Tag::Int(read_i32(r)?),
+        4 => Tag::Long(i64::from_be_bytes(read_array(r)?)),
+        5 => Tag::Float(f32::from_be_bytes(read_array(r)?)),
+        6 => Tag::Double(f64::from_be_bytes(read_array(r)?)),
+        7 => Tag::ByteArray(
+            (0..read_len(r)?)
+                .map(|_| read_u8(r).map(|n| n as i8))
+                .collect::<io::Result<_>>()?,
+        ),
+        8 => Tag::String(read_string(r)?),
+        9 => {
+            let element_id = read_u8(r)?;
+            let len = read_len(r)?;
+            if element_id == TAG_END && len > 0 {
+                return Err(invalid("list of end tags".to_owned()));
+            }
+            let elements = (0..len)
+                .map(|_| read_payload(r, element_id))
+                .collect::<io::Result<_>>()?;
+            Tag::List(element_id, elements)
+        }
+        TAG_COMPOUND => {
+            let mut entries = Vec::new();
+            loop {
+                let id = read_u8(r)?;
+                if id == TAG_END {
+                    break;
+                }
+                let name = read_string(r)?;
+                entries.push((name, read_payload(r, id)?));
+            }
+            Tag::Compound(entries)
+        }
+        11 => Tag::IntArray(
+            (0..read_len(r)?)
+                .map(|_| read_i32(r))
+                .collect::<io::Result<_>>()?,
+        ),
+        12 => Tag::LongArray(
+            (0..read_len(r)?)
+                .map(|_| read_array(r).map(i64::from_be_bytes))
+                .collect::<io::Result<_>>()?,
+        ),
+        _ => return Err(invalid(format!("unknown tag type {id}"))),
+    })
+}
+
+fn write_payload(w: &mut impl Write, tag: &Tag) -> io::Result<()> {
+    match tag {
+        Tag::Byte(n) => w.write_all(&n.to_be_bytes()),
+        Tag::Short(n) => w.write_all(&n.to_be_bytes()),
+        Tag::Int(n) => w.write_all(&n.to_be_bytes()),
+        Tag::Long(n) => w.write_all(&n.to_be_bytes()),
+        Tag::Float(n) => w.write_all(&n.to_be_bytes()),
+        Tag::Double(n) => w.write_all(&n.to_be_bytes()),
+        Tag::ByteArray(n) => {
+            write_len(w, n.len())?;
+            n.iter().try_for_each(|n| w.write_all(&n.to_be_bytes()))
+        }
+        Tag::String(s) => write_string(w, s),
+        Tag::List(element_id, elements) => {
+            w.write_all(&[*element_id])?;
+            write_len(w, elements.len())?;
+            elements.iter().try_for_each(|n| write_payload(w, n))
+        }
+        Tag::Compound(entries) => {
+            for (name, tag) in entries {
+                w.write_all(&[tag.id()])?;
+                write_string(w, name)?;
+                write_payload(w, tag)?;
+            }
+            w.write_all(&[TAG_END])
+        }
+        Tag::IntArray(n) => {
+            write_len(w, n.len())?;
+            n.iter().try_for_each(|n| w.write_all(&n.to_be_bytes()))
+        }
+        Tag::LongArray(n) => {
+            write_len(w, n.len())?;
+            n.iter().try_for_each(|n| w.write_all(&n.to_be_bytes()))
+        }
+    }
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid NBT: {msg}"))
+}
+
+fn read_array<const N: usize>(r: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_u8(r: &mut impl Read) -> io::Result<u8> {
+    Ok(read_array::<1>(r)?[0])
+}
+
+fn read_i32(r: &mut impl Read) -> io::Result<i32> {
+    Ok(i32::from_be_bytes(read_array(r)?))
+}
+
+/// Every element takes at least a byte, so a length longer than
+/// what's left of `r` is corrupt (and isn't allocated for).
+fn read_len(r: &mut &[u8]) -> io::Result<usize> {
+    let len = read_i32(r)?;
+    let len = usize::try_from(len).map_err(|_| invalid(format!("negative length {len}")))?;
+    if len > r.len() {
+        return Err(invalid(format!(
+            "length {len} is longer than the rest of the file ({} bytes)",
+            r.len()
+        )));
+    }
+    Ok(len)
+}
+
+fn write_len(w: &mut impl Write, len: usize) -> io::Result<()> {
+    let len = i32::try_from(len).map_err(|_| invalid(format!("length {len} too big")))?;
+    w.write_all(&len.to_be_bytes())
+}
+
+/// Strings are Java's "modified UTF-8": UTF-16 code units,
+/// each encoded like UTF-8 (`\0` takes two bytes).
+fn read_string(r: &mut &[u8]) -> io::Result<String> {
+    let len = u16::from_be_bytes(read_array(r)?);
+    let mut bytes = vec![0; usize::from(len)];
+    r.read_exact(&mut bytes)?;
+
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut bytes = bytes.into_iter();
+    while let Some(b) = bytes.next() {
+        let mut next = || u16::from(bytes.next().unwrap_or(0) & 0x3F);
+        let unit = if b < 0x80 {
+            u16::from(b)
+        } else if b & 0xE0 == 0xC0 {
+            (u16::from(b & 0x1F) << 6) | next()
+        } else if b & 0xF0 == 0xE0 {
+            (u16::from(b & 0x0F) << 12) | (next() << 6) | next()
+        } else {
+            0xFFFD
+        };
+        units.push(unit);
+    }
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn write_string(w: &mut impl Write, s: &str) -> io::Result<()> {
+    let mut bytes = Vec::with_capacity(s.len());
+    for unit in s.encode_utf16() {
+        match unit {
+            0x01..=0x7F => bytes.push(unit as u8),
+            0x00 | 0x80..=0x7FF => {
+                bytes.push(0xC0 | (unit >> 6) as u8);
+                bytes.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                bytes.push(0xE0 | (unit >> 12) as u8);
+                bytes.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                bytes.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+    let len = u16::try_from(bytes.len()).map_err(|_| invalid("string too long".to_owned()))?;
+    w.write_all(&len.to_be_bytes())?;
+    w.write_all(&bytes)
+}
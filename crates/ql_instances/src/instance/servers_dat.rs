@@ -0,0 +1,262 @@
+//! The in-game multiplayer server list,
+//! stored in `.minecraft/servers.dat`.
+
+use std::path::Path;
+
+use ql_core::{Instance, IntoIoError, IoError, JsonFileError};
+
+use super::nbt::{self, Tag};
+
+const FILE_NAME: &str = "servers.dat";
+const SERVERS: &str = "servers";
+
+/// A server saved in the game's multiplayer menu.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerEntry {
+    pub name: String,
+    /// Address, like `mc.example.com` or `127.0.0.1:25565`
+    pub ip: String,
+    /// Anything else the game stores
+    /// (icon, resource pack choice, ...), kept as-is.
+    extra: Vec<(String, Tag)>,
+}
+
+impl ServerEntry {
+    #[must_use]
+    pub fn new(name: String, ip: String) -> Self {
+        Self {
+            name,
+            ip,
+            extra: Vec::new(),
+        }
+    }
+
+    fn from_tag(tag: Tag) -> Option<Self> {
+        let Tag::Compound(entries) = tag else {
+            return None;
+        };
+        let (mut name, mut ip, mut extra) = (None, None, Vec::new());
+        for (key, value) in entries {
+            match (key.as_str(), value) {
+                ("name", Tag::String(n)) => name = Some(n),
+                ("ip", Tag::String(n)) => ip = Some(n),
+                (_, value) => extra.push((key, value)),
+            }
+        }
+        Some(Self {
+            name: name.unwrap_or_default(),
+            ip: ip?,
+            extra,
+        })
+    }
+
+    fn into_tag(self) -> Tag {
+        let mut entries = vec![
+            ("name".to_owned(), Tag::String(self.name)),
+            ("ip".to_owned(), Tag::String(self.ip)),
+        ];
+        entries.extend(self.extra);
+        Tag::Compound(entries)
+    }
+}
+
+/// Reads the instance's saved multiplayer servers,
+/// in the order shown in game.
+///
+/// Returns an empty list if the instance has none yet
+/// (the game creates `servers.dat` when you first add one).
+///
+/// # Errors
+/// If `servers.dat` (or the instance's `config.json`)
+/// couldn't be read or isn't valid.
+pub async fn read_multiplayer_servers(
+    instance: &Instance,
+) -> Result<Vec<ServerEntry>, JsonFileError> {
+    let path = instance.get_game_dir().await?.join(FILE_NAME);
+    Ok(servers_from_root(read_root(&path).await?))
+}
+
+/// Adds a server to the end of the instance's multiplayer list.
+///
+/// # Errors
+/// If `servers.dat` (or the instance's `config.json`)
+/// couldn't be read, parsed or written.
+pub async fn add_multiplayer_server(
+    instance: &Instance,
+    entry: ServerEntry,
+) -> Result<(), JsonFileError> {
+    let path = instance.get_game_dir().await?.join(FILE_NAME);
+    Ok(edit_servers(&path, |servers| servers.push(entry)).await?)
+}
+
+/// Removes every server with address `ip` from
+/// the instance's multiplayer list.
+///
+/// Returns whether anything was removed.
+///
+/// # Errors
+/// If `servers.dat` (or the instance's `config.json`)
+/// couldn't be read, parsed or written.
+pub async fn remove_multiplayer_server(
+    instance: &Instance,
+    ip: &str,
+) -> Result<bool, JsonFileError> {
+    let path = instance.get_game_dir().await?.join(FILE_NAME);
+    let mut removed = false;
+    edit_servers(&path, |servers| {
+        let len = servers.len();
+        servers.retain(|n| n.ip != ip);
+        removed = servers.len() != len;
+    })
+    .await?;
+    Ok(removed)
+}
+
+async fn read_root(path: &Path) -> Result<Tag, IoError> {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => nbt::read_root(bytes.as_slice()).path(path),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Tag::Compound(Vec::new())),
+        Err(err) => Err(err).path(path),
+    }
+}
+
+fn servers_from_root(root: Tag) -> Vec<ServerEntry> {
+    let Tag::Compound(entries) = root else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .find_map(|(key, value)| match value {
+            Tag::List(_, servers) if key == SERVERS => Some(servers),
+            _ => None,
+        })
+        .into_iter()
+        .flatten()
+        .filter_map(ServerEntry::from_tag)
+        .collect()
+}
+
+/// Reads the list, lets `edit` change it, and writes it back,
+/// keeping anything else in the file untouched.
+async fn edit_servers(
+    path: &Path,
+    edit: impl FnOnce(&mut Vec<ServerEntry>),
+) -> Result<(), IoError> {
+    let mut root = read_root(path).await?;
+    let mut servers = servers_from_root(root.clone());
+    edit(&mut servers);
+
+    let list = Tag::List(
+        nbt::TAG_COMPOUND,
+        servers.into_iter().map(ServerEntry::into_tag).collect(),
+    );
+    if let Tag::Compound(entries) = &mut root {
+        if let Some((_, value)) = entries.iter_mut().find(|(key, _)| key == SERVERS) {
+            *value = list;
+        } else {
+            entries.push((SERVERS.to_owned(), list));
+        }
+    }
+
+    let mut bytes = Vec::new();
+    nbt::write_root(&mut bytes, &root).path(path)?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.path(parent)?;
+    }
+    tokio::fs::write(path, bytes).await.path(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `servers.dat` as written by the game,
+    /// with one server (and an icon).
+    fn sample() -> Vec<u8> {
+        let mut bytes = vec![10, 0, 0]; // root compound, empty name
+        bytes.extend([9, 0, 7]);
+        bytes.extend(b"servers");
+        bytes.extend([10, 0, 0, 0, 1]); // list of 1 compound
+        for (key, value) in [
+            ("name", "My Server"),
+            ("ip", "mc.example.com"),
+            ("icon", "iVBORw0KGgo="),
+        ] {
+            bytes.push(8);
+            bytes.extend(u16::try_from(key.len()).unwrap().to_be_bytes());
+            bytes.extend(key.as_bytes());
+            bytes.extend(u16::try_from(value.len()).unwrap().to_be_bytes());
+            bytes.extend(value.as_bytes());
+        }
+        bytes.extend([1, 0, 14]);
+        bytes.extend(b"acceptTextures");
+        bytes.push(1);
+        bytes.push(0); // end of server
+        bytes.push(0); // end of root
+        bytes
+    }
+
+    #[tokio::test]
+    async fn read_and_add_round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join(FILE_NAME);
+        tokio::fs::write(&path, sample()).await.unwrap();
+
+        let servers = servers_from_root(read_root(&path).await.unwrap());
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "My Server");
+        assert_eq!(servers[0].ip, "mc.example.com");
+
+        edit_servers(&path, |servers| {
+            servers.push(ServerEntry::new(
+                "Local \u{1F600}".to_owned(),
+                "127.0.0.1".to_owned(),
+            ));
+        })
+        .await
+        .unwrap();
+
+        let servers = servers_from_root(read_root(&path).await.unwrap());
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[1].name, "Local \u{1F600}");
+        assert_eq!(servers[1].ip, "127.0.0.1");
+        // Icon and other fields survive the rewrite
+        assert_eq!(
+            servers[0].extra,
+            [
+                ("icon".to_owned(), Tag::String("iVBORw0KGgo=".to_owned())),
+                ("acceptTextures".to_owned(), Tag::Byte(1)),
+            ]
+        );
+
+        // Removing it gets back the game's original file
+        edit_servers(&path, |servers| servers.retain(|n| n.ip != "127.0.0.1"))
+            .await
+            .unwrap();
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), sample());
+    }
+
+    #[tokio::test]
+    async fn huge_length_is_rejected() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(FILE_NAME);
+        let mut bytes = vec![10, 0, 0, 9, 0, 7];
+        bytes.extend(b"servers");
+        bytes.extend([10, 0x7F, 0xFF, 0xFF, 0xFF]); // ~2 billion compounds
+        tokio::fs::write(&path, bytes).await.unwrap();
+
+        let error = read_root(&path).await.unwrap_err();
+        assert!(
+            error.to_string().contains("longer than the rest"),
+            "{error}"
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_file_is_empty() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("servers.dat");
+        assert!(servers_from_root(read_root(&path).await.unwrap()).is_empty());
+    }
+}
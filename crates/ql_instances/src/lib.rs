@@ -55,7 +55,20 @@ mod download;
 mod instance;
 mod json_profiles;
 
-pub use download::{DownloadError, create_instance, repeat_stage};
-pub use instance::{launch::launch, list_versions::list_versions, notes};
+pub use download::{
+    CreateOptions, DownloadError, NewInstanceTemplate, create_instance, download_missing_assets,
+    estimate_instance_size, has_assets, repeat_stage,
+};
+pub use instance::{
+    MigrationChange, MigrationReport, ServerEntry, add_multiplayer_server, collect_log_for_sharing,
+    detect_and_migrate, export_diagnostics,
+    launch::{
+        GameLaunchError, LaunchArguments, LaunchOptions, QuickPlay, launch, launch_arguments,
+    },
+    list_versions::{list_versions, new_versions_since},
+    notes, read_multiplayer_servers, remove_multiplayer_server,
+};
 pub use ql_core::jarmod;
-pub use ql_java_handler::delete_java_installs;
+pub use ql_java_handler::{
+    DetectedJava, delete_java_installs, detect_system_java, prune_unused_java,
+};
@@ -0,0 +1,226 @@
+//! Finding Java installs that are already on the system
+//! (not installed by the launcher), so the user can
+//! skip downloading one.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use ql_core::{JavaVersion, file_utils::exists, pt};
+
+use crate::JAVA;
+
+/// A Java install found on the system by [`detect_system_java`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedJava {
+    /// Path to the `java` (or `javaw` on Windows) binary.
+    pub path: PathBuf,
+    /// Major version, for example `8`, `17` or `21`.
+    pub version: u32,
+    /// For example, "Eclipse Adoptium" or "Oracle Corporation".
+    /// Empty if unknown.
+    pub vendor: String,
+}
+
+impl DetectedJava {
+    /// Whether this can be used in place of the
+    /// launcher-managed `version` (exact major version match).
+    #[must_use]
+    pub fn matches(&self, version: JavaVersion) -> bool {
+        self.version == version as u32
+    }
+}
+
+/// Looks for system-installed Java in `JAVA_HOME`, `PATH`,
+/// and the usual install locations for the OS.
+///
+/// Each candidate is run with `-version` to find out what it is,
+/// so broken installs are skipped. Duplicates (like symlinks in
+/// `PATH` to a JDK in `/usr/lib/jvm`) are only listed once.
+pub async fn detect_system_java() -> Vec<DetectedJava> {
+    let mut found = Vec::new();
+    let mut seen = HashSet::new();
+
+    for candidate in candidates().await {
+        let Ok(path) = tokio::fs::canonicalize(&candidate).await else {
+            continue;
+        };
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        if let Some((version, vendor)) = probe(&path).await {
+            pt!("Found system Java {version} ({vendor}): {path:?}");
+            found.push(DetectedJava {
+                path,
+                version,
+                vendor,
+            });
+        }
+    }
+
+    found
+}
+
+async fn candidates() -> Vec<PathBuf> {
+    let bin_name = if cfg!(target_os = "windows") {
+        format!("{JAVA}.exe")
+    } else {
+        JAVA.to_owned()
+    };
+
+    let mut homes = Vec::new();
+    if let Some(java_home) = std::env::var_os("JAVA_HOME") {
+        homes.push(PathBuf::from(java_home));
+    }
+    for dir in install_dirs() {
+        homes.extend(subdirs(Path::new(dir)).await);
+    }
+
+    let mut candidates: Vec<PathBuf> = homes
+        .into_iter()
+        .flat_map(|home| {
+            [
+                home.join("bin").join(&bin_name),
+                // macOS .jdk bundles
+                home.join("Contents/Home/bin").join(&bin_name),
+            ]
+        })
+        .collect();
+
+    if let Some(path) = std::env::var_os("PATH") {
+        candidates.extend(std::env::split_paths(&path).map(|n| n.join(&bin_name)));
+    }
+
+    let mut existing = Vec::new();
+    for candidate in candidates {
+        if exists(&candidate).await {
+            existing.push(candidate);
+        }
+    }
+    existing
+}
+
+/// Folders that usually contain one folder per Java install.
+fn install_dirs() -> &'static [&'static str] {
+    if cfg!(target_os = "windows") {
+        &[
+            r"C:\Program Files\Java",
+            r"C:\Program Files\Eclipse Adoptium",
+            r"C:\Program Files\Zulu",
+            r"C:\Program Files\Microsoft",
+            r"C:\Program Files\BellSoft",
+            r"C:\Program Files (x86)\Java",
+        ]
+    } else if cfg!(target_os = "macos") {
+        &[
+            "/Library/Java/JavaVirtualMachines",
+            "/opt/homebrew/opt",
+            "/usr/local/opt",
+        ]
+    } else {
+        &[
+            "/usr/lib/jvm",
+            "/usr/lib64/jvm",
+            "/usr/java",
+            "/opt/java",
+            "/usr/local/openjdk",
+        ]
+    }
+}
+
+async fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return dirs;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        dirs.push(entry.path());
+    }
+    dirs
+}
+
+/// Runs the binary to get its major version and vendor.
+async fn probe(java: &Path) -> Option<(u32, String)> {
+    let mut command = tokio::process::Command::new(java);
+    command.args(["-XshowSettings:properties", "-version"]);
+    ql_core::no_window!(command);
+    let output = command.output().await.ok()?;
+    // Java prints all of this to stderr
+    parse_version_output(&String::from_utf8_lossy(&output.stderr))
+}
+
+/// Parses the output of `java -XshowSettings:properties -version`
+/// into the major version and vendor.
+fn parse_version_output(output: &str) -> Option<(u32, String)> {
+    let property = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.trim().split_once('=')?;
+            (key.trim() == name).then(|| value.trim().to_owned())
+        })
+    };
+
+    // `openjdk version "17.0.1" 2021-10-19`
+    let version = output
+        .lines()
+        .find(|n| n.contains(" version \""))
+        .and_then(|n| n.split('"').nth(1))
+        .map(str::to_owned)
+        .or_else(|| property("java.version"))?;
+
+    Some((
+        parse_major_version(&version)?,
+        property("java.vendor").unwrap_or_default(),
+    ))
+}
+
+/// Gets the major version out of a Java version string.
+///
+/// - Old format (Java 8 and below): `1.8.0_292` -> 8
+/// - New format: `17.0.1` -> 17, `21` -> 21, `22-ea` -> 22
+fn parse_major_version(version: &str) -> Option<u32> {
+    let mut parts = version.split(['.', '_', '-', '+']);
+    let first: u32 = parts.next()?.parse().ok()?;
+    if first == 1 {
+        parts.next()?.parse().ok()
+    } else {
+        Some(first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_old_and_new_versions() {
+        assert_eq!(parse_major_version("1.8.0"), Some(8));
+        assert_eq!(parse_major_version("1.8.0_292"), Some(8));
+        assert_eq!(parse_major_version("17.0.1"), Some(17));
+        assert_eq!(parse_major_version("21"), Some(21));
+        assert_eq!(parse_major_version("22-ea"), Some(22));
+        assert_eq!(parse_major_version("garbage"), None);
+
+        let java_8 = r#"Property settings:
+    java.vendor = Oracle Corporation
+    java.version = 1.8.0_292
+
+java version "1.8.0_292"
+Java(TM) SE Runtime Environment (build 1.8.0_292-b10)"#;
+        assert_eq!(
+            parse_version_output(java_8),
+            Some((8, "Oracle Corporation".to_owned()))
+        );
+
+        let java_17 = r#"Property settings:
+    java.vendor = Eclipse Adoptium
+    java.version = 17.0.1
+
+openjdk version "17.0.1" 2021-10-19
+OpenJDK Runtime Environment Temurin-17.0.1+12 (build 17.0.1+12)"#;
+        assert_eq!(
+            parse_version_output(java_17),
+            Some((17, "Eclipse Adoptium".to_owned()))
+        );
+    }
+}
@@ -65,17 +65,21 @@ use thiserror::Error;
 use tokio::fs;
 
 use ql_core::{
-    GenericProgress, IntoIoError, IoError, JsonDownloadError, JsonError, LAUNCHER_DIR,
-    RequestError,
+    CancellationToken, Cancelled, GenericProgress, IntoIoError, IoError, JsonDownloadError,
+    JsonError, LAUNCHER_DIR, RequestError,
     constants::OS_NAME,
     do_jobs_with_limit, err,
     file_utils::{self, DirItem, canonicalize_a, exists, extract_tar_gz},
     info, pt,
 };
 
+pub use detect::{DetectedJava, detect_system_java};
+pub use prune::prune_unused_java;
 pub use ql_core::JavaVersion;
 mod alternate_java;
+mod detect;
 mod json;
+mod prune;
 
 #[allow(dead_code)]
 const fn which_java() -> &'static str {
@@ -146,17 +150,26 @@ pub async fn get_java_binary(
     java_install_progress_sender: Option<&Sender<GenericProgress>>,
 ) -> Result<PathBuf, JavaInstallError> {
     let java_dir = LAUNCHER_DIR.join("java_installs").join(version.to_string());
-    let is_incomplete_install = exists(java_dir.join("install.lock")).await;
 
-    if !exists(&java_dir).await || is_incomplete_install {
+    if !is_java_installed(version).await {
         info!("Installing Java: {version}");
-        install_java(version, java_install_progress_sender).await?;
+        install_java(version, java_install_progress_sender, None).await?;
     }
 
     let bin_path = find_java_bin_in_dir(name, &java_dir).await?;
     Ok(canonicalize_a(&bin_path).await)
 }
 
+/// Checks whether `version` has been (fully) auto-installed
+/// to `QuantumLauncher/java_installs/`.
+///
+/// Interrupted installs (with an `install.lock` left behind)
+/// don't count, they get reinstalled by [`get_java_binary`].
+pub async fn is_java_installed(version: JavaVersion) -> bool {
+    let java_dir = LAUNCHER_DIR.join("java_installs").join(version.to_string());
+    exists(&java_dir).await && !exists(java_dir.join("install.lock")).await
+}
+
 /// Intelligently searches the given path for the given Java binary name, and returns a `PathBuf` to if found.
 ///
 /// # Errors
@@ -211,20 +224,54 @@ const CONCURRENCY_LIMIT: usize = 16;
 #[cfg(not(target_os = "macos"))]
 const CONCURRENCY_LIMIT: usize = 64;
 
-async fn install_java(
+/// Installs Java, even if it's already installed
+/// (overwriting the existing files).
+///
+/// Usually you want [`get_java_binary`] instead,
+/// which only installs Java if needed.
+///
+/// If `cancel` is cancelled (see [`CancellationToken`]), this stops
+/// between files, deletes the partial installation and returns
+/// [`JavaInstallError::Cancelled`].
+///
+/// # Errors
+/// If the Java installation fails, see [`JavaInstallError`].
+pub async fn install_java(
     version: JavaVersion,
     java_install_progress_sender: Option<&Sender<GenericProgress>>,
+    cancel: Option<&CancellationToken>,
 ) -> Result<(), JavaInstallError> {
     let install_dir = get_install_dir(version).await?;
-    let lock_file = lock_init(&install_dir).await?;
+    let cancel = cancel.cloned().unwrap_or_default();
+
+    match install_java_to(version, java_install_progress_sender, &install_dir, &cancel).await {
+        Err(JavaInstallError::Cancelled(n)) => {
+            info!("Cancelled installing {version}, cleaning up");
+            tokio::fs::remove_dir_all(&install_dir)
+                .await
+                .path(&install_dir)?;
+            Err(JavaInstallError::Cancelled(n))
+        }
+        result => result,
+    }
+}
+
+async fn install_java_to(
+    version: JavaVersion,
+    java_install_progress_sender: Option<&Sender<GenericProgress>>,
+    install_dir: &Path,
+    cancel: &CancellationToken,
+) -> Result<(), JavaInstallError> {
+    let lock_file = lock_init(install_dir).await?;
 
     send_progress(java_install_progress_sender, GenericProgress::default());
 
     let java_list_json = JavaListJson::download().await?;
+    cancel.check()?;
     let Some(java_files_url) = java_list_json.get_url(version) else {
         // Mojang doesn't officially provide java for som platforms.
         // In that case, fetch from alternate sources.
-        alternate_java::install(version, java_install_progress_sender, &install_dir).await?;
+        alternate_java::install(version, java_install_progress_sender, install_dir).await?;
         lock_finish(&lock_file).await?;
         return Ok(());
     };
@@ -241,8 +288,9 @@ async fn install_java(
                 &file_num,
                 num_files,
                 file_name,
-                &install_dir,
+                install_dir,
                 file,
+                cancel,
             )
         }),
         CONCURRENCY_LIMIT,
@@ -297,7 +345,9 @@ async fn java_install_fn(
     file_name: &str,
     install_dir: &Path,
     file: &JavaFile,
+    cancel: &CancellationToken,
 ) -> Result<(), JavaInstallError> {
+    cancel.check()?;
     let file_path = install_dir.join(file_name);
     match file {
         JavaFile::file {
@@ -418,6 +468,8 @@ at: {path:?}
         "{ERR_PREF1}{OS_NAME} {ARCH}):\nunknown extension for java: {0}\n\nThis is a bug, please report on discord!"
     )]
     UnknownExtension(String),
+    #[error("{0}")]
+    Cancelled(#[from] Cancelled),
 }
 
 /// Deletes all the auto-installed Java installations.
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use ql_core::{
+    InstanceConfigJson, InstanceKind, IntoIoError, IoError, JavaVersion, LAUNCHER_DIR,
+    file_utils::{exists, read_filenames_from_dir},
+    info,
+    json::VersionDetails,
+    pt,
+};
+
+/// Deletes auto-installed Java versions (in `QuantumLauncher/java_installs/`)
+/// that no instance or server needs, returning the removed ones.
+///
+/// - `keep_if_used`: Keep the versions that instances need
+///   (from their version's `javaVersion`, or their
+///   Java version override). If `false`, every install is removed,
+///   like [`crate::delete_java_installs`].
+///
+/// Installs that are still being installed (have an `install.lock`)
+/// are left alone. Anything removed by mistake (for example, needed
+/// by a broken instance whose `details.json` can't be read) is
+/// automatically reinstalled by [`crate::get_java_binary`] when needed.
+///
+/// # Errors
+/// If the instance folders couldn't be read
+/// or an install couldn't be deleted.
+pub async fn prune_unused_java(keep_if_used: bool) -> Result<Vec<JavaVersion>, IoError> {
+    prune_in(&LAUNCHER_DIR, keep_if_used).await
+}
+
+async fn prune_in(launcher_dir: &Path, keep_if_used: bool) -> Result<Vec<JavaVersion>, IoError> {
+    let installs_dir = launcher_dir.join("java_installs");
+    if !exists(&installs_dir).await {
+        return Ok(Vec::new());
+    }
+    let needed = if keep_if_used {
+        needed_versions(launcher_dir).await?
+    } else {
+        Vec::new()
+    };
+
+    let mut removed = Vec::new();
+    for &version in JavaVersion::ALL {
+        let dir = installs_dir.join(version.to_string());
+        if needed.contains(&version) || !exists(&dir).await {
+            continue;
+        }
+        if exists(dir.join("install.lock")).await {
+            pt!("Skipping {version}: still being installed");
+            continue;
+        }
+        info!("Removing unused Java install: {version}");
+        tokio::fs::remove_dir_all(&dir).await.path(&dir)?;
+        removed.push(version);
+    }
+    Ok(removed)
+}
+
+async fn needed_versions(launcher_dir: &Path) -> Result<Vec<JavaVersion>, IoError> {
+    let mut needed = Vec::new();
+    for kind in [InstanceKind::Client, InstanceKind::Server] {
+        let root = launcher_dir.join(kind.get_dir_name());
+        if !exists(&root).await {
+            continue;
+        }
+        for entry in read_filenames_from_dir(&root).await? {
+            if entry.is_file {
+                continue;
+            }
+            let dir = root.join(&entry.name);
+            let Ok(details) = VersionDetails::load_from_path(&dir).await else {
+                continue;
+            };
+            // Same choice as when launching
+            let version = match InstanceConfigJson::read_from_dir(&dir).await {
+                Ok(config) => config.get_java_version(&details),
+                Err(_) => details.javaVersion.map_or(JavaVersion::Java8, Into::into),
+            };
+            if !needed.contains(&version) {
+                needed.push(version);
+            }
+        }
+    }
+    Ok(needed)
+}
+
+#[cfg(test)]
+mod tests {
+    use ql_core::test_utils::version_json;
+
+    use super::*;
+
+    fn details(java: Option<usize>) -> String {
+        let mut json = version_json("1.21", "2024-01-01T00:00:00+00:00");
+        if let Some(java) = java {
+            json["javaVersion"] =
+                serde_json::json!({ "component": "java-runtime", "majorVersion": java });
+        }
+        json.to_string()
+    }
+
+    #[tokio::test]
+    async fn prunes_unreferenced_installs() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let files = [
+            ("instances/Modern/details.json", details(Some(21))),
+            ("java_installs/java_21/release", String::new()),
+            ("java_installs/java_8/release", String::new()),
+            // Being installed right now
+            ("java_installs/java_17/install.lock", String::new()),
+        ];
+        for (path, contents) in files {
+            let path = dir.join(path);
+            tokio::fs::create_dir_all(path.parent().unwrap())
+                .await
+                .unwrap();
+            tokio::fs::write(&path, contents).await.unwrap();
+        }
+
+        let removed = prune_in(dir, true).await.unwrap();
+        assert_eq!(removed, [JavaVersion::Java8]);
+        assert!(!dir.join("java_installs/java_8").exists());
+        assert!(dir.join("java_installs/java_21").exists());
+        assert!(dir.join("java_installs/java_17").exists());
+
+        // Old versions without `javaVersion` use Java 8
+        tokio::fs::write(dir.join("instances/Modern/details.json"), details(None))
+            .await
+            .unwrap();
+        assert_eq!(needed_versions(dir).await.unwrap(), [JavaVersion::Java8]);
+
+        // The instance's Java override replaces the one it'd use
+        tokio::fs::write(
+            dir.join("instances/Modern/config.json"),
+            r#"{ "mod_type": "Vanilla", "ram_in_mb": 2048, "java_override_version": 17 }"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(needed_versions(dir).await.unwrap(), [JavaVersion::Java17]);
+        tokio::fs::remove_file(dir.join("instances/Modern/config.json"))
+            .await
+            .unwrap();
+
+        assert_eq!(prune_in(dir, false).await.unwrap(), [JavaVersion::Java21]);
+    }
+}
@@ -49,6 +49,8 @@ pub enum ForgeInstallError {
     Zip(#[from] zip::result::ZipError),
     #[error("{FORGE_INSTALL_ERR_PREFIX}couldn't read file {1} from zip:\n{0}")]
     ZipIoError(std::io::Error, String),
+    #[error("{FORGE_INSTALL_ERR_PREFIX}{0}")]
+    JarMod(#[from] ql_core::jarmod::JarModError),
 }
 
 impl_3_errs_jri!(ForgeInstallError, Json, Request, Io);
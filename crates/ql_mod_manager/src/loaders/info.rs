@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use ql_core::{
+    Instance, JsonFileError, Loader,
+    json::{FabricJSON, InstanceConfigJson, VersionDetails},
+};
+
+/// What's installed in an instance, from [`get_loader_info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoaderInfo {
+    pub loader: Loader,
+    /// Version of the loader (eg: `0.15.11` for Fabric, `47.2.0` for Forge).
+    ///
+    /// `None` for vanilla, or if it couldn't be found out.
+    pub version: Option<String>,
+    /// Version of the game (eg: `1.20.1`)
+    pub mc_version: String,
+}
+
+/// Finds out which loader (and which version of it)
+/// is installed in an instance, and for which game version.
+///
+/// The loader version is the one saved in the config when installing.
+/// Fabric/Quilt instances from before it was saved get it
+/// from the loader library in their `fabric.json`.
+///
+/// # Errors
+/// If the instance's `config.json` or `details.json`
+/// couldn't be read or parsed.
+pub async fn get_loader_info(instance: &Instance) -> Result<LoaderInfo, JsonFileError> {
+    get_loader_info_in(&instance.get_instance_path()).await
+}
+
+async fn get_loader_info_in(instance_dir: &Path) -> Result<LoaderInfo, JsonFileError> {
+    let config = InstanceConfigJson::read_from_dir(instance_dir).await?;
+    let details = VersionDetails::load_from_path(instance_dir).await?;
+
+    let mut version = config.mod_type_info.and_then(|n| n.version);
+    if version.is_none() {
+        version = match config.mod_type {
+            Loader::Fabric => {
+                fabric_library_version(instance_dir, "net.fabricmc:fabric-loader:").await
+            }
+            Loader::Quilt => {
+                fabric_library_version(instance_dir, "org.quiltmc:quilt-loader:").await
+            }
+            _ => None,
+        };
+    }
+    Ok(LoaderInfo {
+        loader: config.mod_type,
+        version: version.filter(|_| !config.mod_type.is_vanilla()),
+        mc_version: details.get_id().to_owned(),
+    })
+}
+
+async fn fabric_library_version(instance_dir: &Path, prefix: &str) -> Option<String> {
+    let json = tokio::fs::read_to_string(instance_dir.join("fabric.json"))
+        .await
+        .ok()?;
+    let json: FabricJSON = serde_json::from_str(&json).ok()?;
+    json.libraries
+        .iter()
+        .find_map(|n| n.name.strip_prefix(prefix))
+        .map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use ql_core::{
+        InstanceKind,
+        json::instance_config::{ModTypeInfo, VersionInfo},
+        test_utils::version_json,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fabric_instance() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let details = version_json("1.20.1", "2023-06-12T00:00:00+00:00");
+        tokio::fs::write(dir.join("details.json"), details.to_string())
+            .await
+            .unwrap();
+
+        let mut config =
+            InstanceConfigJson::new(InstanceKind::Client, false, VersionInfo::new("1.20.1"));
+        config.mod_type = Loader::Fabric;
+        config.mod_type_info = Some(ModTypeInfo::new_regular("0.15.11".to_owned()));
+        config.save_to_dir(dir).await.unwrap();
+
+        let fabric = LoaderInfo {
+            loader: Loader::Fabric,
+            version: Some("0.15.11".to_owned()),
+            mc_version: "1.20.1".to_owned(),
+        };
+        assert_eq!(get_loader_info_in(dir).await.unwrap(), fabric);
+
+        // Older installs, without the version in the config
+        config.mod_type_info = None;
+        config.save_to_dir(dir).await.unwrap();
+        assert_eq!(get_loader_info_in(dir).await.unwrap().version, None);
+        tokio::fs::write(
+            dir.join("fabric.json"),
+            r#"{"mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient", "libraries": [
+                {"name": "org.ow2.asm:asm:9.6", "url": "https://maven.fabricmc.net/"},
+                {"name": "net.fabricmc:fabric-loader:0.15.11", "url": "https://maven.fabricmc.net/"}
+            ]}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(get_loader_info_in(dir).await.unwrap(), fabric);
+    }
+}
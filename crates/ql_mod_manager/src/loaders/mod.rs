@@ -10,14 +10,21 @@ use crate::loaders::paper::PaperVer;
 use forge::ForgeInstallProgress;
 use ql_core::{
     GenericProgress, Instance, IntoStringError, JsonFileError, Loader, Progress,
-    json::{InstanceConfigJson, instance_config::ModTypeInfo},
+    json::{InstanceConfigJson, VersionDetails, instance_config::ModTypeInfo},
 };
 
 pub mod fabric;
 pub mod forge;
+mod info;
 pub mod neoforge;
+mod optifabric;
 pub mod optifine;
 pub mod paper;
+mod switch;
+
+pub use info::{LoaderInfo, get_loader_info};
+pub use optifabric::install_optifabric;
+pub use switch::set_loader;
 
 pub(crate) const FORGE_INSTALLER_CLIENT: &[u8] =
     include_bytes!("../../../../assets/installers/forge/ForgeInstaller.class");
@@ -36,11 +43,65 @@ async fn change_instance_type(
     Ok(())
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LoaderInstallResult {
     Ok,
     NeedsOptifine,
-    Unsupported,
+    Unsupported(UnsupportedReason),
+}
+
+/// Why a loader can't be installed on an instance,
+/// see [`LoaderInstallResult::Unsupported`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnsupportedReason {
+    /// Server-only loader (like Paper) on a client
+    ServerOnly(Loader),
+    /// Client-only loader (like OptiFine) on a server
+    ClientOnly(Loader),
+    /// The launcher can't install this loader (yet)
+    NotImplemented(Loader),
+    /// The loader has no builds for this Minecraft version
+    Version { loader: Loader, mc_version: String },
+}
+
+impl std::fmt::Display for UnsupportedReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnsupportedReason::ServerOnly(loader) => write!(f, "{loader} only works on servers"),
+            UnsupportedReason::ClientOnly(loader) => {
+                write!(f, "{loader} isn't supported on servers")
+            }
+            UnsupportedReason::NotImplemented(loader) => {
+                write!(f, "{loader} isn't supported by the launcher yet")
+            }
+            UnsupportedReason::Version { loader, mc_version } => {
+                write!(f, "{loader} has no build for Minecraft {mc_version}")
+            }
+        }
+    }
+}
+
+/// Checks what can be known without downloading anything
+/// (the installers find out the rest).
+fn check_support(
+    loader: Loader,
+    is_server: bool,
+    version_json: &VersionDetails,
+) -> Option<UnsupportedReason> {
+    match loader {
+        Loader::Paper if !is_server => Some(UnsupportedReason::ServerOnly(loader)),
+        Loader::OptiFine if is_server => Some(UnsupportedReason::ClientOnly(loader)),
+        Loader::Liteloader | Loader::Modloader | Loader::Rift => {
+            Some(UnsupportedReason::NotImplemented(loader))
+        }
+        Loader::Neoforge if !version_json.is_after_or_eq(neoforge::V_1_20_2) => {
+            Some(UnsupportedReason::Version {
+                loader,
+                mc_version: version_json.get_id().to_owned(),
+            })
+        }
+        _ => None,
+    }
 }
 
 pub async fn install_specified_loader(
@@ -49,31 +110,37 @@ pub async fn install_specified_loader(
     progress: Option<Arc<Sender<GenericProgress>>>,
     specified_version: Option<String>,
 ) -> Result<LoaderInstallResult, String> {
+    if loader.is_vanilla() {
+        return Ok(LoaderInstallResult::Ok);
+    }
+    let version_json = VersionDetails::load(&instance).await.strerr()?;
+    if let Some(reason) = check_support(loader, instance.is_server(), &version_json) {
+        return Ok(LoaderInstallResult::Unsupported(reason));
+    }
+    let no_build = || {
+        Ok(LoaderInstallResult::Unsupported(
+            UnsupportedReason::Version {
+                loader,
+                mc_version: version_json.get_id().to_owned(),
+            },
+        ))
+    };
+
     match loader {
-        Loader::Vanilla => {}
-        Loader::Fabric => {
+        Loader::Fabric | Loader::Quilt => {
             // TODO: Add legacy fabric support
-            fabric::install(
-                specified_version,
-                instance,
-                progress.as_deref(),
-                fabric::BackendType::Fabric,
-            )
-            .await
-            .strerr()?;
-        }
-        Loader::Quilt => {
-            fabric::install(
-                specified_version,
-                instance,
-                progress.as_deref(),
-                fabric::BackendType::Quilt,
-            )
-            .await
-            .strerr()?;
+            let backend = if let Loader::Quilt = loader {
+                fabric::BackendType::Quilt
+            } else {
+                fabric::BackendType::Fabric
+            };
+            match fabric::install(specified_version, instance, progress.as_deref(), backend).await {
+                Err(fabric::FabricInstallError::NoVersionFound) => return no_build(),
+                n => n.strerr()?,
+            }
         }
 
-        Loader::Forge => {
+        Loader::Forge | Loader::Neoforge => {
             let (send, recv) = std::sync::mpsc::channel();
             if let Some(progress) = progress {
                 std::thread::spawn(move || {
@@ -82,27 +149,21 @@ pub async fn install_specified_loader(
             }
 
             // TODO: Java install progress
-            forge::install(specified_version, instance, Some(send), None)
-                .await
-                .strerr()?;
-        }
-        Loader::Neoforge => {
-            let (send, recv) = std::sync::mpsc::channel();
-            if let Some(progress) = progress {
-                std::thread::spawn(move || {
-                    pipe_progress(recv, &progress);
-                });
+            let result = if let Loader::Forge = loader {
+                forge::install(specified_version, instance, Some(send), None).await
+            } else {
+                neoforge::install(specified_version, instance, Some(send), None).await
+            };
+            match result {
+                Err(
+                    forge::ForgeInstallError::NoForgeVersionFound
+                    | forge::ForgeInstallError::NeoforgeOutdatedMinecraft,
+                ) => return no_build(),
+                n => n.strerr()?,
             }
-
-            neoforge::install(specified_version, instance, Some(send), None)
-                .await
-                .strerr()?;
         }
 
         Loader::Paper => {
-            if !instance.is_server() {
-                return Ok(LoaderInstallResult::Unsupported);
-            }
             paper::install(
                 instance.get_name().to_owned(),
                 if let Some(s) = specified_version {
@@ -115,17 +176,9 @@ pub async fn install_specified_loader(
             .strerr()?;
         }
 
-        Loader::OptiFine => {
-            return Ok(if instance.is_server() {
-                LoaderInstallResult::Unsupported
-            } else {
-                LoaderInstallResult::NeedsOptifine
-            });
-        }
+        Loader::OptiFine => return Ok(LoaderInstallResult::NeedsOptifine),
 
-        Loader::Liteloader | Loader::Modloader | Loader::Rift => {
-            return Ok(LoaderInstallResult::Unsupported);
-        }
+        Loader::Vanilla | Loader::Liteloader | Loader::Modloader | Loader::Rift => {}
     }
     Ok(LoaderInstallResult::Ok)
 }
@@ -152,3 +205,44 @@ pub async fn uninstall_loader(instance: Instance) -> Result<(), String> {
         Loader::Liteloader | Loader::Modloader | Loader::Rift | Loader::Vanilla => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ql_core::test_utils::version_details;
+
+    use super::*;
+
+    #[test]
+    fn unsupported_reasons() {
+        let modern = version_details("1.21.1", "2024-08-08T12:24:45+00:00");
+        assert_eq!(
+            check_support(Loader::OptiFine, true, &modern),
+            Some(UnsupportedReason::ClientOnly(Loader::OptiFine))
+        );
+        assert_eq!(check_support(Loader::OptiFine, false, &modern), None);
+        assert_eq!(
+            check_support(Loader::Paper, false, &modern),
+            Some(UnsupportedReason::ServerOnly(Loader::Paper))
+        );
+        assert_eq!(
+            check_support(Loader::Rift, false, &modern),
+            Some(UnsupportedReason::NotImplemented(Loader::Rift))
+        );
+        assert_eq!(check_support(Loader::Neoforge, false, &modern), None);
+
+        let old = version_details("1.16.5", "2021-01-14T16:05:32+00:00");
+        let reason = check_support(Loader::Neoforge, false, &old);
+        assert_eq!(
+            reason,
+            Some(UnsupportedReason::Version {
+                loader: Loader::Neoforge,
+                mc_version: "1.16.5".to_owned()
+            })
+        );
+        assert_eq!(
+            reason.unwrap().to_string(),
+            "NeoForge has no build for Minecraft 1.16.5"
+        );
+        assert_eq!(check_support(Loader::Forge, false, &old), None);
+    }
+}
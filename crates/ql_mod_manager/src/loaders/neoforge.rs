@@ -16,6 +16,9 @@ use crate::loaders::change_instance_type;
 
 use super::forge::{ForgeInstallError, ForgeInstallProgress};
 
+/// The first Minecraft version NeoForge supports
+pub(super) const V_1_20_2: &str = "2023-09-20T09:02:57+00:00";
+
 const NEOFORGE_VERSIONS_URL: &str =
     "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged/neoforge";
 
@@ -240,7 +243,7 @@ pub async fn get_versions(
     let version_json = VersionDetails::load(&instance_selection).await?;
     let release_time = DateTime::parse_from_rfc3339(&version_json.releaseTime)?;
 
-    let v1_20_2 = DateTime::parse_from_rfc3339("2023-09-20T09:02:57+00:00")?;
+    let v1_20_2 = DateTime::parse_from_rfc3339(V_1_20_2)?;
     if release_time < v1_20_2 {
         return Err(ForgeInstallError::NeoforgeOutdatedMinecraft);
     }
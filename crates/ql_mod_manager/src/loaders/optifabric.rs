@@ -0,0 +1,129 @@
+use std::{path::Path, sync::mpsc::Sender};
+
+use ql_core::{GenericProgress, Instance, Loader, info, json::InstanceConfigJson};
+
+use crate::store::{ModError, ModId, download_mod};
+
+/// <https://www.curseforge.com/minecraft/mc-mods/optifabric>
+const OPTIFABRIC_ID: &str = "322385";
+
+/// Installs [OptiFabric](https://www.curseforge.com/minecraft/mc-mods/optifabric)
+/// to a Fabric instance that has OptiFine in its `mods` folder,
+/// so OptiFine can run alongside Fabric.
+///
+/// The latest OptiFabric version for the instance's
+/// Minecraft version is picked, like any other mod.
+///
+/// Forge instances don't need this, since
+/// OptiFine itself is a Forge mod (see [`super::optifine::install`]).
+///
+/// # Errors
+/// - The instance isn't a Fabric client
+/// - OptiFine isn't in the `mods` folder
+/// - OptiFabric doesn't support this Minecraft version
+/// - OptiFabric couldn't be downloaded
+pub async fn install_optifabric(
+    instance: &Instance,
+    sender: Option<Sender<GenericProgress>>,
+) -> Result<(), ModError> {
+    if instance.is_server() {
+        return Err(ModError::OptifabricServer);
+    }
+    check_optifabric(
+        &instance.get_instance_path(),
+        &instance.get_game_dir().await?,
+    )
+    .await?;
+    info!("Installing OptiFabric");
+
+    let not_allowed = download_mod(
+        &ModId::Curseforge(OPTIFABRIC_ID.to_owned()),
+        instance,
+        sender,
+        None,
+    )
+    .await?;
+    if let Some(n) = not_allowed.into_iter().next() {
+        return Err(ModError::CurseforgeModNotAllowedForDownload(n.name, n.slug));
+    }
+    info!("Installed OptiFabric");
+    Ok(())
+}
+
+async fn check_optifabric(instance_dir: &Path, dot_minecraft: &Path) -> Result<(), ModError> {
+    let config = InstanceConfigJson::read_from_dir(instance_dir).await?;
+    if config.mod_type != Loader::Fabric {
+        return Err(ModError::OptifabricNeedsFabric(config.mod_type));
+    }
+    if !has_optifine(&config, dot_minecraft).await {
+        return Err(ModError::OptifineNotInstalled);
+    }
+    Ok(())
+}
+
+/// Whether an OptiFine jar is in the `mods` folder
+/// (either added through the launcher, or manually).
+async fn has_optifine(config: &InstanceConfigJson, dot_minecraft: &Path) -> bool {
+    let mods_dir = dot_minecraft.join("mods");
+    if let Some(jar) = config
+        .mod_type_info
+        .as_ref()
+        .and_then(|n| n.optifine_jar.as_deref())
+    {
+        if mods_dir.join(jar).is_file() {
+            return true;
+        }
+    }
+
+    let Ok(mut entries) = tokio::fs::read_dir(&mods_dir).await else {
+        return false;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        if name.starts_with("optifine") && name.ends_with(".jar") {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn needs_fabric_and_optifine() {
+        let temp = tempfile::tempdir().unwrap();
+        let instance_dir = temp.path();
+        let dot_minecraft = instance_dir.join(".minecraft");
+        let mods_dir = dot_minecraft.join("mods");
+        tokio::fs::create_dir_all(&mods_dir).await.unwrap();
+        let config_path = instance_dir.join("config.json");
+
+        tokio::fs::write(&config_path, r#"{"ram_in_mb":2048,"mod_type":"Forge"}"#)
+            .await
+            .unwrap();
+        let result = check_optifabric(instance_dir, &dot_minecraft).await;
+        assert!(
+            matches!(result, Err(ModError::OptifabricNeedsFabric(Loader::Forge))),
+            "{result:?}"
+        );
+
+        tokio::fs::write(&config_path, r#"{"ram_in_mb":2048,"mod_type":"Fabric"}"#)
+            .await
+            .unwrap();
+        let result = check_optifabric(instance_dir, &dot_minecraft).await;
+        assert!(
+            matches!(result, Err(ModError::OptifineNotInstalled)),
+            "{result:?}"
+        );
+
+        // Added manually
+        tokio::fs::write(mods_dir.join("OptiFine_1.20.1_HD_U_I6.jar"), "")
+            .await
+            .unwrap();
+        check_optifabric(instance_dir, &dot_minecraft)
+            .await
+            .unwrap();
+    }
+}
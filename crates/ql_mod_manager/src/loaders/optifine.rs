@@ -365,6 +365,8 @@ pub enum OptifineError {
     Json(#[from] JsonError),
     #[error("OptiFine only supports clients, not servers")]
     DoesntSupportServer,
+    #[error("{OPTIFINE_ERR_PREFIX}{0}")]
+    JarMod(#[from] jarmod::JarModError),
 }
 
 impl_3_errs_jri!(OptifineError, Json, Request, Io);
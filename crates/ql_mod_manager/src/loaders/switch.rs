@@ -0,0 +1,304 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, mpsc::Sender},
+};
+
+use ql_core::{
+    GenericProgress, Instance, IntoIoError, IntoStringError, IoError, Loader, err,
+    file_utils::{self, exists},
+    find_forge_shim_file, info,
+    json::VersionDetails,
+};
+
+use super::{
+    LoaderInstallResult, check_support, get_loader_info, install_specified_loader, uninstall_loader,
+};
+
+/// Switches an instance to another loader (or back to vanilla,
+/// with [`Loader::Vanilla`]), uninstalling the current one first.
+///
+/// - `version`: The loader version to install, or `None` for the latest
+///
+/// Unsupported loaders are reported before anything is touched.
+/// [`LoaderInstallResult::NeedsOptifine`] is returned as-is,
+/// with the old loader removed, for the caller to ask
+/// for the OptiFine installer.
+///
+/// If the new loader couldn't be installed (error, or
+/// unsupported), the instance's config and the old loader's
+/// files are put back as they were, and the reason is returned.
+///
+/// # Errors
+/// - The current loader couldn't be found out or uninstalled
+/// - The new loader couldn't be installed
+///   (the error also says if restoring the old one failed)
+pub async fn set_loader(
+    instance: &Instance,
+    loader: Loader,
+    version: Option<String>,
+    progress: Option<Arc<Sender<GenericProgress>>>,
+) -> Result<LoaderInstallResult, String> {
+    let previous = get_loader_info(instance).await.strerr()?;
+    let version_json = VersionDetails::load(instance).await.strerr()?;
+    if let Some(reason) = check_support(loader, instance.is_server(), &version_json) {
+        return Ok(LoaderInstallResult::Unsupported(reason));
+    }
+    set_loader_with(
+        &instance.get_instance_path(),
+        (previous.loader, loader),
+        || uninstall_loader(instance.clone()),
+        || install_specified_loader(instance.clone(), loader, progress.clone(), version),
+    )
+    .await
+}
+
+async fn set_loader_with<UFut, IFut>(
+    instance_dir: &Path,
+    (old_loader, loader): (Loader, Loader),
+    uninstall: impl FnOnce() -> UFut,
+    install: impl FnOnce() -> IFut,
+) -> Result<LoaderInstallResult, String>
+where
+    UFut: Future<Output = Result<(), String>>,
+    IFut: Future<Output = Result<LoaderInstallResult, String>>,
+{
+    info!("Switching loader: {old_loader} -> {loader}");
+    let snapshot = LoaderSnapshot::take(instance_dir, &[old_loader, loader])
+        .await
+        .strerr()?;
+
+    let result = match uninstall().await {
+        Ok(()) => install().await,
+        Err(error) => Err(error),
+    };
+    if let Ok(LoaderInstallResult::Ok | LoaderInstallResult::NeedsOptifine) = result {
+        snapshot.discard().await;
+        return result;
+    }
+
+    err!("Couldn't install {loader}, restoring {old_loader}");
+    let Err(restore_error) = snapshot.restore().await else {
+        return result;
+    };
+    let error = match result {
+        Ok(LoaderInstallResult::Unsupported(reason)) => reason.to_string(),
+        Ok(_) => format!("{loader} can't be installed automatically"),
+        Err(error) => error,
+    };
+    Err(format!(
+        "{error}\n\nAlso couldn't restore the previous loader ({old_loader}):\n{restore_error}"
+    ))
+}
+
+/// Files (relative to the instance dir) that
+/// (un)installing `loader` may add, change or remove.
+fn loader_files(loader: Loader) -> &'static [&'static str] {
+    match loader {
+        Loader::Fabric | Loader::Quilt => &[
+            "fabric.json",
+            "libraries",
+            ".minecraft/.fabric",
+            "fabric-server-launch.jar",
+            "fabric-server-launcher.properties",
+        ],
+        Loader::Forge | Loader::Neoforge => &[
+            "forge",
+            "libraries",
+            "run.sh",
+            "run.bat",
+            "user_jvm_args.txt",
+            "README.txt",
+        ],
+        Loader::OptiFine => &["optifine", ".minecraft/libraries", ".minecraft/versions"],
+        Loader::Paper => &["paper_server.jar", "world", "world_nether", "world_the_end"],
+        Loader::Vanilla | Loader::Liteloader | Loader::Modloader | Loader::Rift => &[],
+    }
+}
+
+/// A copy of an instance's config and loader files,
+/// taken before switching loaders.
+struct LoaderSnapshot {
+    instance_dir: PathBuf,
+    backup_dir: PathBuf,
+    /// Relative paths, and whether they existed
+    paths: Vec<(PathBuf, bool)>,
+}
+
+impl LoaderSnapshot {
+    async fn take(instance_dir: &Path, loaders: &[Loader]) -> Result<Self, IoError> {
+        let backup_dir = instance_dir.join(".loader_switch_backup");
+        if exists(&backup_dir).await {
+            tokio::fs::remove_dir_all(&backup_dir)
+                .await
+                .path(&backup_dir)?;
+        }
+
+        let mut paths: Vec<PathBuf> = ["config.json", "details.json"]
+            .into_iter()
+            .chain(loaders.iter().flat_map(|n| loader_files(*n)).copied())
+            .map(PathBuf::from)
+            .collect();
+        if let Some(shim) = find_forge_shim_file(instance_dir).await {
+            paths.extend(shim.file_name().map(PathBuf::from));
+        }
+        paths.sort();
+        paths.dedup();
+
+        let mut snapshot = Self {
+            instance_dir: instance_dir.to_owned(),
+            backup_dir,
+            paths: Vec::new(),
+        };
+        for path in paths {
+            let src = instance_dir.join(&path);
+            let existed = exists(&src).await;
+            if existed {
+                let dst = snapshot.backup_dir.join(&path);
+                if let Some(parent) = dst.parent() {
+                    tokio::fs::create_dir_all(parent).await.dir(parent)?;
+                }
+                file_utils::copy_dir_recursive(&src, &dst).await?;
+            }
+            snapshot.paths.push((path, existed));
+        }
+        Ok(snapshot)
+    }
+
+    /// Puts the instance back as it was when the snapshot was taken.
+    async fn restore(self) -> Result<(), IoError> {
+        // Left behind by a failed Forge server install
+        if let Some(shim) = find_forge_shim_file(&self.instance_dir).await {
+            if !self.paths.iter().any(|(n, _)| shim.ends_with(n)) {
+                tokio::fs::remove_file(&shim).await.path(&shim)?;
+            }
+        }
+        for (path, existed) in &self.paths {
+            let current = self.instance_dir.join(path);
+            if current.is_dir() {
+                tokio::fs::remove_dir_all(&current).await.path(&current)?;
+            } else if exists(&current).await {
+                tokio::fs::remove_file(&current).await.path(&current)?;
+            }
+            if *existed {
+                let backup = self.backup_dir.join(path);
+                tokio::fs::rename(&backup, &current).await.path(&current)?;
+            }
+        }
+        self.discard().await;
+        Ok(())
+    }
+
+    async fn discard(self) {
+        if let Err(error) = tokio::fs::remove_dir_all(&self.backup_dir).await {
+            err!(
+                "Couldn't remove loader backup {:?}: {error}",
+                self.backup_dir
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Pretends to switch an instance in `dir` from Fabric to Forge,
+    /// returning the calls made to the (un)installers.
+    async fn switch(
+        dir: &Path,
+        result: Result<LoaderInstallResult, String>,
+    ) -> (Result<LoaderInstallResult, String>, Vec<&'static str>) {
+        _ = tokio::fs::remove_dir_all(dir).await;
+        tokio::fs::create_dir_all(dir.join("libraries"))
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("config.json"), "fabric with info")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("details.json"), "{}")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("fabric.json"), "{}")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("libraries/fabric-loader.jar"), "")
+            .await
+            .unwrap();
+
+        let calls = Mutex::new(Vec::new());
+        let result = set_loader_with(
+            dir,
+            (Loader::Fabric, Loader::Forge),
+            || {
+                calls.lock().unwrap().push("uninstall");
+                async {
+                    tokio::fs::remove_file(dir.join("fabric.json"))
+                        .await
+                        .unwrap();
+                    tokio::fs::remove_file(dir.join("libraries/fabric-loader.jar"))
+                        .await
+                        .unwrap();
+                    tokio::fs::write(dir.join("config.json"), "vanilla")
+                        .await
+                        .unwrap();
+                    Ok(())
+                }
+            },
+            || {
+                calls.lock().unwrap().push("install");
+                async {
+                    tokio::fs::create_dir_all(dir.join("forge")).await.unwrap();
+                    tokio::fs::write(dir.join("libraries/forge.jar"), "")
+                        .await
+                        .unwrap();
+                    tokio::fs::write(dir.join("config.json"), "forge")
+                        .await
+                        .unwrap();
+                    result
+                }
+            },
+        )
+        .await;
+        (result, calls.into_inner().unwrap())
+    }
+
+    #[tokio::test]
+    async fn switches_fabric_to_forge() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let (result, calls) = switch(dir, Ok(LoaderInstallResult::Ok)).await;
+        assert!(matches!(result, Ok(LoaderInstallResult::Ok)));
+        assert_eq!(calls, ["uninstall", "install"]);
+        assert!(dir.join("forge").is_dir());
+        assert!(!dir.join("fabric.json").exists());
+        assert!(!dir.join(".loader_switch_backup").exists());
+
+        let (result, _) = switch(dir, Ok(LoaderInstallResult::NeedsOptifine)).await;
+        assert!(matches!(result, Ok(LoaderInstallResult::NeedsOptifine)));
+        assert!(!dir.join("fabric.json").exists());
+    }
+
+    #[tokio::test]
+    async fn failed_switch_restores_old_loader() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let (result, calls) = switch(dir, Err("Forge failed".to_owned())).await;
+        assert_eq!(result.unwrap_err(), "Forge failed");
+        assert_eq!(calls, ["uninstall", "install"]);
+
+        // Config (with the loader's info) and files are back
+        assert_eq!(
+            tokio::fs::read_to_string(dir.join("config.json"))
+                .await
+                .unwrap(),
+            "fabric with info"
+        );
+        assert!(dir.join("fabric.json").is_file());
+        assert!(dir.join("libraries/fabric-loader.jar").is_file());
+        assert!(!dir.join("libraries/forge.jar").exists());
+        assert!(!dir.join("forge").exists());
+        assert!(!dir.join(".loader_switch_backup").exists());
+    }
+}
@@ -84,7 +84,7 @@ impl Preset {
         selected_mods: HashSet<SelectedMod>,
         include_config: bool,
     ) -> Result<Vec<u8>, ModError> {
-        let dot_minecraft = instance.get_dot_minecraft_path();
+        let dot_minecraft = instance.get_game_dir().await?;
         let mods_dir = dot_minecraft.join("mods");
         let config_dir = dot_minecraft.join("config");
 
@@ -181,7 +181,7 @@ impl Preset {
     ) -> Result<PresetOutput, ModError> {
         info!("Importing mod preset");
 
-        let main_dir = instance.get_dot_minecraft_path();
+        let main_dir = instance.get_game_dir().await?;
         let mods_dir = main_dir.join("mods");
 
         let mut zip = zip::ZipArchive::new(Cursor::new(&file)).map_err(ModError::Zip)?;
@@ -193,7 +193,7 @@ impl Preset {
             let Ok(mut index) = zip.by_name("index.json") else {
                 // Else this ain't a QMP file!
                 // Install as regular modpack
-                return match install_modpack(file.clone(), instance.clone(), None)
+                return match install_modpack(file.clone(), instance.clone(), None, None)
                     .await
                     .map_err(Box::new)?
                 {
@@ -1,38 +1,64 @@
-use std::{collections::HashSet, ffi::OsStr, path::PathBuf, sync::mpsc::Sender};
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+};
 
-use ql_core::{GenericProgress, Instance, IntoIoError, err, pt};
+use ql_core::{CancellationToken, GenericProgress, Instance, IntoIoError, do_jobs, err, pt};
 
-use crate::{presets, store::download_mods_bulk};
+use crate::{presets, rate_limiter::lock, store::download_mods_bulk};
 
 use super::{
-    CurseforgeNotAllowed,
+    CurseforgeNotAllowed, LocalModInfo, ModError, ModIndex, jar_metadata,
+    local_json::check_safe_mode,
     modpack::{self, PackError},
 };
 
+/// Adds mod jars, modpacks and presets (`.qmp`) to the instance.
+///
+/// `cancel` stops it between files, and undoes a modpack
+/// that's partway through installing (see [`modpack::install_modpack`]).
 pub async fn add_files(
     instance: Instance,
     paths: Vec<PathBuf>,
     progress: Option<Sender<GenericProgress>>,
+    cancel: Option<CancellationToken>,
 ) -> Result<HashSet<CurseforgeNotAllowed>, PackError> {
-    let mods_dir = instance.get_dot_minecraft_path().join("mods");
-
     let mut not_allowed = HashSet::new();
 
     send_progress(progress.as_ref(), &GenericProgress::default());
 
+    // Added all at once, much faster than one by one
+    let (jars, paths): (Vec<PathBuf>, Vec<PathBuf>) = paths
+        .into_iter()
+        .partition(|n| n.extension().is_some_and(|n| n.eq_ignore_ascii_case("jar")));
+    if !jars.is_empty() {
+        send_progress(
+            progress.as_ref(),
+            &GenericProgress {
+                done: 0,
+                total: paths.len() + 1,
+                message: Some(format!("Installing {} mod(s)", jars.len())),
+                has_finished: false,
+            },
+        );
+        add_files_bulk(&instance, &jars).await?;
+    }
+
     let len = paths.len();
     for (i, path) in paths.into_iter().enumerate() {
+        if let Some(cancel) = &cancel {
+            cancel.check()?;
+        }
         pt!("Adding file: {path:?}");
-        let (Some(extension), Some(filename)) =
-            (path.extension().and_then(OsStr::to_str), path.file_name())
-        else {
+        let Some(extension) = path.extension().and_then(OsStr::to_str) else {
             continue;
         };
 
         let extension = extension.to_lowercase();
 
         let file_type = match extension.as_str() {
-            "jar" => "mod",
             "zip" | "mrpack" => "modpack",
             "qmp" => "QuantumLauncher mod preset",
             _ => "Unknown file (ERROR)",
@@ -48,15 +74,15 @@ pub async fn add_files(
         );
 
         match extension.as_str() {
-            "jar" => {
-                tokio::fs::copy(&path, mods_dir.join(filename))
-                    .await
-                    .path(&path)?;
-            }
             "zip" | "mrpack" => {
                 let file = tokio::fs::read(&path).await.path(&path)?;
-                if let Some(not_allowed_new) =
-                    modpack::install_modpack(file, instance.clone(), progress.as_ref()).await?
+                if let Some(not_allowed_new) = modpack::install_modpack(
+                    file,
+                    instance.clone(),
+                    progress.as_ref(),
+                    cancel.as_ref(),
+                )
+                .await?
                 {
                     not_allowed.extend(not_allowed_new);
                 }
@@ -79,6 +105,65 @@ pub async fn add_files(
     Ok(not_allowed)
 }
 
+/// Adds local mod jars to the instance, reading their
+/// name, version and loader from the jar's metadata
+/// (`fabric.mod.json`, `META-INF/mods.toml`, ...)
+/// into [`ModIndex::local_mods`], instead of just
+/// showing the file name.
+///
+/// The jars are copied concurrently and the index
+/// is only saved once, so this is much faster than
+/// adding them one by one.
+///
+/// Returns the file names of the added mods.
+///
+/// # Errors
+/// If a jar couldn't be copied, or the
+/// mod index couldn't be loaded/saved.
+pub async fn add_files_bulk(
+    instance: &Instance,
+    paths: &[PathBuf],
+) -> Result<Vec<String>, ModError> {
+    check_safe_mode(instance).await?;
+    let mods_dir = instance.get_game_dir().await?.join("mods");
+    let added = add_jars(&mods_dir, paths).await?;
+
+    let _guard = lock().await;
+    let mut index = ModIndex::load(instance).await?;
+    let mut filenames = Vec::new();
+    for (filename, info) in added {
+        if let Some(info) = info {
+            index.local_mods.insert(filename.clone(), info);
+        }
+        filenames.push(filename);
+    }
+    index.save(instance).await?;
+    Ok(filenames)
+}
+
+async fn add_jars(
+    mods_dir: &Path,
+    paths: &[PathBuf],
+) -> Result<Vec<(String, Option<LocalModInfo>)>, ModError> {
+    tokio::fs::create_dir_all(mods_dir).await.path(mods_dir)?;
+    do_jobs(paths.iter().filter_map(|path| {
+        let filename = path.file_name()?.to_string_lossy().into_owned();
+        Some(async move {
+            pt!("Adding mod: {filename}");
+            tokio::fs::copy(path, mods_dir.join(&filename))
+                .await
+                .path(path)?;
+            let jar = path.clone();
+            let info = tokio::task::spawn_blocking(move || jar_metadata::read(&jar))
+                .await
+                .ok()
+                .flatten();
+            Ok((filename, info))
+        })
+    }))
+    .await
+}
+
 fn send_progress(sender: Option<&Sender<GenericProgress>>, progress: &GenericProgress) {
     if let Some(sender) = sender {
         if sender.send(progress.clone()).is_ok() {
@@ -89,3 +174,70 @@ fn send_progress(sender: Option<&Sender<GenericProgress>>, progress: &GenericPro
         pt!("{msg}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn jar(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn adds_jars_with_metadata() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let downloads = dir.join("downloads");
+        tokio::fs::create_dir_all(&downloads).await.unwrap();
+
+        let fabric = downloads.join("sodium-fabric-0.5.8.jar");
+        let json = r#"{"schemaVersion": 1, "id": "sodium", "name": "Sodium", "version": "0.5.8"}"#;
+        tokio::fs::write(&fabric, jar(&[("fabric.mod.json", json)]))
+            .await
+            .unwrap();
+
+        let forge = downloads.join("jei-1.20.1.jar");
+        let toml =
+            "[[mods]]\nmodId=\"jei\"\nversion=\"15.3.0\"\ndisplayName=\"Just Enough Items\"\n";
+        tokio::fs::write(&forge, jar(&[("META-INF/mods.toml", toml)]))
+            .await
+            .unwrap();
+
+        let mods_dir = dir.join("mods");
+        let mut added = add_jars(&mods_dir, &[fabric, forge]).await.unwrap();
+        added.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            added,
+            [
+                (
+                    "jei-1.20.1.jar".to_owned(),
+                    Some(LocalModInfo {
+                        name: "Just Enough Items".to_owned(),
+                        version: Some("15.3.0".to_owned()),
+                        loader: Some(ql_core::Loader::Forge),
+                        minecraft_version: None,
+                    })
+                ),
+                (
+                    "sodium-fabric-0.5.8.jar".to_owned(),
+                    Some(LocalModInfo {
+                        name: "Sodium".to_owned(),
+                        version: Some("0.5.8".to_owned()),
+                        loader: Some(ql_core::Loader::Fabric),
+                        minecraft_version: None,
+                    })
+                ),
+            ]
+        );
+        assert!(mods_dir.join("jei-1.20.1.jar").is_file());
+        assert!(mods_dir.join("sodium-fabric-0.5.8.jar").is_file());
+    }
+}
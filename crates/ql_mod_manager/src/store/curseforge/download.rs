@@ -4,15 +4,17 @@ use std::{
 };
 
 use ql_core::{
-    GenericProgress, Instance, InstanceConfigJson, download, err, file_utils, info,
-    json::VersionDetails, pt,
+    CancellationToken, GenericProgress, Instance, InstanceConfigJson, download, err, file_utils,
+    info, json::VersionDetails, pt,
 };
 
 use crate::store::{
     CurseforgeNotAllowed, DirStructure, ModConfig, ModError, ModFile, ModId, ModIndex, QueryType,
     StoreBackendType,
-    curseforge::{ModQuery, get_query_type},
+    curseforge::{CurseforgeFileQuery, ModQuery, get_query_type},
     install_modpack,
+    local_json::check_safe_mode,
+    versions::VersionDownloader,
 };
 
 use super::Mod;
@@ -29,6 +31,11 @@ pub struct ModDownloader<'a> {
     pub not_allowed: HashSet<CurseforgeNotAllowed>,
     already_installed: HashSet<String>,
     pub sender: Option<&'a Sender<GenericProgress>>,
+    /// (mod id, file id): Installs this file of the mod
+    /// instead of the latest compatible one.
+    pub pinned_file: Option<(String, i32)>,
+    /// Stops a modpack's installation early, see [`install_modpack`].
+    pub cancel: Option<CancellationToken>,
 }
 
 impl<'a> ModDownloader<'a> {
@@ -36,6 +43,7 @@ impl<'a> ModDownloader<'a> {
         instance: Instance,
         sender: Option<&'a Sender<GenericProgress>>,
     ) -> Result<Self, ModError> {
+        check_safe_mode(&instance).await?;
         let version_json = VersionDetails::load(&instance).await?;
         let config = InstanceConfigJson::read(&instance).await?;
 
@@ -49,6 +57,8 @@ impl<'a> ModDownloader<'a> {
             instance,
             sender,
             not_allowed: HashSet::new(),
+            pinned_file: None,
+            cancel: None,
         })
     }
 
@@ -66,6 +76,8 @@ impl<'a> ModDownloader<'a> {
             instance,
             sender: None,
             not_allowed: HashSet::new(),
+            pinned_file: None,
+            cancel: None,
         })
     }
 
@@ -130,15 +142,21 @@ impl<'a> ModDownloader<'a> {
 
         let query_type = get_query_type(response.class_id).await?;
 
-        let (file_query, file_id) = response
-            .get_file(
-                response.name.clone(),
-                id,
-                self.version.clone(),
-                self.loader,
-                query_type,
-            )
-            .await?;
+        let pinned = self.pinned_file.as_ref().filter(|n| n.0 == id);
+        let (file_query, file_id) = if let Some(&(_, file_id)) = pinned {
+            pt!("Using pinned file: {file_id}");
+            (CurseforgeFileQuery::load(id, file_id).await?, file_id)
+        } else {
+            response
+                .get_file(
+                    response.name.clone(),
+                    id,
+                    self.version.clone(),
+                    self.loader,
+                    query_type,
+                )
+                .await?
+        };
         let Some(url) = file_query.data.downloadUrl.clone() else {
             self.not_allowed.insert(CurseforgeNotAllowed {
                 name: response.name.clone(),
@@ -155,13 +173,18 @@ impl<'a> ModDownloader<'a> {
             QueryType::Mods => &self.dirs.mods,
             QueryType::ResourcePacks => &self.dirs.resource_packs,
             QueryType::Shaders => &self.dirs.shaders,
+            QueryType::Plugins => &self.dirs.plugins,
             QueryType::ModPacks => {
                 let bytes = file_utils::download_file_to_bytes(&url, true).await?;
                 self.index.save(&self.instance).await?;
-                if let Some(not_allowed_new) =
-                    install_modpack(bytes, self.instance.clone(), self.sender)
-                        .await
-                        .map_err(Box::new)?
+                if let Some(not_allowed_new) = install_modpack(
+                    bytes,
+                    self.instance.clone(),
+                    self.sender,
+                    self.cancel.as_ref(),
+                )
+                .await
+                .map_err(Box::new)?
                 {
                     self.not_allowed.extend(not_allowed_new);
                 } else {
@@ -227,6 +250,7 @@ impl<'a> ModDownloader<'a> {
                 project_source: StoreBackendType::Curseforge,
                 project_id: id_mod.clone(),
                 files: vec![ModFile {
+                    hashes: file_query.data.mod_hashes(),
                     url,
                     filename: file_query.data.fileName,
                     primary: true,
@@ -251,6 +275,7 @@ impl<'a> ModDownloader<'a> {
                 } else {
                     HashSet::new()
                 },
+                pinned: false,
             },
         );
     }
@@ -266,6 +291,16 @@ impl<'a> ModDownloader<'a> {
     }
 }
 
+impl VersionDownloader for ModDownloader<'_> {
+    fn index(&mut self) -> &mut ModIndex {
+        &mut self.index
+    }
+
+    async fn download_mod(&mut self, id: &str) -> Result<(), ModError> {
+        self.download(id, None).await
+    }
+}
+
 fn mid(id: &str) -> ModId {
     ModId::Curseforge(id.to_owned())
 }
@@ -7,7 +7,8 @@ use std::{
 use chrono::DateTime;
 use download::ModDownloader;
 use ql_core::{
-    CLIENT, GenericProgress, IntoJsonError, JsonDownloadError, Loader, RequestError, err, pt,
+    CLIENT, CancellationToken, GenericProgress, IntoJsonError, JsonDownloadError, Loader,
+    RequestError, err, pt,
 };
 use reqwest::header::HeaderValue;
 use serde::Deserialize;
@@ -15,19 +16,32 @@ use serde::Deserialize;
 use crate::{
     rate_limiter::{RATE_LIMITER, lock},
     store::{
-        Category, ModId, SearchMod, StoreBackendType,
+        Category, ModHashes, ModId, ModVersionEntry, SearchMod, StoreBackendType,
         curseforge::categories::CfCategory,
         types::{GalleryItem, UrlKind},
     },
 };
 
-use super::{Backend, CurseforgeNotAllowed, ModError, QueryType, SearchResult};
+use super::{
+    Backend, CurseforgeNotAllowed, ModError, QueryType, SearchResult, versions::install_version,
+};
 use categories::get_categories;
 use ql_core::request::check_for_success;
 
 mod categories;
 mod download;
 
+/// Reads the (HTML) changelog from a
+/// `/mods/{id}/files/{file_id}/changelog` response
+pub(super) fn parse_changelog(json: &str) -> Result<String, ModError> {
+    #[derive(Deserialize)]
+    struct Changelog {
+        data: String,
+    }
+    let changelog: Changelog = serde_json::from_str(json).json(json.to_owned())?;
+    Ok(changelog.data)
+}
+
 const NOT_LOADED: i32 = -1;
 pub static MC_ID: AtomicI32 = AtomicI32::new(NOT_LOADED);
 
@@ -109,6 +123,11 @@ impl Mod {
         Ok((file_query, file.fileId))
     }
 
+    /// The newest file of any game version
+    pub(crate) fn latest_file_id(&self) -> Option<i32> {
+        self.latest_files_indexes.iter().map(|n| n.fileId).max()
+    }
+
     fn iter_files(&self, version: String) -> impl Iterator<Item = &CurseforgeFileIdx> {
         self.latest_files_indexes
             .iter()
@@ -198,6 +217,7 @@ impl CurseforgeFileQuery {
 #[derive(Deserialize, Clone, Debug)]
 #[allow(non_snake_case)]
 pub struct CurseforgeFile {
+    pub id: i32,
     pub fileName: String,
     pub downloadUrl: Option<String>,
     pub gameVersions: Vec<String>,
@@ -205,6 +225,29 @@ pub struct CurseforgeFile {
     pub fileDate: String,
     pub displayName: String,
     pub fileLength: u64,
+    #[serde(default)]
+    pub hashes: Vec<CurseforgeHash>,
+}
+
+impl CurseforgeFile {
+    pub fn mod_hashes(&self) -> ModHashes {
+        // https://docs.curseforge.com/rest-api/#tocS_HashAlgo
+        const SHA1: u8 = 1;
+        ModHashes {
+            sha512: None,
+            sha1: self
+                .hashes
+                .iter()
+                .find(|n| n.algo == SHA1)
+                .map(|n| n.value.clone()),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct CurseforgeHash {
+    pub value: String,
+    pub algo: u8,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -374,9 +417,11 @@ impl Backend for CurseforgeBackend {
         id: &str,
         instance: &ql_core::Instance,
         sender: Option<Sender<GenericProgress>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<HashSet<CurseforgeNotAllowed>, ModError> {
         let _guard = lock().await;
         let mut downloader = ModDownloader::new(instance.clone(), sender.as_ref()).await?;
+        downloader.cancel = cancel.cloned();
 
         downloader.ensure_essential_mods().await?;
 
@@ -386,6 +431,108 @@ impl Backend for CurseforgeBackend {
         Ok(downloader.not_allowed)
     }
 
+    async fn get_versions(
+        id: &str,
+        version: &str,
+        loader: Loader,
+    ) -> Result<Vec<ModVersionEntry>, ModError> {
+        #[derive(Deserialize)]
+        struct Files {
+            data: Vec<CurseforgeFile>,
+            pagination: Pagination,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Pagination {
+            total_count: usize,
+        }
+        // Maximum allowed by the API
+        const PAGE_SIZE: usize = 50;
+
+        let response = ModQuery::load(id).await?;
+        let query_type = get_query_type(response.data.class_id).await?;
+
+        let mut params = HashMap::from([
+            ("gameVersion", version.to_owned()),
+            ("pageSize", PAGE_SIZE.to_string()),
+        ]);
+        if let (QueryType::Mods | QueryType::ModPacks, Some(loader)) =
+            (query_type, loader.not_vanilla())
+        {
+            params.insert("modLoaderType", loader.to_curseforge_num().to_owned());
+        }
+
+        let mut files = Vec::new();
+        loop {
+            params.insert("index", files.len().to_string());
+            let page = send_request(&format!("mods/{id}/files"), &params).await?;
+            let page: Files = serde_json::from_str(&page).json(page)?;
+            let len = page.data.len();
+            files.extend(page.data);
+            if len < PAGE_SIZE || files.len() >= page.pagination.total_count {
+                break;
+            }
+        }
+
+        files
+            .into_iter()
+            .map(|file| {
+                let hashes = file.mod_hashes();
+                let (loaders, game_versions) =
+                    file.gameVersions.into_iter().partition(|n| is_loader(n));
+                Ok(ModVersionEntry {
+                    id: file.id.to_string(),
+                    changelog_url: format!(
+                        "https://www.curseforge.com/minecraft/{}/{}/files/{}",
+                        query_type.to_curseforge_str(),
+                        response.data.slug,
+                        file.id
+                    ),
+                    version_number: file.displayName,
+                    date_published: DateTime::parse_from_rfc3339(&file.fileDate)?,
+                    filename: file.fileName,
+                    hashes,
+                    game_versions,
+                    loaders: loaders.iter().map(|n: &String| n.to_lowercase()).collect(),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_changelog(id: &str, version_id: &str) -> Result<String, ModError> {
+        let changelog = send_request(
+            &format!("mods/{id}/files/{version_id}/changelog"),
+            &HashMap::new(),
+        )
+        .await?;
+        parse_changelog(&changelog)
+    }
+
+    async fn download_version(
+        id: &str,
+        version_id: &str,
+        instance: &ql_core::Instance,
+        sender: Option<Sender<GenericProgress>>,
+    ) -> Result<HashSet<CurseforgeNotAllowed>, ModError> {
+        let file_id: i32 = version_id.parse()?;
+        let _guard = lock().await;
+        let mut downloader = ModDownloader::new(instance.clone(), sender.as_ref()).await?;
+        downloader.pinned_file = Some((id.to_owned(), file_id));
+
+        downloader.ensure_essential_mods().await?;
+
+        let mods_dir = instance.get_game_dir().await?.join("mods");
+        install_version(
+            &mut downloader,
+            &ModId::Curseforge(id.to_owned()),
+            &mods_dir,
+        )
+        .await?;
+        downloader.index.save(instance).await?;
+
+        Ok(downloader.not_allowed)
+    }
+
     async fn download_bulk(
         ids: &[String],
         instance: &ql_core::Instance,
@@ -624,6 +771,20 @@ pub async fn get_mc_id() -> Result<i32, ModError> {
     }
 }
 
+/// Curseforge lists loaders (`Fabric`, `NeoForge`, ...)
+/// among a file's game versions.
+fn is_loader(game_version: &str) -> bool {
+    [
+        Loader::Forge,
+        Loader::Fabric,
+        Loader::Quilt,
+        Loader::Neoforge,
+        Loader::Liteloader,
+    ]
+    .iter()
+    .any(|n| game_version.eq_ignore_ascii_case(n.to_modrinth_str()))
+}
+
 pub async fn get_query_type(class_id: i32) -> Result<QueryType, ModError> {
     let categories = get_categories().await?;
     Ok(
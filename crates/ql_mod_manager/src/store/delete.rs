@@ -1,6 +1,6 @@
 use crate::{
     rate_limiter::lock,
-    store::{ModError, ModId, ModIndex},
+    store::{ModError, ModId, ModIndex, local_json::check_safe_mode},
 };
 use ql_core::{Instance, IoError, err, info, pt};
 use std::{
@@ -15,10 +15,11 @@ pub async fn delete_mods(ids: Vec<ModId>, instance: Instance) -> Result<Vec<ModI
         return Ok(ids);
     }
 
+    check_safe_mode(&instance).await?;
     info!("Deleting mods:");
     let mut index = ModIndex::load(&instance).await?;
 
-    let mods_dir = instance.get_dot_minecraft_path().join("mods");
+    let mods_dir = instance.get_game_dir().await?.join("mods");
 
     // let mut downloaded_mods = HashSet::new();
 
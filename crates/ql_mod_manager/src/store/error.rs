@@ -1,6 +1,6 @@
 use std::{fmt::Display, num::ParseIntError};
 
-use ql_core::{IoError, JsonError, RequestError, impl_3_errs_jri};
+use ql_core::{IoError, JsonError, Loader, RequestError, impl_3_errs_jri};
 use thiserror::Error;
 
 use crate::store::QueryType;
@@ -54,6 +54,22 @@ pub enum ModError {
     Pack(#[from] Box<PackError>),
     #[error("{MOD_ERR_PREFIX}not a valid modpack or QMP preset!")]
     NotValidPack,
+    #[error("{MOD_ERR_PREFIX}OptiFabric needs a Fabric instance, but this one uses {0}")]
+    OptifabricNeedsFabric(Loader),
+    #[error("{MOD_ERR_PREFIX}OptiFabric (and OptiFine) can't be used on servers")]
+    OptifabricServer,
+    #[error(
+        "{MOD_ERR_PREFIX}OptiFine isn't installed\nAdd the OptiFine jar to the mods folder, then try again"
+    )]
+    OptifineNotInstalled,
+    #[error("{MOD_ERR_PREFIX}plugins can only be installed on servers")]
+    PluginsNeedServer,
+    #[error("{MOD_ERR_PREFIX}plugins need a Paper server, but this one uses {0}")]
+    PluginsNeedPaper(Loader),
+    #[error(
+        "{MOD_ERR_PREFIX}the instance is running in safe mode (with its mods moved aside)\nClose the game first, then try again"
+    )]
+    SafeMode,
     #[error("{MOD_ERR_PREFIX}API Error: {error_id}\n{description}")]
     ApiError {
         error_id: String,
@@ -69,15 +85,36 @@ impl From<reqwest::Error> for ModError {
     }
 }
 
-#[derive(Debug)]
+/// Something a mod (or action) needs from the
+/// instance, but didn't get (like a loader or game version).
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GameExpectation {
     expected: String,
     got: String,
+    /// The mod that has this expectation, if known.
+    by: Option<String>,
+}
+
+impl GameExpectation {
+    pub(crate) fn new(expected: impl Display, got: impl Display, by: Option<String>) -> Self {
+        Self {
+            expected: expected.to_string(),
+            got: got.to_string(),
+            by,
+        }
+    }
 }
 
 impl Display for GameExpectation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.got == "Vanilla" {
+        if let Some(by) = &self.by {
+            write!(
+                f,
+                "{by} needs {expected}, but this instance has {got}",
+                expected = self.expected,
+                got = self.got
+            )
+        } else if self.got == "Vanilla" {
             write!(
                 f,
                 "You don't have {exp} installed!\nPlease install {exp}",
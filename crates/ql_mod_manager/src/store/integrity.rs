@@ -0,0 +1,392 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use ql_core::{
+    Instance, Loader, err,
+    file_utils::exists,
+    json::{InstanceConfigJson, VersionDetails},
+};
+
+use crate::store::{GameExpectation, ModIndex, jar_metadata};
+
+/// A likely reason for a modded instance to
+/// crash on launch, found by [`check_integrity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityWarning {
+    /// The instance is set to use a loader,
+    /// but its files are missing (broken install).
+    LoaderMissing(Loader),
+    /// An API mod that most mods for this loader depend on
+    /// (like Fabric API) isn't installed.
+    ApiModMissing { loader: Loader, name: &'static str },
+    /// A mod is made for a different loader or game version,
+    /// see [`check_game_expectations`].
+    ModMismatch(GameExpectation),
+}
+
+impl Display for IntegrityWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityWarning::LoaderMissing(loader) => write!(
+                f,
+                "{loader} files are missing, try reinstalling {loader} from the Mods menu"
+            ),
+            IntegrityWarning::ApiModMissing { loader, name } => write!(
+                f,
+                "{name} isn't installed, but most {loader} mods need it. Install it if the game crashes"
+            ),
+            IntegrityWarning::ModMismatch(expectation) => write!(f, "{expectation}"),
+        }
+    }
+}
+
+struct ApiMod {
+    name: &'static str,
+    ids: &'static [&'static str],
+    /// For mods added as a file, not through the store.
+    file_prefixes: &'static [&'static str],
+}
+
+const FABRIC_API: ApiMod = ApiMod {
+    name: "Fabric API",
+    ids: &["P7dR8mSH", "306612"],
+    file_prefixes: &["fabric-api"],
+};
+
+/// Quilt can use either QFAPI/QSL or plain Fabric API
+const QUILT_API: ApiMod = ApiMod {
+    name: "Quilted Fabric API",
+    ids: &["qvIfYCYJ", "634179", "P7dR8mSH", "306612"],
+    file_prefixes: &["qfapi", "quilted-fabric-api", "qsl", "fabric-api"],
+};
+
+/// Checks a Fabric/Quilt/Forge/NeoForge instance (with mods)
+/// for common reasons why it may crash on the first launch:
+///
+/// - The loader's files are missing
+/// - A commonly required API mod (Fabric API) isn't installed
+/// - Mods made for another loader or game version
+///   (see [`check_game_expectations`])
+///
+/// These are only warnings, the game can still be launched
+/// (the check could be wrong, for example if the mods
+/// don't actually need the API).
+pub async fn check_integrity(instance: &Instance) -> Vec<IntegrityWarning> {
+    let Some(dot_minecraft) = game_dir(instance).await else {
+        return Vec::new();
+    };
+    let index = load_index(instance).await;
+    let instance_dir = instance.get_instance_path();
+
+    let mut warnings =
+        check_dirs(&instance_dir, &dot_minecraft, instance.is_server(), &index).await;
+    warnings.extend(
+        check_expectations(&instance_dir, &dot_minecraft, &index)
+            .await
+            .into_iter()
+            .map(IntegrityWarning::ModMismatch),
+    );
+    warnings
+}
+
+/// Finds enabled mods that won't work with the instance:
+///
+/// - Made for another loader (from the jar's metadata,
+///   like `fabric.mod.json` or `META-INF/mods.toml`).
+///   Quilt also loads Fabric mods, and NeoForge loads
+///   (1.20.1-era) Forge mods.
+/// - Downloaded for other Minecraft versions
+///   (for mods installed from the store).
+///
+/// Jars without any known metadata are skipped.
+pub async fn check_game_expectations(instance: &Instance) -> Vec<GameExpectation> {
+    let Some(dot_minecraft) = game_dir(instance).await else {
+        return Vec::new();
+    };
+    let index = load_index(instance).await;
+    check_expectations(&instance.get_instance_path(), &dot_minecraft, &index).await
+}
+
+async fn game_dir(instance: &Instance) -> Option<PathBuf> {
+    match instance.get_game_dir().await {
+        Ok(n) => Some(n),
+        Err(error) => {
+            err!("Couldn't check mods (instance config unreadable): {error}");
+            None
+        }
+    }
+}
+
+async fn load_index(instance: &Instance) -> ModIndex {
+    match ModIndex::load(instance).await {
+        Ok(index) => index,
+        Err(error) => {
+            err!("Couldn't load mod index for integrity check: {error}");
+            ModIndex::default()
+        }
+    }
+}
+
+async fn check_dirs(
+    instance_dir: &Path,
+    dot_minecraft: &Path,
+    is_server: bool,
+    index: &ModIndex,
+) -> Vec<IntegrityWarning> {
+    let mut warnings = Vec::new();
+
+    // Broken config is reported by the launch itself
+    let Ok(config) = InstanceConfigJson::read_from_dir(instance_dir).await else {
+        return warnings;
+    };
+    let loader = config.mod_type;
+
+    let (loader_file, api_mod) = match loader {
+        Loader::Fabric => (Some("fabric.json"), Some(FABRIC_API)),
+        Loader::Quilt => (Some("fabric.json"), Some(QUILT_API)),
+        // Server installs are laid out differently per version
+        Loader::Forge | Loader::Neoforge if is_server => (None, None),
+        Loader::Forge | Loader::Neoforge => (Some("forge/details.json"), None),
+        _ => return warnings,
+    };
+
+    let mod_files = list_jars(&dot_minecraft.join("mods")).await;
+    if mod_files.is_empty() {
+        return warnings;
+    }
+
+    if let Some(file) = loader_file {
+        if !exists(instance_dir.join(file)).await {
+            warnings.push(IntegrityWarning::LoaderMissing(loader));
+        }
+    }
+
+    if let Some(api_mod) = api_mod {
+        let in_index = index.mods.iter().any(|(id, config)| {
+            api_mod.ids.contains(&id.get_internal_id()) || config.name == api_mod.name
+        });
+        let in_files = mod_files
+            .iter()
+            .any(|file| api_mod.file_prefixes.iter().any(|n| file.starts_with(n)));
+        if !in_index && !in_files {
+            warnings.push(IntegrityWarning::ApiModMissing {
+                loader,
+                name: api_mod.name,
+            });
+        }
+    }
+
+    warnings
+}
+
+async fn check_expectations(
+    instance_dir: &Path,
+    dot_minecraft: &Path,
+    index: &ModIndex,
+) -> Vec<GameExpectation> {
+    let Ok(config) = InstanceConfigJson::read_from_dir(instance_dir).await else {
+        return Vec::new();
+    };
+    let loader = config.mod_type;
+    if !matches!(
+        loader,
+        Loader::Vanilla | Loader::Fabric | Loader::Quilt | Loader::Forge | Loader::Neoforge
+    ) {
+        return Vec::new();
+    }
+    let game_version = VersionDetails::load_from_path(instance_dir)
+        .await
+        .ok()
+        .map(|n| n.get_id().to_owned());
+
+    // File name -> (mod name, supported versions)
+    let indexed: HashMap<String, (String, Vec<String>)> = index
+        .mods
+        .values()
+        .flat_map(|config| {
+            config.files.iter().map(|file| {
+                (
+                    file.filename.clone(),
+                    (config.name.clone(), config.supported_versions.clone()),
+                )
+            })
+        })
+        .collect();
+
+    let mods_dir = dot_minecraft.join("mods");
+    let jars = tokio::task::spawn_blocking(move || {
+        list_jar_paths(&mods_dir)
+            .into_iter()
+            .map(|path| {
+                let loaders = jar_metadata::loaders(&path);
+                (path, loaders)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+
+    let mut expectations = Vec::new();
+    for (path, loaders) in jars {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let (name, supported_versions) = match indexed.get(&file_name) {
+            Some((name, versions)) => (name.clone(), versions.as_slice()),
+            None => (file_name, [].as_slice()),
+        };
+
+        if let Some(&expected) = loaders.first() {
+            if !loaders.iter().any(|&n| can_load(loader, n)) {
+                expectations.push(GameExpectation::new(expected, loader, Some(name)));
+                continue;
+            }
+        }
+        if let Some(game_version) = &game_version {
+            if !supported_versions.is_empty() && !supported_versions.contains(game_version) {
+                expectations.push(GameExpectation::new(
+                    format!("Minecraft {}", supported_versions.join(", ")),
+                    format!("Minecraft {game_version}"),
+                    Some(name),
+                ));
+            }
+        }
+    }
+    expectations
+}
+
+fn can_load(instance_loader: Loader, mod_loader: Loader) -> bool {
+    match instance_loader {
+        Loader::Quilt => matches!(mod_loader, Loader::Quilt | Loader::Fabric),
+        Loader::Neoforge => matches!(mod_loader, Loader::Neoforge | Loader::Forge),
+        _ => instance_loader == mod_loader,
+    }
+}
+
+/// Paths of enabled mods, sorted.
+fn list_jar_paths(mods_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(mods_dir) else {
+        return Vec::new();
+    };
+    let mut jars: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|n| n.path())
+        .filter(|n| n.extension().is_some_and(|n| n.eq_ignore_ascii_case("jar")))
+        .collect();
+    jars.sort();
+    jars
+}
+
+/// Lowercase file names of enabled mods.
+async fn list_jars(mods_dir: &Path) -> Vec<String> {
+    let mut jars = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(mods_dir).await else {
+        return jars;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        if name.ends_with(".jar") {
+            jars.push(name);
+        }
+    }
+    jars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jar(files: &[&str]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for file in files {
+            zip.start_file(*file, zip::write::SimpleFileOptions::default())
+                .unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn forge_mod_on_fabric_is_reported() {
+        let temp = tempfile::tempdir().unwrap();
+        let instance_dir = temp.path();
+        let dot_minecraft = instance_dir.join(".minecraft");
+        let mods_dir = dot_minecraft.join("mods");
+        tokio::fs::create_dir_all(&mods_dir).await.unwrap();
+        tokio::fs::write(
+            instance_dir.join("config.json"),
+            r#"{"ram_in_mb":2048,"mod_type":"Fabric"}"#,
+        )
+        .await
+        .unwrap();
+
+        let files = [
+            ("jei-forge-15.2.0.jar", jar(&["META-INF/mods.toml"])),
+            ("sodium-fabric-0.6.0.jar", jar(&["fabric.mod.json"])),
+            // Works on both
+            (
+                "architectury-13.0.jar",
+                jar(&["fabric.mod.json", "META-INF/mods.toml"]),
+            ),
+            ("not-a-mod.jar", b"garbage".to_vec()),
+        ];
+        for (name, bytes) in files {
+            tokio::fs::write(mods_dir.join(name), bytes).await.unwrap();
+        }
+
+        let index = ModIndex::default();
+        let expectations = check_expectations(instance_dir, &dot_minecraft, &index).await;
+        assert_eq!(
+            expectations,
+            [GameExpectation::new(
+                Loader::Forge,
+                Loader::Fabric,
+                Some("jei-forge-15.2.0.jar".to_owned())
+            )]
+        );
+        assert_eq!(
+            expectations[0].to_string(),
+            "jei-forge-15.2.0.jar needs Forge, but this instance has Fabric"
+        );
+    }
+
+    #[tokio::test]
+    async fn fabric_without_fabric_api_warns() {
+        let temp = tempfile::tempdir().unwrap();
+        let instance_dir = temp.path();
+        let dot_minecraft = instance_dir.join(".minecraft");
+
+        let files = [
+            ("config.json", r#"{"ram_in_mb":2048,"mod_type":"Fabric"}"#),
+            ("fabric.json", "{}"),
+            (".minecraft/mods/sodium-fabric-0.6.0.jar", ""),
+        ];
+        for (path, contents) in files {
+            let path = instance_dir.join(path);
+            tokio::fs::create_dir_all(path.parent().unwrap())
+                .await
+                .unwrap();
+            tokio::fs::write(&path, contents).await.unwrap();
+        }
+
+        let index = ModIndex::default();
+        let warnings = check_dirs(instance_dir, &dot_minecraft, false, &index).await;
+        assert_eq!(
+            warnings,
+            [IntegrityWarning::ApiModMissing {
+                loader: Loader::Fabric,
+                name: "Fabric API",
+            }]
+        );
+
+        // Added manually, not through the store
+        tokio::fs::write(dot_minecraft.join("mods/fabric-api-0.100.0.jar"), "")
+            .await
+            .unwrap();
+        let warnings = check_dirs(instance_dir, &dot_minecraft, false, &index).await;
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+}
@@ -0,0 +1,350 @@
+//! Reading a mod's details from the metadata inside its jar,
+//! for mods that didn't come from a store.
+//!
+//! Supported formats:
+//! - Quilt: `quilt.mod.json`
+//! - Fabric: `fabric.mod.json`
+//! - NeoForge: `META-INF/neoforge.mods.toml`
+//! - Forge (1.13+): `META-INF/mods.toml`
+//! - Forge (old): `mcmod.info`
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+
+use ql_core::Loader;
+use serde::Deserialize;
+use serde_json::Value;
+use zip::ZipArchive;
+
+use super::LocalModInfo;
+
+/// Metadata files that say which loader a mod is for,
+/// in the order they're preferred.
+const LOADER_FILES: &[(&str, Loader)] = &[
+    ("quilt.mod.json", Loader::Quilt),
+    ("fabric.mod.json", Loader::Fabric),
+    ("META-INF/neoforge.mods.toml", Loader::Neoforge),
+    ("META-INF/mods.toml", Loader::Forge),
+];
+
+/// Reads the details of a mod from the metadata in its jar.
+///
+/// Returns `None` if the jar can't be read
+/// or has no (valid) metadata.
+pub(super) fn read(jar: &Path) -> Option<LocalModInfo> {
+    let mut archive = open(jar)?;
+
+    for (name, loader) in LOADER_FILES {
+        let Some(contents) = read_entry(&mut archive, name) else {
+            continue;
+        };
+        return match loader {
+            Loader::Quilt => parse_quilt(&contents),
+            Loader::Fabric => parse_fabric(&contents),
+            _ => {
+                let manifest = read_entry(&mut archive, "META-INF/MANIFEST.MF");
+                parse_mods_toml(&contents, manifest.as_deref(), *loader)
+            }
+        };
+    }
+    let json = read_entry(&mut archive, "mcmod.info")?;
+    parse_mcmod_info(&json)
+}
+
+/// Loaders that a mod jar has metadata for
+/// (empty if it isn't a mod, or is too old to have any).
+pub(super) fn loaders(jar: &Path) -> Vec<Loader> {
+    let Some(archive) = open(jar) else {
+        return Vec::new();
+    };
+    LOADER_FILES
+        .iter()
+        .filter(|(name, _)| archive.index_for_name(name).is_some())
+        .map(|(_, loader)| *loader)
+        .collect()
+}
+
+fn open(jar: &Path) -> Option<ZipArchive<BufReader<File>>> {
+    let file = File::open(jar).ok()?;
+    ZipArchive::new(BufReader::new(file)).ok()
+}
+
+fn read_entry(archive: &mut ZipArchive<impl Read + Seek>, name: &str) -> Option<String> {
+    let mut file = archive.by_name(name).ok()?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).ok()?;
+    Some(contents)
+}
+
+#[derive(Deserialize)]
+struct FabricModJson {
+    id: String,
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    depends: serde_json::Map<String, Value>,
+}
+
+fn parse_fabric(json: &str) -> Option<LocalModInfo> {
+    let json: FabricModJson = serde_json::from_str(json).ok()?;
+    Some(LocalModInfo {
+        name: json.name.unwrap_or(json.id),
+        version: json.version,
+        loader: Some(Loader::Fabric),
+        minecraft_version: json.depends.get("minecraft").and_then(version_range),
+    })
+}
+
+#[derive(Deserialize)]
+struct QuiltModJson {
+    quilt_loader: QuiltLoader,
+}
+
+#[derive(Deserialize)]
+struct QuiltLoader {
+    id: String,
+    version: Option<String>,
+    #[serde(default)]
+    metadata: QuiltMetadata,
+    #[serde(default)]
+    depends: Vec<Value>,
+}
+
+#[derive(Deserialize, Default)]
+struct QuiltMetadata {
+    name: Option<String>,
+}
+
+fn parse_quilt(json: &str) -> Option<LocalModInfo> {
+    let json: QuiltModJson = serde_json::from_str(json).ok()?;
+    let loader = json.quilt_loader;
+    // Either just an id, or {"id": ..., "versions": ...}
+    let minecraft_version = loader
+        .depends
+        .iter()
+        .find(|n| n.get("id").and_then(Value::as_str) == Some("minecraft"))
+        .and_then(|n| n.get("versions"))
+        .and_then(version_range);
+    Some(LocalModInfo {
+        name: loader.metadata.name.unwrap_or(loader.id),
+        version: loader.version,
+        loader: Some(Loader::Quilt),
+        minecraft_version,
+    })
+}
+
+/// A version requirement: `">=1.20"`, or `["1.20", "1.20.1"]` (any of them)
+fn version_range(value: &Value) -> Option<String> {
+    match value {
+        Value::String(n) => Some(n.clone()),
+        Value::Array(n) => {
+            let parts: Vec<&str> = n.iter().filter_map(Value::as_str).collect();
+            (!parts.is_empty()).then(|| parts.join(" || "))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum McmodInfo {
+    List(Vec<McmodEntry>),
+    /// `"modListVersion": 2`
+    Versioned {
+        #[serde(rename = "modList")]
+        mod_list: Vec<McmodEntry>,
+    },
+}
+
+#[derive(Deserialize)]
+struct McmodEntry {
+    modid: String,
+    name: Option<String>,
+    version: Option<String>,
+    mcversion: Option<String>,
+}
+
+fn parse_mcmod_info(json: &str) -> Option<LocalModInfo> {
+    let entry = match serde_json::from_str(json).ok()? {
+        McmodInfo::List(n) | McmodInfo::Versioned { mod_list: n } => n.into_iter().next()?,
+    };
+    // Build tools sometimes leave their `${...}` placeholders in
+    let filled = |n: Option<String>| n.filter(|n| !n.is_empty() && !n.contains("${"));
+    Some(LocalModInfo {
+        name: filled(entry.name).unwrap_or(entry.modid),
+        version: filled(entry.version),
+        loader: Some(Loader::Forge),
+        minecraft_version: filled(entry.mcversion),
+    })
+}
+
+#[derive(Deserialize)]
+struct ModsToml {
+    #[serde(default)]
+    mods: Vec<ModsTomlEntry>,
+    /// Mod id -> its dependencies
+    #[serde(default)]
+    dependencies: HashMap<String, Vec<ModsTomlDependency>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModsTomlEntry {
+    mod_id: String,
+    version: Option<String>,
+    display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModsTomlDependency {
+    mod_id: String,
+    version_range: Option<String>,
+}
+
+fn parse_mods_toml(toml: &str, manifest: Option<&str>, loader: Loader) -> Option<LocalModInfo> {
+    let toml: ModsToml = toml::from_str(toml).ok()?;
+    let entry = toml.mods.into_iter().next()?;
+
+    let minecraft_version = toml
+        .dependencies
+        .get(&entry.mod_id)
+        .into_iter()
+        .flatten()
+        .find(|n| n.mod_id == "minecraft")
+        .and_then(|n| n.version_range.clone());
+
+    // Usually filled in from the jar manifest
+    let version = match entry.version {
+        Some(n) if n == "${file.jarVersion}" => manifest.and_then(|n| {
+            n.lines()
+                .find_map(|n| n.strip_prefix("Implementation-Version:"))
+                .map(|n| n.trim().to_owned())
+        }),
+        n => n,
+    };
+    Some(LocalModInfo {
+        name: entry.display_name.unwrap_or(entry.mod_id),
+        version,
+        loader: Some(loader),
+        minecraft_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str, version: &str, loader: Loader, mc: &str) -> Option<LocalModInfo> {
+        Some(LocalModInfo {
+            name: name.to_owned(),
+            version: Some(version.to_owned()),
+            loader: Some(loader),
+            minecraft_version: Some(mc.to_owned()),
+        })
+    }
+
+    #[test]
+    fn fabric() {
+        let json = r#"{
+            "schemaVersion": 1,
+            "id": "sodium",
+            "version": "0.5.8+mc1.20.1",
+            "name": "Sodium",
+            "depends": { "fabricloader": ">=0.12.0", "minecraft": ["1.20", "1.20.1"] }
+        }"#;
+        assert_eq!(
+            parse_fabric(json),
+            info("Sodium", "0.5.8+mc1.20.1", Loader::Fabric, "1.20 || 1.20.1")
+        );
+    }
+
+    #[test]
+    fn quilt() {
+        let json = r#"{
+            "schema_version": 1,
+            "quilt_loader": {
+                "group": "org.quiltmc",
+                "id": "qsl",
+                "version": "6.1.2",
+                "metadata": { "name": "Quilt Standard Libraries" },
+                "depends": [
+                    "quilt_loader",
+                    { "id": "minecraft", "versions": ">=1.20.1" }
+                ]
+            }
+        }"#;
+        assert_eq!(
+            parse_quilt(json),
+            info(
+                "Quilt Standard Libraries",
+                "6.1.2",
+                Loader::Quilt,
+                ">=1.20.1"
+            )
+        );
+    }
+
+    #[test]
+    fn forge_mods_toml() {
+        let toml = r#"
+modLoader="javafml"
+loaderVersion="[47,)"
+
+[[mods]]
+modId="examplemod"
+version="${file.jarVersion}" # from the manifest
+displayName="Example Mod"
+description='''
+A mod = with "quotes"
+[[mods]]
+modId="not a real table"
+'''
+
+[[dependencies.examplemod]]
+modId="forge"
+versionRange="[47,)"
+
+[[dependencies.examplemod]]
+modId="minecraft"
+versionRange="[1.20.1,1.21)"
+"#;
+        let manifest = "Manifest-Version: 1.0\r\nImplementation-Version: 2.1.0\r\n";
+        assert_eq!(
+            parse_mods_toml(toml, Some(manifest), Loader::Forge),
+            info("Example Mod", "2.1.0", Loader::Forge, "[1.20.1,1.21)")
+        );
+    }
+
+    #[test]
+    fn forge_mcmod_info() {
+        let json = r#"[{
+            "modid": "journeymap",
+            "name": "JourneyMap",
+            "version": "5.7.1",
+            "mcversion": "1.12.2",
+            "authorList": ["techbrew"]
+        }]"#;
+        assert_eq!(
+            parse_mcmod_info(json),
+            info("JourneyMap", "5.7.1", Loader::Forge, "1.12.2")
+        );
+
+        let json = r#"{"modListVersion": 2, "modList": [
+            {"modid": "oldmod", "version": "${version}", "mcversion": "1.7.10"}
+        ]}"#;
+        assert_eq!(
+            parse_mcmod_info(json),
+            Some(LocalModInfo {
+                name: "oldmod".to_owned(),
+                version: None,
+                loader: Some(Loader::Forge),
+                minecraft_version: Some("1.7.10".to_owned()),
+            })
+        );
+    }
+}
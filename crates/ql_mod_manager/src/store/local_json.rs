@@ -5,14 +5,15 @@ use std::{
 };
 
 use ql_core::{
-    Instance, IntoIoError, IntoJsonError, IoError, JsonFileError, file_utils::exists, info,
+    Instance, IntoIoError, IntoJsonError, IoError, JsonFileError, Loader, SAFE_MODE_MODS_DIR,
+    file_utils::exists, info,
 };
 use serde::{Deserialize, Serialize};
 use tokio::fs;
 
 use crate::store::ModId;
 
-use super::StoreBackendType;
+use super::{ModError, StoreBackendType};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ModConfig {
@@ -30,24 +31,49 @@ pub struct ModConfig {
     pub supported_versions: Vec<String>,
     pub dependencies: HashSet<ModId>,
     pub dependents: HashSet<ModId>,
+    /// Frozen at the installed version,
+    /// skipped when checking for updates
+    /// (see [`super::pin_mods`]).
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// A mod added from a local jar file (not from a store),
+/// with details read from the jar's metadata.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LocalModInfo {
+    pub name: String,
+    pub version: Option<String>,
+    /// The loader the jar is made for
+    #[serde(default)]
+    pub loader: Option<Loader>,
+    /// Minecraft version(s) the mod says it supports,
+    /// in the loader's own syntax (eg: `>=1.20`, `[1.20.1,1.21)`).
+    #[serde(default)]
+    pub minecraft_version: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct ModIndex {
     pub mods: HashMap<ModId, ModConfig>,
+    /// Mods added from local files (see [`super::add_files_bulk`]),
+    /// by file name (without `.disabled`).
+    #[serde(default)]
+    pub local_mods: HashMap<String, LocalModInfo>,
     is_server: Option<bool>,
 }
 
 impl ModIndex {
     pub async fn load(selected_instance: &Instance) -> Result<Self, JsonFileError> {
         let mut index = load_inner(selected_instance).await?;
-        index.fix(selected_instance).await?;
+        index.fix(&selected_instance.get_game_dir().await?).await?;
         Ok(index)
     }
 
     pub async fn save(&mut self, selected_instance: &Instance) -> Result<(), JsonFileError> {
         let index_dir = selected_instance
-            .get_dot_minecraft_path()
+            .get_game_dir()
+            .await?
             .join("mod_index.json");
 
         let index_str = serde_json::to_string(&self).json_to()?;
@@ -55,15 +81,53 @@ impl ModIndex {
         Ok(())
     }
 
+    /// Finds mods that are installed more than once
+    /// (the same file under a different name or ID),
+    /// a common cause of crashes.
+    ///
+    /// Returns groups of the IDs of duplicate mods, by
+    /// the hash of their main file. Mods without hashes
+    /// (installed by older versions) aren't checked.
+    #[must_use]
+    pub fn find_duplicates(&self) -> Vec<Vec<ModId>> {
+        let mut by_hash: HashMap<String, Vec<ModId>> = HashMap::new();
+        for (id, config) in &self.mods {
+            let primary = config
+                .files
+                .iter()
+                .find(|n| n.primary)
+                .or_else(|| config.files.first());
+            if let Some(hash) = primary.and_then(|n| n.hashes.key()) {
+                by_hash.entry(hash).or_default().push(id.clone());
+            }
+        }
+
+        let mut duplicates: Vec<Vec<ModId>> = by_hash
+            .into_values()
+            .filter(|n| n.len() > 1)
+            .map(|mut n| {
+                n.sort();
+                n
+            })
+            .collect();
+        duplicates.sort();
+        duplicates
+    }
+
     fn new(instance_name: &Instance) -> Self {
         Self {
             mods: HashMap::new(),
+            local_mods: HashMap::new(),
             is_server: Some(instance_name.is_server()),
         }
     }
 
-    async fn fix(&mut self, selected_instance: &Instance) -> Result<(), IoError> {
-        let mods_dir = selected_instance.get_dot_minecraft_path().join("mods");
+    async fn fix(&mut self, game_dir: &Path) -> Result<(), IoError> {
+        if exists(game_dir.join(SAFE_MODE_MODS_DIR)).await {
+            // The real mods are moved aside, don't forget about them
+            return Ok(());
+        }
+        let mods_dir = game_dir.join("mods");
         if !exists(&mods_dir).await {
             fs::create_dir(&mods_dir).await.path(&mods_dir)?;
             self.mods.clear();
@@ -122,11 +186,27 @@ impl ModIndex {
         for id in removed_ids {
             self.mods.remove(&id);
         }
+
+        self.local_mods.retain(|filename, _| {
+            mods_dir.join(filename).is_file()
+                || mods_dir.join(format!("{filename}.disabled")).is_file()
+        });
+    }
+}
+
+/// Mods can't be installed or removed while the
+/// instance is running in safe mode, since its real
+/// `mods` folder is moved aside (see [`SAFE_MODE_MODS_DIR`]).
+pub(crate) async fn check_safe_mode(instance: &Instance) -> Result<(), ModError> {
+    if exists(instance.get_game_dir().await?.join(SAFE_MODE_MODS_DIR)).await {
+        Err(ModError::SafeMode)
+    } else {
+        Ok(())
     }
 }
 
 async fn load_inner(selected_instance: &Instance) -> Result<ModIndex, JsonFileError> {
-    let dot_mc_dir = selected_instance.get_dot_minecraft_path();
+    let dot_mc_dir = selected_instance.get_game_dir().await?;
 
     let mods_dir = dot_mc_dir.join("mods");
     if !exists(&mods_dir).await {
@@ -175,7 +255,9 @@ async fn load_inner(selected_instance: &Instance) -> Result<ModIndex, JsonFileEr
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModFile {
-    // pub hashes: ModHashes,
+    /// Empty for mods installed before hashes were tracked.
+    #[serde(default)]
+    pub hashes: ModHashes,
     pub url: String,
     pub filename: String,
     pub primary: bool,
@@ -183,8 +265,116 @@ pub struct ModFile {
     // pub file_type: Option<String>,
 }
 
-// #[derive(Serialize, Deserialize, Debug, Clone)]
-// pub struct ModHashes {
-//     pub sha512: String,
-//     pub sha1: String,
-// }
+/// Hashes of a [`ModFile`], as given by the store.
+///
+/// Modrinth gives both, Curseforge only gives SHA1.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModHashes {
+    pub sha512: Option<String>,
+    pub sha1: Option<String>,
+}
+
+impl ModHashes {
+    /// The hash to compare files by. SHA1 is
+    /// preferred since both stores provide it.
+    fn key(&self) -> Option<String> {
+        self.sha1
+            .as_deref()
+            .map(|n| format!("sha1:{}", n.to_lowercase()))
+            .or_else(|| {
+                self.sha512
+                    .as_deref()
+                    .map(|n| format!("sha512:{}", n.to_lowercase()))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mod_config(id: &ModId, filename: &str, sha1: Option<&str>) -> ModConfig {
+        ModConfig {
+            name: filename.to_owned(),
+            manually_installed: true,
+            installed_version: "1.0.0".to_owned(),
+            version_release_time: String::new(),
+            enabled: true,
+            description: String::new(),
+            icon_url: None,
+            project_source: StoreBackendType::Modrinth,
+            project_id: id.clone(),
+            files: vec![ModFile {
+                hashes: ModHashes {
+                    sha512: None,
+                    sha1: sha1.map(str::to_owned),
+                },
+                url: String::new(),
+                filename: filename.to_owned(),
+                primary: true,
+            }],
+            supported_versions: Vec::new(),
+            dependencies: HashSet::new(),
+            dependents: HashSet::new(),
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn groups_same_hash() {
+        let sodium = ModId::Modrinth("AANobbMI".to_owned());
+        let sodium_cf = ModId::Curseforge("394468".to_owned());
+        let lithium = ModId::Modrinth("gvQqBUqZ".to_owned());
+        let old = ModId::Modrinth("old".to_owned());
+
+        let mut index = ModIndex::default();
+        for (id, filename, sha1) in [
+            (&sodium, "sodium-fabric-0.5.8.jar", Some("d2a4c0e3")),
+            (&sodium_cf, "sodium-0.5.8-renamed.jar", Some("D2A4C0E3")),
+            (&lithium, "lithium-fabric-0.12.1.jar", Some("9f5b1e07")),
+            (&old, "old.jar", None),
+        ] {
+            index
+                .mods
+                .insert(id.clone(), mod_config(id, filename, sha1));
+        }
+
+        assert_eq!(index.find_duplicates(), vec![vec![sodium, sodium_cf]]);
+    }
+
+    #[tokio::test]
+    async fn keeps_mods_in_safe_mode() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let sodium = ModId::Modrinth("AANobbMI".to_owned());
+        let mut index = ModIndex::default();
+        index
+            .mods
+            .insert(sodium.clone(), mod_config(&sodium, "sodium.jar", None));
+
+        // The real mods folder is moved aside, `mods` is empty
+        tokio::fs::create_dir_all(dir.join("mods")).await.unwrap();
+        tokio::fs::create_dir_all(dir.join(SAFE_MODE_MODS_DIR))
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join(SAFE_MODE_MODS_DIR).join("sodium.jar"), "jar")
+            .await
+            .unwrap();
+        index.fix(dir).await.unwrap();
+        assert!(index.mods.contains_key(&sodium));
+
+        // Back to normal, and the mod is really gone
+        tokio::fs::remove_dir_all(dir.join(SAFE_MODE_MODS_DIR))
+            .await
+            .unwrap();
+        index.fix(dir).await.unwrap();
+        assert!(index.mods.is_empty());
+    }
+
+    #[test]
+    fn reads_old_index_without_hashes() {
+        let file: ModFile =
+            serde_json::from_str(r#"{"url":"","filename":"a.jar","primary":true}"#).unwrap();
+        assert_eq!(file.hashes, ModHashes::default());
+    }
+}
@@ -1,7 +1,14 @@
-use std::{collections::HashSet, path::PathBuf, sync::mpsc::Sender};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+};
 
 use chrono::DateTime;
-use ql_core::{GenericProgress, Instance, IntoIoError, Loader, do_jobs, json::VersionDetails, pt};
+use ql_core::{
+    CancellationToken, GenericProgress, Instance, IntoIoError, Loader, do_jobs,
+    json::VersionDetails, pt,
+};
 
 mod add_file;
 mod curseforge;
@@ -9,29 +16,45 @@ mod delete;
 mod error;
 mod id;
 pub mod image;
+mod integrity;
+mod jar_metadata;
 mod local_json;
 mod modpack;
 mod modrinth;
+mod plugin;
 mod recommended;
+mod snapshot;
 mod toggle;
 mod types;
 mod update;
+mod versions;
 
-pub use add_file::add_files;
+pub use add_file::{add_files, add_files_bulk};
 pub use curseforge::CurseforgeBackend;
 pub use delete::delete_mods;
 pub use error::{GameExpectation, ModError};
 pub use id::ModId;
-pub use local_json::{ModConfig, ModFile, ModIndex};
-pub use modpack::{PackError, install_modpack};
+pub use integrity::{IntegrityWarning, check_game_expectations, check_integrity};
+pub use local_json::{LocalModInfo, ModConfig, ModFile, ModHashes, ModIndex};
+pub use modpack::{
+    ModpackRequirements, PackError, download_modpack, install_modpack, read_modpack_requirements,
+};
 pub use modrinth::ModrinthBackend;
+pub use plugin::{PluginConfig, PluginIndex, download_plugin, toggle_plugins};
 pub use recommended::{RECOMMENDED_MODS, RecommendedMod};
-pub use toggle::{flip_filename, toggle_mods, toggle_mods_local};
+pub use snapshot::{Snapshot, SnapshotId, list_snapshots, restore_snapshot, snapshot_mods};
+pub use toggle::{flip_filename, pin_mods, toggle_mods, toggle_mods_local, unpin_mods};
 pub use types::{
     Category, CurseforgeNotAllowed, Query, QueryType, SearchMod, SearchResult, SelectedMod,
     StoreBackendType,
 };
-pub use update::{ChangelogFile, apply_updates, check_for_updates};
+pub use update::{
+    ChangelogFile, ModUpdates, apply_updates, check_all_instances_for_updates, check_for_updates,
+    check_for_updates_with_pinned, update_before_launch,
+};
+pub use versions::{
+    ModVersionEntry, download_specific_version, get_mod_changelog, list_mod_versions,
+};
 
 #[allow(async_fn_in_trait)]
 pub trait Backend {
@@ -67,11 +90,42 @@ pub trait Backend {
 
     /// Downloads a single mod to the `instance`.
     ///
-    /// Optionally takes in a `sender` to use if it's a modpack.
+    /// Optionally takes in a `sender` and `cancel`
+    /// (see [`install_modpack`]) to use if it's a modpack.
     async fn download(
         id: &str,
         instance: &Instance,
         sender: Option<Sender<GenericProgress>>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<HashSet<CurseforgeNotAllowed>, ModError>;
+
+    /// Gets the versions of a mod, for picking one manually.
+    ///
+    /// `version` and `loader` may be used to narrow down the request,
+    /// but the result isn't guaranteed to be filtered or sorted
+    /// (see [`list_mod_versions`] for that).
+    async fn get_versions(
+        id: &str,
+        version: &str,
+        loader: Loader,
+    ) -> Result<Vec<ModVersionEntry>, ModError>;
+
+    /// Gets the changelog of a specific version of a mod
+    /// (from [`Backend::get_versions`]).
+    ///
+    /// This may use Markdown, HTML, or a mix of both.
+    /// Empty if the author didn't write one.
+    async fn get_changelog(id: &str, version_id: &str) -> Result<String, ModError>;
+
+    /// Downloads a specific version of a mod
+    /// (from [`Backend::get_versions`]) to the `instance`.
+    ///
+    /// Dependencies are still picked automatically.
+    async fn download_version(
+        id: &str,
+        version_id: &str,
+        instance: &Instance,
+        sender: Option<Sender<GenericProgress>>,
     ) -> Result<HashSet<CurseforgeNotAllowed>, ModError>;
     /// Downloads multiple mods to the `instance`.
     ///
@@ -88,7 +142,7 @@ pub trait Backend {
         let mut not_allowed = HashSet::new();
         for id in ids {
             // We don't do this concurrently as there's likely a lock on the index
-            match Self::download(id, instance, sender.cloned()).await {
+            match Self::download(id, instance, sender.cloned(), None).await {
                 Ok(n) => not_allowed.extend(n),
                 Err(ModError::NoCompatibleVersionFound(name)) if ignore_incompatible => {
                     pt!("No compatible version found for mod {name} {id}, skipping...");
@@ -163,15 +217,17 @@ pub async fn search(
 
 /// Downloads a single mod to the `instance`.
 ///
-/// Optionally takes in a `sender` to use if it's a modpack.
+/// Optionally takes in a `sender` and `cancel`
+/// (see [`install_modpack`]) to use if it's a modpack.
 pub async fn download_mod(
     id: &ModId,
     instance: &Instance,
     sender: Option<Sender<GenericProgress>>,
+    cancel: Option<&CancellationToken>,
 ) -> Result<HashSet<CurseforgeNotAllowed>, ModError> {
     match id {
-        ModId::Modrinth(n) => ModrinthBackend::download(n, instance, sender).await,
-        ModId::Curseforge(n) => CurseforgeBackend::download(n, instance, sender).await,
+        ModId::Modrinth(n) => ModrinthBackend::download(n, instance, sender, cancel).await,
+        ModId::Curseforge(n) => CurseforgeBackend::download(n, instance, sender, cancel).await,
     }
 }
 
@@ -301,19 +357,29 @@ struct DirStructure {
     resource_packs: PathBuf,
     shaders: PathBuf,
     data_packs: PathBuf,
+    /// Only used by servers, so not created by default.
+    plugins: PathBuf,
 }
 
 impl DirStructure {
-    async fn new(
-        instance_name: &Instance,
+    async fn new(instance: &Instance, version_json: &VersionDetails) -> Result<Self, ModError> {
+        Self::new_in(
+            &instance.get_game_dir().await?,
+            &instance.get_instance_path(),
+            version_json,
+        )
+        .await
+    }
+
+    async fn new_in(
+        dot_minecraft_dir: &Path,
+        instance_dir: &Path,
         version_json: &VersionDetails,
     ) -> Result<Self, ModError> {
         // Minecraft 13w23b release date (1.6.1 snapshot)
         // Last version with Texture Packs instead of Resource Packs
         const V1_6_1: &str = "2013-06-08T00:32:01+00:00";
 
-        let dot_minecraft_dir = instance_name.get_dot_minecraft_path();
-
         // this doesn't get loaded by default but there are datapack loader mods
         // that are used my modpacks that want to include datapacks.
         // for example https://modrinth.com/mod/dataloader
@@ -344,6 +410,7 @@ impl DirStructure {
             resource_packs,
             shaders,
             data_packs,
+            plugins: plugin::plugins_dir(instance_dir),
         })
     }
 
@@ -353,7 +420,46 @@ impl DirStructure {
             QueryType::ResourcePacks => self.resource_packs.clone(),
             QueryType::Mods => self.mods.clone(),
             QueryType::Shaders => self.shaders.clone(),
+            QueryType::Plugins => self.plugins.clone(),
             QueryType::ModPacks => return Err(PackError::ModpackInModpack),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ql_core::{
+        InstanceKind,
+        json::{InstanceConfigJson, instance_config::VersionInfo},
+        test_utils::version_details,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn mods_go_in_game_dir_override() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let (instance_dir, game_dir) = (dir.join("instances/Moved"), dir.join("ssd/Moved"));
+        tokio::fs::create_dir_all(&instance_dir).await.unwrap();
+
+        let mut config =
+            InstanceConfigJson::new(InstanceKind::Client, false, VersionInfo::new("1.21"));
+        config.game_dir_override = Some(game_dir.clone());
+        config.save_to_dir(&instance_dir).await.unwrap();
+
+        let version_json = version_details("1.21", "2024-06-13T08:24:03+00:00");
+        let config = InstanceConfigJson::read_from_dir(&instance_dir)
+            .await
+            .unwrap();
+        let game_dir_used = config.get_game_dir(&Instance::client("Moved"));
+        assert_eq!(game_dir_used, game_dir);
+
+        let dirs = DirStructure::new_in(&game_dir_used, &instance_dir, &version_json)
+            .await
+            .unwrap();
+        assert_eq!(dirs.get(QueryType::Mods).unwrap(), game_dir.join("mods"));
+        assert!(game_dir.join("mods").is_dir());
+        assert!(!instance_dir.join(".minecraft").exists());
+    }
+}
@@ -17,7 +17,7 @@ use crate::store::{
     curseforge::{self, CFSearchResult, CurseforgeFileQuery, ModQuery, get_query_type},
 };
 
-use super::PackError;
+use super::{InstallTracker, ModpackRequirements, PackError};
 
 #[derive(Deserialize)]
 pub struct PackIndex {
@@ -58,11 +58,12 @@ impl PackFile {
         sender: Option<&Sender<GenericProgress>>,
         (i, len): (&Mutex<usize>, usize),
         cache: &HashMap<i32, curseforge::Mod>,
-        index: &Mutex<ModIndex>,
+        (index, tracker): (&Mutex<ModIndex>, &InstallTracker),
     ) -> Result<(), PackError> {
         if !self.required {
             return Ok(());
         }
+        tracker.check()?;
 
         let mod_info = if let Some(n) = cache.get(&self.projectID) {
             n.clone()
@@ -88,6 +89,7 @@ impl PackFile {
             }
         }
 
+        tracker.will_write(path.clone()).await?;
         download(&url).user_agent_ql().path(&path).await?;
         add_to_index(index, self.projectID.to_string(), &mod_info, query, url).await;
 
@@ -135,6 +137,7 @@ async fn add_to_index(
                 project_source: StoreBackendType::Curseforge,
                 project_id,
                 files: vec![ModFile {
+                    hashes: query.data.mod_hashes(),
                     url,
                     filename: query.data.fileName,
                     primary: true,
@@ -148,6 +151,7 @@ async fn add_to_index(
                     .collect(),
                 dependencies: HashSet::new(),
                 dependents: HashSet::new(),
+                pinned: false,
             },
         );
     }
@@ -180,12 +184,42 @@ async fn send_progress(
     }
 }
 
+impl PackIndex {
+    pub fn requirements(&self) -> Result<ModpackRequirements, PackError> {
+        // `forge-47.2.0`, `fabric-0.15.7`, ...
+        let (loader, loader_version) = self
+            .minecraft
+            .modLoaders
+            .iter()
+            .find_map(|n| {
+                let (name, version) = n.id.split_once('-')?;
+                let loader = match name {
+                    "forge" => Loader::Forge,
+                    "neoforge" => Loader::Neoforge,
+                    "fabric" => Loader::Fabric,
+                    "quilt" => Loader::Quilt,
+                    _ => return None,
+                };
+                Some((loader, version.to_owned()))
+            })
+            .ok_or(PackError::NoLoadersSpecified)?;
+
+        Ok(ModpackRequirements {
+            name: self.name.clone(),
+            minecraft_version: self.minecraft.version.clone(),
+            loader,
+            loader_version: Some(loader_version),
+        })
+    }
+}
+
 pub async fn install(
     instance: &Instance,
     config: &InstanceConfigJson,
     json: &VersionDetails,
     index: &PackIndex,
     sender: Option<&Sender<GenericProgress>>,
+    tracker: &InstallTracker,
 ) -> Result<HashSet<CurseforgeNotAllowed>, PackError> {
     if json.get_id() != index.minecraft.version {
         return Err(PackError::GameVersion {
@@ -236,12 +270,16 @@ pub async fn install(
             .collect()
     };
 
-    do_jobs::<(), PackError>(
-        index
-            .files
-            .iter()
-            .map(|file| file.download(&not_allowed, &dirs, sender, (&i, len), &cache, &mod_index)),
-    )
+    do_jobs::<(), PackError>(index.files.iter().map(|file| {
+        file.download(
+            &not_allowed,
+            &dirs,
+            sender,
+            (&i, len),
+            &cache,
+            (&mod_index, tracker),
+        )
+    }))
     .await?;
 
     mod_index.lock().await.save(instance).await?;
@@ -1,4 +1,4 @@
-use ql_core::{IoError, JsonError, Loader, RequestError, impl_3_errs_jri};
+use ql_core::{Cancelled, IoError, JsonError, Loader, RequestError, impl_3_errs_jri};
 use thiserror::Error;
 
 use crate::store::ModError;
@@ -31,12 +31,18 @@ pub enum PackError {
         "{PACK_ERR_PREFIX}This modpack doesn't have any mod loaders specified.\nIt may be corrupt, unsupported or invalid.\nPlease report this bug in discord."
     )]
     NoLoadersSpecified,
+    #[error(
+        "{PACK_ERR_PREFIX}This modpack doesn't specify a Minecraft version.\nIt may be corrupt, unsupported or invalid."
+    )]
+    NoGameVersion,
     #[error("{PACK_ERR_PREFIX}{0}")]
     Mod(#[from] ModError),
     #[error("{PACK_ERR_PREFIX}found modpack inside modpack!")]
     ModpackInModpack,
     #[error("{PACK_ERR_PREFIX}couldn't identify format (not CurseForge/Modrinth/QMP/...)")]
     NoBackendFound,
+    #[error("{0}")]
+    Cancelled(#[from] Cancelled),
 }
 
 impl_3_errs_jri!(PackError, Json, Request, Io);
@@ -1,14 +1,17 @@
 use std::{
     collections::HashSet,
     io::{Cursor, Read},
+    path::{Path, PathBuf},
     sync::mpsc::Sender,
 };
 
 use ql_core::{
-    GenericProgress, Instance, IntoIoError, IntoJsonError, err, info,
+    CancellationToken, Cancelled, GenericProgress, Instance, IntoIoError, IntoJsonError, Loader,
+    err, file_utils, info,
     json::{InstanceConfigJson, VersionDetails},
     pt,
 };
+use tokio::sync::Mutex;
 
 mod curseforge;
 mod error;
@@ -18,7 +21,11 @@ pub use error::PackError;
 
 use crate::{Preset, store::download_mods_bulk};
 
-use super::CurseforgeNotAllowed;
+use super::{
+    CurseforgeNotAllowed, ModError, ModId,
+    curseforge::{CurseforgeFileQuery, ModQuery},
+    modrinth::{ModVersion, version_sort},
+};
 
 /// Installs a modpack file.
 ///
@@ -34,6 +41,9 @@ use super::CurseforgeNotAllowed;
 /// - `instance: InstanceSelection`: The selected instance you want to download this pack to.
 /// - `sender: Option<&Sender<GenericProgress>>`: Supply a [`Sender`] if you want
 ///   to see the progress of installation. Leave `None` if otherwise.
+/// - `cancel: Option<&CancellationToken>`: Lets you stop the installation.
+///   The files installed so far are deleted, and [`PackError::Cancelled`]
+///   is returned. Leave `None` if not needed.
 ///
 /// # Returns
 /// - `Ok(Some(HashSet<CurseforgeNotAllowed))` - The list of mods that
@@ -46,6 +56,7 @@ pub async fn install_modpack(
     file: Vec<u8>,
     instance: Instance,
     sender: Option<&Sender<GenericProgress>>,
+    cancel: Option<&CancellationToken>,
 ) -> Result<Option<HashSet<CurseforgeNotAllowed>>, PackError> {
     let mut zip = zip::ZipArchive::new(Cursor::new(file.as_slice()))?;
 
@@ -80,29 +91,222 @@ pub async fn install_modpack(
         .as_ref()
         .map_or("overrides".to_owned(), |n| n.overrides.clone());
 
-    let mc_dir = instance.get_dot_minecraft_path();
+    let mc_dir = instance.get_game_dir().await?;
     let config = InstanceConfigJson::read(&instance).await?;
     let json = VersionDetails::load(&instance).await?;
 
-    let mut is_valid = false;
+    let tracker = InstallTracker::new(cancel.cloned().unwrap_or_default(), &mc_dir);
+    let result = async {
+        if let Some(index) = index_json_modrinth {
+            modrinth::install(&instance, &mc_dir, &config, &json, &index, sender, &tracker).await?;
+        }
+        let not_allowed = if let Some(index) = index_json_curseforge {
+            curseforge::install(&instance, &config, &json, &index, sender, &tracker).await?
+        } else {
+            HashSet::new()
+        };
 
-    if let Some(index) = index_json_modrinth {
-        is_valid = true;
-        modrinth::install(&instance, &mc_dir, &config, &json, &index, sender).await?;
+        extract_overrides(
+            &mut zip,
+            &overrides,
+            &mc_dir,
+            |i, len, name| {
+                if let Some(sender) = sender {
+                    _ = sender.send(GenericProgress {
+                        done: i,
+                        total: len,
+                        message: Some(format!(
+                            "Modpack: Creating overrides: {name} ({i}/{len})",
+                            i = i + 1
+                        )),
+                        has_finished: false,
+                    });
+                }
+            },
+            &tracker,
+        )
+        .await?;
+        Ok(not_allowed)
     }
-    let not_allowed = if let Some(index) = index_json_curseforge {
-        is_valid = true;
-        curseforge::install(&instance, &config, &json, &index, sender).await?
-    } else {
-        HashSet::new()
+    .await;
+
+    let not_allowed = match result {
+        Ok(n) => n,
+        Err(PackError::Cancelled(n)) => {
+            info!("Cancelled installing modpack, cleaning up");
+            tracker.undo_written().await;
+            return Err(PackError::Cancelled(n));
+        }
+        Err(err) => {
+            tracker.finish().await;
+            return Err(err);
+        }
     };
+    tracker.finish().await;
 
-    if !is_valid {
-        return Ok(None);
+    pt!("Done!");
+
+    Ok(Some(not_allowed))
+}
+
+/// What a modpack needs from the instance
+/// it's installed to, see [`read_modpack_requirements`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModpackRequirements {
+    /// Name of the modpack
+    pub name: String,
+    pub minecraft_version: String,
+    pub loader: Loader,
+    /// Exact loader version the pack was made with, if known
+    pub loader_version: Option<String>,
+}
+
+/// Reads which Minecraft version and loader a modpack
+/// (Curseforge or Modrinth, like [`install_modpack`]) needs,
+/// for making an instance to install it on.
+///
+/// # Errors
+/// - The file isn't a Curseforge/Modrinth modpack
+///   ([`PackError::NoBackendFound`], QMP presets included)
+/// - The pack's index is invalid or doesn't
+///   specify a Minecraft version or loader
+pub fn read_modpack_requirements(file: &[u8]) -> Result<ModpackRequirements, PackError> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(file))?;
+    if let Some(index) = read_json_from_zip::<modrinth::PackIndex>(&mut zip, "modrinth.index.json")?
+    {
+        return index.requirements();
     }
+    if let Some(index) = read_json_from_zip::<curseforge::PackIndex>(&mut zip, "manifest.json")? {
+        return index.requirements();
+    }
+    Err(PackError::NoBackendFound)
+}
 
+/// Downloads the newest version of a modpack from the store,
+/// for passing to [`install_modpack`].
+///
+/// # Errors
+/// - The pack has no versions/files
+/// - Curseforge doesn't allow downloading it through the launcher
+/// - Network errors, invalid API responses, ...
+pub async fn download_modpack(id: &ModId) -> Result<Vec<u8>, ModError> {
+    let url = match id {
+        ModId::Modrinth(n) => {
+            let mut versions = ModVersion::download(n).await?;
+            versions.sort_by(version_sort);
+            let version = versions.pop().ok_or(ModError::NoFilesFound)?;
+            version
+                .files
+                .iter()
+                .find(|n| n.primary)
+                .or_else(|| version.files.first())
+                .ok_or(ModError::NoFilesFound)?
+                .url
+                .clone()
+        }
+        ModId::Curseforge(n) => {
+            let query = ModQuery::load(n).await?;
+            let file_id = query.data.latest_file_id().ok_or(ModError::NoFilesFound)?;
+            CurseforgeFileQuery::load(n, file_id)
+                .await?
+                .data
+                .downloadUrl
+                .ok_or(ModError::CurseforgeModNotAllowedForDownload(
+                    query.data.name.clone(),
+                    query.data.slug.clone(),
+                ))?
+        }
+    };
+    pt!("Downloading modpack: {url}");
+    Ok(file_utils::download_file_to_bytes(&url, true).await?)
+}
+
+/// Lets a modpack installation be cancelled,
+/// remembering which files it wrote so they can be undone.
+///
+/// Files that the pack overwrites are backed up
+/// (to `.ql_modpack_backup` in the game dir) first,
+/// so cancelling restores them instead of deleting them.
+struct InstallTracker {
+    cancel: CancellationToken,
+    backup_dir: PathBuf,
+    /// Written files, with their backup if they already existed
+    written: Mutex<Vec<(PathBuf, Option<PathBuf>)>>,
+}
+
+impl InstallTracker {
+    fn new(cancel: CancellationToken, mc_dir: &Path) -> Self {
+        Self {
+            cancel,
+            backup_dir: mc_dir.join(".ql_modpack_backup"),
+            written: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn check(&self) -> Result<(), Cancelled> {
+        self.cancel.check()
+    }
+
+    /// Call this before writing to `path`.
+    async fn will_write(&self, path: PathBuf) -> Result<(), PackError> {
+        let mut written = self.written.lock().await;
+        if written.iter().any(|(n, _)| *n == path) {
+            return Ok(());
+        }
+        let backup = if path.is_file() {
+            tokio::fs::create_dir_all(&self.backup_dir)
+                .await
+                .dir(&self.backup_dir)?;
+            let backup = self.backup_dir.join(written.len().to_string());
+            tokio::fs::copy(&path, &backup).await.path(&path)?;
+            Some(backup)
+        } else {
+            None
+        };
+        written.push((path, backup));
+        Ok(())
+    }
+
+    /// Deletes the files the pack added, and
+    /// restores the ones it overwrote.
+    async fn undo_written(&self) {
+        for (path, backup) in self.written.lock().await.drain(..) {
+            let result = match backup {
+                Some(backup) => tokio::fs::rename(&backup, &path).await,
+                None => tokio::fs::remove_file(&path).await,
+            };
+            if let Err(error) = result {
+                err!("Couldn't undo {path:?}: {error}");
+            }
+        }
+        self.finish().await;
+    }
+
+    /// Removes the backups, keeping what was written.
+    async fn finish(&self) {
+        if self.backup_dir.exists() {
+            if let Err(error) = tokio::fs::remove_dir_all(&self.backup_dir).await {
+                err!(
+                    "Couldn't remove modpack backup {:?}: {error}",
+                    self.backup_dir
+                );
+            }
+        }
+    }
+}
+
+/// Copies the files in `overrides/` (configs, resource packs, etc.)
+/// from the modpack to the `.minecraft` folder.
+async fn extract_overrides(
+    zip: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    overrides: &str,
+    mc_dir: &Path,
+    on_progress: impl Fn(usize, usize, &str),
+    tracker: &InstallTracker,
+) -> Result<(), PackError> {
     let len = zip.len();
     for i in 0..len {
+        tracker.check()?;
         let mut file = zip.by_index(i)?;
         let name = file.name().to_owned();
 
@@ -110,17 +314,7 @@ pub async fn install_modpack(
             continue;
         }
 
-        if let Some(sender) = sender {
-            _ = sender.send(GenericProgress {
-                done: i,
-                total: len,
-                message: Some(format!(
-                    "Modpack: Creating overrides: {name} ({i}/{len})",
-                    i = i + 1
-                )),
-                has_finished: false,
-            });
-        }
+        on_progress(i, len, &name);
 
         if let Some(name) = name
             .strip_prefix(&format!("{overrides}/"))
@@ -142,16 +336,14 @@ pub async fn install_modpack(
                 file.read_to_end(&mut buf)
                     .map_err(|n| PackError::ZipIoError(n, name.to_owned()))?;
 
+                tracker.will_write(path.clone()).await?;
                 tokio::fs::write(&path, &buf).await.path(&path)?;
             }
         } else {
             err!("Unrecognised file: {name}");
         }
     }
-
-    pt!("Done!");
-
-    Ok(Some(not_allowed))
+    Ok(())
 }
 
 fn read_json_from_zip<T: serde::de::DeserializeOwned>(
@@ -167,3 +359,132 @@ fn read_json_from_zip<T: serde::de::DeserializeOwned>(
         None
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn zip_with(name: &str, contents: &str) -> Vec<u8> {
+        let mut pack = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut pack);
+            zip.start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+            zip.finish().unwrap();
+        }
+        pack.into_inner()
+    }
+
+    #[test]
+    fn modpack_requirements() {
+        let mrpack = zip_with(
+            "modrinth.index.json",
+            r#"{
+                "formatVersion": 1,
+                "game": "minecraft",
+                "versionId": "1.0.0",
+                "name": "Fabulously Optimized",
+                "files": [],
+                "dependencies": { "minecraft": "1.20.1", "fabric-loader": "0.15.7" }
+            }"#,
+        );
+        assert_eq!(
+            read_modpack_requirements(&mrpack).unwrap(),
+            ModpackRequirements {
+                name: "Fabulously Optimized".to_owned(),
+                minecraft_version: "1.20.1".to_owned(),
+                loader: Loader::Fabric,
+                loader_version: Some("0.15.7".to_owned()),
+            }
+        );
+
+        let curseforge = zip_with(
+            "manifest.json",
+            r#"{
+                "minecraft": {
+                    "version": "1.12.2",
+                    "modLoaders": [{ "id": "forge-14.23.5.2860", "primary": true }]
+                },
+                "manifestType": "minecraftModpack",
+                "name": "RLCraft",
+                "files": [],
+                "overrides": "overrides"
+            }"#,
+        );
+        let requirements = read_modpack_requirements(&curseforge).unwrap();
+        assert_eq!(requirements.minecraft_version, "1.12.2");
+        assert_eq!(requirements.loader, Loader::Forge);
+        assert_eq!(requirements.loader_version.as_deref(), Some("14.23.5.2860"));
+
+        let preset = zip_with("index.json", "{}");
+        assert!(matches!(
+            read_modpack_requirements(&preset),
+            Err(PackError::NoBackendFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn cancel_removes_partial_overrides() {
+        let mut pack = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut pack);
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("modrinth.index.json", options).unwrap();
+            zip.write_all(b"{}").unwrap();
+            for name in ["a.txt", "config/b.txt", "config/c.txt"] {
+                zip.start_file(format!("overrides/{name}"), options)
+                    .unwrap();
+                zip.write_all(name.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        let pack = pack.into_inner();
+        let mut zip = zip::ZipArchive::new(Cursor::new(pack.as_slice())).unwrap();
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        // Already there, the user's own
+        tokio::fs::create_dir_all(dir.join("config")).await.unwrap();
+        tokio::fs::write(dir.join("config/b.txt"), "mine")
+            .await
+            .unwrap();
+
+        let tracker = InstallTracker::new(CancellationToken::new(), dir);
+        // Cancel partway through (while extracting the second file)
+        let result = extract_overrides(
+            &mut zip,
+            "overrides",
+            dir,
+            |i, _, _| {
+                if i == 2 {
+                    tracker.cancel.cancel();
+                }
+            },
+            &tracker,
+        )
+        .await;
+
+        assert!(matches!(result, Err(PackError::Cancelled(Cancelled))));
+        assert!(dir.join("a.txt").is_file());
+        assert_eq!(
+            tokio::fs::read_to_string(dir.join("config/b.txt"))
+                .await
+                .unwrap(),
+            "config/b.txt"
+        );
+        assert!(!dir.join("config/c.txt").exists());
+
+        tracker.undo_written().await;
+        assert!(!dir.join("a.txt").exists());
+        assert_eq!(
+            tokio::fs::read_to_string(dir.join("config/b.txt"))
+                .await
+                .unwrap(),
+            "mine"
+        );
+        assert!(!dir.join(".ql_modpack_backup").exists());
+    }
+}
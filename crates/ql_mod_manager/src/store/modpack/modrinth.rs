@@ -8,7 +8,7 @@ use ql_core::{
 use serde::Deserialize;
 use tokio::sync::Mutex;
 
-use super::PackError;
+use super::{InstallTracker, ModpackRequirements, PackError};
 
 #[derive(Deserialize)]
 pub struct PackIndex {
@@ -39,6 +39,32 @@ pub struct PackEnv {
     server: String,
 }
 
+impl PackIndex {
+    pub fn requirements(&self) -> Result<ModpackRequirements, PackError> {
+        let minecraft_version = self
+            .dependencies
+            .get("minecraft")
+            .ok_or(PackError::NoGameVersion)?
+            .clone();
+        let (loader, loader_version) = [
+            ("forge", Loader::Forge),
+            ("neoforge", Loader::Neoforge),
+            ("fabric-loader", Loader::Fabric),
+            ("quilt-loader", Loader::Quilt),
+        ]
+        .into_iter()
+        .find_map(|(key, loader)| Some((loader, self.dependencies.get(key)?.clone())))
+        .ok_or(PackError::NoLoadersSpecified)?;
+
+        Ok(ModpackRequirements {
+            name: self.name.clone(),
+            minecraft_version,
+            loader,
+            loader_version: Some(loader_version),
+        })
+    }
+}
+
 pub async fn install(
     instance: &Instance,
     mc_dir: &Path,
@@ -46,6 +72,7 @@ pub async fn install(
     json: &VersionDetails,
     index: &PackIndex,
     sender: Option<&Sender<GenericProgress>>,
+    tracker: &InstallTracker,
 ) -> Result<(), PackError> {
     if let Some(version) = index.dependencies.get("minecraft") {
         if json.get_id() != *version {
@@ -101,7 +128,9 @@ pub async fn install(
                     url
                 };
 
+                tracker.check()?;
                 let bytes_path = mc_dir.join(&file.path);
+                tracker.will_write(bytes_path.clone()).await?;
                 download(url).user_agent_ql().path(&bytes_path).await?;
 
                 send_progress(sender, i, len, file).await;
@@ -6,14 +6,15 @@ use std::{
 
 use chrono::DateTime;
 use ql_core::{
-    GenericProgress, Instance, InstanceConfigJson, download, err, file_utils, info,
-    json::VersionDetails, pt,
+    CancellationToken, GenericProgress, Instance, InstanceConfigJson, download, err, file_utils,
+    info, json::VersionDetails, pt,
 };
 
 use crate::store::{
     DirStructure, ModError, ModId, QueryType, StoreBackendType, install_modpack,
-    local_json::{ModConfig, ModIndex},
+    local_json::{ModConfig, ModIndex, check_safe_mode},
     modrinth::versions::ModVersion,
+    versions::VersionDownloader,
 };
 
 use super::info::ProjectInfo;
@@ -28,6 +29,11 @@ pub struct ModDownloader {
     pub info: HashMap<String, ProjectInfo>,
     sender: Option<Sender<GenericProgress>>,
     dirs: DirStructure,
+    /// (project id, version id): Installs this version of the project
+    /// instead of the latest compatible one.
+    pub pinned_version: Option<(String, String)>,
+    /// Stops a modpack's installation early, see [`install_modpack`].
+    pub cancel: Option<CancellationToken>,
 }
 
 impl ModDownloader {
@@ -35,6 +41,7 @@ impl ModDownloader {
         instance: &Instance,
         sender: Option<Sender<GenericProgress>>,
     ) -> Result<ModDownloader, ModError> {
+        check_safe_mode(instance).await?;
         let version_json = VersionDetails::load(instance).await?;
         let config = InstanceConfigJson::read(instance).await?;
         let index = ModIndex::load(instance).await?;
@@ -53,6 +60,8 @@ impl ModDownloader {
             sender,
 
             dirs: DirStructure::new(instance, &version_json).await?,
+            pinned_version: None,
+            cancel: None,
         })
     }
 
@@ -74,6 +83,8 @@ impl ModDownloader {
             instance: instance.clone(),
             sender: None,
             dirs: DirStructure::new(instance, &version_json).await?,
+            pinned_version: None,
+            cancel: None,
         })
     }
 
@@ -236,6 +247,11 @@ impl ModDownloader {
         title: Option<&str>,
         project_type: QueryType,
     ) -> Result<ModVersion, ModError> {
+        if let Some((_, version_id)) = self.pinned_version.as_ref().filter(|n| n.0 == id) {
+            pt!("Getting download info (version: {version_id})");
+            return ModVersion::load(version_id).await;
+        }
+
         pt!("Getting download info");
         let download_info = ModVersion::download(id).await?;
 
@@ -275,9 +291,14 @@ impl ModDownloader {
     ) -> Result<(), ModError> {
         if let QueryType::ModPacks = project_type {
             let bytes = file_utils::download_file_to_bytes(&file.url, true).await?;
-            let incompatible = install_modpack(bytes, self.instance.clone(), self.sender.as_ref())
-                .await
-                .map_err(Box::new)?;
+            let incompatible = install_modpack(
+                bytes,
+                self.instance.clone(),
+                self.sender.as_ref(),
+                self.cancel.as_ref(),
+            )
+            .await
+            .map_err(Box::new)?;
             debug_assert!(
                 incompatible.is_some(),
                 "invalid modpack downloaded from modrinth store!"
@@ -318,6 +339,7 @@ impl ModDownloader {
             installed_version: download_version.version_number.clone(),
             version_release_time: download_version.date_published.clone(),
             project_source: StoreBackendType::Modrinth,
+            pinned: false,
         };
 
         if let QueryType::Mods = project_type {
@@ -348,6 +370,16 @@ pub fn version_sort(a: &ModVersion, b: &ModVersion) -> Ordering {
     a.cmp(&b)
 }
 
+impl VersionDownloader for ModDownloader {
+    fn index(&mut self) -> &mut ModIndex {
+        &mut self.index
+    }
+
+    async fn download_mod(&mut self, id: &str) -> Result<(), ModError> {
+        self.download(id, None, true).await
+    }
+}
+
 fn print_downloading_message(project_info: &ProjectInfo, dependent: Option<&str>) {
     if let Some(dependent) = dependent {
         pt!(
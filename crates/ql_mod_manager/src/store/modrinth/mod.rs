@@ -1,19 +1,24 @@
 use std::{collections::HashSet, sync::mpsc::Sender, time::Instant};
 
 use chrono::DateTime;
-use download::version_sort;
+pub(super) use download::version_sort;
 use indexmap::IndexMap;
-use info::ProjectInfo;
-use ql_core::{GenericProgress, Instance, Loader, download, pt};
+pub(super) use info::ProjectInfo;
+use ql_core::{
+    CancellationToken, GenericProgress, Instance, IntoJsonError, Loader, download, file_utils, pt,
+};
 use serde::Deserialize;
-use versions::ModVersion;
+pub(super) use versions::ModVersion;
 
 use crate::{
     rate_limiter::{RATE_LIMITER, lock},
     store::{Category, ModId, QueryType, SearchMod, StoreBackendType, types::GalleryItem},
 };
 
-use super::{Backend, CurseforgeNotAllowed, ModError, Query, SearchResult};
+use super::{
+    Backend, CurseforgeNotAllowed, ModError, ModVersionEntry, Query, SearchResult,
+    versions::install_version,
+};
 
 mod download;
 mod info;
@@ -22,6 +27,16 @@ mod versions;
 
 pub struct ModrinthBackend;
 
+/// Reads the changelog from a `/version/{id}` response
+pub(super) fn parse_changelog(json: &str) -> Result<String, ModError> {
+    #[derive(Deserialize)]
+    struct Version {
+        changelog: Option<String>,
+    }
+    let version: Version = serde_json::from_str(json).json(json.to_owned())?;
+    Ok(version.changelog.unwrap_or_default())
+}
+
 impl Backend for ModrinthBackend {
     async fn search(query: Query, offset: usize) -> Result<SearchResult, ModError> {
         RATE_LIMITER.lock().await;
@@ -111,10 +126,12 @@ impl Backend for ModrinthBackend {
         id: &str,
         instance: &Instance,
         sender: Option<Sender<GenericProgress>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<HashSet<CurseforgeNotAllowed>, ModError> {
         let _guard = lock().await;
 
         let mut downloader = download::ModDownloader::new(instance, sender).await?;
+        downloader.cancel = cancel.cloned();
         downloader.download(id, None, true).await?;
 
         downloader.index.save(instance).await?;
@@ -124,6 +141,63 @@ impl Backend for ModrinthBackend {
         Ok(HashSet::new())
     }
 
+    async fn get_versions(
+        id: &str,
+        _version: &str,
+        _loader: Loader,
+    ) -> Result<Vec<ModVersionEntry>, ModError> {
+        let versions = ModVersion::download(id).await?;
+        let mut entries = Vec::new();
+        for version in versions {
+            let Some(file) = version
+                .files
+                .iter()
+                .find(|file| file.primary)
+                .or_else(|| version.files.first())
+            else {
+                continue;
+            };
+            entries.push(ModVersionEntry {
+                changelog_url: format!("https://modrinth.com/project/{id}/version/{}", version.id),
+                filename: file.filename.clone(),
+                hashes: file.hashes.clone(),
+                date_published: DateTime::parse_from_rfc3339(&version.date_published)?,
+                id: version.id,
+                version_number: version.version_number,
+                game_versions: version.game_versions,
+                loaders: version.loaders,
+            });
+        }
+        Ok(entries)
+    }
+
+    async fn get_changelog(_id: &str, version_id: &str) -> Result<String, ModError> {
+        RATE_LIMITER.lock().await;
+        let url = format!("https://api.modrinth.com/v2/version/{version_id}");
+        let json = file_utils::download_file_to_string(&url, true).await?;
+        parse_changelog(&json)
+    }
+
+    async fn download_version(
+        id: &str,
+        version_id: &str,
+        instance: &Instance,
+        sender: Option<Sender<GenericProgress>>,
+    ) -> Result<HashSet<CurseforgeNotAllowed>, ModError> {
+        let _guard = lock().await;
+
+        let mut downloader = download::ModDownloader::new(instance, sender).await?;
+        downloader.pinned_version = Some((id.to_owned(), version_id.to_owned()));
+        let mods_dir = instance.get_game_dir().await?.join("mods");
+        install_version(&mut downloader, &ModId::Modrinth(id.to_owned()), &mods_dir).await?;
+
+        downloader.index.save(instance).await?;
+
+        pt!("Finished");
+
+        Ok(HashSet::new())
+    }
+
     async fn download_bulk(
         ids: &[String],
         instance: &Instance,
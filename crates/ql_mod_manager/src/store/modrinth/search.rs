@@ -1,6 +1,6 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Duration};
 
-use ql_core::IntoJsonError;
+use ql_core::{IntoJsonError, retry_with_backoff, should_retry_response};
 use serde::Deserialize;
 
 use crate::store::{ModError, Query, QueryType};
@@ -50,13 +50,12 @@ pub async fn do_request(query: &Query, offset: usize) -> Result<Search, ModError
     let filters = serde_json::to_string(&filters).json_to()?;
     params.insert("facets", filters);
 
-    let text = ql_core::CLIENT
-        .get(SEARCH_URL)
-        .query(&params)
-        .send()
-        .await?
-        .text()
-        .await?;
+    let text = retry_with_backoff(3, Duration::from_millis(500), should_retry_response, || {
+        ql_core::CLIENT.get(SEARCH_URL).query(&params).send()
+    })
+    .await?
+    .text()
+    .await?;
 
     let json: Search = match serde_json::from_str(&text) {
         Ok(json) => json,
@@ -9,7 +9,7 @@ use super::ModError;
 pub struct ModVersion {
     pub game_versions: Vec<String>,
     pub loaders: Vec<String>,
-    // pub id: String,
+    pub id: String,
     // pub project_id: String,
     // pub author_id: String,
     // pub featured: bool,
@@ -35,6 +35,12 @@ impl ModVersion {
         Ok(file_utils::download_file_to_json(&url, true).await?)
     }
 
+    pub async fn load(version_id: &str) -> Result<Self, ModError> {
+        RATE_LIMITER.lock().await;
+        let url = format!("https://api.modrinth.com/v2/version/{version_id}");
+        Ok(file_utils::download_file_to_json(&url, true).await?)
+    }
+
     // pub async fn is_compatible(
     //     project_id: &str,
     //     minecraft_version: &String,
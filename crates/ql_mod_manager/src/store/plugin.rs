@@ -0,0 +1,296 @@
+//! Server plugins (Paper/Spigot/Bukkit), installed from Modrinth
+//! into `servers/<NAME>/plugins/`.
+//!
+//! These are tracked separately from mods, in a [`PluginIndex`]
+//! (`servers/<NAME>/plugin_index.json`).
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use ql_core::{
+    Instance, InstanceConfigJson, IntoIoError, IntoJsonError, JsonFileError, Loader, download,
+    file_utils::exists, info, json::VersionDetails, pt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::rate_limiter::lock;
+
+use super::{
+    ModError, ModId, StoreBackendType,
+    modrinth::{ModVersion, ProjectInfo, version_sort},
+    toggle::{flip_filename, rename_file},
+};
+
+/// Modrinth loaders whose plugins run on Paper.
+const PLUGIN_LOADERS: &[&str] = &["paper", "spigot", "bukkit"];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PluginConfig {
+    pub name: String,
+    pub description: String,
+    pub icon_url: Option<String>,
+    pub project_source: StoreBackendType,
+    pub project_id: ModId,
+    /// Name of the plugin's `.jar` in the `plugins` folder
+    /// (without `.disabled`, even if disabled).
+    pub filename: String,
+    pub installed_version: String,
+    pub version_release_time: String,
+    pub enabled: bool,
+}
+
+/// Plugins installed on a server from the store,
+/// like [`super::ModIndex`] for mods.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PluginIndex {
+    pub plugins: HashMap<ModId, PluginConfig>,
+}
+
+impl PluginIndex {
+    pub async fn load(server: &Instance) -> Result<Self, JsonFileError> {
+        Self::load_from_dir(&server.get_instance_path()).await
+    }
+
+    pub async fn save(&self, server: &Instance) -> Result<(), JsonFileError> {
+        self.save_to_dir(&server.get_instance_path()).await
+    }
+
+    async fn load_from_dir(server_dir: &Path) -> Result<Self, JsonFileError> {
+        let path = server_dir.join("plugin_index.json");
+        match tokio::fs::read_to_string(&path).await {
+            Ok(index) => Ok(serde_json::from_str(&index).json(index)?),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(ql_core::IoError::Io { error, path }.into()),
+        }
+    }
+
+    async fn save_to_dir(&self, server_dir: &Path) -> Result<(), JsonFileError> {
+        let path = server_dir.join("plugin_index.json");
+        let index = serde_json::to_string(self).json_to()?;
+        tokio::fs::write(&path, index).await.path(path)?;
+        Ok(())
+    }
+}
+
+pub(crate) fn plugins_dir(server_dir: &Path) -> PathBuf {
+    server_dir.join("plugins")
+}
+
+/// Downloads a plugin from Modrinth (by project ID)
+/// into the `plugins` folder of a Paper server.
+///
+/// Plugin dependencies aren't installed automatically.
+/// If another version of the plugin is installed, its
+/// file is removed once the new one is downloaded.
+///
+/// # Errors
+/// - `server` isn't a server, or doesn't have Paper installed
+/// - No version of the plugin supports the server's Minecraft version
+/// - Modrinth couldn't be reached, or the plugin couldn't be saved
+pub async fn download_plugin(id: &str, server: &Instance) -> Result<(), ModError> {
+    if !server.is_server() {
+        return Err(ModError::PluginsNeedServer);
+    }
+    let config = InstanceConfigJson::read(server).await?;
+    if config.mod_type != Loader::Paper {
+        return Err(ModError::PluginsNeedPaper(config.mod_type));
+    }
+    let version_json = VersionDetails::load(server).await?;
+    let minecraft_version = version_json.get_id();
+
+    let _guard = lock().await;
+    info!("Installing plugin (id: {id})");
+    let project = ProjectInfo::download(id).await?;
+    pt!("Getting download info");
+    let version = ModVersion::download(id)
+        .await?
+        .into_iter()
+        .filter(|n| n.game_versions.iter().any(|v| v == minecraft_version))
+        .filter(|n| {
+            n.loaders
+                .iter()
+                .any(|l| PLUGIN_LOADERS.contains(&l.as_str()))
+        })
+        .max_by(version_sort)
+        .ok_or_else(|| ModError::NoCompatibleVersionFound(project.title.clone()))?;
+    let file = version
+        .files
+        .iter()
+        .find(|n| n.primary)
+        .or_else(|| version.files.first())
+        .ok_or(ModError::NoFilesFound)?;
+
+    let server_dir = server.get_instance_path();
+    let plugins_dir = plugins_dir(&server_dir);
+    pt!("Downloading {}", project.title);
+    download(&file.url)
+        .user_agent_ql()
+        .path(&plugins_dir.join(&file.filename))
+        .await?;
+
+    let mut index = PluginIndex::load_from_dir(&server_dir).await?;
+    add_to_index(
+        &mut index,
+        &plugins_dir,
+        PluginConfig {
+            name: project.title,
+            description: project.description,
+            icon_url: project.icon_url,
+            project_source: StoreBackendType::Modrinth,
+            project_id: ModId::Modrinth(project.id),
+            filename: file.filename.clone(),
+            installed_version: version.version_number,
+            version_release_time: version.date_published,
+            enabled: true,
+        },
+    )
+    .await?;
+    index.save_to_dir(&server_dir).await?;
+    info!("Installed plugin");
+    Ok(())
+}
+
+/// Adds a freshly downloaded plugin to the index, removing the
+/// file(s) of the version it replaces, as Paper refuses
+/// to start with two copies of a plugin.
+async fn add_to_index(
+    index: &mut PluginIndex,
+    plugins_dir: &Path,
+    config: PluginConfig,
+) -> Result<(), ModError> {
+    let filename = config.filename.clone();
+    let Some(old) = index.plugins.insert(config.project_id.clone(), config) else {
+        return Ok(());
+    };
+    let disabled = flip_filename(&old.filename);
+    let old_files = [old.filename, disabled]
+        .into_iter()
+        .filter(|n| *n != filename);
+    for name in old_files {
+        let path = plugins_dir.join(name);
+        if exists(&path).await {
+            tokio::fs::remove_file(&path).await.path(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Enables/disables plugins on a server, by renaming
+/// their `.jar` to `.jar.disabled` (or back).
+pub async fn toggle_plugins(ids: Vec<ModId>, server: Instance) -> Result<(), ModError> {
+    let _guard = lock().await;
+    toggle_in_dir(&ids, &server.get_instance_path()).await
+}
+
+async fn toggle_in_dir(ids: &[ModId], server_dir: &Path) -> Result<(), ModError> {
+    let mut index = PluginIndex::load_from_dir(server_dir).await?;
+    let plugins_dir = plugins_dir(server_dir);
+
+    for id in ids {
+        let Some(plugin) = index.plugins.get_mut(id) else {
+            continue;
+        };
+        let enabled_path = plugins_dir.join(&plugin.filename);
+        let disabled_path = plugins_dir.join(flip_filename(&plugin.filename));
+        if plugin.enabled {
+            rename_file(&enabled_path, &disabled_path).await?;
+        } else {
+            rename_file(&disabled_path, &enabled_path).await?;
+        }
+        plugin.enabled = !plugin.enabled;
+    }
+
+    index.save_to_dir(server_dir).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn luckperms(id: &ModId, filename: &str) -> PluginConfig {
+        PluginConfig {
+            name: "LuckPerms".to_owned(),
+            description: String::new(),
+            icon_url: None,
+            project_source: StoreBackendType::Modrinth,
+            project_id: id.clone(),
+            filename: filename.to_owned(),
+            installed_version: "5.4".to_owned(),
+            version_release_time: String::new(),
+            enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn installs_into_plugins_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let server_dir = temp.path();
+        tokio::fs::create_dir_all(plugins_dir(server_dir))
+            .await
+            .unwrap();
+        assert_eq!(plugins_dir(server_dir), server_dir.join("plugins"));
+
+        let id = ModId::Modrinth("fRQREgAc".to_owned());
+        let jar = plugins_dir(server_dir).join("LuckPerms-Bukkit-5.4.jar");
+        tokio::fs::write(&jar, "jar").await.unwrap();
+
+        let mut index = PluginIndex::load_from_dir(server_dir).await.unwrap();
+        assert!(index.plugins.is_empty());
+        add_to_index(
+            &mut index,
+            &plugins_dir(server_dir),
+            luckperms(&id, "LuckPerms-Bukkit-5.4.jar"),
+        )
+        .await
+        .unwrap();
+        index.save_to_dir(server_dir).await.unwrap();
+
+        let index = PluginIndex::load_from_dir(server_dir).await.unwrap();
+        assert_eq!(index.plugins[&id].filename, "LuckPerms-Bukkit-5.4.jar");
+
+        toggle_in_dir(std::slice::from_ref(&id), server_dir)
+            .await
+            .unwrap();
+        assert!(!jar.exists());
+        assert!(
+            plugins_dir(server_dir)
+                .join("LuckPerms-Bukkit-5.4.jar.disabled")
+                .exists()
+        );
+        let index = PluginIndex::load_from_dir(server_dir).await.unwrap();
+        assert!(!index.plugins[&id].enabled);
+
+        toggle_in_dir(&[id], server_dir).await.unwrap();
+        assert!(jar.exists());
+    }
+
+    #[tokio::test]
+    async fn new_version_replaces_old_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let old_jar = dir.join("LuckPerms-Bukkit-5.3.jar.disabled");
+        let new_jar = dir.join("LuckPerms-Bukkit-5.4.jar");
+        tokio::fs::write(&old_jar, "old").await.unwrap();
+        tokio::fs::write(&new_jar, "new").await.unwrap();
+
+        let id = ModId::Modrinth("fRQREgAc".to_owned());
+        let mut old = luckperms(&id, "LuckPerms-Bukkit-5.3.jar");
+        old.enabled = false;
+        let mut index = PluginIndex::default();
+        index.plugins.insert(id.clone(), old);
+
+        let new = luckperms(&id, "LuckPerms-Bukkit-5.4.jar");
+        add_to_index(&mut index, dir, new).await.unwrap();
+        assert!(!old_jar.exists());
+        assert!(new_jar.is_file());
+        assert_eq!(index.plugins[&id].filename, "LuckPerms-Bukkit-5.4.jar");
+
+        // Same file again (reinstall) is kept
+        let new = luckperms(&id, "LuckPerms-Bukkit-5.4.jar");
+        add_to_index(&mut index, dir, new).await.unwrap();
+        assert!(new_jar.is_file());
+    }
+}
@@ -0,0 +1,332 @@
+//! Restore points for an instance's mods, like before
+//! applying updates or presets (see [`super::apply_updates`]).
+//!
+//! Each snapshot is stored in `INSTANCE/mod_snapshots/ID/`, with:
+//! - `snapshot.json`: label and time
+//! - `mod_index.json`: a copy of the mod index
+//! - `mods/` and `config/`: copies of those folders
+
+use std::{fmt::Display, path::Path};
+
+use chrono::{DateTime, Utc};
+use ql_core::{Instance, IntoIoError, IntoJsonError, file_utils, info, pt};
+use serde::{Deserialize, Serialize};
+
+use super::ModError;
+
+const SNAPSHOTS_DIR: &str = "mod_snapshots";
+const SNAPSHOT_JSON: &str = "snapshot.json";
+/// Copied into and out of the snapshot, relative to `.minecraft`.
+const SNAPSHOT_DIRS: &[&str] = &["mods", "config"];
+/// Added to [`SNAPSHOT_DIRS`] while they're being restored.
+const RESTORING_SUFFIX: &str = ".restoring";
+
+/// Identifies a snapshot of an instance's mods,
+/// from [`snapshot_mods`] or [`list_snapshots`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SnapshotId(String);
+
+impl Display for SnapshotId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub id: SnapshotId,
+    pub label: String,
+    pub created: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotJson {
+    label: String,
+    /// Unix timestamp, in milliseconds
+    created: i64,
+}
+
+/// Saves the instance's mods (`mods` folder, mod index
+/// and `config` folder) so they can be rolled back to later
+/// with [`restore_snapshot`].
+///
+/// # Errors
+/// If the files couldn't be copied.
+pub async fn snapshot_mods(instance: &Instance, label: &str) -> Result<SnapshotId, ModError> {
+    info!("Creating mods snapshot: {label}");
+    snapshot_dirs(
+        &instance.get_instance_path(),
+        &instance.get_game_dir().await?,
+        label,
+        Utc::now(),
+    )
+    .await
+}
+
+/// Rolls the instance's mods back to a snapshot from [`snapshot_mods`].
+/// Any changes to mods or configs since then are lost.
+///
+/// # Errors
+/// - The snapshot doesn't exist (or is corrupted)
+/// - Files couldn't be copied
+pub async fn restore_snapshot(instance: &Instance, id: &SnapshotId) -> Result<(), ModError> {
+    info!("Restoring mods snapshot: {id}");
+    restore_dirs(
+        &instance.get_instance_path(),
+        &instance.get_game_dir().await?,
+        id,
+    )
+    .await
+}
+
+/// Lists the snapshots of an instance, oldest first.
+///
+/// # Errors
+/// If the snapshots folder couldn't be read.
+pub async fn list_snapshots(instance: &Instance) -> Result<Vec<Snapshot>, ModError> {
+    list_dirs(&instance.get_instance_path()).await
+}
+
+async fn snapshot_dirs(
+    instance_dir: &Path,
+    dot_minecraft: &Path,
+    label: &str,
+    now: DateTime<Utc>,
+) -> Result<SnapshotId, ModError> {
+    let snapshots_dir = instance_dir.join(SNAPSHOTS_DIR);
+    let mut id = now.timestamp_millis().to_string();
+    // Two snapshots in the same millisecond
+    while file_utils::exists(&snapshots_dir.join(&id)).await {
+        id.push('_');
+    }
+    let snapshot_dir = snapshots_dir.join(&id);
+    tokio::fs::create_dir_all(&snapshot_dir)
+        .await
+        .path(&snapshot_dir)?;
+
+    for dir in SNAPSHOT_DIRS {
+        let src = dot_minecraft.join(dir);
+        if src.is_dir() {
+            pt!("Saving {dir}");
+            file_utils::copy_dir_recursive(&src, &snapshot_dir.join(dir)).await?;
+        }
+    }
+    let index_path = dot_minecraft.join("mod_index.json");
+    if index_path.is_file() {
+        let dest = snapshot_dir.join("mod_index.json");
+        tokio::fs::copy(&index_path, &dest)
+            .await
+            .path(&index_path)?;
+    }
+
+    // Written last, so incomplete snapshots aren't listed
+    let json = SnapshotJson {
+        label: label.to_owned(),
+        created: now.timestamp_millis(),
+    };
+    let json = serde_json::to_string(&json).json_to()?;
+    let json_path = snapshot_dir.join(SNAPSHOT_JSON);
+    tokio::fs::write(&json_path, json).await.path(&json_path)?;
+
+    Ok(SnapshotId(id))
+}
+
+async fn restore_dirs(
+    instance_dir: &Path,
+    dot_minecraft: &Path,
+    id: &SnapshotId,
+) -> Result<(), ModError> {
+    let snapshot_dir = instance_dir.join(SNAPSHOTS_DIR).join(&id.0);
+    // Also checks that the snapshot is complete
+    read_snapshot_json(&snapshot_dir).await?;
+
+    // Copied next to the real folders first, and only swapped
+    // in once everything's copied, so a failed copy doesn't lose mods
+    let mut staged = Vec::new();
+    for dir in SNAPSHOT_DIRS {
+        let src = snapshot_dir.join(dir);
+        let staging = dot_minecraft.join(format!("{dir}{RESTORING_SUFFIX}"));
+        if staging.is_dir() {
+            tokio::fs::remove_dir_all(&staging).await.path(&staging)?;
+        }
+        if src.is_dir() {
+            pt!("Restoring {dir}");
+            if let Err(err) = file_utils::copy_dir_recursive(&src, &staging).await {
+                staged.push(staging);
+                for staging in staged {
+                    _ = tokio::fs::remove_dir_all(&staging).await;
+                }
+                return Err(err.into());
+            }
+        }
+        staged.push(staging);
+    }
+    for (dir, staging) in SNAPSHOT_DIRS.iter().zip(staged) {
+        let dest = dot_minecraft.join(dir);
+        if dest.is_dir() {
+            tokio::fs::remove_dir_all(&dest).await.path(&dest)?;
+        }
+        if staging.is_dir() {
+            tokio::fs::rename(&staging, &dest).await.path(&dest)?;
+        }
+    }
+    // An empty mods folder is expected to exist
+    let mods_dir = dot_minecraft.join("mods");
+    tokio::fs::create_dir_all(&mods_dir).await.path(&mods_dir)?;
+
+    let index_path = dot_minecraft.join("mod_index.json");
+    let saved_index = snapshot_dir.join("mod_index.json");
+    if saved_index.is_file() {
+        tokio::fs::copy(&saved_index, &index_path)
+            .await
+            .path(&saved_index)?;
+    } else if index_path.is_file() {
+        // Snapshot was made before any mods were installed
+        tokio::fs::remove_file(&index_path)
+            .await
+            .path(&index_path)?;
+    }
+    Ok(())
+}
+
+async fn list_dirs(instance_dir: &Path) -> Result<Vec<Snapshot>, ModError> {
+    let snapshots_dir = instance_dir.join(SNAPSHOTS_DIR);
+    if !snapshots_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = Vec::new();
+    let mut entries = tokio::fs::read_dir(&snapshots_dir)
+        .await
+        .dir(&snapshots_dir)?;
+    while let Some(entry) = entries.next_entry().await.dir(&snapshots_dir)? {
+        let path = entry.path();
+        let Ok(json) = read_snapshot_json(&path).await else {
+            continue;
+        };
+        snapshots.push(Snapshot {
+            id: SnapshotId(entry.file_name().to_string_lossy().into_owned()),
+            label: json.label,
+            created: DateTime::from_timestamp_millis(json.created).unwrap_or_default(),
+        });
+    }
+    snapshots.sort_by(|a, b| a.created.cmp(&b.created).then_with(|| a.id.cmp(&b.id)));
+    Ok(snapshots)
+}
+
+async fn read_snapshot_json(snapshot_dir: &Path) -> Result<SnapshotJson, ModError> {
+    let path = snapshot_dir.join(SNAPSHOT_JSON);
+    let json = tokio::fs::read_to_string(&path).await.path(&path)?;
+    Ok(serde_json::from_str(&json).json(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(path: &Path) -> Option<String> {
+        tokio::fs::read_to_string(path).await.ok()
+    }
+
+    #[tokio::test]
+    async fn round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+        let instance_dir = temp.path();
+        let dot_minecraft = instance_dir.join(".minecraft");
+
+        let files = [
+            ("mods/sodium-0.5.jar", "sodium 0.5"),
+            ("config/sodium-options.json", "{\"fast\":true}"),
+            ("mod_index.json", "{\"mods\":{\"AANobbMI\":{}}}"),
+        ];
+        for (path, contents) in files {
+            let path = dot_minecraft.join(path);
+            tokio::fs::create_dir_all(path.parent().unwrap())
+                .await
+                .unwrap();
+            tokio::fs::write(&path, contents).await.unwrap();
+        }
+
+        let created = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let id = snapshot_dirs(instance_dir, &dot_minecraft, "Before updates", created)
+            .await
+            .unwrap();
+
+        // Like an update
+        tokio::fs::remove_file(dot_minecraft.join("mods/sodium-0.5.jar"))
+            .await
+            .unwrap();
+        tokio::fs::write(dot_minecraft.join("mods/sodium-0.6.jar"), "sodium 0.6")
+            .await
+            .unwrap();
+        tokio::fs::write(dot_minecraft.join("config/sodium-options.json"), "{}")
+            .await
+            .unwrap();
+        tokio::fs::write(dot_minecraft.join("mod_index.json"), "{\"mods\":{}}")
+            .await
+            .unwrap();
+
+        let snapshots = list_dirs(instance_dir).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].id, id);
+        assert_eq!(snapshots[0].label, "Before updates");
+        assert_eq!(snapshots[0].created, created);
+
+        restore_dirs(instance_dir, &dot_minecraft, &id)
+            .await
+            .unwrap();
+        for (path, contents) in files {
+            assert_eq!(
+                read(&dot_minecraft.join(path)).await.as_deref(),
+                Some(contents)
+            );
+        }
+        assert!(
+            read(&dot_minecraft.join("mods/sodium-0.6.jar"))
+                .await
+                .is_none()
+        );
+
+        let missing = SnapshotId("0".to_owned());
+        assert!(
+            restore_dirs(instance_dir, &dot_minecraft, &missing)
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failed_restore_keeps_mods() {
+        let temp = tempfile::tempdir().unwrap();
+        let instance_dir = temp.path();
+        let dot_minecraft = instance_dir.join(".minecraft");
+        tokio::fs::create_dir_all(dot_minecraft.join("mods"))
+            .await
+            .unwrap();
+
+        let id = snapshot_dirs(instance_dir, &dot_minecraft, "Empty", Utc::now())
+            .await
+            .unwrap();
+        // Can't be copied back
+        let snapshot_mods = instance_dir.join(SNAPSHOTS_DIR).join(&id.0).join("mods");
+        tokio::fs::symlink(instance_dir.join("nowhere"), snapshot_mods.join("broken"))
+            .await
+            .unwrap();
+        tokio::fs::write(dot_minecraft.join("mods/sodium.jar"), "sodium")
+            .await
+            .unwrap();
+
+        assert!(
+            restore_dirs(instance_dir, &dot_minecraft, &id)
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            read(&dot_minecraft.join("mods/sodium.jar"))
+                .await
+                .as_deref(),
+            Some("sodium")
+        );
+        assert!(!dot_minecraft.join("mods.restoring").exists());
+    }
+}
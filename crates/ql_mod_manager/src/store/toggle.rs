@@ -2,7 +2,10 @@ use std::path::Path;
 
 use ql_core::{Instance, IoError, err};
 
-use crate::store::{ModId, ModIndex};
+use crate::{
+    rate_limiter::lock,
+    store::{ModId, ModIndex},
+};
 
 use super::ModError;
 
@@ -16,7 +19,7 @@ pub fn flip_filename(name: &str) -> String {
 }
 
 pub async fn toggle_mods_local(names: Vec<String>, instance: Instance) -> Result<(), ModError> {
-    let mods_dir = instance.get_dot_minecraft_path().join("mods");
+    let mods_dir = instance.get_game_dir().await?.join("mods");
 
     for file in names {
         let flipped = flip_filename(&file);
@@ -28,7 +31,7 @@ pub async fn toggle_mods_local(names: Vec<String>, instance: Instance) -> Result
 pub async fn toggle_mods(ids: Vec<ModId>, instance: Instance) -> Result<(), ModError> {
     let mut index = ModIndex::load(&instance).await?;
 
-    let mods_dir = instance.get_dot_minecraft_path().join("mods");
+    let mods_dir = instance.get_game_dir().await?.join("mods");
 
     for id in ids {
         if let Some(info) = index.mods.get_mut(&id) {
@@ -50,7 +53,34 @@ pub async fn toggle_mods(ids: Vec<ModId>, instance: Instance) -> Result<(), ModE
     Ok(())
 }
 
-async fn rename_file(a: &Path, b: &Path) -> Result<(), ModError> {
+/// Pins mods at their installed version,
+/// so [`super::check_for_updates`] and
+/// [`super::apply_updates`] leave them alone.
+///
+/// Useful for freezing a mod at a known-good version.
+pub async fn pin_mods(ids: Vec<ModId>, instance: Instance) -> Result<(), ModError> {
+    set_pinned(ids, instance, true).await
+}
+
+/// Undoes [`pin_mods`], letting the mods be updated again.
+pub async fn unpin_mods(ids: Vec<ModId>, instance: Instance) -> Result<(), ModError> {
+    set_pinned(ids, instance, false).await
+}
+
+async fn set_pinned(ids: Vec<ModId>, instance: Instance, pinned: bool) -> Result<(), ModError> {
+    // Installs and updates save the index too
+    let _guard = lock().await;
+    let mut index = ModIndex::load(&instance).await?;
+    for id in ids {
+        if let Some(info) = index.mods.get_mut(&id) {
+            info.pinned = pinned;
+        }
+    }
+    index.save(&instance).await?;
+    Ok(())
+}
+
+pub(super) async fn rename_file(a: &Path, b: &Path) -> Result<(), ModError> {
     if let Err(error) = tokio::fs::rename(a, b).await {
         if let std::io::ErrorKind::NotFound = error.kind() {
             err!("Cannot find file for renaming, skipping: {a:?} -> {b:?}");
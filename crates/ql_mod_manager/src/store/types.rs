@@ -52,6 +52,17 @@ pub struct CurseforgeNotAllowed {
     pub file_id: usize,
 }
 
+impl CurseforgeNotAllowed {
+    /// The Curseforge page for downloading this file manually.
+    #[must_use]
+    pub fn download_url(&self) -> String {
+        format!(
+            "https://www.curseforge.com/minecraft/{}/{}/download/{}",
+            self.project_type, self.slug, self.file_id
+        )
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum QueryType {
     Mods,
@@ -59,8 +70,11 @@ pub enum QueryType {
     Shaders,
     ModPacks,
     DataPacks,
-    // TODO:
-    // Plugins,
+    /// Server plugins (Paper/Spigot/Bukkit).
+    ///
+    /// Not in [`QueryType::ALL`], since these are only for servers,
+    /// and installed separately with [`crate::store::download_plugin`].
+    Plugins,
 }
 
 impl Display for QueryType {
@@ -71,6 +85,7 @@ impl Display for QueryType {
             QueryType::Shaders => "Shaders",
             QueryType::ModPacks => "Modpacks",
             QueryType::DataPacks => "Data Packs",
+            QueryType::Plugins => "Plugins",
         })
     }
 }
@@ -97,6 +112,7 @@ impl QueryType {
             QueryType::Shaders => "shader",
             QueryType::ModPacks => "modpack",
             QueryType::DataPacks => "datapack",
+            QueryType::Plugins => "plugin",
         }
     }
 
@@ -108,6 +124,7 @@ impl QueryType {
             "shader" => Some(QueryType::Shaders),
             "modpack" => Some(QueryType::ModPacks),
             "datapack" => Some(QueryType::DataPacks),
+            "plugin" => Some(QueryType::Plugins),
             _ => None,
         }
     }
@@ -120,6 +137,7 @@ impl QueryType {
             QueryType::Shaders => "shaders",
             QueryType::ModPacks => "modpacks",
             QueryType::DataPacks => "data-packs",
+            QueryType::Plugins => "bukkit-plugins",
         }
     }
 
@@ -131,6 +149,7 @@ impl QueryType {
             "shaders" => Some(QueryType::Shaders),
             "modpacks" => Some(QueryType::ModPacks),
             "data-packs" => Some(QueryType::DataPacks),
+            "bukkit-plugins" => Some(QueryType::Plugins),
             _ => None,
         }
     }
@@ -248,3 +267,23 @@ impl Display for UrlKind {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curseforge_download_url() {
+        let entry = CurseforgeNotAllowed {
+            name: "JourneyMap".to_owned(),
+            slug: "journeymap".to_owned(),
+            filename: "journeymap-1.20.1-5.9.18-forge.jar".to_owned(),
+            project_type: QueryType::Mods.to_curseforge_str().to_owned(),
+            file_id: 5_157_542,
+        };
+        assert_eq!(
+            entry.download_url(),
+            "https://www.curseforge.com/minecraft/mc-mods/journeymap/download/5157542"
+        );
+    }
+}
@@ -1,10 +1,14 @@
 use std::path::PathBuf;
 use std::sync::mpsc::Sender;
+use std::time::Duration;
 
-use chrono::DateTime;
 use chrono::Local;
+use chrono::{DateTime, FixedOffset};
 use ql_core::InstanceConfigJson;
-use ql_core::{GenericProgress, Instance, do_jobs, err, info, json::VersionDetails};
+use ql_core::{
+    GenericProgress, Instance, InstanceSummary, do_jobs, do_jobs_with_limit, err, info,
+    json::VersionDetails, pt,
+};
 
 use crate::store::{get_latest_version_date, toggle_mods};
 
@@ -24,6 +28,13 @@ pub async fn apply_updates(
 ) -> Result<Option<ChangelogFile>, ModError> {
     let mod_index = ModIndex::load(&selected_instance).await?;
 
+    let (updates, pinned): (Vec<_>, Vec<_>) = updates
+        .into_iter()
+        .partition(|(id, _)| !mod_index.mods.get(id).is_some_and(|n| n.pinned));
+    for (id, _) in &pinned {
+        pt!("Skipping pinned mod: {}", id.get_internal_id());
+    }
+
     let update_ids: Vec<ModId> = updates.iter().map(|(id, _)| id.clone()).collect();
 
     let disabled_mods: Vec<_> = update_ids
@@ -106,7 +117,28 @@ fn trim(value: &str) -> &str {
     }
 }
 
+/// Mod updates found by [`check_for_updates_with_pinned`].
+#[derive(Debug, Clone, Default)]
+pub struct ModUpdates {
+    /// Mods with a newer version (id, new version name)
+    pub updates: Vec<(ModId, String)>,
+    /// Pinned mods (see [`super::pin_mods`]) that have a
+    /// newer version, which won't be installed.
+    pub pinned: Vec<(ModId, String)>,
+}
+
+/// Checks the instance's mods for newer versions,
+/// returning the mods to update (id, new version name).
+///
+/// Pinned mods are left out,
+/// see [`check_for_updates_with_pinned`] to list them too.
 pub async fn check_for_updates(instance: Instance) -> Result<Vec<(ModId, String)>, ModError> {
+    Ok(check_for_updates_with_pinned(instance).await?.updates)
+}
+
+/// Like [`check_for_updates`], but also reports
+/// pinned mods that have a newer version, separately.
+pub async fn check_for_updates_with_pinned(instance: Instance) -> Result<ModUpdates, ModError> {
     let index = ModIndex::load(&instance).await?;
     let version_json = VersionDetails::load(&instance).await?;
     let config = InstanceConfigJson::read(&instance).await?;
@@ -120,29 +152,355 @@ pub async fn check_for_updates(instance: Instance) -> Result<Vec<(ModId, String)
 
     let version = version_json.get_id();
 
-    let updated_mods: Result<Vec<Option<(ModId, String)>>, ModError> = do_jobs(
-        index
-            .mods
-            .into_iter()
-            .map(|(mod_id, installed_mod)| async move {
-                let (download_version_time, download_version) =
-                    get_latest_version_date(loader, &mod_id, version).await?;
+    let updates = find_updates(index, |mod_id| async move {
+        get_latest_version_date(loader, &mod_id, version).await
+    })
+    .await?;
+
+    if updates.updates.is_empty() {
+        info!("No mod updates found");
+    } else {
+        info!("Found mod updates");
+    }
+    if !updates.pinned.is_empty() {
+        pt!("Not updating {} pinned mod(s)", updates.pinned.len());
+    }
+
+    Ok(updates)
+}
+
+async fn find_updates<F, Fut>(index: ModIndex, get_latest: F) -> Result<ModUpdates, ModError>
+where
+    F: Fn(ModId) -> Fut,
+    Fut: Future<Output = Result<(DateTime<FixedOffset>, String), ModError>>,
+{
+    let found: Vec<Option<(ModId, String, bool)>> =
+        do_jobs(index.mods.into_iter().map(|(mod_id, installed_mod)| {
+            let get_latest = &get_latest;
+            async move {
+                let (download_version_time, download_version) = get_latest(mod_id.clone()).await?;
 
                 let installed_version_time =
                     DateTime::parse_from_rfc3339(&installed_mod.version_release_time)?;
 
-                Ok((download_version_time > installed_version_time)
-                    .then_some((mod_id, download_version)))
-            }),
+                Ok::<_, ModError>((download_version_time > installed_version_time).then_some((
+                    mod_id,
+                    download_version,
+                    installed_mod.pinned,
+                )))
+            }
+        }))
+        .await?;
+
+    let mut updates = ModUpdates::default();
+    for (mod_id, version, pinned) in found.into_iter().flatten() {
+        if pinned {
+            updates.pinned.push((mod_id, version));
+        } else {
+            updates.updates.push((mod_id, version));
+        }
+    }
+    Ok(updates)
+}
+
+/// How long [`update_before_launch`] waits for the update check,
+/// before giving up and launching with the current mods.
+const UPDATE_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// If the instance has [`InstanceConfigJson::auto_update_mods`]
+/// enabled, checks for mod updates and installs them.
+/// Call this before launching the game.
+///
+/// This never fails: if the check is slow (over 15 seconds)
+/// or errors, the game is launched with the current mods.
+/// Updates that were installed are logged, and returned.
+///
+/// Only the check has a timeout, since cancelling
+/// halfway through installing updates would leave mods missing.
+pub async fn update_before_launch(instance: &Instance) -> Vec<(ModId, String)> {
+    let enabled = InstanceConfigJson::read(instance)
+        .await
+        .is_ok_and(|n| !n.mod_type.is_vanilla() && n.auto_update_mods.unwrap_or(false));
+    if !enabled {
+        return Vec::new();
+    }
+    info!("Auto-updating mods before launch");
+    update_with_timeout(
+        || check_for_updates(instance.clone()),
+        |updates| apply_updates(instance.clone(), updates, None, true),
+        UPDATE_CHECK_TIMEOUT,
+    )
+    .await
+}
+
+async fn update_with_timeout<C, CFut, A, AFut, T>(
+    check: C,
+    apply: A,
+    timeout: Duration,
+) -> Vec<(ModId, String)>
+where
+    C: FnOnce() -> CFut,
+    CFut: Future<Output = Result<Vec<(ModId, String)>, ModError>>,
+    A: FnOnce(Vec<(ModId, String)>) -> AFut,
+    AFut: Future<Output = Result<T, ModError>>,
+{
+    let updates = match tokio::time::timeout(timeout, check()).await {
+        Ok(Ok(updates)) => updates,
+        Ok(Err(error)) => {
+            err!("Couldn't check for mod updates, launching anyway: {error}");
+            return Vec::new();
+        }
+        Err(_) => {
+            err!("Checking for mod updates took too long, launching anyway");
+            return Vec::new();
+        }
+    };
+    if updates.is_empty() {
+        return updates;
+    }
+
+    if let Err(error) = apply(updates.clone()).await {
+        err!("Couldn't install mod updates, launching anyway: {error}");
+        return Vec::new();
+    }
+    for (id, version) in &updates {
+        pt!("Updated {}: {version}", id.get_internal_id());
+    }
+    info!("Installed {} mod update(s)", updates.len());
+    updates
+}
+
+/// How many instances [`check_all_instances_for_updates`] checks
+/// at once (each one already checks all its mods concurrently).
+const INSTANCES_AT_ONCE: usize = 4;
+
+/// Checks every modded instance (and server) for mod updates,
+/// using [`check_for_updates`]. Vanilla instances are skipped.
+///
+/// Returns the instances that have updates (sorted by name),
+/// with their updates like in [`check_for_updates`].
+///
+/// Instances that couldn't be checked (broken, or the store
+/// couldn't be reached) are logged and skipped.
+///
+/// # Errors
+/// - The instance list couldn't be read
+/// - No instance could be checked (the first error is returned)
+pub async fn check_all_instances_for_updates()
+-> Result<Vec<(Instance, Vec<(ModId, String)>)>, ModError> {
+    let instances = ql_core::list_instances().await?;
+    check_instances(instances, check_for_updates).await
+}
+
+async fn check_instances<F, Fut>(
+    instances: Vec<InstanceSummary>,
+    check: F,
+) -> Result<Vec<(Instance, Vec<(ModId, String)>)>, ModError>
+where
+    F: Fn(Instance) -> Fut,
+    Fut: Future<Output = Result<Vec<(ModId, String)>, ModError>>,
+{
+    let modded: Vec<Instance> = instances
+        .iter()
+        .filter(|n| n.loader.is_some_and(|n| !n.is_vanilla()))
+        .map(InstanceSummary::instance)
+        .collect();
+    if modded.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let results: Result<Vec<_>, ModError> = do_jobs_with_limit(
+        modded.into_iter().map(|instance| {
+            let check = &check;
+            async move {
+                let result = check(instance.clone()).await;
+                Ok((instance, result))
+            }
+        }),
+        INSTANCES_AT_ONCE,
     )
     .await;
-    let updated_mods: Vec<(ModId, String)> = updated_mods?.into_iter().flatten().collect();
 
-    if updated_mods.is_empty() {
-        info!("No mod updates found");
-    } else {
-        info!("Found mod updates");
+    let mut updates = Vec::new();
+    let mut first_error = None;
+    let mut checked_any = false;
+    for (instance, result) in results? {
+        match result {
+            Ok(mods) => {
+                checked_any = true;
+                if !mods.is_empty() {
+                    updates.push((instance, mods));
+                }
+            }
+            Err(error) => {
+                err!(
+                    "Couldn't check {} for updates: {error}",
+                    instance.get_name()
+                );
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    if let (false, Some(error)) = (checked_any, first_error) {
+        return Err(error);
+    }
+
+    updates
+        .sort_by(|a, b| (a.0.is_server(), a.0.get_name()).cmp(&(b.0.is_server(), b.0.get_name())));
+    Ok(updates)
+}
+
+#[cfg(test)]
+mod tests {
+    use ql_core::Loader;
+
+    use super::*;
+
+    fn summary(name: &str, loader: Option<Loader>) -> InstanceSummary {
+        InstanceSummary {
+            name: name.to_owned(),
+            version: Some("1.20.1".to_owned()),
+            loader,
+            is_server: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn checks_modded_instances() {
+        let instances = vec![
+            summary("Vanilla", Some(Loader::Vanilla)),
+            summary("Fabric Pack", Some(Loader::Fabric)),
+            summary("Broken", None),
+            summary("Forge Pack", Some(Loader::Forge)),
+            summary("Up To Date", Some(Loader::Quilt)),
+        ];
+        let checked = std::sync::Mutex::new(Vec::new());
+
+        let updates = check_instances(instances, |instance: Instance| {
+            checked.lock().unwrap().push(instance.get_name().to_owned());
+            async move {
+                Ok(match instance.get_name() {
+                    "Fabric Pack" => {
+                        vec![(ModId::Modrinth("AANobbMI".to_owned()), "0.6.0".to_owned())]
+                    }
+                    "Forge Pack" => {
+                        vec![(ModId::Curseforge("238222".to_owned()), "15.0".to_owned())]
+                    }
+                    _ => Vec::new(),
+                })
+            }
+        })
+        .await
+        .unwrap();
+
+        let mut checked = checked.into_inner().unwrap();
+        checked.sort();
+        assert_eq!(checked, ["Fabric Pack", "Forge Pack", "Up To Date"]);
+
+        let names: Vec<&str> = updates.iter().map(|(n, _)| n.get_name()).collect();
+        assert_eq!(names, ["Fabric Pack", "Forge Pack"]);
+        assert_eq!(updates[0].1[0].1, "0.6.0");
     }
 
-    Ok(updated_mods)
+    #[tokio::test]
+    async fn updates_before_launch() {
+        let installed = std::sync::Mutex::new(Vec::new());
+        let update = (ModId::Modrinth("AANobbMI".to_owned()), "0.6.0".to_owned());
+
+        let updated = update_with_timeout(
+            || async { Ok(vec![update.clone()]) },
+            |updates| async {
+                installed.lock().unwrap().extend(updates);
+                Ok(())
+            },
+            Duration::from_secs(5),
+        )
+        .await;
+        // Installed by the time it returns (and the game is launched)
+        assert_eq!(*installed.lock().unwrap(), [update]);
+        assert_eq!(updated, *installed.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn slow_update_check_times_out() {
+        let installed = std::sync::atomic::AtomicBool::new(false);
+        let updated = update_with_timeout(
+            || async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok(vec![(ModId::Modrinth("x".to_owned()), "1.0".to_owned())])
+            },
+            |_| async {
+                installed.store(true, std::sync::atomic::Ordering::SeqCst);
+                Ok::<(), ModError>(())
+            },
+            Duration::from_millis(50),
+        )
+        .await;
+        assert!(updated.is_empty());
+        assert!(!installed.into_inner());
+    }
+
+    #[tokio::test]
+    async fn fails_only_if_nothing_was_checked() {
+        let instances = vec![
+            summary("Fabric Pack", Some(Loader::Fabric)),
+            summary("Offline", Some(Loader::Fabric)),
+        ];
+        let updates = check_instances(instances.clone(), |instance: Instance| async move {
+            match instance.get_name() {
+                "Offline" => Err(ModError::NoFilesFound),
+                _ => Ok(Vec::new()),
+            }
+        })
+        .await
+        .unwrap();
+        assert!(updates.is_empty());
+
+        let result = check_instances(instances, |_| async { Err(ModError::NoFilesFound) }).await;
+        assert!(matches!(result, Err(ModError::NoFilesFound)));
+    }
+
+    #[tokio::test]
+    async fn pinned_mods_are_not_updated() {
+        let mut index = ModIndex::default();
+        for (id, pinned) in [("sodium", false), ("iris", true)] {
+            let id = ModId::Modrinth(id.to_owned());
+            index.mods.insert(
+                id.clone(),
+                crate::store::ModConfig {
+                    name: id.get_internal_id().to_owned(),
+                    manually_installed: true,
+                    installed_version: "1.0".to_owned(),
+                    version_release_time: "2024-01-01T00:00:00Z".to_owned(),
+                    enabled: true,
+                    description: String::new(),
+                    icon_url: None,
+                    project_source: crate::store::StoreBackendType::Modrinth,
+                    project_id: id,
+                    files: Vec::new(),
+                    supported_versions: Vec::new(),
+                    dependencies: std::collections::HashSet::new(),
+                    dependents: std::collections::HashSet::new(),
+                    pinned,
+                },
+            );
+        }
+
+        let updates = find_updates(index, |_| async {
+            Ok((
+                DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap(),
+                "2.0".to_owned(),
+            ))
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            updates.updates,
+            [(ModId::Modrinth("sodium".to_owned()), "2.0".to_owned())]
+        );
+        assert_eq!(
+            updates.pinned,
+            [(ModId::Modrinth("iris".to_owned()), "2.0".to_owned())]
+        );
+    }
 }
@@ -0,0 +1,362 @@
+//! Listing every version of a mod, so the user can
+//! pick a specific one instead of the latest.
+
+use std::{collections::HashSet, path::Path, sync::mpsc::Sender};
+
+use chrono::{DateTime, FixedOffset};
+use ql_core::{GenericProgress, Instance, IntoIoError, Loader, file_utils::exists};
+
+use super::{
+    Backend, CurseforgeBackend, CurseforgeNotAllowed, ModConfig, ModError, ModHashes, ModId,
+    ModIndex, ModrinthBackend, flip_filename,
+};
+
+/// A version of a mod, as listed by [`list_mod_versions`].
+#[derive(Debug, Clone)]
+pub struct ModVersionEntry {
+    /// Modrinth version id or Curseforge file id.
+    /// Pass this to [`download_specific_version`].
+    pub id: String,
+    /// Version name (eg: `v2.0.1`)
+    pub version_number: String,
+    pub date_published: DateTime<FixedOffset>,
+    /// Web page of this version, with its changelog
+    pub changelog_url: String,
+    /// Filename of the version's primary file
+    pub filename: String,
+    pub hashes: ModHashes,
+    pub game_versions: Vec<String>,
+    /// Lowercase loader names (`fabric`, `neoforge`, ...)
+    pub loaders: Vec<String>,
+}
+
+/// Gets every version of a mod that works on Minecraft
+/// `mc_version` with `loader`, newest first.
+///
+/// Unlike [`super::get_latest_version_date`] this gives
+/// all the options, for picking one manually
+/// (eg: to go back to an older version of a buggy mod).
+/// Install the chosen one with [`download_specific_version`].
+///
+/// # Errors
+/// Depends on the backend (network, invalid JSON, ...)
+pub async fn list_mod_versions(
+    id: &ModId,
+    mc_version: &str,
+    loader: Loader,
+) -> Result<Vec<ModVersionEntry>, ModError> {
+    let versions = match id {
+        ModId::Modrinth(n) => ModrinthBackend::get_versions(n, mc_version, loader).await?,
+        ModId::Curseforge(n) => CurseforgeBackend::get_versions(n, mc_version, loader).await?,
+    };
+    Ok(filter_and_sort(versions, mc_version, loader))
+}
+
+/// Installs a specific version of a mod (from [`list_mod_versions`])
+/// along with its dependencies, replacing the currently
+/// installed version if any.
+///
+/// The old version is only removed once the new one is downloaded,
+/// so nothing is lost if that fails. A disabled (or pinned) mod
+/// stays disabled (or pinned), and mods depending on it keep doing so.
+///
+/// # Errors
+/// Same as [`super::download_mod`], or if the
+/// old version couldn't be removed.
+pub async fn download_specific_version(
+    id: &ModId,
+    version_id: &str,
+    instance: Instance,
+    sender: Option<Sender<GenericProgress>>,
+) -> Result<HashSet<CurseforgeNotAllowed>, ModError> {
+    match id {
+        ModId::Modrinth(n) => {
+            ModrinthBackend::download_version(n, version_id, &instance, sender).await
+        }
+        ModId::Curseforge(n) => {
+            CurseforgeBackend::download_version(n, version_id, &instance, sender).await
+        }
+    }
+}
+
+/// A backend's mod downloader, for [`install_version`].
+pub(super) trait VersionDownloader {
+    fn index(&mut self) -> &mut ModIndex;
+    /// Downloads a mod (whichever version the downloader
+    /// was told to pin) and its dependencies into the index.
+    /// Mods already in the index are skipped.
+    async fn download_mod(&mut self, id: &str) -> Result<(), ModError>;
+}
+
+/// Downloads the pinned version of `id` with `downloader`,
+/// in place of the currently installed version (if any).
+///
+/// The installed version is taken out of the index meanwhile,
+/// so the downloader doesn't skip the mod as already installed,
+/// and then swapped out with [`replace_version`].
+pub(super) async fn install_version(
+    downloader: &mut impl VersionDownloader,
+    id: &ModId,
+    mods_dir: &Path,
+) -> Result<(), ModError> {
+    let old = downloader.index().mods.remove(id);
+    downloader.download_mod(id.get_internal_id()).await?;
+    if let Some(old) = old {
+        replace_version(downloader.index(), id, old, mods_dir).await?;
+    }
+    Ok(())
+}
+
+/// Swaps the previously installed version of a mod (`old`)
+/// for the freshly downloaded one in `index`, keeping the
+/// user's settings and dependency links,
+/// and removing the old version's files.
+async fn replace_version(
+    index: &mut ModIndex,
+    id: &ModId,
+    old: ModConfig,
+    mods_dir: &Path,
+) -> Result<(), ModError> {
+    let Some(new) = index.mods.get_mut(id) else {
+        // Nothing was downloaded (eg: blocked by Curseforge), keep the old one
+        index.mods.insert(id.clone(), old);
+        return Ok(());
+    };
+    new.manually_installed = old.manually_installed;
+    new.dependents = old.dependents;
+    new.pinned = old.pinned;
+    new.enabled = old.enabled;
+
+    for file in &old.files {
+        if new.files.iter().any(|n| n.filename == file.filename) {
+            continue;
+        }
+        for name in [file.filename.clone(), format!("{}.disabled", file.filename)] {
+            let path = mods_dir.join(name);
+            if exists(&path).await {
+                tokio::fs::remove_file(&path).await.path(&path)?;
+            }
+        }
+    }
+    if !new.enabled {
+        for file in &new.files {
+            let path = mods_dir.join(&file.filename);
+            if exists(&path).await {
+                let disabled = mods_dir.join(flip_filename(&file.filename));
+                tokio::fs::rename(&path, &disabled).await.path(&path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Gets the changelog ("what changed") of a specific version of a mod.
+///
+/// `version` is the version's id ([`ModVersionEntry::id`]),
+/// not its name.
+///
+/// This may use Markdown (Modrinth) or HTML (Curseforge).
+/// Empty if the author didn't write one.
+///
+/// # Errors
+/// Depends on the backend (network, invalid JSON, ...)
+pub async fn get_mod_changelog(id: &ModId, version: &str) -> Result<String, ModError> {
+    match id {
+        ModId::Modrinth(n) => ModrinthBackend::get_changelog(n, version).await,
+        ModId::Curseforge(n) => CurseforgeBackend::get_changelog(n, version).await,
+    }
+}
+
+fn filter_and_sort(
+    mut versions: Vec<ModVersionEntry>,
+    mc_version: &str,
+    loader: Loader,
+) -> Vec<ModVersionEntry> {
+    versions.retain(|v| {
+        v.game_versions.iter().any(|n| n == mc_version)
+            && (loader.is_vanilla()
+                // Resource packs, shaders, ...
+                || v.loaders.first().is_none_or(|n| n == "minecraft")
+                || v.loaders.iter().any(|n| n == loader.to_modrinth_str()))
+    });
+    versions.sort_by_key(|n| std::cmp::Reverse(n.date_published));
+    versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{ModFile, StoreBackendType, curseforge, modrinth};
+
+    fn entry(id: &str, date: &str, game_version: &str, loader: &str) -> ModVersionEntry {
+        ModVersionEntry {
+            id: id.to_owned(),
+            version_number: id.to_owned(),
+            date_published: DateTime::parse_from_rfc3339(date).unwrap(),
+            changelog_url: String::new(),
+            filename: format!("{id}.jar"),
+            hashes: ModHashes::default(),
+            game_versions: vec![game_version.to_owned()],
+            loaders: vec![loader.to_owned()],
+        }
+    }
+
+    #[test]
+    fn changelogs() {
+        let modrinth = r#"{
+            "id": "IZskON6d",
+            "project_id": "AANobbMI",
+            "name": "Sodium 0.5.8",
+            "version_number": "mc1.20.1-0.5.8",
+            "changelog": "**Fixes**\n- Fixed a crash with some GPUs",
+            "date_published": "2024-02-07T18:43:33.000000Z"
+        }"#;
+        assert_eq!(
+            modrinth::parse_changelog(modrinth).unwrap(),
+            "**Fixes**\n- Fixed a crash with some GPUs"
+        );
+        let modrinth = r#"{"id": "IZskON6d", "changelog": null}"#;
+        assert_eq!(modrinth::parse_changelog(modrinth).unwrap(), "");
+
+        let curseforge = r#"{"data": "<p>Updated to 1.20.1</p>"}"#;
+        assert_eq!(
+            curseforge::parse_changelog(curseforge).unwrap(),
+            "<p>Updated to 1.20.1</p>"
+        );
+    }
+
+    /// An installed mod, with `filename` as its only file.
+    fn config(id: &ModId, filename: &str) -> ModConfig {
+        ModConfig {
+            name: "Sodium".to_owned(),
+            manually_installed: false,
+            installed_version: filename.to_owned(),
+            version_release_time: String::new(),
+            enabled: true,
+            description: String::new(),
+            icon_url: None,
+            project_source: match id {
+                ModId::Modrinth(_) => StoreBackendType::Modrinth,
+                ModId::Curseforge(_) => StoreBackendType::Curseforge,
+            },
+            project_id: id.clone(),
+            files: vec![ModFile {
+                hashes: ModHashes::default(),
+                url: String::new(),
+                filename: filename.to_owned(),
+                primary: true,
+            }],
+            supported_versions: Vec::new(),
+            dependencies: HashSet::new(),
+            dependents: HashSet::new(),
+            pinned: false,
+        }
+    }
+
+    /// Like the Curseforge downloader, skips mods
+    /// already in the index, otherwise "downloads" `file`.
+    struct FakeDownloader<'a> {
+        index: ModIndex,
+        dir: &'a Path,
+        file: &'static str,
+    }
+
+    impl VersionDownloader for FakeDownloader<'_> {
+        fn index(&mut self) -> &mut ModIndex {
+            &mut self.index
+        }
+
+        async fn download_mod(&mut self, id: &str) -> Result<(), ModError> {
+            let id = ModId::Curseforge(id.to_owned());
+            if self.index.mods.contains_key(&id) {
+                return Ok(());
+            }
+            tokio::fs::write(self.dir.join(self.file), "new")
+                .await
+                .unwrap();
+            self.index.mods.insert(id.clone(), config(&id, self.file));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn pinned_file_replaces_installed() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        tokio::fs::write(dir.join("sodium-0.5.8.jar"), "old")
+            .await
+            .unwrap();
+
+        let id = ModId::Curseforge("394468".to_owned());
+        let mut old = config(&id, "sodium-0.5.8.jar");
+        old.manually_installed = true;
+        let mut downloader = FakeDownloader {
+            index: ModIndex::default(),
+            dir,
+            file: "sodium-0.5.3.jar",
+        };
+        downloader.index.mods.insert(id.clone(), old);
+
+        install_version(&mut downloader, &id, dir).await.unwrap();
+        let new = &downloader.index.mods[&id];
+        assert_eq!(new.installed_version, "sodium-0.5.3.jar");
+        assert!(new.manually_installed);
+        assert!(dir.join("sodium-0.5.3.jar").is_file());
+        assert!(!dir.join("sodium-0.5.8.jar").exists());
+    }
+
+    #[tokio::test]
+    async fn replaces_old_version() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        tokio::fs::write(dir.join("sodium-0.5.8.jar.disabled"), "old")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("sodium-0.5.3.jar"), "new")
+            .await
+            .unwrap();
+
+        let id = ModId::Modrinth("AANobbMI".to_owned());
+        let mut old = config(&id, "sodium-0.5.8.jar");
+        old.manually_installed = true;
+        old.enabled = false;
+        old.pinned = true;
+        let dependent = ModId::Modrinth("sodium-extra".to_owned());
+        old.dependents.insert(dependent.clone());
+
+        let mut index = ModIndex::default();
+        index
+            .mods
+            .insert(id.clone(), config(&id, "sodium-0.5.3.jar"));
+        replace_version(&mut index, &id, old, dir).await.unwrap();
+
+        let new = &index.mods[&id];
+        assert_eq!(new.installed_version, "sodium-0.5.3.jar");
+        assert!(new.manually_installed && new.pinned && !new.enabled);
+        assert!(new.dependents.contains(&dependent));
+        assert!(!dir.join("sodium-0.5.8.jar.disabled").exists());
+        assert!(dir.join("sodium-0.5.3.jar.disabled").is_file());
+    }
+
+    #[test]
+    fn versions_are_filtered_and_sorted() {
+        let versions = vec![
+            entry("old", "2024-01-01T00:00:00Z", "1.20.1", "fabric"),
+            entry("forge", "2024-03-01T00:00:00Z", "1.20.1", "forge"),
+            entry("other_mc", "2024-04-01T00:00:00Z", "1.21", "fabric"),
+            entry("new", "2024-02-01T00:00:00+01:00", "1.20.1", "fabric"),
+        ];
+
+        let ids = |versions: Vec<ModVersionEntry>| -> Vec<String> {
+            versions.into_iter().map(|n| n.id).collect()
+        };
+        assert_eq!(
+            ids(filter_and_sort(versions.clone(), "1.20.1", Loader::Fabric)),
+            ["new", "old"]
+        );
+        assert_eq!(
+            ids(filter_and_sort(versions, "1.20.1", Loader::Vanilla)),
+            ["forge", "new", "old"]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! `checksums.json` in exported instances: a map of every
+//! file's path (relative, `/`-separated) to its SHA256 hash,
+//! to detect files corrupted while transferring the export.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use ql_core::{IntoIoError, IntoJsonError, IoError, err, pt};
+use sha2::{Digest, Sha256};
+
+use crate::InstancePackageError;
+
+pub const CHECKSUMS_FILE: &str = "checksums.json";
+
+/// Hashes every file in `dir` and writes `checksums.json` to it.
+pub async fn write_checksums(dir: &Path) -> Result<(), InstancePackageError> {
+    pt!("Calculating checksums");
+    let mut checksums = BTreeMap::new();
+    for (relative, path) in list_files(dir).await? {
+        checksums.insert(relative, hash_file(&path).await?);
+    }
+    let checksums = serde_json::to_string_pretty(&checksums).json_to()?;
+    let path = dir.join(CHECKSUMS_FILE);
+    tokio::fs::write(&path, checksums).await.path(&path)?;
+    Ok(())
+}
+
+/// Checks the files in an extracted export against its
+/// `checksums.json` (then deletes it), logging any mismatches.
+///
+/// Returns the relative paths of files that are corrupted or missing.
+/// Older exports without `checksums.json` aren't checked.
+pub async fn verify_checksums(dir: &Path) -> Result<Vec<String>, InstancePackageError> {
+    let path = dir.join(CHECKSUMS_FILE);
+    let checksums = match tokio::fs::read_to_string(&path).await {
+        Ok(n) => n,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            pt!("No checksums in export (older version), skipping verification");
+            return Ok(Vec::new());
+        }
+        Err(error) => return Err(IoError::Io { error, path }.into()),
+    };
+    let checksums: BTreeMap<String, String> = serde_json::from_str(&checksums).json(checksums)?;
+    tokio::fs::remove_file(&path).await.path(&path)?;
+
+    pt!("Verifying checksums");
+    let mut corrupted = Vec::new();
+    for (relative, expected) in checksums {
+        let file = dir.join(&relative);
+        let matches = match hash_file(&file).await {
+            Ok(hash) => hash.eq_ignore_ascii_case(&expected),
+            Err(_) => false,
+        };
+        if !matches {
+            err!("File corrupted or missing in export: {relative}");
+            corrupted.push(relative);
+        }
+    }
+    Ok(corrupted)
+}
+
+async fn hash_file(path: &Path) -> Result<String, IoError> {
+    let bytes = tokio::fs::read(path).await.path(path)?;
+    let mut hasher = Sha256::default();
+    hasher.update(&bytes);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// All files in `dir` (recursively), except `checksums.json`,
+/// with their `/`-separated relative paths.
+async fn list_files(dir: &Path) -> Result<Vec<(String, PathBuf)>, IoError> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_owned()];
+    while let Some(current) = dirs.pop() {
+        let mut entries = tokio::fs::read_dir(&current).await.dir(&current)?;
+        while let Some(entry) = entries.next_entry().await.dir(&current)? {
+            let path = entry.path();
+            let file_type = entry.file_type().await.path(&path)?;
+            if file_type.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let relative: Vec<_> = relative
+                .components()
+                .map(|n| n.as_os_str().to_string_lossy())
+                .collect();
+            let relative = relative.join("/");
+            if relative != CHECKSUMS_FILE {
+                files.push((relative, path));
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use ql_core::file_utils;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn detects_tampered_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let export_dir = root.join("export");
+        let import_dir = root.join("import");
+
+        let files = [
+            ("config.json", "{}"),
+            (".minecraft/options.txt", "fov:0.5"),
+            (".minecraft/mods/sodium.jar", "jar"),
+        ];
+        for (path, contents) in files {
+            let path = export_dir.join(path);
+            tokio::fs::create_dir_all(path.parent().unwrap())
+                .await
+                .unwrap();
+            tokio::fs::write(&path, contents).await.unwrap();
+        }
+        write_checksums(&export_dir).await.unwrap();
+
+        // Corrupted after the checksums were made
+        tokio::fs::write(export_dir.join(".minecraft/options.txt"), "fov:1.0")
+            .await
+            .unwrap();
+        let zip = file_utils::zip_directory_to_bytes(&export_dir)
+            .await
+            .unwrap();
+        file_utils::extract_zip_archive(std::io::Cursor::new(zip), &import_dir, true)
+            .await
+            .unwrap();
+
+        let corrupted = verify_checksums(&import_dir).await.unwrap();
+        assert_eq!(corrupted, [".minecraft/options.txt"]);
+        assert!(!file_utils::exists(import_dir.join(CHECKSUMS_FILE)).await);
+
+        // Old exports are skipped
+        assert!(verify_checksums(&import_dir).await.unwrap().is_empty());
+    }
+}
@@ -1,7 +1,7 @@
-use ql_core::{GenericProgress, file_utils};
+use ql_core::{GenericProgress, file_utils, json::InstanceConfigJson};
 use ql_core::{Instance, IntoIoError, IntoJsonError, info, pt};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
 use tokio::fs;
 
@@ -13,6 +13,7 @@ pub const EXCEPTIONS: &[&str] = &[
     "libraries",
     "fabric.json",
     "forge",
+    "mod_snapshots",
 ];
 
 fn create_instance_info(instance: &Instance, mut exceptions: HashSet<String>) -> InstanceInfo {
@@ -47,7 +48,8 @@ fn create_instance_info(instance: &Instance, mut exceptions: HashSet<String>) ->
 /// 3. Copies the instance files into a temporary directory.
 /// 4. Writes the `InstanceInfo` to a `quantum-config.json` inside temp folder.
 /// 5. Deletes the excluded directories/files from the temp copy.
-/// 6. Compresses the temp folder into a `.zip` archive at the given destination.
+/// 6. Writes `checksums.json` (SHA256 of every file), checked on import.
+/// 7. Compresses the temp folder into a `.zip` archive at the given destination.
 ///
 /// # Errors
 ///
@@ -85,12 +87,14 @@ pub async fn export_instance(
         .collect();
     file_utils::copy_dir_recursive_ext(&instance_path, dir.path(), &collect).await?;
     let folder_path = dir.path();
+    copy_moved_game_dir(&instance, &export_config.exceptions, folder_path).await?;
 
     // pt!("{:?}",temp_instance_path);
     pt!("Creating metadata");
     let config = serde_json::to_string_pretty(&export_config).json_to()?;
     let config_path = folder_path.join("quantum-config.json");
     fs::write(&config_path, config).await.path(&config_path)?;
+    crate::checksums::write_checksums(folder_path).await?;
 
     pt!("Packaging the instance into zip");
     if let Some(prog) = &progress {
@@ -108,3 +112,34 @@ pub async fn export_instance(
 
     Ok(bytes)
 }
+
+/// If the instance's game folder was moved elsewhere
+/// ([`InstanceConfigJson::game_dir_override`]), exports it
+/// as a regular `.minecraft` (the custom path wouldn't exist
+/// on the machine it's imported on anyway).
+async fn copy_moved_game_dir(
+    instance: &Instance,
+    exceptions: &HashSet<String>,
+    folder_path: &Path,
+) -> Result<(), InstancePackageError> {
+    let game_dir = instance.get_game_dir().await?;
+    if game_dir == instance.get_dot_minecraft_path() {
+        return Ok(());
+    }
+    pt!("Copying game folder from {game_dir:?}");
+    let exceptions: Vec<PathBuf> = exceptions
+        .iter()
+        .filter_map(|n| n.strip_prefix(".minecraft/"))
+        .map(|n| game_dir.join(n))
+        .collect();
+    let exported = folder_path.join(".minecraft");
+    if exported.is_dir() {
+        fs::remove_dir_all(&exported).await.path(&exported)?;
+    }
+    file_utils::copy_dir_recursive_ext(&game_dir, &exported, &exceptions).await?;
+
+    let mut config = InstanceConfigJson::read_from_dir(folder_path).await?;
+    config.game_dir_override = None;
+    config.save_to_dir(folder_path).await?;
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use ql_core::{
-    GenericProgress, Instance, InstanceKind, IntoIoError, IntoJsonError, ListEntry, Progress,
+    GenericProgress, Instance, InstanceKind, IntoIoError, IntoJsonError, ListEntry, Progress, err,
     file_utils, info,
     json::{InstanceConfigJson, VersionDetails},
     pt,
@@ -23,7 +23,8 @@ pub const OUT_OF: usize = 4;
 ///
 /// This function performs the following:
 /// 1. Extracts the ZIP archive to a temporary directory.
-/// 2. Reads the `quantum-config.json` from the extracted directory to get instance metadata.
+/// 2. Reads the `quantum-config.json` from the extracted directory to get instance metadata,
+///    and checks the files against `checksums.json` (if present), warning if any are corrupted.
 /// 3. Creates a new instance using the extracted configuration.
 /// 4. Copies the extracted files to the main instances directory.
 ///
@@ -99,6 +100,13 @@ async fn import_quantumlauncher(
     info!("Importing QuantumLauncher instance...");
 
     let instance_info: InstanceInfo = serde_json::from_str(&instance_info).json(instance_info)?;
+    let corrupted = crate::checksums::verify_checksums(temp_dir).await?;
+    if !corrupted.is_empty() {
+        err!(
+            "{} file(s) in this export are corrupted (damaged while transferring?)\nThe instance may not work properly. Try exporting it again.",
+            corrupted.len()
+        );
+    }
     let version_json: VersionDetails = VersionDetails::load_from_path(temp_dir).await?;
     let config_json: InstanceConfigJson = {
         let path = temp_dir.join("config.json");
@@ -128,13 +136,18 @@ async fn import_quantumlauncher(
     }
 
     if instance_info.is_server {
-        ql_servers::create_server(instance_info.instance_name, version, Some(&d_send)).await?;
+        ql_servers::create_server(instance_info.instance_name, version, Some(&d_send), false)
+            .await?;
     } else {
         ql_instances::create_instance(
             instance_info.instance_name,
             version,
             Some(d_send),
-            download_assets,
+            ql_instances::CreateOptions {
+                download_assets,
+                install_java: false,
+            },
+            None,
         )
         .await?;
     }
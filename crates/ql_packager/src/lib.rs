@@ -1,19 +1,27 @@
 use std::{collections::HashSet, path::PathBuf};
 
 use ql_core::{IoError, JsonError, RequestError, impl_3_errs_jri};
-use ql_mod_manager::loaders::{fabric::FabricInstallError, forge::ForgeInstallError};
+use ql_mod_manager::{
+    loaders::{fabric::FabricInstallError, forge::ForgeInstallError},
+    store::{ModError, PackError},
+};
 use ql_servers::ServerError;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use ql_instances::DownloadError;
+use ql_instances::{DownloadError, GameLaunchError};
 
+mod checksums;
 mod export;
 mod import;
+mod modpack;
 mod multimc;
+mod portable;
 
 pub use export::{EXCEPTIONS, export_instance};
 pub use import::import_instance;
+pub use modpack::{ModpackSource, create_instance_from_modpack};
+pub use portable::export_portable;
 
 const PKG_ERR_PREFIX: &str = "while importing/exporting instance:\n";
 #[derive(Debug, Error)]
@@ -36,6 +44,10 @@ pub enum InstancePackageError {
     Download(#[from] DownloadError),
     #[error("{PKG_ERR_PREFIX}while creating new base server for import:\n{0}")]
     Server(#[from] ServerError),
+    #[error("{PKG_ERR_PREFIX}while getting launch arguments:\n{0}")]
+    Launch(#[from] GameLaunchError),
+    #[error("{PKG_ERR_PREFIX}portable export only works for client instances")]
+    PortableServer,
     #[error("{PKG_ERR_PREFIX}while installing packaged loader:\n{0}")]
     Loader(String),
 
@@ -43,6 +55,10 @@ pub enum InstancePackageError {
     Forge(#[from] ForgeInstallError),
     #[error("{PKG_ERR_PREFIX}{0}")]
     Fabric(#[from] FabricInstallError),
+    #[error("{PKG_ERR_PREFIX}while downloading modpack:\n{0}")]
+    Mod(#[from] ModError),
+    #[error("{PKG_ERR_PREFIX}{0}")]
+    Pack(#[from] PackError),
 
     #[error("{PKG_ERR_PREFIX}while dealing with zip:\n{0}")]
     Zip(#[from] zip::result::ZipError),
@@ -0,0 +1,110 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, mpsc::Sender},
+};
+
+use ql_core::{GenericProgress, Instance, IntoIoError, ListEntry, err, info};
+use ql_instances::NewInstanceTemplate;
+use ql_mod_manager::store::{
+    CurseforgeNotAllowed, ModId, download_modpack, install_modpack, read_modpack_requirements,
+};
+
+use crate::{InstancePackageError, import::pipe_progress};
+
+/// Where to get a modpack from, for [`create_instance_from_modpack`].
+#[derive(Debug, Clone)]
+pub enum ModpackSource {
+    /// The newest version of a modpack on Modrinth/Curseforge
+    Store(ModId),
+    /// A `.mrpack` or Curseforge `.zip` file
+    File(PathBuf),
+}
+
+/// Creates a new instance for a modpack and installs the pack on it,
+/// in one go (instead of creating a matching instance by hand first).
+///
+/// The instance is made at the Minecraft version the pack needs,
+/// with the loader (and loader version) it was made with.
+///
+/// # Returns
+/// The name of the created instance (see [`ql_instances::create_instance`]),
+/// and the mods that Curseforge blocked the launcher from downloading.
+/// The user must download these manually from the browser and import them.
+///
+/// If the loader or pack couldn't be installed,
+/// the half-made instance is deleted.
+///
+/// # Errors
+/// - The pack couldn't be downloaded/read, or isn't a
+///   Curseforge/Modrinth modpack (QMP presets need an existing instance)
+/// - The instance couldn't be created, or an instance
+///   with that name already exists
+/// - The loader or pack's mods couldn't be installed
+pub async fn create_instance_from_modpack(
+    name: String,
+    pack: &ModpackSource,
+    sender: Option<Sender<GenericProgress>>,
+) -> Result<(String, HashSet<CurseforgeNotAllowed>), InstancePackageError> {
+    let file = match pack {
+        ModpackSource::Store(id) => download_modpack(id).await?,
+        ModpackSource::File(path) => tokio::fs::read(path).await.path(path)?,
+    };
+    let requirements = read_modpack_requirements(&file)?;
+    info!(
+        "Creating instance for modpack {} (Minecraft {}, {})",
+        requirements.name, requirements.minecraft_version, requirements.loader
+    );
+
+    let sender = sender.map(Arc::new);
+    let (d_send, d_recv) = std::sync::mpsc::channel();
+    if let Some(sender) = sender.clone() {
+        std::thread::spawn(move || {
+            pipe_progress(d_recv, &sender);
+        });
+    }
+    let download_assets = NewInstanceTemplate::load()
+        .await
+        .download_assets
+        .unwrap_or(true);
+    let name = ql_instances::create_instance(
+        name,
+        ListEntry::new(requirements.minecraft_version.clone()),
+        Some(d_send),
+        ql_instances::CreateOptions {
+            download_assets,
+            install_java: false,
+        },
+        None,
+    )
+    .await?;
+    let instance = Instance::client(&name);
+
+    let result = async {
+        crate::multimc::install_loader(
+            sender.as_deref(),
+            &instance,
+            Some(requirements.loader),
+            requirements.loader_version.clone(),
+        )
+        .await?;
+        let not_allowed = install_modpack(file, instance.clone(), sender.as_deref(), None).await?;
+        Ok::<_, InstancePackageError>(not_allowed.unwrap_or_default())
+    }
+    .await;
+
+    match result {
+        Ok(not_allowed) => {
+            info!("Finished creating modpack instance: {name}");
+            Ok((name, not_allowed))
+        }
+        Err(error) => {
+            err!("Couldn't install modpack, removing instance: {name}");
+            let dir = instance.get_instance_path();
+            if let Err(cleanup) = tokio::fs::remove_dir_all(&dir).await {
+                err!("Couldn't remove half-made instance {dir:?}: {cleanup}");
+            }
+            Err(error)
+        }
+    }
+}
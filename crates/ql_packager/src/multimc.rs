@@ -90,7 +90,13 @@ pub async fn import(
     )
     .await?;
 
-    install_loader(sender.as_deref(), &instance, &instance_recipe).await?;
+    install_loader(
+        sender.as_deref(),
+        &instance,
+        instance_recipe.loader,
+        instance_recipe.loader_version.clone(),
+    )
+    .await?;
 
     copy_files(temp_dir, sender, &instance).await?;
 
@@ -264,30 +270,21 @@ async fn get_instance_recipe(mmc_pack: &MmcPack) -> Result<InstanceRecipe, Insta
     Ok(recipe)
 }
 
-async fn install_loader(
+/// Installs a specific version of a loader
+/// (or the latest one, if `version` is `None`).
+pub(crate) async fn install_loader(
     sender: Option<&Sender<GenericProgress>>,
     instance: &Instance,
-    instance_recipe: &InstanceRecipe,
+    loader: Option<Loader>,
+    version: Option<String>,
 ) -> Result<(), InstancePackageError> {
-    if let Some(loader) = instance_recipe.loader {
+    if let Some(loader) = loader {
         match loader {
             n @ (Loader::Fabric | Loader::Quilt) => {
-                install_fabric(
-                    sender,
-                    instance,
-                    instance_recipe.loader_version.clone(),
-                    matches!(n, Loader::Quilt),
-                )
-                .await?;
+                install_fabric(sender, instance, version, matches!(n, Loader::Quilt)).await?;
             }
             n @ (Loader::Forge | Loader::Neoforge) => {
-                mmc_forge(
-                    sender,
-                    instance,
-                    instance_recipe.loader_version.clone(),
-                    matches!(n, Loader::Neoforge),
-                )
-                .await?;
+                mmc_forge(sender, instance, version, matches!(n, Loader::Neoforge)).await?;
             }
             loader => {
                 err!("Unimplemented MultiMC Component: {loader:?}");
@@ -452,7 +449,11 @@ async fn create_minecraft_instance(
         instance_name.to_owned(),
         version,
         Some(d_send),
-        download_assets,
+        ql_instances::CreateOptions {
+            download_assets,
+            install_java: false,
+        },
+        None,
     )
     .await?;
     Ok(())
@@ -0,0 +1,224 @@
+use std::{path::Path, sync::Arc};
+
+use ql_core::{
+    Instance, IntoIoError, IntoJsonError, LAUNCHER_DIR, file_utils, info, json::VersionDetails, pt,
+};
+use ql_instances::LaunchArguments;
+use serde::Serialize;
+
+use crate::InstancePackageError;
+
+/// Username used in the generated launch script (offline mode).
+const PORTABLE_USERNAME: &str = "Player";
+
+/// `portable.json`, describing what's
+/// needed to run an exported instance.
+#[derive(Debug, Serialize)]
+struct PortableManifest<'a> {
+    instance_name: &'a str,
+    minecraft_version: &'a str,
+    /// Major Java version, like `17`.
+    java_version: u32,
+    main_class: &'a str,
+    launch_script: &'a str,
+}
+
+/// Exports a client instance to a folder that can be run
+/// outside the launcher, for debugging:
+///
+/// ```txt
+/// out/
+/// ├── instance/         (copy of the instance)
+/// ├── assets/           (game assets, if any)
+/// ├── portable.json     (Java version, main class...)
+/// └── run.sh / run.bat  (launch script)
+/// ```
+///
+/// The launch script runs the game in offline mode, with
+/// `java` from `PATH` (or the `JAVA` environment variable).
+/// It only works on the OS (and architecture) it was exported on,
+/// since the classpath and natives are platform-specific.
+///
+/// Unlike [`crate::export_instance`], this isn't meant
+/// to be imported back into the launcher.
+///
+/// # Errors
+/// - `instance` is a server (they're already runnable with `java -jar`)
+/// - The launch arguments couldn't be built (broken instance)
+/// - Files couldn't be copied or written to `out`
+pub async fn export_portable(instance: &Instance, out: &Path) -> Result<(), InstancePackageError> {
+    if instance.is_server() {
+        return Err(InstancePackageError::PortableServer);
+    }
+    info!("Exporting portable instance to {out:?}");
+
+    let mut args = ql_instances::launch_arguments(
+        Arc::from(instance.get_name()),
+        PORTABLE_USERNAME.to_owned(),
+        None,
+    )
+    .await?;
+    let version_json = VersionDetails::load(instance).await?;
+
+    let instance_dir = instance.get_instance_path();
+    let assets_dir = LAUNCHER_DIR.join("assets/dir");
+
+    pt!("Copying instance files");
+    tokio::fs::create_dir_all(out).await.path(out)?;
+    file_utils::copy_dir_recursive(&instance_dir, &out.join("instance")).await?;
+    let game_dir = instance.get_game_dir().await?;
+    if game_dir != instance.get_dot_minecraft_path() {
+        // Moved elsewhere, the script expects it in `instance/.minecraft`
+        pt!("Copying game folder from {game_dir:?}");
+        file_utils::copy_dir_recursive(&game_dir, &out.join("instance/.minecraft")).await?;
+        relativize(&mut args, &game_dir, ".")?;
+    }
+
+    let uses_assets = contains_path(&args, &assets_dir);
+    if uses_assets && file_utils::exists(&assets_dir).await {
+        pt!("Copying assets");
+        file_utils::copy_dir_recursive(&assets_dir, &out.join("assets")).await?;
+    }
+
+    // The script runs the game from `instance/.minecraft`
+    relativize(&mut args, &instance_dir, "..")?;
+    relativize(&mut args, &assets_dir, "../../assets")?;
+
+    let windows = cfg!(target_os = "windows");
+    let script_name = if windows { "run.bat" } else { "run.sh" };
+    let script_path = out.join(script_name);
+    tokio::fs::write(&script_path, launch_script(&args, windows))
+        .await
+        .path(&script_path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755))
+            .await
+            .path(&script_path)?;
+    }
+
+    let manifest = PortableManifest {
+        instance_name: instance.get_name(),
+        minecraft_version: version_json.get_id(),
+        java_version: args.java_version as u32,
+        main_class: &args.main_class,
+        launch_script: script_name,
+    };
+    let manifest = serde_json::to_string_pretty(&manifest).json_to()?;
+    let manifest_path = out.join("portable.json");
+    tokio::fs::write(&manifest_path, manifest)
+        .await
+        .path(&manifest_path)?;
+
+    info!("Exported portable instance");
+    Ok(())
+}
+
+fn contains_path(args: &LaunchArguments, path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    args.java
+        .iter()
+        .chain(&args.game)
+        .any(|n| n.contains(&*path))
+}
+
+/// Replaces the absolute `path` in all arguments with `relative`.
+fn relativize(
+    args: &mut LaunchArguments,
+    path: &Path,
+    relative: &str,
+) -> Result<(), InstancePackageError> {
+    let path = path
+        .to_str()
+        .ok_or_else(|| InstancePackageError::PathBufToStr(path.to_owned()))?;
+    for arg in args.java.iter_mut().chain(&mut args.game) {
+        if arg.contains(path) {
+            *arg = arg.replace(path, relative);
+        }
+    }
+    Ok(())
+}
+
+fn launch_script(args: &LaunchArguments, windows: bool) -> String {
+    let java_version = args.java_version as u32;
+    let all_args = args
+        .java
+        .iter()
+        .chain(std::iter::once(&args.main_class))
+        .chain(&args.game)
+        .filter(|n| !n.is_empty());
+
+    if windows {
+        let mut script = format!(
+            "@echo off\r\n\
+            rem Generated by QuantumLauncher\r\n\
+            rem Needs Java {java_version}. Set JAVA to use a specific install.\r\n\
+            if not defined JAVA set \"JAVA=java\"\r\n\
+            cd /d \"%~dp0instance\\.minecraft\"\r\n\
+            \"%JAVA%\""
+        );
+        for arg in all_args {
+            script.push_str(" ^\r\n    \"");
+            script.push_str(&arg.replace('%', "%%").replace('"', "\\\""));
+            script.push('"');
+        }
+        script.push_str("\r\n");
+        script
+    } else {
+        let mut script = format!(
+            "#!/bin/sh\n\
+            # Generated by QuantumLauncher\n\
+            # Needs Java {java_version}. Set JAVA to use a specific install.\n\
+            cd \"$(dirname \"$0\")/instance/.minecraft\" || exit 1\n\
+            exec \"${{JAVA:-java}}\""
+        );
+        for arg in all_args {
+            script.push_str(" \\\n    '");
+            script.push_str(&arg.replace('\'', r"'\''"));
+            script.push('\'');
+        }
+        script.push('\n');
+        script
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ql_core::JavaVersion;
+
+    use super::*;
+
+    #[test]
+    fn script_has_main_class_and_java() {
+        let instance_dir = PathBuf::from("/home/steve/QuantumLauncher/instances/Test");
+        let mut args = LaunchArguments {
+            java: vec![
+                "-Xmx2048M".to_owned(),
+                "-Djava.library.path=/home/steve/QuantumLauncher/instances/Test/libraries/natives"
+                    .to_owned(),
+                "-cp".to_owned(),
+                "/home/steve/QuantumLauncher/instances/Test/libraries/a.jar:/home/steve/QuantumLauncher/instances/Test/.minecraft/versions/1.20.1/1.20.1.jar".to_owned(),
+            ],
+            main_class: "net.fabricmc.loader.impl.launch.knot.KnotClient".to_owned(),
+            game: vec!["--username".to_owned(), "it's me".to_owned()],
+            java_version: JavaVersion::Java17,
+            working_dir: instance_dir.join(".minecraft"),
+        };
+        relativize(&mut args, &instance_dir, "..").unwrap();
+
+        let script = launch_script(&args, false);
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("Needs Java 17."));
+        assert!(script.contains("\n    'net.fabricmc.loader.impl.launch.knot.KnotClient' \\\n"));
+        assert!(script.contains("'../libraries/a.jar:../.minecraft/versions/1.20.1/1.20.1.jar'"));
+        assert!(script.contains(r"'it'\''s me'"));
+        assert!(!script.contains("/home/steve"));
+
+        let script = launch_script(&args, true);
+        assert!(script.contains("rem Needs Java 17."));
+        assert!(script.contains("\"net.fabricmc.loader.impl.launch.knot.KnotClient\" ^\r\n"));
+    }
+}
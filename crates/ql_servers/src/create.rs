@@ -1,16 +1,56 @@
-use std::sync::mpsc::Sender;
+use std::{path::Path, sync::mpsc::Sender};
 
 use ql_core::{
-    DownloadProgress, IntoIoError, IntoJsonError, IntoStringError, LAUNCHER_DIR, ListEntry,
-    download,
+    DownloadProgress, InstanceKind, IntoIoError, IntoJsonError, IntoStringError, LAUNCHER_DIR,
+    ListEntry, Loader, NameError,
     file_utils::{self, exists},
-    info,
+    get_entries, info,
     json::{InstanceConfigJson, Manifest, VersionDetails, instance_config::VersionInfo},
-    pt, sanitize_instance_name,
+    pt, sanitize_instance_name, validate_instance_name,
 };
 
 use crate::ServerError;
 
+/// A server made by [`create_server`].
+#[derive(Debug, Clone)]
+pub struct CreatedServer {
+    /// The server's name (sanitized).
+    pub name: String,
+    /// Things the user should know about (and maybe fix)
+    /// before running the server.
+    pub warnings: Vec<ServerWarning>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerWarning {
+    /// The user plays with an offline account, but the server
+    /// has `online-mode=true`, which only lets in logged-in accounts.
+    ///
+    /// Fix with [`crate::set_online_mode`] (`false`).
+    OfflineAccountOnlineMode,
+    /// The server was moved to another version with
+    /// [`crate::upgrade_server_version`], but its loader
+    /// is still the one made for the old version.
+    ///
+    /// It has no build for the new version yet
+    /// (or couldn't be installed).
+    LoaderNotUpgraded(Loader),
+}
+
+impl std::fmt::Display for ServerWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerWarning::OfflineAccountOnlineMode => f.write_str(
+                "You're using an offline account, but servers only allow logged-in accounts by default (online-mode=true), so you won't be able to join",
+            ),
+            ServerWarning::LoaderNotUpgraded(loader) => write!(
+                f,
+                "{loader} couldn't be upgraded, it may not have a build for the new version yet. The server might not start until it's reinstalled"
+            ),
+        }
+    }
+}
+
 /// Creates a minecraft server with the given name and version.
 ///
 /// # Arguments
@@ -18,6 +58,9 @@ use crate::ServerError;
 /// - `version` - The version of the server.
 /// - `sender` - A sender to send progress updates to
 ///   (optional).
+/// - `offline_account` - Whether the user's selected account
+///   is offline, to warn about `online-mode`
+///   (see [`CreatedServer::warnings`]).
 ///
 /// # Errors
 ///
@@ -46,11 +89,10 @@ pub async fn create_server(
     name: String,
     version: ListEntry,
     sender: Option<&Sender<DownloadProgress>>,
-) -> Result<String, ServerError> {
+    offline_account: bool,
+) -> Result<CreatedServer, ServerError> {
     let name = sanitize_instance_name(name);
-    if name.is_empty() {
-        return Err(ServerError::InvalidName);
-    }
+    validate_instance_name(&name, &get_entries(InstanceKind::Server).await?)?;
 
     info!(
         "Started creating server: {name} (version: {}, kind: {})",
@@ -60,9 +102,6 @@ pub async fn create_server(
     let manifest = Manifest::download().await?;
 
     let server_dir = get_server_dir(&name).await?;
-    let server_jar_path = server_dir.join("server.jar");
-
-    let mut is_classic_server = false;
 
     let version_manifest = manifest
         .find_name(&version.name)
@@ -76,19 +115,8 @@ pub async fn create_server(
     };
 
     progress_server_jar(sender);
-    if version.name.starts_with("c0.") {
-        is_classic_server = true;
-
-        let archive = file_utils::download_file_to_bytes(&server.url, true).await?;
-        file_utils::extract_zip_archive(std::io::Cursor::new(archive), &server_dir, true).await?;
-
-        let old_path = server_dir.join("minecraft-server.jar");
-        tokio::fs::rename(&old_path, &server_jar_path)
-            .await
-            .path(old_path)?;
-    } else {
-        download(&server.url).path(&server_jar_path).await?;
-    }
+    let is_classic_server =
+        download_server_jar(&version.name, &server.url, &server_dir, sender).await?;
 
     version_json.save_to_dir(&server_dir).await?;
     write_eula(&server_dir).await?;
@@ -99,7 +127,55 @@ pub async fn create_server(
 
     pt!("Finished");
 
-    Ok(name)
+    let warnings = creation_warnings(offline_account);
+    for warning in &warnings {
+        pt!("Warning: {warning}");
+    }
+    Ok(CreatedServer { name, warnings })
+}
+
+/// Downloads the server jar of `version` (from `url`)
+/// to `server_dir/server.jar`.
+///
+/// Returns whether it's a classic server. Those come
+/// in a zip, which is extracted into `server_dir`.
+pub(crate) async fn download_server_jar(
+    version: &str,
+    url: &str,
+    server_dir: &Path,
+    sender: Option<&Sender<DownloadProgress>>,
+) -> Result<bool, ServerError> {
+    let server_jar_path = server_dir.join("server.jar");
+    if version.starts_with("c0.") {
+        let archive = file_utils::download_file_to_bytes(url, true).await?;
+        file_utils::extract_zip_archive(std::io::Cursor::new(archive), server_dir, true).await?;
+
+        let old_path = server_dir.join("minecraft-server.jar");
+        tokio::fs::rename(&old_path, &server_jar_path)
+            .await
+            .path(old_path)?;
+        return Ok(true);
+    }
+
+    let on_progress = |progress: u64, out_of: Option<u64>| {
+        if let Some(sender) = sender {
+            _ = sender.send(DownloadProgress::DownloadingJar {
+                progress: progress as usize,
+                out_of: out_of.unwrap_or_default() as usize,
+            });
+        }
+    };
+    file_utils::download_file_to_path_with(url, &server_jar_path, false, &on_progress).await?;
+    Ok(false)
+}
+
+fn creation_warnings(offline_account: bool) -> Vec<ServerWarning> {
+    let mut warnings = Vec::new();
+    if offline_account {
+        // New servers always start with `online-mode=true`
+        warnings.push(ServerWarning::OfflineAccountOnlineMode);
+    }
+    warnings
 }
 
 async fn write_config(
@@ -125,7 +201,7 @@ async fn write_config(
 async fn get_server_dir(name: &str) -> Result<std::path::PathBuf, ServerError> {
     let server_dir = LAUNCHER_DIR.join("servers").join(name);
     if exists(&server_dir).await {
-        return Err(ServerError::ServerAlreadyExists);
+        return Err(NameError::AlreadyExists(name.to_owned()).into());
     }
     tokio::fs::create_dir_all(&server_dir)
         .await
@@ -133,7 +209,7 @@ async fn get_server_dir(name: &str) -> Result<std::path::PathBuf, ServerError> {
     Ok(server_dir)
 }
 
-fn progress_manifest(sender: Option<&Sender<DownloadProgress>>) {
+pub(crate) fn progress_manifest(sender: Option<&Sender<DownloadProgress>>) {
     pt!("Downloading Manifest");
     if let Some(sender) = sender {
         sender
@@ -150,14 +226,19 @@ async fn write_eula(server_dir: &std::path::Path) -> Result<(), ServerError> {
     Ok(())
 }
 
-fn progress_server_jar(sender: Option<&Sender<DownloadProgress>>) {
+pub(crate) fn progress_server_jar(sender: Option<&Sender<DownloadProgress>>) {
     pt!("Downloading server jar");
     if let Some(sender) = sender {
-        sender.send(DownloadProgress::DownloadingJar).unwrap();
+        sender
+            .send(DownloadProgress::DownloadingJar {
+                progress: 0,
+                out_of: 0,
+            })
+            .unwrap();
     }
 }
 
-fn progress_json(sender: Option<&Sender<DownloadProgress>>) {
+pub(crate) fn progress_json(sender: Option<&Sender<DownloadProgress>>) {
     pt!("Downloading version JSON");
     if let Some(sender) = sender {
         sender
@@ -180,3 +261,17 @@ pub fn delete_server(name: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warns_offline_accounts() {
+        assert_eq!(
+            creation_warnings(true),
+            [ServerWarning::OfflineAccountOnlineMode]
+        );
+        assert!(creation_warnings(false).is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, imageops::FilterType};
+use ql_core::{IntoIoError, LAUNCHER_DIR, info};
+
+use crate::ServerError;
+
+const ICON_SIZE: u32 = 64;
+
+/// Sets the icon shown for the server in the server list
+/// (`server-icon.png`).
+///
+/// The icon must be square, and is resized to 64x64
+/// (and converted to PNG) if needed.
+///
+/// # Errors
+/// - `png` isn't a valid image, or isn't square
+/// - The icon couldn't be written to the server folder
+pub async fn set_server_icon(name: &str, png: &[u8]) -> Result<(), ServerError> {
+    let icon = prepare_icon(png)?;
+    let path = LAUNCHER_DIR
+        .join("servers")
+        .join(name)
+        .join("server-icon.png");
+    tokio::fs::write(&path, icon).await.path(path)?;
+    info!("Set server icon");
+    Ok(())
+}
+
+/// Validates the icon, and re-encodes it as a 64x64 PNG
+/// if it isn't one already.
+fn prepare_icon(bytes: &[u8]) -> Result<Vec<u8>, ServerError> {
+    let format = image::guess_format(bytes).map_err(|_| ServerError::IconNotImage)?;
+    let img = image::load_from_memory_with_format(bytes, format)
+        .map_err(|_| ServerError::IconNotImage)?;
+
+    let (width, height) = (img.width(), img.height());
+    if width != height {
+        return Err(ServerError::IconNotSquare(width, height));
+    }
+    if format == ImageFormat::Png && width == ICON_SIZE {
+        return Ok(bytes.to_owned());
+    }
+
+    let resized = img.resize_exact(ICON_SIZE, ICON_SIZE, FilterType::Triangle);
+    let mut buf = Vec::new();
+    resized
+        .write_to(&mut Cursor::new(&mut buf), ImageFormat::Png)
+        .map_err(|_| ServerError::IconNotImage)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, RgbaImage};
+
+    use super::*;
+
+    fn image(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        let img = DynamicImage::ImageRgba8(RgbaImage::new(width, height));
+        let mut buf = Vec::new();
+        img.write_to(&mut Cursor::new(&mut buf), format).unwrap();
+        buf
+    }
+
+    #[test]
+    fn icon_dimensions() {
+        let icon = image(64, 64, ImageFormat::Png);
+        assert_eq!(prepare_icon(&icon).unwrap(), icon);
+
+        for bytes in [
+            image(128, 128, ImageFormat::Png),
+            image(32, 32, ImageFormat::Jpeg),
+        ] {
+            let icon = prepare_icon(&bytes).unwrap();
+            assert_eq!(image::guess_format(&icon).unwrap(), ImageFormat::Png);
+            let icon = image::load_from_memory(&icon).unwrap();
+            assert_eq!((icon.width(), icon.height()), (64, 64));
+        }
+
+        assert!(matches!(
+            prepare_icon(&image(64, 32, ImageFormat::Png)),
+            Err(ServerError::IconNotSquare(64, 32))
+        ));
+        assert!(matches!(
+            prepare_icon(b"not an image"),
+            Err(ServerError::IconNotImage)
+        ));
+    }
+}
@@ -8,16 +8,26 @@
 
 use std::path::PathBuf;
 
-use ql_core::{IoError, JsonError, RequestError, impl_3_errs_jri};
+use ql_core::{IoError, JsonError, NameError, RequestError, impl_3_errs_jri};
 use ql_java_handler::JavaInstallError;
 
 mod create;
+mod icon;
+mod playit;
+mod properties_preset;
 mod run;
 mod server_properties;
+mod supervisor;
+mod upgrade;
 // mod ssh;
-pub use create::{create_server, delete_server};
+pub use create::{CreatedServer, ServerWarning, create_server, delete_server};
+pub use icon::set_server_icon;
+pub use playit::{PlayitError, PlayitTunnel, start_playit_tunnel};
+pub use properties_preset::{PropertiesPreset, apply_properties_preset, set_online_mode};
 pub use run::run;
 pub use server_properties::ServerProperties;
+pub use supervisor::{SupervisedServer, run_supervised};
+pub use upgrade::upgrade_server_version;
 // pub use ssh::run_tunnel;
 
 use thiserror::Error;
@@ -40,16 +50,22 @@ pub enum ServerError {
         "{SERVER_ERR_PREFIX}couldn't find download field:\n(details.json).downloads.server is null"
     )]
     NoServerDownload,
-    #[error("server name is invalid (empty/disallowed characters)")]
-    InvalidName,
-    #[error("A server with that name already exists!")]
-    ServerAlreadyExists,
+    #[error("{0}")]
+    InvalidName(#[from] NameError),
     #[error("{SERVER_ERR_PREFIX}zip extract error:\n{0}")]
     ZipExtract(#[from] zip::result::ZipError),
-    #[error("{SERVER_ERR_PREFIX}couldn't find forge shim file")]
+    #[error(
+        "{SERVER_ERR_PREFIX}couldn't find forge shim file (forge-*-shim.jar)\nor launch args (libraries/net/minecraftforge/forge/*/{{unix,win}}_args.txt)\n\nTry reinstalling Forge"
+    )]
     NoForgeShimFound,
     #[error("{SERVER_ERR_PREFIX}couldn't convert PathBuf to str: {0:?}")]
     PathBufToStr(PathBuf),
+    #[error("{SERVER_ERR_PREFIX}{0}")]
+    Playit(#[from] PlayitError),
+    #[error("{SERVER_ERR_PREFIX}server icon isn't a valid image")]
+    IconNotImage,
+    #[error("{SERVER_ERR_PREFIX}server icon must be square (64x64), but it's {0}x{1}")]
+    IconNotSquare(u32, u32),
 }
 
 impl_3_errs_jri!(ServerError, Json, Request, Io);
@@ -0,0 +1,369 @@
+//! [playit.gg](https://playit.gg) tunnels, to let others
+//! join a server without port forwarding.
+//!
+//! This runs the official playit agent (downloaded to
+//! `QuantumLauncher/playit/`), which is linked to the user's
+//! playit.gg account on first use.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use ql_core::{
+    IntoIoError, IoError, LAUNCHER_DIR, LaunchedProcess, err, file_utils, info, no_window, pt,
+};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::{Child, Command},
+};
+
+use crate::ServerError;
+
+/// How long to wait for the agent to report the tunnel's address.
+const TUNNEL_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long the agent is kept running (in the background)
+/// for the user to finish linking their account.
+const CLAIM_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Error)]
+pub enum PlayitError {
+    #[error("the playit.gg agent isn't available for this platform")]
+    UnsupportedPlatform,
+    #[error(
+        "playit.gg isn't set up yet:\n\
+        1. Open {claim_url}\n\
+        2. Log in (or sign up), and approve the agent\n\
+        3. Add a \"Minecraft Java\" tunnel for your server's port\n\
+        4. Start the tunnel again"
+    )]
+    NotLinked { claim_url: String },
+    #[error(
+        "playit.gg has no tunnel for port {0}\nAdd a \"Minecraft Java\" tunnel with local port {0} on playit.gg, then try again"
+    )]
+    NoTunnel(u16),
+    #[error("the playit.gg agent exited before the tunnel was ready")]
+    AgentExited,
+    #[error("the latest playit.gg release has no {0}")]
+    AgentNotReleased(String),
+    #[error(
+        "the downloaded playit.gg agent is corrupted (expected {expected}, got {got})\nTry again later"
+    )]
+    AgentCorrupted { expected: String, got: String },
+}
+
+/// A running playit.gg tunnel, see [`start_playit_tunnel`].
+///
+/// The agent is stopped when this is dropped.
+pub struct PlayitTunnel {
+    /// Public address players can join with,
+    /// like `cool-name.gl.joinmc.link`.
+    pub address: String,
+    agent: Child,
+}
+
+impl PlayitTunnel {
+    /// Stops the tunnel.
+    ///
+    /// # Errors
+    /// If the OS refused to kill the agent.
+    pub async fn stop(mut self) -> Result<(), IoError> {
+        self.agent.kill().await.map_err(IoError::Process)
+    }
+
+    /// Keeps the tunnel running until `server` exits, then stops it.
+    pub fn stop_with(self, server: &LaunchedProcess) {
+        let server = server.child.clone();
+        tokio::spawn(async move {
+            loop {
+                // Only lock briefly, so log reading isn't blocked
+                if !matches!(server.lock().await.try_wait(), Ok(None)) {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            pt!("Server stopped, closing playit.gg tunnel");
+            drop(self);
+        });
+    }
+}
+
+/// Exposes a server running on `server_port` (usually `25565`)
+/// to the internet through [playit.gg](https://playit.gg),
+/// returning the public address.
+///
+/// The agent is downloaded if needed. Use [`PlayitTunnel::stop_with`]
+/// to stop the tunnel along with the server.
+///
+/// # Errors
+/// - [`PlayitError::NotLinked`] if this is the first time,
+///   with the link to set up playit.gg (the agent keeps
+///   running in the background for a while, for that)
+/// - [`PlayitError::NoTunnel`] if the account has no tunnel for `server_port`
+/// - The agent couldn't be downloaded, or exited early
+pub async fn start_playit_tunnel(server_port: u16) -> Result<PlayitTunnel, ServerError> {
+    info!("Starting playit.gg tunnel (port {server_port})");
+    let dir = LAUNCHER_DIR.join("playit");
+    let agent = get_agent(&dir).await?;
+
+    let mut command = Command::new(&agent);
+    command
+        .arg("--secret_path")
+        .arg(dir.join("playit.toml"))
+        .args(["--stdout", "start"]);
+    no_window!(command);
+    start_agent(command, server_port, TUNNEL_TIMEOUT).await
+}
+
+const AGENT_RELEASE_URL: &str =
+    "https://api.github.com/repos/playit-cloud/playit-agent/releases/latest";
+
+/// Downloads the agent (if not already there) into `dir`.
+///
+/// It's downloaded next to the final path and checked against
+/// the size and digest of the release asset before being moved
+/// into place, so a cut-off or tampered download is never run.
+async fn get_agent(dir: &Path) -> Result<PathBuf, ServerError> {
+    let name = agent_download_name().ok_or(PlayitError::UnsupportedPlatform)?;
+    let path = dir.join(if cfg!(windows) {
+        "playit.exe"
+    } else {
+        "playit"
+    });
+    if file_utils::exists(&path).await {
+        return Ok(path);
+    }
+
+    pt!("Downloading playit.gg agent");
+    tokio::fs::create_dir_all(dir).await.path(dir)?;
+    let release: serde_json::Value =
+        file_utils::download_file_to_json(AGENT_RELEASE_URL, true).await?;
+    let asset = release["assets"]
+        .as_array()
+        .and_then(|assets| assets.iter().find(|n| n["name"] == name))
+        .ok_or_else(|| PlayitError::AgentNotReleased(name.to_owned()))?;
+    let url = asset["browser_download_url"]
+        .as_str()
+        .ok_or_else(|| PlayitError::AgentNotReleased(name.to_owned()))?;
+
+    let temp = dir.join(format!("{name}.download"));
+    file_utils::download_file_to_path(url, &temp, false, None).await?;
+    let bytes = tokio::fs::read(&temp).await.path(&temp)?;
+    if let Err(error) = verify_agent(&bytes, asset["size"].as_u64(), asset["digest"].as_str()) {
+        _ = tokio::fs::remove_file(&temp).await;
+        return Err(error.into());
+    }
+    #[cfg(target_family = "unix")]
+    file_utils::set_executable(&temp).await?;
+    tokio::fs::rename(&temp, &path).await.path(&path)?;
+    Ok(path)
+}
+
+/// Checks a downloaded agent against the release asset's
+/// `size` and `digest` (`sha256:<hex>`).
+///
+/// Older releases have no digest, in which case only
+/// the size is checked.
+fn verify_agent(bytes: &[u8], size: Option<u64>, digest: Option<&str>) -> Result<(), PlayitError> {
+    if let Some(size) = size {
+        if bytes.len() as u64 != size {
+            return Err(PlayitError::AgentCorrupted {
+                expected: format!("{size} bytes"),
+                got: format!("{} bytes", bytes.len()),
+            });
+        }
+    }
+    let Some(expected) = digest.and_then(|n| n.strip_prefix("sha256:")) else {
+        err!("playit.gg agent release has no checksum, only checking its size");
+        return Ok(());
+    };
+    let got = format!("{:x}", Sha256::digest(bytes));
+    if got.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(PlayitError::AgentCorrupted {
+            expected: expected.to_owned(),
+            got,
+        })
+    }
+}
+
+fn agent_download_name() -> Option<&'static str> {
+    Some(match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => "playit-linux-amd64",
+        ("linux", "aarch64") => "playit-linux-aarch64",
+        ("linux", "arm") => "playit-linux-armv7",
+        ("linux", "x86") => "playit-linux-i686",
+        ("windows", "x86_64") => "playit-windows-x86_64-signed.exe",
+        ("windows", "x86") => "playit-windows-x86-signed.exe",
+        _ => return None,
+    })
+}
+
+async fn start_agent(
+    mut command: Command,
+    server_port: u16,
+    timeout: Duration,
+) -> Result<PlayitTunnel, ServerError> {
+    let mut agent = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(IoError::Process)?;
+    let stdout = agent.stdout.take().ok_or(PlayitError::AgentExited)?;
+    let mut lines = BufReader::new(stdout).lines();
+
+    let event = tokio::time::timeout(timeout, async {
+        while let Some(line) = lines.next_line().await.map_err(IoError::Process)? {
+            if let Some(event) = parse_line(&line, server_port) {
+                return Ok(event);
+            }
+        }
+        Err(ServerError::Playit(PlayitError::AgentExited))
+    })
+    .await
+    .map_err(|_| PlayitError::NoTunnel(server_port))??;
+
+    match event {
+        AgentEvent::Tunnel(address) => {
+            info!("playit.gg tunnel running: {address}");
+            // Keep reading, so the agent doesn't block on a full pipe
+            tokio::spawn(async move { while let Ok(Some(_)) = lines.next_line().await {} });
+            Ok(PlayitTunnel { address, agent })
+        }
+        AgentEvent::Claim(claim_url) => {
+            pt!("playit.gg isn't set up, waiting for it in the background");
+            tokio::spawn(async move {
+                // Killed (on drop) if the user doesn't finish in time
+                _ = tokio::time::timeout(CLAIM_TIMEOUT, agent.wait()).await;
+            });
+            Err(PlayitError::NotLinked { claim_url }.into())
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum AgentEvent {
+    /// The agent isn't linked to an account,
+    /// the user needs to open this link.
+    Claim(String),
+    /// The public address of the tunnel for the server.
+    Tunnel(String),
+}
+
+/// Parses a line of the agent's `--stdout` output, like:
+///
+/// ```txt
+/// Visit link to setup https://playit.gg/claim/1a2b3c4d5e
+/// cool-name.gl.joinmc.link => 127.0.0.1:25565 (minecraft-java)
+/// ```
+fn parse_line(line: &str, server_port: u16) -> Option<AgentEvent> {
+    const CLAIM_LINK: &str = "https://playit.gg/claim/";
+    if let Some(start) = line.find(CLAIM_LINK) {
+        let url = line[start..].split_whitespace().next()?;
+        return Some(AgentEvent::Claim(url.to_owned()));
+    }
+
+    let (address, local) = line.split_once(" => ")?;
+    let address = address.split_whitespace().next_back()?;
+    let local = local.split_whitespace().next()?;
+    let (_, port) = local.rsplit_once(':')?;
+    (port.parse() == Ok(server_port)).then(|| AgentEvent::Tunnel(address.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_agent_output() {
+        assert_eq!(
+            parse_line(
+                "cool-name.gl.joinmc.link => 127.0.0.1:25565 (minecraft-java)",
+                25565
+            ),
+            Some(AgentEvent::Tunnel("cool-name.gl.joinmc.link".to_owned()))
+        );
+        // Some other tunnel
+        assert_eq!(
+            parse_line("147.185.221.1:1234 => 127.0.0.1:8080 (tcp)", 25565),
+            None
+        );
+        assert_eq!(
+            parse_line(
+                "Visit link to setup https://playit.gg/claim/1a2b3c4d5e",
+                25565
+            ),
+            Some(AgentEvent::Claim(
+                "https://playit.gg/claim/1a2b3c4d5e".to_owned()
+            ))
+        );
+        assert_eq!(
+            parse_line("tunnel running, 1 tunnels registered", 25565),
+            None
+        );
+    }
+
+    #[test]
+    fn verifies_agent() {
+        let agent = b"#!/bin/sh\necho hi\n";
+        let digest = format!("sha256:{:x}", Sha256::digest(agent));
+        assert!(verify_agent(agent, Some(agent.len() as u64), Some(&digest)).is_ok());
+        // Older releases without a digest
+        assert!(verify_agent(agent, Some(agent.len() as u64), None).is_ok());
+
+        // Cut off
+        assert!(matches!(
+            verify_agent(&agent[..4], Some(agent.len() as u64), Some(&digest)),
+            Err(PlayitError::AgentCorrupted { .. })
+        ));
+        // Same size, different contents
+        let other = b"#!/bin/sh\necho no\n";
+        assert!(matches!(
+            verify_agent(other, Some(agent.len() as u64), Some(&digest)),
+            Err(PlayitError::AgentCorrupted { .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn mock_agent() {
+        let mut command = Command::new("sh");
+        command.args([
+            "-c",
+            "echo 'tunnel running, 2 tunnels registered'; \
+            echo 'other.gl.joinmc.link => 127.0.0.1:8080 (tcp)'; \
+            echo 'cool-name.gl.joinmc.link => 127.0.0.1:25565 (minecraft-java)'; \
+            exec sleep 30",
+        ]);
+        let tunnel = start_agent(command, 25565, Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(tunnel.address, "cool-name.gl.joinmc.link");
+        tunnel.stop().await.unwrap();
+
+        let mut command = Command::new("sh");
+        command.args([
+            "-c",
+            "echo 'Visit link to setup https://playit.gg/claim/abc'",
+        ]);
+        let result = start_agent(command, 25565, Duration::from_secs(10)).await;
+        assert!(matches!(
+            result,
+            Err(ServerError::Playit(PlayitError::NotLinked { claim_url }))
+                if claim_url == "https://playit.gg/claim/abc"
+        ));
+
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo 'starting'"]);
+        let result = start_agent(command, 25565, Duration::from_secs(10)).await;
+        assert!(matches!(
+            result,
+            Err(ServerError::Playit(PlayitError::AgentExited))
+        ));
+    }
+}
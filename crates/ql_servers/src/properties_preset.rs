@@ -0,0 +1,209 @@
+use std::{collections::HashSet, fmt::Display};
+
+use ql_core::{IntoIoError, IoError, LAUNCHER_DIR, info};
+
+use crate::ServerError;
+
+/// Built-in `server.properties` defaults for common kinds of servers,
+/// see [`apply_properties_preset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertiesPreset {
+    Survival,
+    Hardcore,
+    Creative,
+    /// For minigame/lobby servers (usually with plugins).
+    Minigame,
+}
+
+impl PropertiesPreset {
+    pub const ALL: &[Self] = &[
+        Self::Survival,
+        Self::Hardcore,
+        Self::Creative,
+        Self::Minigame,
+    ];
+
+    /// The `server.properties` keys and values set by this preset.
+    #[must_use]
+    pub fn values(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            PropertiesPreset::Survival => &[
+                ("gamemode", "survival"),
+                ("force-gamemode", "false"),
+                ("difficulty", "normal"),
+                ("hardcore", "false"),
+                ("pvp", "true"),
+                ("spawn-protection", "16"),
+                ("spawn-monsters", "true"),
+                ("allow-flight", "false"),
+            ],
+            PropertiesPreset::Hardcore => &[
+                ("gamemode", "survival"),
+                ("force-gamemode", "false"),
+                ("difficulty", "hard"),
+                ("hardcore", "true"),
+                ("pvp", "true"),
+                ("spawn-protection", "16"),
+                ("spawn-monsters", "true"),
+                ("allow-flight", "false"),
+            ],
+            PropertiesPreset::Creative => &[
+                ("gamemode", "creative"),
+                ("force-gamemode", "true"),
+                ("difficulty", "peaceful"),
+                ("hardcore", "false"),
+                ("pvp", "false"),
+                ("spawn-protection", "0"),
+                ("spawn-monsters", "false"),
+                ("allow-flight", "true"),
+            ],
+            PropertiesPreset::Minigame => &[
+                ("gamemode", "adventure"),
+                ("force-gamemode", "true"),
+                ("difficulty", "normal"),
+                ("hardcore", "false"),
+                ("pvp", "true"),
+                ("spawn-protection", "0"),
+                ("spawn-monsters", "false"),
+                // Minigames often launch players around,
+                // which the anti-cheat would kick them for
+                ("allow-flight", "true"),
+            ],
+        }
+    }
+}
+
+impl Display for PropertiesPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PropertiesPreset::Survival => "Survival",
+            PropertiesPreset::Hardcore => "Hardcore",
+            PropertiesPreset::Creative => "Creative",
+            PropertiesPreset::Minigame => "Minigame",
+        })
+    }
+}
+
+/// Applies a [`PropertiesPreset`] to the server's `server.properties`.
+///
+/// Only the keys in the preset are changed, everything else
+/// (other settings, comments, ordering) is kept as-is.
+/// If the file doesn't exist yet (server never started),
+/// it's created with just the preset's values.
+///
+/// # Errors
+/// If `server.properties` couldn't be read or written.
+pub async fn apply_properties_preset(
+    name: &str,
+    preset: PropertiesPreset,
+) -> Result<(), ServerError> {
+    info!("Applying {preset} preset to server.properties");
+    write_properties(name, preset.values()).await
+}
+
+/// Sets `online-mode` in the server's `server.properties`.
+///
+/// With `false`, offline (cracked) accounts can join, but so can
+/// anyone else, with any username (no login check).
+///
+/// # Errors
+/// If `server.properties` couldn't be read or written.
+pub async fn set_online_mode(name: &str, online: bool) -> Result<(), ServerError> {
+    info!("Setting online-mode={online}");
+    let online = if online { "true" } else { "false" };
+    write_properties(name, &[("online-mode", online)]).await
+}
+
+/// Merges `values` into the server's `server.properties`
+/// (see [`merge_properties`]), creating it if needed.
+async fn write_properties(name: &str, values: &[(&str, &str)]) -> Result<(), ServerError> {
+    let path = LAUNCHER_DIR
+        .join("servers")
+        .join(name)
+        .join("server.properties");
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(n) => n,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(IoError::Io { error, path }.into()),
+    };
+    let contents = merge_properties(&contents, values);
+    tokio::fs::write(&path, contents).await.path(path)?;
+    Ok(())
+}
+
+/// Sets `values` in the `server.properties` `contents`,
+/// replacing existing lines (in place) and appending the rest.
+fn merge_properties(contents: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(contents.len());
+    let mut replaced = HashSet::new();
+
+    for line in contents.lines() {
+        let value = line
+            .split_once('=')
+            .filter(|_| !line.starts_with('#'))
+            .and_then(|(key, _)| values.iter().find(|(k, _)| *k == key.trim()));
+        if let Some((key, value)) = value {
+            out.push_str(key);
+            out.push('=');
+            out.push_str(value);
+            replaced.insert(*key);
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    for (key, value) in values {
+        if !replaced.contains(key) {
+            out.push_str(key);
+            out.push('=');
+            out.push_str(value);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creative_preset_keeps_other_keys() {
+        let contents = "#Minecraft server properties\n\
+            #Sat Jan 01 00:00:00 UTC 2022\n\
+            difficulty=easy\n\
+            gamemode=survival\n\
+            motd=My \\u00A7aServer\n\
+            pvp=true\n\
+            server-port=25566\n";
+        let merged = merge_properties(contents, PropertiesPreset::Creative.values());
+        let lines: Vec<&str> = merged.lines().collect();
+
+        // Changed in place
+        assert_eq!(
+            lines[..7],
+            [
+                "#Minecraft server properties",
+                "#Sat Jan 01 00:00:00 UTC 2022",
+                "difficulty=peaceful",
+                "gamemode=creative",
+                "motd=My \\u00A7aServer",
+                "pvp=false",
+                "server-port=25566",
+            ]
+        );
+        // Missing ones are added
+        assert!(lines.contains(&"spawn-protection=0"));
+        assert!(lines.contains(&"force-gamemode=true"));
+        assert_eq!(
+            lines.len(),
+            7 + PropertiesPreset::Creative.values().len() - 3
+        );
+
+        // Applying again changes nothing
+        assert_eq!(
+            merge_properties(&merged, PropertiesPreset::Creative.values()),
+            merged
+        );
+    }
+}
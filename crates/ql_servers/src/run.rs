@@ -6,11 +6,12 @@ use std::{
 
 use ql_core::{
     GenericProgress, Instance, IntoIoError, LAUNCHER_DIR, LaunchedProcess, Loader,
+    file_utils::{self, exists},
     find_forge_shim_file, info,
     json::{InstanceConfigJson, VersionDetails},
     no_window, pt,
 };
-use ql_java_handler::{JavaVersion, get_java_binary};
+use ql_java_handler::get_java_binary;
 use tokio::{process::Command, sync::Mutex};
 
 use crate::ServerError;
@@ -32,7 +33,8 @@ use crate::ServerError;
 /// - Java binary path could not be obtained
 /// - Java could not be installed (if not found)
 /// - `Command` couldn't be spawned (IO Error)
-/// - Forge shim file (`forge-*-shim.jar`) couldn't be found
+/// - Forge shim file (`forge-*-shim.jar`) or launch args
+///   (`libraries/.../unix_args.txt`) couldn't be found
 /// - Other stuff I'm too dumb to see
 pub async fn run(
     name: Arc<str>,
@@ -40,11 +42,11 @@ pub async fn run(
 ) -> Result<LaunchedProcess, ServerError> {
     let launcher = ServerLauncher::new(&name).await?;
 
-    let server_jar_path = launcher.get_server_jar().await?;
+    let entry = launcher.get_entry().await?;
 
     let java_path = launcher.get_java(java_install_progress.as_ref()).await?;
 
-    let java_args = launcher.get_java_args(&server_jar_path).await?;
+    let java_args = launcher.get_java_args(&entry).await?;
     let mut game_args = launcher.config.game_args.clone().unwrap_or_default();
     game_args.push("nogui".to_owned());
 
@@ -66,7 +68,7 @@ pub async fn run(
             .stdin(Stdio::piped());
     }
 
-    let child = command.spawn().path(server_jar_path)?;
+    let child = command.spawn().path(entry.path())?;
     if let Some(id) = child.id() {
         pt!("PID: {id}");
     } else {
@@ -79,6 +81,32 @@ pub async fn run(
     })
 }
 
+#[cfg(target_family = "unix")]
+const ARGS_FILENAME: &str = "unix_args.txt";
+#[cfg(target_os = "windows")]
+const ARGS_FILENAME: &str = "win_args.txt";
+#[cfg(not(any(target_family = "unix", target_os = "windows")))]
+const ARGS_FILENAME: &str = "YOUR_OS_IS_UNSUPPORTED";
+
+/// What the server is started from.
+#[derive(Debug, PartialEq)]
+enum ServerEntry {
+    /// `java -jar <JAR>` (or `-cp` for classic servers)
+    Jar(PathBuf),
+    /// `java @<FILE>`, where the `<os>_args.txt` file
+    /// that the modern Forge (1.17+) and NeoForge installers
+    /// make has the classpath, main class and so on.
+    ArgsFile(PathBuf),
+}
+
+impl ServerEntry {
+    fn path(&self) -> &Path {
+        match self {
+            ServerEntry::Jar(path) | ServerEntry::ArgsFile(path) => path,
+        }
+    }
+}
+
 struct ServerLauncher {
     dir: PathBuf,
     version_json: VersionDetails,
@@ -95,10 +123,6 @@ impl ServerLauncher {
         })
     }
 
-    fn is_neoforge(&self) -> bool {
-        self.config.mod_type == Loader::Neoforge
-    }
-
     fn is_classic_server(&self) -> bool {
         self.config.is_classic_server.unwrap_or_default()
     }
@@ -107,45 +131,67 @@ impl ServerLauncher {
         &self,
         java_install_progress: Option<&Sender<GenericProgress>>,
     ) -> Result<PathBuf, ServerError> {
-        let version = if let Some(version) = self.version_json.javaVersion.clone() {
-            version.into()
-        } else {
-            JavaVersion::Java8
-        };
-
         if let Some(java_path) = self.config.get_java_override() {
             return Ok(java_path);
         }
+        let version = self.config.get_java_version(&self.version_json);
         let path = get_java_binary(version, "java", java_install_progress).await?;
         Ok(path)
     }
 
-    async fn get_server_jar(&self) -> Result<PathBuf, ServerError> {
-        Ok(if let Some(custom_jar) = &self.config.custom_jar {
+    async fn get_entry(&self) -> Result<ServerEntry, ServerError> {
+        if let Some(custom_jar) = &self.config.custom_jar {
             // Should I prioritize Fabric/Forge/Paper over a custom JAR?
-            PathBuf::from(&custom_jar.name)
-        } else {
-            let regular = self.dir.join("server.jar");
-            match self.config.mod_type {
-                Loader::Fabric | Loader::Quilt => self.dir.join("fabric-server-launch.jar"),
-                Loader::Forge => find_forge_shim_file(&self.dir)
-                    .await
-                    .ok_or(ServerError::NoForgeShimFound)?,
-                Loader::Paper => self.dir.join("paper_server.jar"),
-                Loader::OptiFine => {
-                    debug_assert!(false, "Optifine can't run on servers");
-                    regular
-                }
-                Loader::Neoforge
-                | Loader::Vanilla
-                | Loader::Liteloader
-                | Loader::Modloader
-                | Loader::Rift => regular,
+            return Ok(ServerEntry::Jar(PathBuf::from(&custom_jar.name)));
+        }
+        let loader_version = self
+            .config
+            .mod_type_info
+            .as_ref()
+            .and_then(|n| n.version.as_deref());
+
+        let regular = ServerEntry::Jar(self.dir.join("server.jar"));
+        Ok(match self.config.mod_type {
+            Loader::Fabric | Loader::Quilt => {
+                ServerEntry::Jar(self.dir.join("fabric-server-launch.jar"))
+            }
+            Loader::Forge => self.get_forge_entry(loader_version).await?,
+            Loader::Neoforge => {
+                let neoforge_dir = self.dir.join("libraries/net/neoforged/neoforge");
+                ServerEntry::ArgsFile(match find_args_file(&neoforge_dir, loader_version).await {
+                    Some(n) => n,
+                    // Let the launch fail with the expected path
+                    None => neoforge_dir
+                        .join(loader_version.unwrap_or_default())
+                        .join(ARGS_FILENAME),
+                })
+            }
+            Loader::Paper => ServerEntry::Jar(self.dir.join("paper_server.jar")),
+            Loader::OptiFine => {
+                debug_assert!(false, "Optifine can't run on servers");
+                regular
             }
+            Loader::Vanilla | Loader::Liteloader | Loader::Modloader | Loader::Rift => regular,
         })
     }
 
-    async fn get_java_args(&self, jar: &Path) -> Result<Vec<String>, ServerError> {
+    /// Newer Forge versions start from a `forge-*-shim.jar`,
+    /// but Forge 1.17 to 1.20.3 only have the launch args
+    /// in `libraries/.../<os>_args.txt`.
+    async fn get_forge_entry(&self, version: Option<&str>) -> Result<ServerEntry, ServerError> {
+        if let Some(shim) = find_forge_shim_file(&self.dir).await {
+            return Ok(ServerEntry::Jar(shim));
+        }
+        // Saved as just the Forge version (`47.2.0`)
+        let version = version.map(|n| format!("{}-{n}", self.version_json.get_id()));
+        let forge_dir = self.dir.join("libraries/net/minecraftforge/forge");
+        find_args_file(&forge_dir, version.as_deref())
+            .await
+            .map(ServerEntry::ArgsFile)
+            .ok_or(ServerError::NoForgeShimFound)
+    }
+
+    async fn get_java_args(&self, entry: &ServerEntry) -> Result<Vec<String>, ServerError> {
         let mut java_args: Vec<String> = self.config.get_java_args(&[]);
         java_args.push(self.config.get_ram_argument());
         if self.config.mod_type == Loader::Forge {
@@ -169,44 +215,29 @@ impl ServerLauncher {
                     ));
                 }
             }
-        } else if self.is_neoforge() {
-            #[cfg(target_family = "unix")]
-            const FILENAME: &str = "unix_args.txt";
-            #[cfg(target_os = "windows")]
-            const FILENAME: &str = "win_args.txt";
-            #[cfg(not(any(target_family = "unix", target_os = "windows")))]
-            const FILENAME: &str = "YOUR_OS_IS_UNSUPPORTED";
-
-            let mut args_path = self.dir.join("libraries/net/neoforged/neoforge");
-            if let Some(ver) = self
-                .config
-                .mod_type_info
-                .as_ref()
-                .and_then(|n| n.version.as_deref())
-            {
-                args_path = args_path.join(ver);
-            }
-            let args_path = args_path.join(FILENAME);
-
-            let args = tokio::fs::read_to_string(&args_path)
-                .await
-                .path(args_path)?;
-            java_args.extend(
-                args.lines()
-                    .flat_map(str::split_whitespace)
-                    .filter(|l| !l.is_empty())
-                    .map(str::to_owned),
-            );
         }
 
         let is_cl_sr = self.is_classic_server();
-        if !self.is_neoforge() {
-            java_args.push(if is_cl_sr { "-cp" } else { "-jar" }.to_owned());
-            java_args.push(
-                jar.to_str()
-                    .ok_or(ServerError::PathBufToStr(jar.to_owned()))?
-                    .to_owned(),
-            );
+        match entry {
+            ServerEntry::Jar(jar) => {
+                java_args.push(if is_cl_sr { "-cp" } else { "-jar" }.to_owned());
+                java_args.push(
+                    jar.to_str()
+                        .ok_or(ServerError::PathBufToStr(jar.to_owned()))?
+                        .to_owned(),
+                );
+            }
+            ServerEntry::ArgsFile(args_path) => {
+                // Checked here, otherwise Java just says
+                // "could not open file" without a path
+                tokio::fs::metadata(args_path).await.path(args_path)?;
+                let args_path = args_path
+                    .to_str()
+                    .ok_or(ServerError::PathBufToStr(args_path.to_owned()))?;
+                // Java reads the file itself (Java 9+, these loaders need 16+),
+                // so quoted paths with spaces work like in Forge's `run.sh`
+                java_args.push(format!("@{args_path}"));
+            }
         }
 
         if is_cl_sr {
@@ -216,3 +247,83 @@ impl ServerLauncher {
         Ok(java_args)
     }
 }
+
+/// Finds the `<os>_args.txt` file in `group_dir/<VERSION>/`
+/// (eg: `libraries/net/minecraftforge/forge/1.20.1-47.2.0/unix_args.txt`).
+///
+/// Tries `version` first, then any version that has one
+/// (in case the loader version wasn't saved in the config).
+async fn find_args_file(group_dir: &Path, version: Option<&str>) -> Option<PathBuf> {
+    if let Some(version) = version {
+        let path = group_dir.join(version).join(ARGS_FILENAME);
+        if exists(&path).await {
+            return Some(path);
+        }
+    }
+    if !exists(group_dir).await {
+        return None;
+    }
+    for entry in file_utils::read_filenames_from_dir(group_dir).await.ok()? {
+        let path = group_dir.join(&entry.name).join(ARGS_FILENAME);
+        if !entry.is_file && exists(&path).await {
+            return Some(path);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use ql_core::{
+        InstanceKind,
+        json::instance_config::{ModTypeInfo, VersionInfo},
+        test_utils::version_details,
+    };
+
+    use super::*;
+
+    fn forge_server(dir: PathBuf) -> ServerLauncher {
+        let version_json = version_details("1.20.1", "2023-06-12T00:00:00+00:00");
+        let mut config =
+            InstanceConfigJson::new(InstanceKind::Server, false, VersionInfo::new("1.20.1"));
+        config.mod_type = Loader::Forge;
+        config.mod_type_info = Some(ModTypeInfo::new_regular("47.2.0".to_owned()));
+        ServerLauncher {
+            dir,
+            version_json,
+            config,
+        }
+    }
+
+    #[tokio::test]
+    async fn modern_forge_launches_with_args_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let launcher = forge_server(dir.to_owned());
+        assert!(matches!(
+            launcher.get_entry().await,
+            Err(ServerError::NoForgeShimFound)
+        ));
+
+        let args_dir = dir.join("libraries/net/minecraftforge/forge/1.20.1-47.2.0");
+        tokio::fs::create_dir_all(&args_dir).await.unwrap();
+        let args_file = args_dir.join(ARGS_FILENAME);
+        tokio::fs::write(&args_file, "-p libraries/a.jar\n")
+            .await
+            .unwrap();
+
+        let entry = launcher.get_entry().await.unwrap();
+        assert_eq!(entry, ServerEntry::ArgsFile(args_file.clone()));
+        let java_args = launcher.get_java_args(&entry).await.unwrap();
+        assert!(!java_args.iter().any(|n| n == "-jar"));
+        assert_eq!(
+            java_args.last(),
+            Some(&format!("@{}", args_file.to_str().unwrap()))
+        );
+
+        // The shim is used if there is one
+        let shim = dir.join("forge-1.20.1-47.2.0-shim.jar");
+        tokio::fs::write(&shim, "").await.unwrap();
+        assert_eq!(launcher.get_entry().await.unwrap(), ServerEntry::Jar(shim));
+    }
+}
@@ -42,4 +42,133 @@ impl ServerProperties {
             .path(properties_file)?;
         Ok(())
     }
+
+    /// Sets the server's MOTD (message shown in the server list).
+    ///
+    /// Color/formatting codes can be written with `§`
+    /// or `&` (like `&aGreen &lbold`, `&&` for a literal `&`).
+    /// Up to two lines are shown, extra lines are dropped.
+    pub fn set_motd(&mut self, motd: &str) {
+        self.entries.insert("motd".to_owned(), encode_motd(motd));
+    }
+
+    /// The server's MOTD, with `§` formatting codes (if any).
+    #[must_use]
+    pub fn motd(&self) -> Option<String> {
+        self.entries.get("motd").map(|n| decode_value(n))
+    }
+}
+
+const MOTD_MAX_LINES: usize = 2;
+
+/// Converts `&` formatting codes to `§`, and escapes the MOTD
+/// for `server.properties` (newlines, and non-ASCII characters
+/// as `\uXXXX`, since older servers don't read it as UTF-8).
+fn encode_motd(motd: &str) -> String {
+    let motd = motd
+        .lines()
+        .take(MOTD_MAX_LINES)
+        .map(convert_markup)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut out = String::with_capacity(motd.len());
+    for c in motd.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_ascii() && !c.is_ascii_control() => out.push(c),
+            c => {
+                let mut buf = [0; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    _ = write!(out, "\\u{unit:04X}");
+                }
+            }
+        }
+    }
+    out
+}
+
+/// `&a` to `§a` (for all valid formatting codes), `&&` to `&`.
+fn convert_markup(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('&') => {
+                out.push('&');
+                chars.next();
+            }
+            Some(&code) if is_format_code(code) => {
+                out.push('§');
+                out.push(code.to_ascii_lowercase());
+                chars.next();
+            }
+            _ => out.push('&'),
+        }
+    }
+    out
+}
+
+fn is_format_code(c: char) -> bool {
+    matches!(c.to_ascii_lowercase(), '0'..='9' | 'a'..='f' | 'k'..='o' | 'r')
+}
+
+/// Undoes the escaping of `server.properties` values
+/// (`\n`, `\uXXXX`, `\\`...).
+fn decode_value(value: &str) -> String {
+    let mut units = Vec::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 2];
+            units.extend_from_slice(c.encode_utf16(&mut buf));
+            continue;
+        }
+        match chars.next() {
+            Some('n') => units.push(u16::from(b'\n')),
+            Some('t') => units.push(u16::from(b'\t')),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                if let Ok(unit) = u16::from_str_radix(&hex, 16) {
+                    units.push(unit);
+                }
+            }
+            Some(c) => {
+                let mut buf = [0; 2];
+                units.extend_from_slice(c.encode_utf16(&mut buf));
+            }
+            None => {}
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn motd_encoding() {
+        assert_eq!(encode_motd("A Minecraft Server"), "A Minecraft Server");
+        assert_eq!(
+            encode_motd("&aWelcome &&  friends\n&lHave fun"),
+            "\\u00A7aWelcome &  friends\\n\\u00A7lHave fun"
+        );
+        // `§` works directly, `&` without a valid code is kept
+        assert_eq!(encode_motd("§cRed & &zblue"), "\\u00A7cRed & &zblue");
+        // Only two lines fit in the server list
+        assert_eq!(encode_motd("one\ntwo\nthree"), "one\\ntwo");
+        assert_eq!(encode_motd("C:\\Server 😀"), "C:\\\\Server \\uD83D\\uDE00");
+
+        let mut properties = ServerProperties {
+            entries: HashMap::new(),
+        };
+        properties.set_motd("&6Gold\nsecond line 😀");
+        assert_eq!(properties.motd().as_deref(), Some("§6Gold\nsecond line 😀"));
+    }
 }
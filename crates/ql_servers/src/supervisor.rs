@@ -0,0 +1,205 @@
+use std::{
+    process::ExitStatus,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+    },
+    time::Duration,
+};
+
+use ql_core::{GenericProgress, IoError, LaunchedProcess, err, info};
+use tokio::{sync::watch, task::JoinHandle, time::Instant};
+
+use crate::{ServerError, run};
+
+/// Wait before the first restart, doubled for every
+/// restart after that (up to [`MAX_BACKOFF`]).
+const BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(2 * 60);
+/// If the server ran at least this long before crashing,
+/// it's counted as a new crash (restart counter resets).
+const STABLE_TIME: Duration = Duration::from_secs(10 * 60);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A server started with [`run_supervised`].
+pub struct SupervisedServer {
+    /// The currently running server process.
+    ///
+    /// Replaced with a new process on every restart,
+    /// use [`watch::Receiver::changed`] to reattach logs/input.
+    pub process: watch::Receiver<LaunchedProcess>,
+    /// Set this to `true` before sending the `stop` command
+    /// (or killing the server), so the exit isn't
+    /// mistaken for a crash.
+    pub has_issued_stop_command: Arc<AtomicBool>,
+    /// Finishes once the server has stopped for good,
+    /// with the number of times it was restarted.
+    pub supervisor: JoinHandle<usize>,
+}
+
+/// Runs a server (like [`run`]), restarting it
+/// if it crashes (exits with a non-zero code).
+///
+/// Restarts happen up to `max_restarts` times in a row, waiting
+/// longer before each one. The server isn't restarted if it
+/// was stopped on purpose ([`SupervisedServer::has_issued_stop_command`]).
+///
+/// # Errors
+/// If the server couldn't be started the first time (see [`run`]).
+/// Errors while restarting are only logged.
+pub async fn run_supervised(
+    name: Arc<str>,
+    max_restarts: usize,
+    sender: Option<Sender<GenericProgress>>,
+) -> Result<SupervisedServer, ServerError> {
+    let process = run(name.clone(), sender.clone()).await?;
+    let has_issued_stop_command = Arc::new(AtomicBool::new(false));
+    let (process_tx, process_rx) = watch::channel(process.clone());
+
+    let supervisor = tokio::spawn(supervise(
+        process,
+        move || run(name.clone(), sender.clone()),
+        max_restarts,
+        BACKOFF,
+        has_issued_stop_command.clone(),
+        process_tx,
+    ));
+
+    Ok(SupervisedServer {
+        process: process_rx,
+        has_issued_stop_command,
+        supervisor,
+    })
+}
+
+async fn supervise<F, Fut>(
+    mut process: LaunchedProcess,
+    start: F,
+    max_restarts: usize,
+    backoff: Duration,
+    has_issued_stop_command: Arc<AtomicBool>,
+    process_tx: watch::Sender<LaunchedProcess>,
+) -> usize
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<LaunchedProcess, ServerError>>,
+{
+    let mut total_restarts = 0;
+    let mut restarts = 0;
+    loop {
+        let started = Instant::now();
+        let status = match wait_for_exit(&process).await {
+            Ok(n) => n,
+            Err(error) => {
+                err!("Couldn't check if server is running, no longer supervising it: {error}");
+                return total_restarts;
+            }
+        };
+        if has_issued_stop_command.load(Ordering::SeqCst) || status.success() {
+            info!("Server stopped");
+            return total_restarts;
+        }
+
+        if started.elapsed() >= STABLE_TIME {
+            restarts = 0;
+        }
+        if restarts >= max_restarts {
+            err!("Server crashed ({status}), not restarting (gave up after {max_restarts} tries)");
+            return total_restarts;
+        }
+        let delay = backoff
+            .saturating_mul(1 << restarts.min(8))
+            .min(MAX_BACKOFF);
+        restarts += 1;
+        err!("Server crashed ({status}), restarting in {delay:?} ({restarts}/{max_restarts})");
+        tokio::time::sleep(delay).await;
+        if has_issued_stop_command.load(Ordering::SeqCst) {
+            return total_restarts;
+        }
+
+        process = match start().await {
+            Ok(n) => n,
+            Err(error) => {
+                err!("Couldn't restart server: {error}");
+                return total_restarts;
+            }
+        };
+        total_restarts += 1;
+        // No one watching is fine
+        _ = process_tx.send(process.clone());
+    }
+}
+
+/// Waits for the process to exit, only locking it
+/// briefly so log reading isn't blocked.
+async fn wait_for_exit(process: &LaunchedProcess) -> Result<ExitStatus, IoError> {
+    loop {
+        if let Some(status) = process
+            .child
+            .lock()
+            .await
+            .try_wait()
+            .map_err(IoError::Process)?
+        {
+            return Ok(status);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use ql_core::Instance;
+    use tokio::{process::Command, sync::Mutex};
+
+    use super::*;
+
+    fn mock_server(script: &str) -> LaunchedProcess {
+        let child = Command::new("sh").args(["-c", script]).spawn().unwrap();
+        LaunchedProcess {
+            child: Arc::new(Mutex::new(child)),
+            instance: Instance::server("Test"),
+            is_classic_server: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn restarts_after_crash() {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (tx, mut rx) = watch::channel(mock_server("exit 1"));
+
+        // Crashes once more, then exits normally
+        let start = {
+            let starts = starts.clone();
+            move || {
+                let n = starts.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(mock_server(if n == 0 { "exit 1" } else { "exit 0" })) }
+            }
+        };
+        let first = rx.borrow_and_update().clone();
+        let restarts = supervise(first.clone(), start, 3, Duration::ZERO, stop.clone(), tx).await;
+        assert_eq!(restarts, 2);
+        assert_eq!(starts.load(Ordering::SeqCst), 2);
+        // Watchers get the restarted process
+        assert!(!Arc::ptr_eq(&rx.borrow().child, &first.child));
+
+        // Gives up after `max_restarts`
+        let (tx, _rx) = watch::channel(mock_server("exit 1"));
+        let start = || async { Ok(mock_server("exit 3")) };
+        let restarts = supervise(mock_server("exit 1"), start, 2, Duration::ZERO, stop, tx).await;
+        assert_eq!(restarts, 2);
+    }
+
+    #[tokio::test]
+    async fn intentional_stop_isnt_a_crash() {
+        let stop = Arc::new(AtomicBool::new(true));
+        let (tx, _rx) = watch::channel(mock_server("exit 1"));
+        let start = || async { panic!("server shouldn't be restarted") };
+        let restarts = supervise(mock_server("exit 1"), start, 3, Duration::ZERO, stop, tx).await;
+        assert_eq!(restarts, 0);
+    }
+}
@@ -0,0 +1,229 @@
+//! Moving a server to another version of the game.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+};
+
+use ql_core::{
+    DownloadProgress, Instance, IntoIoError, LAUNCHER_DIR, Loader, err,
+    file_utils::{self, exists},
+    info,
+    json::{InstanceConfigJson, Manifest, VersionDetails, instance_config::VersionInfo},
+    pt,
+};
+use ql_mod_manager::loaders::{LoaderInstallResult, install_specified_loader};
+
+use crate::{
+    ServerError, ServerWarning,
+    create::{download_server_jar, progress_json, progress_manifest, progress_server_jar},
+};
+
+/// Moves a server to another version of the game
+/// (`target`, eg: from `1.20.1` to `1.21`).
+///
+/// Only the server jar and version details are replaced,
+/// so worlds, plugins, mods and `server.properties` are kept.
+/// The old jar is backed up as `server-<OLD_VERSION>.jar.bak`
+/// in case the new version doesn't work out.
+///
+/// The installed loader (Fabric, Forge, Paper, ...) is reinstalled
+/// for `target`, otherwise it would keep launching the old version
+/// (like Paper's `paper_server.jar`). If the loader has no build
+/// for `target` yet, a [`ServerWarning::LoaderNotUpgraded`] is returned.
+///
+/// # Errors
+/// - The server's `config.json` or `details.json` couldn't be read
+/// - The manifest or version JSON couldn't be downloaded,
+///   or `target` isn't in the manifest
+/// - `target` has no server jar
+/// - The new jar couldn't be downloaded (the old one is put back)
+/// - The updated `config.json` or `details.json` couldn't be saved
+pub async fn upgrade_server_version(
+    name: &str,
+    target: &str,
+    sender: Option<&Sender<DownloadProgress>>,
+) -> Result<Vec<ServerWarning>, ServerError> {
+    info!("Upgrading server {name} to {target}");
+    progress_manifest(sender);
+    let manifest = Manifest::download().await?;
+    let version_manifest = manifest
+        .find_name(target)
+        .ok_or_else(|| ServerError::VersionNotFoundInManifest(target.to_owned()))?;
+    progress_json(sender);
+    let version_json: VersionDetails =
+        file_utils::download_file_to_json(&version_manifest.url, false).await?;
+
+    let server_dir = LAUNCHER_DIR.join("servers").join(name);
+    upgrade_in(
+        &server_dir,
+        version_json,
+        |url, dir| async move {
+            progress_server_jar(sender);
+            download_server_jar(target, &url, &dir, sender).await
+        },
+        |loader| upgrade_loader(name, loader),
+    )
+    .await
+}
+
+/// Reinstalls `loader` for the server's (new) version,
+/// returning `false` if it has no build for it
+/// or couldn't be installed.
+async fn upgrade_loader(name: &str, loader: Loader) -> bool {
+    pt!("Upgrading {loader}");
+    match install_specified_loader(Instance::server(name), loader, None, None).await {
+        Ok(LoaderInstallResult::Ok) => true,
+        Ok(_) => false,
+        Err(error) => {
+            err!("Couldn't upgrade {loader}: {error}");
+            false
+        }
+    }
+}
+
+/// `download_jar(url, server_dir)` puts the new jar in
+/// `server_dir/server.jar`, returning whether it's a classic server.
+///
+/// `upgrade_loader(loader)` is run for modded servers once
+/// the new version is saved, returning whether it was upgraded.
+async fn upgrade_in<F, Fut, L, LFut>(
+    server_dir: &Path,
+    version_json: VersionDetails,
+    download_jar: F,
+    upgrade_loader: L,
+) -> Result<Vec<ServerWarning>, ServerError>
+where
+    F: FnOnce(String, PathBuf) -> Fut,
+    Fut: Future<Output = Result<bool, ServerError>>,
+    L: FnOnce(Loader) -> LFut,
+    LFut: Future<Output = bool>,
+{
+    let mut config = InstanceConfigJson::read_from_dir(server_dir).await?;
+    let old_version = VersionDetails::load_from_path(server_dir).await?;
+    let Some(server) = &version_json.downloads.server else {
+        return Err(ServerError::NoServerDownload);
+    };
+
+    let jar_path = server_dir.join("server.jar");
+    let backup_path = server_dir.join(format!("server-{}.jar.bak", old_version.get_id()));
+    let backed_up = exists(&jar_path).await;
+    if backed_up {
+        pt!("Backing up old server jar");
+        tokio::fs::rename(&jar_path, &backup_path)
+            .await
+            .path(&jar_path)?;
+    }
+
+    let is_classic_server = match download_jar(server.url.clone(), server_dir.to_owned()).await {
+        Ok(n) => n,
+        Err(error) => {
+            if backed_up {
+                tokio::fs::rename(&backup_path, &jar_path)
+                    .await
+                    .path(&backup_path)?;
+            }
+            return Err(error);
+        }
+    };
+
+    version_json.save_to_dir(server_dir).await?;
+    config.is_classic_server = Some(is_classic_server);
+    config.set_version_info(VersionInfo::new(version_json.get_id()));
+    config.save_to_dir(server_dir).await?;
+
+    let mut warnings = Vec::new();
+    if !config.mod_type.is_vanilla() && !upgrade_loader(config.mod_type).await {
+        warnings.push(ServerWarning::LoaderNotUpgraded(config.mod_type));
+    }
+    for warning in &warnings {
+        pt!("Warning: {warning}");
+    }
+    pt!("Finished");
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use ql_core::{InstanceKind, test_utils::version_json};
+
+    use super::*;
+
+    fn details(id: &str) -> String {
+        let mut json = version_json(id, "2024-01-01T00:00:00+00:00");
+        json["downloads"]["server"] = serde_json::json!({
+            "sha1": "", "size": 0, "url": format!("https://example.com/{id}/server.jar")
+        });
+        json.to_string()
+    }
+
+    /// "Downloads" the jar by writing its URL into it
+    async fn fake_download(url: String, dir: PathBuf) -> Result<bool, ServerError> {
+        tokio::fs::write(dir.join("server.jar"), url).await.unwrap();
+        Ok(false)
+    }
+
+    #[tokio::test]
+    async fn upgrades_vanilla_server() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        tokio::fs::create_dir_all(dir.join("world")).await.unwrap();
+
+        let files = [
+            ("details.json", details("1.20.1")),
+            ("server.jar", "old jar".to_owned()),
+            ("server.properties", "motd=Kept\n".to_owned()),
+            ("world/level.dat", "world".to_owned()),
+        ];
+        for (path, contents) in files {
+            tokio::fs::write(dir.join(path), contents).await.unwrap();
+        }
+        let mut config =
+            InstanceConfigJson::new(InstanceKind::Server, false, VersionInfo::new("1.20.1"));
+        config.save_to_dir(dir).await.unwrap();
+
+        let target: VersionDetails = serde_json::from_str(&details("1.21")).unwrap();
+        let warnings = upgrade_in(dir, target.clone(), fake_download, |_| async {
+            unreachable!("vanilla servers have no loader")
+        })
+        .await
+        .unwrap();
+        assert!(warnings.is_empty());
+
+        let read = |path: &str| std::fs::read_to_string(dir.join(path)).unwrap();
+        assert_eq!(read("server.jar"), "https://example.com/1.21/server.jar");
+        assert_eq!(read("server-1.20.1.jar.bak"), "old jar");
+        assert_eq!(read("server.properties"), "motd=Kept\n");
+        assert_eq!(read("world/level.dat"), "world");
+        let new_details = VersionDetails::load_from_path(dir).await.unwrap();
+        assert_eq!(new_details.get_id(), "1.21");
+
+        // Failed downloads put the old jar back
+        let failed = upgrade_in(
+            dir,
+            target.clone(),
+            |_, _| async { Err(ServerError::NoServerDownload) },
+            |_| async { true },
+        )
+        .await;
+        assert!(failed.is_err());
+        assert_eq!(read("server.jar"), "https://example.com/1.21/server.jar");
+
+        // Only warn about loaders without a build for the new version
+        config.mod_type = Loader::Paper;
+        config.save_to_dir(dir).await.unwrap();
+        let has_build = upgrade_in(dir, target.clone(), fake_download, |loader| async move {
+            assert_eq!(loader, Loader::Paper);
+            true
+        })
+        .await
+        .unwrap();
+        assert!(has_build.is_empty());
+        assert_eq!(
+            upgrade_in(dir, target, fake_download, |_| async { false })
+                .await
+                .unwrap(),
+            [ServerWarning::LoaderNotUpgraded(Loader::Paper)]
+        );
+    }
+}
@@ -9,27 +9,57 @@ use crate::{
     config::{ConfigAccount, LauncherConfig},
 };
 
+/// Picks the account to launch with, and refreshes it.
+///
+/// Like the GUI (see [`auth::pick_launch_account`]), the instance's
+/// default account (`instance_default`, a UUID) is used if it's
+/// logged in. Otherwise it's `username` if `use_account` is set,
+/// or offline mode (`None`).
 pub async fn refresh_account(
-    username: &String,
+    username: &str,
     use_account: bool,
     show_progress: bool,
     override_account_type: Option<&str>,
+    instance_default: Option<&str>,
 ) -> Result<Option<auth::AccountData>, Box<dyn std::error::Error>> {
-    if !use_account {
+    let config = match LauncherConfig::load_s() {
+        Ok(config) => Some(config),
+        Err(err) if use_account => return Err(err.into()),
+        // Offline, a missing config only means no default account
+        Err(_) => None,
+    };
+
+    let selected = if use_account {
+        let Some((key, account)) = config
+            .as_ref()
+            .and_then(|n| get_account(n, username, override_account_type))
+        else {
+            err!("No logged-in account called {username:?} was found!");
+            exit(1);
+        };
+        Some(saved_account(key, account)?)
+    } else {
+        None
+    };
+    let saved: Vec<auth::AccountData> = if instance_default.is_some() {
+        config
+            .iter()
+            .flat_map(|n| n.accounts.iter().flatten())
+            .filter_map(|(key, account)| saved_account(key, account).ok())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let Some(account) = auth::pick_launch_account(&saved, instance_default, selected.as_ref())
+    else {
         if show_progress {
             tokio::task::spawn_blocking(|| {
                 show_notification("Launching game", "Enjoy!");
             });
         }
         return Ok(None);
-    }
-
-    let config = LauncherConfig::load_s()?;
-    let Some(account) = get_account(&config, username, override_account_type) else {
-        err!("No logged-in account called {username:?} was found!");
-        exit(1);
     };
-    let refresh_name = account.get_keyring_identifier(username);
 
     if show_progress {
         tokio::task::spawn_blocking(|| {
@@ -37,27 +67,76 @@ pub async fn refresh_account(
         });
     }
 
-    let refresh_token =
-        auth::read_refresh_token(refresh_name, account.account_type.unwrap_or_default())?;
-
     // Hook: Account types
-    let account = if let Some(account_type @ (AccountType::ElyBy | AccountType::LittleSkin)) =
-        account.account_type
-    {
-        auth::yggdrasil::login_refresh(refresh_name.to_owned(), refresh_token, account_type).await?
-    } else {
-        let refresh_token = auth::read_refresh_token(username, AccountType::Microsoft)?;
-        auth::ms::login_refresh(username.clone(), refresh_token, None).await?
+    let account = match account.account_type {
+        account_type @ (AccountType::ElyBy | AccountType::LittleSkin) => {
+            match auth::yggdrasil::login_refresh(
+                account.username.clone(),
+                account.refresh_token.clone(),
+                account_type,
+            )
+            .await?
+            {
+                auth::yggdrasil::Account::Account(data) => data,
+                auth::yggdrasil::Account::NeedsOTP => {
+                    err!(
+                        "{account_type} account {} needs a 2FA code to log in again.\nOpen the launcher GUI and log in with the code.",
+                        account.nice_username
+                    );
+                    exit(1);
+                }
+            }
+        }
+        AccountType::Microsoft => {
+            auth::ms::login_refresh(
+                account.username.clone(),
+                account.refresh_token.clone(),
+                None,
+            )
+            .await?
+        }
     };
 
     Ok(Some(account))
 }
 
+/// A logged-in account from the config (under `key`),
+/// with its refresh token read from the keyring.
+fn saved_account(
+    key: &str,
+    account: &ConfigAccount,
+) -> Result<auth::AccountData, auth::KeyringError> {
+    let account_type = if key.ends_with(" (elyby)") {
+        AccountType::ElyBy
+    } else if key.ends_with(" (littleskin)") {
+        AccountType::LittleSkin
+    } else {
+        account.account_type.unwrap_or_default()
+    };
+    let keyring_username = account.get_keyring_identifier(key);
+    let refresh_token = auth::read_refresh_token(keyring_username, account_type)?;
+
+    Ok(auth::AccountData {
+        access_token: None,
+        uuid: account.uuid.clone(),
+        refresh_token,
+        needs_refresh: true,
+        account_type,
+
+        username: keyring_username.to_owned(),
+        nice_username: account
+            .username_nice
+            .clone()
+            .unwrap_or_else(|| key.to_owned()),
+        alias: account.alias.clone(),
+    })
+}
+
 fn get_account<'a>(
     config: &'a LauncherConfig,
     username: &str,
     override_account_type: Option<&str>,
-) -> Option<&'a ConfigAccount> {
+) -> Option<(&'a String, &'a ConfigAccount)> {
     let Some(accounts) = &config.accounts else {
         return None;
     };
@@ -78,18 +157,15 @@ fn get_account<'a>(
         };
 
         let key_username = acc_type.add_suffix_to_name(username);
-        return accounts.get(&key_username);
+        return accounts.get_key_value(&key_username);
     }
 
-    accounts.get(username).or_else(|| {
-        accounts
-            .iter()
-            .find(|a| {
-                a.1.keyring_identifier
-                    .as_ref()
-                    .is_some_and(|i| i == username)
-                    || a.1.username_nice.as_ref().is_some_and(|u| u == username)
-            })
-            .map(|n| n.1)
+    accounts.get_key_value(username).or_else(|| {
+        accounts.iter().find(|a| {
+            a.1.keyring_identifier
+                .as_ref()
+                .is_some_and(|i| i == username)
+                || a.1.username_nice.as_ref().is_some_and(|u| u == username)
+        })
     })
 }
@@ -1,15 +1,17 @@
 use owo_colors::{OwoColorize, Style};
 use ql_core::{
-    Instance, InstanceKind, IntoStringError, ListEntry, Loader, OptifineUniqueVersion, eeprintln,
-    err, info,
+    Instance, InstanceKind, InstanceSummary, IntoStringError, ListEntry, Loader,
+    OptifineUniqueVersion, RunningProcess, eeprintln, err, info,
     json::{InstanceConfigJson, VersionDetails},
 };
+use ql_instances::LaunchOptions;
 use ql_mod_manager::loaders::LoaderInstallResult;
+use serde::Serialize;
 use std::{path::PathBuf, process::exit, sync::Arc};
 
 use crate::{
     cli::{QLoader, account::refresh_account, helpers::render_row},
-    state::get_entries,
+    config::LauncherConfig,
 };
 
 use super::PrintCmd;
@@ -63,43 +65,35 @@ pub fn list_instances(
         cmds.push(PrintCmd::Name);
     }
 
-    let runtime = tokio::runtime::Runtime::new()?;
-
-    let (instances, _) = tokio::runtime::Runtime::new()?.block_on(get_entries(kind))?;
+    let instances = tokio::runtime::Runtime::new()?
+        .block_on(ql_core::list_instances())?
+        .into_iter()
+        .filter(|n| n.is_server == kind.is_server());
 
     let mut cmds_name = String::new();
     let mut cmds_version = String::new();
     let mut cmds_loader = String::new();
 
     for instance in instances {
-        let instance_dir = kind.get_root_directory().join(&instance);
         for cmd in &cmds {
             match cmd {
                 PrintCmd::Name => {
-                    _ = writeln!(cmds_name, "{}", instance.bold().underline());
+                    _ = writeln!(cmds_name, "{}", instance.name.bold().underline());
                 }
                 PrintCmd::Version => {
-                    match runtime.block_on(VersionDetails::load_from_path(&instance_dir)) {
-                        Ok(json) => {
-                            cmds_version.push_str(&json.id);
-                        }
-                        Err(err) => {
-                            err!("{err}");
-                        }
+                    if let Some(version) = &instance.version {
+                        cmds_version.push_str(version);
+                    } else {
+                        err!("Couldn't read the version of {}", instance.name);
                     }
                     cmds_version.push('\n');
                 }
                 PrintCmd::Loader => {
-                    let config_json =
-                        match runtime.block_on(InstanceConfigJson::read_from_dir(&instance_dir)) {
-                            Ok(json) => json,
-                            Err(err) => {
-                                err!("{err}");
-                                cmds_loader.push('\n');
-                                continue;
-                            }
-                        };
-                    let m = config_json.mod_type;
+                    let Some(m) = instance.loader else {
+                        err!("Couldn't read the loader of {}", instance.name);
+                        cmds_loader.push('\n');
+                        continue;
+                    };
 
                     match m {
                         Loader::Vanilla => writeln!(cmds_loader, "{}", m.bright_black()),
@@ -137,6 +131,61 @@ pub fn list_instances(
     Ok(())
 }
 
+/// The output of `list --json`.
+///
+/// This is meant for scripts, so keep it stable:
+/// fields may be added, but never renamed or removed.
+#[derive(Serialize, Debug)]
+pub struct ListJson {
+    pub instances: Vec<ListJsonEntry>,
+    pub servers: Vec<ListJsonEntry>,
+}
+
+/// An instance/server in [`ListJson`].
+#[derive(Serialize, Debug)]
+pub struct ListJsonEntry {
+    pub name: String,
+    /// Minecraft version, or `null` if it couldn't be read
+    /// (broken instance)
+    pub version: Option<String>,
+    /// `"Vanilla"`, `"Fabric"`, `"Forge"`, ...
+    /// or `null` if it couldn't be read (broken instance)
+    pub loader: Option<Loader>,
+    /// Whether the game is running (started by any launcher
+    /// session, see [`RunningProcess::find`]).
+    ///
+    /// Always `false` for servers, which aren't tracked.
+    pub running: bool,
+}
+
+impl ListJsonEntry {
+    async fn new(summary: InstanceSummary) -> Self {
+        let running = RunningProcess::find(&summary.instance()).await.is_some();
+        Self {
+            name: summary.name,
+            version: summary.version,
+            loader: summary.loader,
+            running,
+        }
+    }
+}
+
+pub async fn list_instances_json() -> Result<(), Box<dyn std::error::Error>> {
+    let mut list = ListJson {
+        instances: Vec::new(),
+        servers: Vec::new(),
+    };
+    for summary in ql_core::list_instances().await? {
+        if summary.is_server {
+            list.servers.push(ListJsonEntry::new(summary).await);
+        } else {
+            list.instances.push(ListJsonEntry::new(summary).await);
+        }
+    }
+    println!("{}", serde_json::to_string_pretty(&list)?);
+    Ok(())
+}
+
 pub async fn create_instance(
     instance_name: String,
     version: String,
@@ -147,10 +196,20 @@ pub async fn create_instance(
 
     match kind {
         InstanceKind::Client => {
-            ql_instances::create_instance(instance_name, entry, None, !skip_assets).await?;
+            ql_instances::create_instance(
+                instance_name,
+                entry,
+                None,
+                ql_instances::CreateOptions {
+                    download_assets: !skip_assets,
+                    install_java: true,
+                },
+                None,
+            )
+            .await?;
         }
         InstanceKind::Server => {
-            ql_servers::create_server(instance_name, entry, None).await?;
+            ql_servers::create_server(instance_name, entry, None, false).await?;
         }
     }
 
@@ -214,9 +273,21 @@ pub async fn launch_instance(
     kind: InstanceKind,
     show_progress: bool,
     account_type: Option<&str>,
+    options: LaunchOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let account = if matches!(kind, InstanceKind::Client) {
-        refresh_account(&username, use_account, show_progress, account_type).await?
+        let default_account = InstanceConfigJson::read(&Instance::client(instance_name))
+            .await
+            .ok()
+            .and_then(|n| n.default_account);
+        refresh_account(
+            &username,
+            use_account,
+            show_progress,
+            account_type,
+            default_account.as_deref(),
+        )
+        .await?
     } else {
         None
     };
@@ -225,13 +296,20 @@ pub async fn launch_instance(
 
     let child = match kind {
         InstanceKind::Client => {
+            // Same global defaults as the GUI, if the config is readable
+            let config = LauncherConfig::load_s().ok();
+            let global_settings = config.as_ref().and_then(|c| c.global_settings.clone());
+            let extra_java_args = config.and_then(|c| c.extra_java_args).unwrap_or_default();
+
+            ql_mod_manager::store::update_before_launch(&Instance::client(&instance_name)).await;
             ql_instances::launch(
                 instance_name,
                 username,
                 None,
                 account.clone(),
-                None, // No global defaults in CLI mode
-                Vec::new(),
+                global_settings,
+                extra_java_args,
+                options,
             )
             .await?
         }
@@ -326,8 +404,8 @@ pub async fn loader(cmd: QLoader, kind: InstanceKind) -> Result<(), Box<dyn std:
                 LoaderInstallResult::NeedsOptifine => {
                     install_optifine(more, instance).await?;
                 }
-                LoaderInstallResult::Unsupported => {
-                    err!("This loader is unsupported!");
+                LoaderInstallResult::Unsupported(reason) => {
+                    err!("This loader is unsupported: {reason}");
                     exit(1);
                 }
             }
@@ -372,3 +450,47 @@ async fn install_optifine(
     .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_json_shape() {
+        let list = ListJson {
+            instances: vec![
+                ListJsonEntry {
+                    name: "Survival".to_owned(),
+                    version: Some("1.21.4".to_owned()),
+                    loader: Some(Loader::Fabric),
+                    running: true,
+                },
+                ListJsonEntry {
+                    name: "Broken".to_owned(),
+                    version: None,
+                    loader: None,
+                    running: false,
+                },
+            ],
+            servers: vec![ListJsonEntry {
+                name: "SMP".to_owned(),
+                version: Some("1.20.1".to_owned()),
+                loader: Some(Loader::Paper),
+                running: false,
+            }],
+        };
+
+        assert_eq!(
+            serde_json::to_value(&list).unwrap(),
+            serde_json::json!({
+                "instances": [
+                    { "name": "Survival", "version": "1.21.4", "loader": "Fabric", "running": true },
+                    { "name": "Broken", "version": null, "loader": null, "running": false },
+                ],
+                "servers": [
+                    { "name": "SMP", "version": "1.20.1", "loader": "Paper", "running": false },
+                ],
+            })
+        );
+    }
+}
@@ -6,6 +6,7 @@ use std::{
 use clap::{Parser, Subcommand};
 use owo_colors::{OwoColorize, Style};
 use ql_core::{InstanceKind, LAUNCHER_VERSION_NAME, REDACT_SENSITIVE_INFO, WEBSITE, err};
+use ql_instances::{LaunchOptions, QuickPlay};
 
 use crate::{
     cli::helpers::render_row,
@@ -42,6 +43,9 @@ struct Cli {
     server: bool,
     #[arg(long)]
     dir: Option<PathBuf>,
+    #[arg(long)]
+    #[arg(help = "Opens the launcher even if another launcher window is running")]
+    force: bool,
 }
 
 #[derive(Subcommand)]
@@ -59,7 +63,11 @@ enum QSubCommand {
     Launch {
         instance_name: Arc<str>,
         #[arg(help = "Username to play with")]
-        username: String,
+        #[arg(required_unless_present = "account")]
+        username: Option<String>,
+        #[arg(long, conflicts_with_all = ["username", "use_account"])]
+        #[arg(help = "Logged in account to play with (same as `<USERNAME> --use-account`)")]
+        account: Option<String>,
 
         // Used by shortcuts, do not break
         #[arg(short, long, short_alias = 'a')]
@@ -72,10 +80,25 @@ enum QSubCommand {
         #[arg(long)]
         #[arg(help = "microsoft/elyby/littleskin")]
         account_type: Option<String>,
+        #[arg(long)]
+        #[arg(help = "Launches once with all mods disabled, to check if they cause a crash")]
+        safe_mode: bool,
+        #[arg(long, conflicts_with = "join")]
+        #[arg(help = "Opens this world (folder name in saves) right away, 1.20+ only")]
+        world: Option<String>,
+        #[arg(long)]
+        #[arg(help = "Joins this server address right away, 1.20+ only")]
+        join: Option<String>,
     },
     #[command(aliases = ["list", "list-instances"], short_flag = 'l')]
+    #[command(long_flag = "list-instances")]
     #[command(about = "Lists installed instances")]
-    ListInstalled { properties: Option<Vec<String>> },
+    ListInstalled {
+        properties: Option<Vec<String>>,
+        #[arg(long)]
+        #[arg(help = "Prints all instances and servers as JSON (for scripts)")]
+        json: bool,
+    },
     #[command(about = "Deletes the specified instance")]
     Delete {
         instance_name: String,
@@ -201,7 +224,12 @@ fn get_right_text() -> String {
     message
 }
 
-pub fn start_cli(is_dir_err: bool, launcher_dir: &mut Option<PathBuf>) {
+/// Handles the command line arguments, exiting if a
+/// subcommand was run.
+///
+/// Returns whether `--force` was passed
+/// (skip the single-instance check).
+pub fn start_cli(is_dir_err: bool, launcher_dir: &mut Option<PathBuf>) -> bool {
     let cli = Cli::parse();
     *REDACT_SENSITIVE_INFO.lock().unwrap() = !cli.no_redact_info;
     *EXPERIMENTAL_SERVERS.write().unwrap() = cli.enable_server_manager;
@@ -241,10 +269,18 @@ pub fn start_cli(is_dir_err: bool, launcher_dir: &mut Option<PathBuf>) {
             QSubCommand::Launch {
                 instance_name,
                 username,
+                account,
                 use_account,
                 show_progress,
                 account_type,
+                safe_mode,
+                world,
+                join,
             } => {
+                let (username, use_account) = launch_user(username, account, use_account);
+                let quick_play = world
+                    .map(QuickPlay::Singleplayer)
+                    .or(join.map(QuickPlay::Multiplayer));
                 let res = runtime.block_on(command::launch_instance(
                     &instance_name,
                     username,
@@ -252,6 +288,10 @@ pub fn start_cli(is_dir_err: bool, launcher_dir: &mut Option<PathBuf>) {
                     kind,
                     show_progress,
                     account_type.as_deref(),
+                    LaunchOptions {
+                        safe_mode,
+                        quick_play,
+                    },
                 ));
                 std::process::exit(if let Err(err) = res {
                     err!("{err}");
@@ -276,7 +316,10 @@ pub fn start_cli(is_dir_err: bool, launcher_dir: &mut Option<PathBuf>) {
                 instance_name,
                 force,
             } => quit(command::delete_instance(&instance_name, force, kind)),
-            QSubCommand::ListInstalled { properties } => {
+            QSubCommand::ListInstalled { json: true, .. } => {
+                quit(runtime.block_on(command::list_instances_json()));
+            }
+            QSubCommand::ListInstalled { properties, .. } => {
                 quit(command::list_instances(properties.as_deref(), kind));
             }
             QSubCommand::Loader(cmd) => {
@@ -286,6 +329,21 @@ pub fn start_cli(is_dir_err: bool, launcher_dir: &mut Option<PathBuf>) {
     } else {
         print_intro();
     }
+    cli.force
+}
+
+/// Returns the username to launch with, and whether
+/// it's a logged in account (`--account`) or offline.
+fn launch_user(
+    username: Option<String>,
+    account: Option<String>,
+    use_account: bool,
+) -> (String, bool) {
+    match account {
+        Some(account) => (account, true),
+        // clap ensures either one is present
+        None => (username.unwrap_or_default(), use_account),
+    }
 }
 
 fn show_notification(title: &str, body: &str) {
@@ -317,3 +375,78 @@ fn quit(res: Result<(), Box<dyn std::error::Error + 'static>>) {
         0
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_launch(args: &[&str]) -> (Arc<str>, String, bool) {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let Some(QSubCommand::Launch {
+            instance_name,
+            username,
+            account,
+            use_account,
+            ..
+        }) = cli.command
+        else {
+            panic!("{args:?} didn't parse to a launch command");
+        };
+        let (username, use_account) = launch_user(username, account, use_account);
+        (instance_name, username, use_account)
+    }
+
+    #[test]
+    fn launch_with_account() {
+        let (instance, username, use_account) = parse_launch(&[
+            "quantum_launcher",
+            "launch",
+            "My Instance",
+            "--account",
+            "Steve",
+        ]);
+        assert_eq!(&*instance, "My Instance");
+        assert_eq!(username, "Steve");
+        assert!(use_account);
+    }
+
+    #[test]
+    fn launch_shortcut_args() {
+        // Used by desktop shortcuts, must keep working
+        let (instance, username, use_account) =
+            parse_launch(&["quantum_launcher", "launch", "1.21", "Alex", "-a"]);
+        assert_eq!(&*instance, "1.21");
+        assert_eq!(username, "Alex");
+        assert!(use_account);
+
+        let (_, username, use_account) =
+            parse_launch(&["quantum_launcher", "launch", "1.21", "Alex"]);
+        assert_eq!(username, "Alex");
+        assert!(!use_account);
+    }
+
+    #[test]
+    fn launch_needs_user() {
+        assert!(Cli::try_parse_from(["quantum_launcher", "launch", "1.21"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "quantum_launcher",
+                "launch",
+                "1.21",
+                "Alex",
+                "--account",
+                "Steve"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn list_instances_flag() {
+        let cli = Cli::try_parse_from(["quantum_launcher", "--list-instances"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(QSubCommand::ListInstalled { .. })
+        ));
+    }
+}
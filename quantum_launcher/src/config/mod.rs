@@ -6,7 +6,10 @@ use ql_core::{
     InstanceKind, IntoIoError, IntoJsonError, JsonFileError, LAUNCHER_DIR, LAUNCHER_VERSION_NAME,
     ListEntryKind, err, json::GlobalSettings,
 };
-use ql_instances::auth::{AccountData, AccountType};
+use ql_instances::{
+    NewInstanceTemplate,
+    auth::{AccountData, AccountType},
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::{
@@ -18,6 +21,9 @@ pub mod discord_rpc;
 pub mod sidebar;
 
 pub const SIDEBAR_WIDTH: f32 = 0.33;
+/// What configs without a `version` field
+/// (added in v0.3) are treated as.
+const PRE_V0_3_VERSION: &str = "0.2.0";
 const OPACITY: f32 = 0.9;
 
 /// Global launcher configuration stored in
@@ -106,6 +112,13 @@ pub struct LauncherConfig {
     pub sidebar: Option<SidebarConfig>,
     // Since: TBD
     pub discord_rpc: Option<RpcConfig>,
+    /// Whether to receive beta (pre-release) launcher updates.
+    // Since: TBD
+    pub update_channel: Option<UpdateChannel>,
+    /// Settings (memory, Java args, ...) that
+    /// new instances start with, instead of the defaults.
+    // Since: TBD
+    pub new_instance_template: Option<NewInstanceTemplate>,
     /// Time of last auto-update check result, in seconds since the Unix epoch.
     // Since: TBD
     #[cfg(feature = "auto_update")]
@@ -136,6 +149,8 @@ impl Default for LauncherConfig {
             persistent: None,
             sidebar: None,
             discord_rpc: None,
+            update_channel: None,
+            new_instance_template: None,
             _extra: HashMap::new(),
             #[cfg(feature = "auto_update")]
             last_update_check: None,
@@ -228,6 +243,10 @@ impl LauncherConfig {
     }
 
     fn fix(&mut self) {
+        if self.version.is_none() {
+            self.version = Some(PRE_V0_3_VERSION.to_owned());
+        }
+
         if let (Some(accounts), Some(selected)) = (&self.accounts, &self.account_selected) {
             if !accounts.contains_key(selected) {
                 self.account_selected = None;
@@ -307,6 +326,10 @@ impl LauncherConfig {
         self.sidebar.get_or_insert_default()
     }
 
+    pub fn c_new_instance_template(&mut self) -> &mut NewInstanceTemplate {
+        self.new_instance_template.get_or_insert_default()
+    }
+
     pub fn c_idle_fps(&self) -> u64 {
         const IDLE_FPS: u64 = 6;
 
@@ -328,6 +351,11 @@ impl LauncherConfig {
         self.discord_rpc.as_ref().is_some_and(|n| n.enable)
     }
 
+    #[cfg(feature = "auto_update")]
+    pub fn c_update_channel(&self) -> UpdateChannel {
+        self.update_channel.unwrap_or_default()
+    }
+
     #[cfg(feature = "auto_update")]
     pub fn should_update_check(&self) -> bool {
         const INTERVAL_SECS: u64 = 60 * 60;
@@ -389,6 +417,11 @@ pub struct ConfigAccount {
     /// would be an email.
     pub username_nice: Option<String>,
 
+    /// A name the user gave this account (eg: "Main", "Alt"),
+    /// shown instead of the username. Not used for logging in.
+    // Since: TBD
+    pub alias: Option<String>,
+
     #[serde(flatten)]
     _extra: HashMap<String, serde_json::Value>,
 }
@@ -401,6 +434,7 @@ impl ConfigAccount {
             account_type: Some(data.account_type),
             keyring_identifier: Some(data.username.clone()),
             username_nice: Some(data.nice_username.clone()),
+            alias: data.alias.clone(),
             _extra: HashMap::new(),
         }
     }
@@ -500,6 +534,18 @@ impl AfterLaunchBehavior {
     }
 }
 
+/// Which launcher releases to auto-update to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UpdateChannel {
+    #[serde(rename = "stable")]
+    #[default]
+    #[serde(other)]
+    Stable,
+    /// Also includes pre-releases (betas).
+    #[serde(rename = "beta")]
+    Beta,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
 pub enum UiWindowDecorations {
     #[serde(rename = "left")]
@@ -564,3 +610,24 @@ impl PersistentSettings {
             .unwrap_or_else(ListEntryKind::default_selected)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pre_v0_3_config_gets_version() {
+        let mut config: LauncherConfig =
+            serde_json::from_str(r#"{ "username": "steve", "java_installs": [] }"#).unwrap();
+        config.fix();
+        assert_eq!(config.version.as_deref(), Some(PRE_V0_3_VERSION));
+
+        // Running it again changes nothing
+        config.fix();
+        assert_eq!(config.version.as_deref(), Some(PRE_V0_3_VERSION));
+
+        let mut config = LauncherConfig::default();
+        config.fix();
+        assert_eq!(config.version.as_deref(), Some(LAUNCHER_VERSION_NAME));
+    }
+}
@@ -1,31 +1,43 @@
 use std::{
     ffi::OsStr,
-    path::Path,
+    path::{Path, PathBuf},
     process::Command,
     sync::{Arc, mpsc::Sender},
 };
 
 use ql_core::{
-    GenericProgress, IntoIoError, IoError, JsonError, LAUNCHER_VERSION, RequestError, err,
+    GenericProgress, IntoIoError, IoError, JsonError, LAUNCHER_DIR, LAUNCHER_VERSION, RequestError,
+    err,
     file_utils::{self, exists},
     impl_3_errs_jri, pt,
 };
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 use tokio::task::JoinError;
 
+use crate::config::UpdateChannel;
+
 #[derive(Debug, Clone)]
 pub enum UpdateCheckInfo {
     UpToDate,
-    NewVersion { url: String },
+    NewVersion {
+        url: String,
+        /// SHA256 of the download, published alongside the release.
+        sha256: Option<String>,
+    },
 }
 
 /// Checks for any launcher updates to be installed.
 ///
+/// The `channel` decides whether pre-releases (betas)
+/// are considered, or only stable releases.
+///
 /// Returns `Ok(UpdateCheckInfo::UpToDate)` if the launcher is up to date.
 ///
-/// Returns `Ok(UpdateCheckInfo::NewVersion { url: String })` if there is a new version available.
-/// (url pointing to zip file containing new version executable).
+/// Returns `Ok(UpdateCheckInfo::NewVersion { url, sha256 })` if there is a new version available.
+/// (url pointing to zip file containing new version executable,
+/// and the SHA256 checksum of it, if found).
 ///
 /// # Errors
 /// - current version is ahead of latest version
@@ -37,41 +49,11 @@ pub enum UpdateCheckInfo {
 ///   with semver (even after conversion)
 /// - user is on unsupported architecture or OS
 /// - no matching download could be found for the platform
-pub async fn check() -> Result<UpdateCheckInfo, UpdateError> {
+pub async fn check(channel: UpdateChannel) -> Result<UpdateCheckInfo, UpdateError> {
     const URL: &str = "https://api.github.com/repos/Mrmayman/quantum-launcher/releases";
 
     let json: Vec<GithubRelease> = file_utils::download_file_to_json(URL, true).await?;
-    let mut json = json.into_iter();
-
-    let mut version;
-    let mut latest;
-
-    loop {
-        latest = json.next().ok_or(UpdateError::NoReleases)?;
-
-        version = latest.tag_name.clone();
-        // v0.2 -> 0.2
-        if version.starts_with('v') {
-            version = version[1..version.len()].to_owned();
-        }
-        // 0.2 -> 0.2.0
-        if version.chars().filter(|n| *n == '.').count() == 1 {
-            version.push_str(".0");
-        }
-
-        // The new update has been disabled/yanked for whatever reason
-        // so look for another one.
-        // Naming scheme: ends with "-D" followed by (optional) numbers
-        if version
-            .trim_end_matches(|c: char| c.is_numeric())
-            .ends_with("-D")
-        {
-            continue;
-        }
-        break;
-    }
-
-    let version = semver::Version::parse(&version)?;
+    let (version, latest) = find_latest_release(json, channel)?;
 
     match version.cmp(&LAUNCHER_VERSION) {
         std::cmp::Ordering::Less => Err(UpdateError::AheadOfLatestVersion),
@@ -120,57 +102,93 @@ pub async fn check() -> Result<UpdateCheckInfo, UpdateError> {
                 .find(|asset| asset.name.replace('-', "_").starts_with(&name))
                 .ok_or(UpdateError::NoMatchingDownloadFound)?;
 
+            let sha256 = find_checksum(&latest, matching_release).await;
+
             Ok(UpdateCheckInfo::NewVersion {
                 url: matching_release.browser_download_url.clone(),
+                sha256,
             })
         }
     }
 }
 
+/// The previous launcher binary is kept under this name
+/// (next to the launcher) after an update, for
+/// [`rollback_launcher_update`].
+const OLD_LAUNCHER_NAME: &str = "quantum_launcher.old";
+
+/// Exists while the launcher is starting up. If it's still
+/// there on the next start, the launcher crashed before
+/// the UI could load.
+const LAUNCH_SENTINEL: &str = "launch_in_progress";
+
 /// Installs a new version of the launcher.
-/// The launcher will be backed up, the new version
-/// will be downloaded and extracted.
+/// The new version will be downloaded and verified,
+/// then the launcher will be backed up (as
+/// `quantum_launcher.old`) and the new version extracted.
 ///
 /// The new version will be started. You must exit the current process manually.
 ///
 /// # Arguments
 /// - `url`: The url to the zip file containing the new version of the launcher.
+/// - `sha256`: The expected checksum of the download (from [`check`]).
 /// - `progress`: A channel to send progress updates to.
 ///
 /// # Errors
 /// ## New version:
 /// - Couldn't be downloaded
+/// - Has no published checksum, or doesn't match it
+///   (the current launcher is left untouched)
 /// - Couldn't be extracted (invalid zip)
 /// - Couldn't be started
 /// ## Current executable:
 /// - Couldn't be found
 /// - Has a name with invalid UNICODE
-pub async fn install(url: String, progress: Sender<GenericProgress>) -> Result<(), UpdateError> {
+pub async fn install(
+    url: String,
+    sha256: Option<String>,
+    progress: Sender<GenericProgress>,
+) -> Result<(), UpdateError> {
     _ = progress.send(GenericProgress::default());
 
     let exe_path = std::env::current_exe().map_err(UpdateError::CurrentExeError)?;
-    let exe_location = exe_path.parent().ok_or(UpdateError::ExeParentPathError)?;
 
-    let exe_name = exe_path.file_name().ok_or(UpdateError::ExeFileNameError)?;
-    let exe_name = exe_name
-        .to_str()
-        .ok_or(UpdateError::OsStrToStr(exe_name.into()))?;
+    send_progress(&progress, 1, "Downloading new launcher version");
+    let download_zip = file_utils::download_file_to_bytes(&url, false).await?;
 
-    let mut backup_idx = 1;
-    while exists(&exe_location.join(format!("backup_{backup_idx}_{exe_name}"))).await {
-        backup_idx += 1;
-    }
+    let new_path =
+        replace_launcher(&exe_path, &url, download_zip, sha256.as_deref(), &progress).await?;
+    _ = Command::new(&new_path).spawn().path(new_path)?;
+
+    Ok(())
+}
+
+/// Replaces the launcher at `exe_path` with the one in `download_zip`,
+/// returning the path of the new executable.
+///
+/// The download is verified against `sha256` (if there's one)
+/// *before* anything is touched, so a corrupted or tampered
+/// download leaves the current launcher as-is.
+async fn replace_launcher(
+    exe_path: &Path,
+    url: &str,
+    download_zip: Vec<u8>,
+    sha256: Option<&str>,
+    progress: &Sender<GenericProgress>,
+) -> Result<PathBuf, UpdateError> {
+    send_progress(progress, 2, "Verifying download");
+    verify_checksum(&download_zip, sha256)?;
+
+    let exe_location = exe_path.parent().ok_or(UpdateError::ExeParentPathError)?;
 
-    send_progress(&progress, 1, "Backing up existing launcher");
-    let backup_path = exe_location.join(format!("backup_{backup_idx}_{exe_name}"));
-    tokio::fs::rename(&exe_path, &backup_path)
+    send_progress(progress, 3, "Backing up existing launcher");
+    let backup_path = exe_location.join(OLD_LAUNCHER_NAME);
+    clean_file(exe_location, OLD_LAUNCHER_NAME).await?;
+    tokio::fs::rename(exe_path, &backup_path)
         .await
         .path(backup_path)?;
 
-    send_progress(&progress, 2, "Downloading new launcher version");
-    let download_zip = file_utils::download_file_to_bytes(&url, false).await?;
-
-    send_progress(&progress, 3, "Extracting new launcher");
+    send_progress(progress, 4, "Extracting new launcher");
     let url_cmp = url.to_lowercase();
     if url_cmp.ends_with(".tar.gz") {
         let exe_location = exe_location.to_owned();
@@ -183,7 +201,7 @@ pub async fn install(url: String, progress: Sender<GenericProgress>) -> Result<(
         file_utils::extract_zip_archive(std::io::Cursor::new(download_zip), exe_location, true)
             .await?;
     } else {
-        return Err(UpdateError::UnknownFileExtension(url));
+        return Err(UpdateError::UnknownFileExtension(url.to_owned()));
     }
 
     clean_file(exe_location, "README.md").await?;
@@ -195,12 +213,165 @@ pub async fn install(url: String, progress: Sender<GenericProgress>) -> Result<(
         "quantum_launcher"
     };
 
-    let new_path = exe_location.join(extract_name);
-    _ = Command::new(&new_path).spawn().path(new_path)?;
+    Ok(exe_location.join(extract_name))
+}
 
+/// Swaps the current launcher with the version
+/// from before the last update (`quantum_launcher.old`).
+///
+/// The previous version will be started.
+/// You must exit the current process manually.
+///
+/// Running this again undoes the rollback.
+///
+/// # Errors
+/// - No previous version was kept
+/// - Current executable couldn't be found
+/// - Launcher couldn't be swapped or started
+pub fn rollback_launcher_update() -> Result<(), UpdateError> {
+    let exe_path = std::env::current_exe().map_err(UpdateError::CurrentExeError)?;
+    swap_with_old(&exe_path)?;
+    _ = Command::new(&exe_path).spawn().path(exe_path)?;
+    Ok(())
+}
+
+fn swap_with_old(exe_path: &Path) -> Result<(), UpdateError> {
+    let exe_location = exe_path.parent().ok_or(UpdateError::ExeParentPathError)?;
+    let old_path = exe_location.join(OLD_LAUNCHER_NAME);
+    if !old_path.is_file() {
+        return Err(UpdateError::NoRollbackAvailable);
+    }
+
+    let temp_path = exe_location.join(format!("{OLD_LAUNCHER_NAME}.swap"));
+    std::fs::rename(exe_path, &temp_path).path(&temp_path)?;
+    if let Err(error) = std::fs::rename(&old_path, exe_path) {
+        // Put the current version back, don't leave the user without a launcher
+        _ = std::fs::rename(&temp_path, exe_path);
+        return Err(error.path(exe_path).into());
+    }
+    std::fs::rename(&temp_path, &old_path).path(old_path)?;
     Ok(())
 }
 
+/// Marks the launcher as starting up. Call this before the UI loads,
+/// and [`mark_launch_succeeded`] once it has.
+///
+/// Returns `true` if the previous start crashed before the UI
+/// loaded (likely a broken update), and
+/// [`rollback_launcher_update`] can be offered.
+pub fn mark_launch_started() -> bool {
+    let sentinel = LAUNCHER_DIR.join(LAUNCH_SENTINEL);
+    let crashed = sentinel.is_file();
+    if let Err(error) = std::fs::write(&sentinel, "") {
+        err!("Couldn't write launch sentinel: {error}");
+    }
+
+    crashed
+        && std::env::current_exe().is_ok_and(|exe| {
+            exe.parent()
+                .is_some_and(|dir| dir.join(OLD_LAUNCHER_NAME).is_file())
+        })
+}
+
+/// See [`mark_launch_started`].
+pub fn mark_launch_succeeded() {
+    let sentinel = LAUNCHER_DIR.join(LAUNCH_SENTINEL);
+    match std::fs::remove_file(&sentinel) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+            err!("Couldn't remove launch sentinel: {error}");
+        }
+        _ => {}
+    }
+}
+
+/// Finds the SHA256 of a release `asset`, either from the
+/// `digest` GitHub computes for every upload, or from a
+/// `<asset>.sha256` file published in the same release.
+async fn find_checksum(release: &GithubRelease, asset: &GithubAsset) -> Option<String> {
+    if let Some(digest) = asset.digest.as_deref().and_then(parse_sha256) {
+        return Some(digest);
+    }
+
+    let sidecar_name = format!("{}.sha256", asset.name);
+    let sidecar = release.assets.iter().find(|n| n.name == sidecar_name)?;
+    match file_utils::download_file_to_string(&sidecar.browser_download_url, false).await {
+        Ok(contents) => parse_sha256(&contents),
+        Err(error) => {
+            err!("Couldn't download launcher update checksum: {error}");
+            None
+        }
+    }
+}
+
+/// Parses a SHA256 in any of these forms:
+/// - `sha256:<hex>` (GitHub asset digest)
+/// - `<hex>  <file name>` (`sha256sum` output)
+/// - `<hex>`
+fn parse_sha256(input: &str) -> Option<String> {
+    let input = input.trim();
+    let hash = input.strip_prefix("sha256:").unwrap_or(input);
+    let hash = hash.split_whitespace().next()?.to_lowercase();
+    (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())).then_some(hash)
+}
+
+/// Checks `bytes` against the `expected` SHA256.
+///
+/// Older releases (and ones uploaded before GitHub
+/// started computing digests) don't have a checksum,
+/// those are only warned about.
+fn verify_checksum(bytes: &[u8], expected: Option<&str>) -> Result<(), UpdateError> {
+    let Some(expected) = expected else {
+        err!("No checksum was published for this update, installing it unverified");
+        return Ok(());
+    };
+    let got = format!("{:x}", Sha256::digest(bytes));
+    if got.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(UpdateError::ChecksumMismatch {
+            expected: expected.to_owned(),
+            got,
+        })
+    }
+}
+
+/// Finds the newest release (GitHub lists them newest first)
+/// that isn't yanked, and matches the update `channel`.
+fn find_latest_release(
+    releases: Vec<GithubRelease>,
+    channel: UpdateChannel,
+) -> Result<(semver::Version, GithubRelease), UpdateError> {
+    for release in releases {
+        let mut version = release.tag_name.clone();
+        // v0.2 -> 0.2
+        if version.starts_with('v') {
+            version = version[1..version.len()].to_owned();
+        }
+        // 0.2 -> 0.2.0
+        if version.chars().filter(|n| *n == '.').count() == 1 {
+            version.push_str(".0");
+        }
+
+        // The new update has been disabled/yanked for whatever reason
+        // so look for another one.
+        // Naming scheme: ends with "-D" followed by (optional) numbers
+        if version
+            .trim_end_matches(|c: char| c.is_numeric())
+            .ends_with("-D")
+        {
+            continue;
+        }
+
+        let version = semver::Version::parse(&version)?;
+        let is_prerelease = release.prerelease || !version.pre.is_empty();
+        if is_prerelease && channel == UpdateChannel::Stable {
+            continue;
+        }
+        return Ok((version, release));
+    }
+    Err(UpdateError::NoReleases)
+}
+
 async fn clean_file(parent: &Path, name: &str) -> Result<(), UpdateError> {
     let p = parent.join(name);
     if exists(&p).await {
@@ -210,10 +381,10 @@ async fn clean_file(parent: &Path, name: &str) -> Result<(), UpdateError> {
 }
 
 fn send_progress(progress: &Sender<GenericProgress>, done: usize, msg: &str) {
-    pt!("({done}/4) {msg}");
+    pt!("({done}/5) {msg}");
     _ = progress.send(GenericProgress {
         done,
-        total: 4,
+        total: 5,
         message: Some(msg.to_owned()),
         has_finished: false,
     });
@@ -252,6 +423,12 @@ pub enum UpdateError {
     UnknownFileExtension(String),
     #[error("Running dev build... (ahead of latest version)")]
     AheadOfLatestVersion,
+    #[error(
+        "{ERR_PREFIX}downloaded update doesn't match its checksum (corrupted or tampered download)\nexpected: {expected}\ngot: {got}"
+    )]
+    ChecksumMismatch { expected: String, got: String },
+    #[error("{ERR_PREFIX}no previous launcher version to roll back to")]
+    NoRollbackAvailable,
 
     #[error("{ERR_PREFIX}could not get current exe path: {0}")]
     CurrentExeError(std::io::Error),
@@ -269,6 +446,8 @@ impl_3_errs_jri!(UpdateError, Json, Request, Io);
 struct GithubRelease {
     tag_name: String,
     assets: Vec<GithubAsset>,
+    #[serde(default)]
+    prerelease: bool,
     // url: String,
     // assets_url: String,
     // upload_url: String,
@@ -276,7 +455,6 @@ struct GithubRelease {
     // id: usize,
     // name: String,
     // draft: bool,
-    // prerelease: bool,
     // created_at: String,
     // published_at: String,
 }
@@ -285,4 +463,114 @@ struct GithubRelease {
 struct GithubAsset {
     name: String,
     browser_download_url: String,
+    /// `sha256:<hex>`, not present on older uploads
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag_name: &str, prerelease: bool) -> GithubRelease {
+        GithubRelease {
+            tag_name: tag_name.to_owned(),
+            assets: Vec::new(),
+            prerelease,
+        }
+    }
+
+    fn releases() -> Vec<GithubRelease> {
+        let next_beta = format!(
+            "v{}.{}.0-beta.1",
+            LAUNCHER_VERSION.major,
+            LAUNCHER_VERSION.minor + 1
+        );
+        vec![
+            release(&next_beta, true),
+            release(&format!("v{LAUNCHER_VERSION}"), false),
+        ]
+    }
+
+    #[test]
+    fn beta_channel_gets_prerelease() {
+        let (version, _) = find_latest_release(releases(), UpdateChannel::Beta).unwrap();
+        assert!(!version.pre.is_empty());
+        assert!(version > LAUNCHER_VERSION);
+    }
+
+    #[test]
+    fn parses_checksum_formats() {
+        let hash = "A".repeat(64);
+        let expected = Some("a".repeat(64));
+        assert_eq!(parse_sha256(&format!("sha256:{hash}")), expected);
+        assert_eq!(parse_sha256(&format!("{hash}  launcher.zip\n")), expected);
+        assert_eq!(parse_sha256("sha256:abcd"), None);
+    }
+
+    #[test]
+    fn missing_checksum_is_allowed() {
+        assert!(verify_checksum(b"update", None).is_ok());
+        assert!(matches!(
+            verify_checksum(b"update", Some(&"0".repeat(64))),
+            Err(UpdateError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn tampered_update_is_rejected() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let exe_path = dir.join("quantum_launcher");
+        tokio::fs::write(&exe_path, b"old launcher").await.unwrap();
+
+        let genuine = b"genuine update".to_vec();
+        let sha256 = format!("{:x}", Sha256::digest(&genuine));
+        let (sender, _receiver) = std::sync::mpsc::channel();
+
+        let result = replace_launcher(
+            &exe_path,
+            "https://example.com/quantum_launcher_linux_x86_64.zip",
+            b"tampered update".to_vec(),
+            Some(&sha256),
+            &sender,
+        )
+        .await;
+        assert!(matches!(result, Err(UpdateError::ChecksumMismatch { .. })));
+
+        // Old launcher untouched, no backup made
+        assert_eq!(tokio::fs::read(&exe_path).await.unwrap(), b"old launcher");
+        let mut entries = tokio::fs::read_dir(&dir).await.unwrap();
+        let mut count = 0;
+        while entries.next_entry().await.unwrap().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn rollback_swaps_binaries() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let exe_path = dir.join("quantum_launcher");
+
+        std::fs::write(&exe_path, b"new").unwrap();
+        assert!(matches!(
+            swap_with_old(&exe_path),
+            Err(UpdateError::NoRollbackAvailable)
+        ));
+        assert_eq!(std::fs::read(&exe_path).unwrap(), b"new");
+
+        std::fs::write(dir.join(OLD_LAUNCHER_NAME), b"old").unwrap();
+        swap_with_old(&exe_path).unwrap();
+        assert_eq!(std::fs::read(&exe_path).unwrap(), b"old");
+        assert_eq!(std::fs::read(dir.join(OLD_LAUNCHER_NAME)).unwrap(), b"new");
+        assert!(!dir.join(format!("{OLD_LAUNCHER_NAME}.swap")).exists());
+    }
+
+    #[test]
+    fn stable_channel_ignores_prerelease() {
+        let (version, _) = find_latest_release(releases(), UpdateChannel::Stable).unwrap();
+        assert_eq!(version, LAUNCHER_VERSION);
+    }
 }
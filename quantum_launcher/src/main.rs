@@ -28,11 +28,9 @@ use std::{borrow::Cow, time::Duration};
 use config::LauncherConfig;
 use iced::{Settings, Task};
 use owo_colors::OwoColorize;
-use state::{Launcher, Message, get_entries};
+use state::{Launcher, Message, list_instances};
 
-use ql_core::{
-    InstanceKind, IntoStringError, JsonFileError, constants::OS_NAME, err, file_utils, info, pt,
-};
+use ql_core::{IntoStringError, JsonFileError, constants::OS_NAME, err, file_utils, info, pt};
 
 use crate::{
     menu_renderer::FONT_DEFAULT,
@@ -96,14 +94,14 @@ impl Launcher {
     ) -> (Self, Task<Message>) {
         #[cfg(feature = "auto_update")]
         let check_for_updates_command = {
-            let should_check = if let Ok(c) = &config {
-                c.should_update_check()
+            let (should_check, channel) = if let Ok(c) = &config {
+                (c.should_update_check(), c.c_update_channel())
             } else {
-                true
+                (true, config::UpdateChannel::default())
             };
             if should_check {
                 Task::perform(
-                    async move { launcher_update::check().await.strerr() },
+                    async move { launcher_update::check(channel).await.strerr() },
                     Message::UpdateCheckResult,
                 )
             } else {
@@ -117,6 +115,18 @@ impl Launcher {
             Launcher::load_new(is_new_user, config).unwrap_or_else(Launcher::with_error);
         // let mut launcher = Launcher::with_error("test");
 
+        #[cfg(feature = "auto_update")]
+        if launcher_update::mark_launch_started() && matches!(launcher.state, State::Launch(_)) {
+            launcher.state = State::ConfirmAction {
+                msg1: "roll back to the previous launcher version".to_owned(),
+                msg2:
+                    "The launcher didn't start properly last time, possibly due to a broken update"
+                        .to_owned(),
+                yes: Message::UpdateRollback,
+                no: menu_renderer::back_to_launch_screen(None),
+            };
+        }
+
         let load_notes_command = if let (Some(instance), State::Launch(menu)) =
             (launcher.selected_instance.clone(), &mut launcher.state)
         {
@@ -135,8 +145,7 @@ impl Launcher {
             launcher,
             Task::batch([
                 check_for_updates_command,
-                Task::perform(get_entries(InstanceKind::Client), Message::CoreListLoaded),
-                Task::perform(get_entries(InstanceKind::Server), Message::CoreListLoaded),
+                Task::perform(list_instances(), Message::CoreListLoaded),
                 load_notes_command,
                 presence_task,
                 Task::perform(ql_core::clean::dir("logs"), |n| {
@@ -145,6 +154,9 @@ impl Launcher {
                 Task::perform(ql_core::clean::dir("downloads/cache"), |n| {
                     Message::CoreCleanComplete(n.strerr())
                 }),
+                Task::perform(ql_instances::detect_and_migrate(), |n| {
+                    Message::CoreMigrateComplete(n.strerr())
+                }),
                 CustomJarState::load(),
             ]),
         )
@@ -185,7 +197,14 @@ fn main() {
     // let is_new_user = true; // Uncomment to test the intro screen.
 
     let (mut launcher_dir, is_dir_err) = load_launcher_dir();
-    cli::start_cli(is_dir_err, &mut launcher_dir);
+    let force = cli::start_cli(is_dir_err, &mut launcher_dir);
+
+    // Held until the launcher exits
+    let _lock = if force || launcher_dir.is_none() {
+        None
+    } else {
+        lock_launcher()
+    };
 
     info!(no_log, "Starting up the launcher... (OS: {OS_NAME})");
     if let Some(dir) = &launcher_dir {
@@ -233,6 +252,40 @@ fn main() {
         .unwrap();
 }
 
+/// Prevents two launchers from running at once
+/// (they would overwrite each other's config).
+fn lock_launcher() -> Option<ql_core::LauncherLock> {
+    // The old launcher might still be closing
+    // (after an update or rollback restarts the launcher)
+    let mut result = ql_core::LauncherLock::acquire();
+    for _ in 0..10 {
+        if !matches!(result, Err(ql_core::LockError::AlreadyRunning)) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(300));
+        result = ql_core::LauncherLock::acquire();
+    }
+
+    match result {
+        Ok(lock) => Some(lock),
+        Err(err @ ql_core::LockError::AlreadyRunning) => {
+            err!("{err}");
+            rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Warning)
+                .set_title("QuantumLauncher is already running")
+                .set_description(err.to_string())
+                .set_buttons(rfd::MessageButtons::Ok)
+                .show();
+            std::process::exit(1);
+        }
+        Err(err) => {
+            // Not worth refusing to start over
+            err!("{err}");
+            None
+        }
+    }
+}
+
 fn load_launcher_dir() -> (Option<std::path::PathBuf>, bool) {
     let launcher_dir_res = file_utils::get_launcher_dir();
     let mut launcher_dir = None;
@@ -252,6 +252,9 @@ impl MenuCreateInstanceChoosing {
             widget::text("If disabled, creating instance will be MUCH faster\nbut no sound or music will play").size(12),
             Position::FollowCursor
         )))
+        .push_maybe(self.estimated_size.filter(|_| matches!(self.kind, InstanceKind::Client)).map(|size| {
+            widget::text!("Download size: ~{:.1} MB", size as f64 / 1_048_576.0).size(12).style(tsubtitle)
+        }))
         .push(widget::horizontal_rule(1))
 
         .push(
@@ -5,7 +5,8 @@ use crate::{
         tsubtitle,
     },
     state::{
-        CustomJarState, EditInstanceMessage, ListMessage, MenuEditInstance, Message, NONE_JAR_NAME,
+        CustomJarState, EditInstanceMessage, ListMessage, MenuEditInstance, Message,
+        NO_DEFAULT_ACCOUNT, NONE_JAR_NAME,
     },
     stylesheet::{color::Color, styles::LauncherTheme, widgets::StyleButton},
 };
@@ -18,9 +19,11 @@ use ql_core::{
     JavaVersion,
     json::{
         GlobalSettings,
-        instance_config::{MainClassMode, PreLaunchPrefixMode},
+        instance_config::{JavaFlagsPreset, MainClassMode, PreLaunchPrefixMode},
     },
 };
+use ql_instances::auth::AccountData;
+use std::collections::HashMap;
 
 use super::Element;
 
@@ -29,6 +32,7 @@ impl MenuEditInstance {
         &'a self,
         selected_instance: &Instance,
         jar_choices: Option<&'a CustomJarState>,
+        accounts: &'a HashMap<String, AccountData>,
     ) -> Element<'a> {
         widget::scrollable(
             checkered_list([
@@ -43,6 +47,17 @@ impl MenuEditInstance {
                             |n| EditInstanceMessage::WindowWidthChanged(n).into(),
                             |n| EditInstanceMessage::WindowHeightChanged(n).into(),
                         ),
+                        self.item_default_account(accounts),
+                        column![
+                            widget::checkbox("Auto-update mods before launching", self.config.auto_update_mods.unwrap_or(false))
+                                .on_toggle(|t| EditInstanceMessage::AutoUpdateModsToggle(t).into()),
+                            widget::text("Checks for mod updates (and installs them) every time you play.\nIf it takes too long, the game launches with the current mods").size(12).style(tsubtitle),
+                        ].spacing(5),
+                        column![
+                            widget::checkbox("Keep a private copy of assets", self.config.private_assets.unwrap_or(false))
+                                .on_toggle(|t| EditInstanceMessage::PrivateAssetsToggle(t).into()),
+                            widget::text("Copies the game assets (sounds, languages) into the instance folder on next launch,\nso it doesn't depend on the launcher's shared assets. Uses more disk space").size(12).style(tsubtitle),
+                        ].spacing(5),
                         column![
                             widget::Space::with_height(5),
                             widget::checkbox("DEBUG: Enable log system (recommended)", self.config.enable_logger.unwrap_or(true))
@@ -134,6 +149,17 @@ impl MenuEditInstance {
             get_args_list(self.config.java_args.as_deref(), |n| Message::EditInstance(
                 EditInstanceMessage::JavaArgs(n)
             )),
+            row![
+                widget::text("GC flags preset:").size(14),
+                horizontal_space(),
+                widget::pick_list(
+                    JavaFlagsPreset::ALL,
+                    Some(self.config.java_flags_preset.unwrap_or_default()),
+                    |n| EditInstanceMessage::JavaFlagsPresetChanged(n).into()
+                )
+                .text_size(12)
+            ]
+            .align_y(Alignment::Center),
             sp(),
             "Game arguments:",
             get_args_list(self.config.game_args.as_deref(), |n| Message::EditInstance(
@@ -202,7 +228,7 @@ impl MenuEditInstance {
     }
 
     fn item_mem_alloc(&self) -> Column<'_> {
-        // 2 ^ 8 = 256 MB
+        // 2 ^ 8 = 256 MB (`MIN_RAM_MB_FOR_INSTANCE`)
         const MEM_256_MB_IN_TWOS_EXPONENT: f32 = 8.0;
         // 2 ^ 15 = 32768 MB (32 GB)
         const MEM_32768_MB_IN_TWOS_EXPONENT: f32 = 15.0;
@@ -323,7 +349,86 @@ Heavy modpacks / High settings: 4-8 GB+"
                         EditInstanceMessage::BrowseJavaOverride
                     ))
             )
-            .spacing(5)
+            .spacing(5),
+            self.item_system_java(java_override),
+        ]
+        .spacing(5)
+    }
+
+    fn item_system_java(&self, java_override: &str) -> Element<'_> {
+        let Some(found) = &self.system_java else {
+            return row![
+                widget::Space::with_width(16),
+                widget::button(widget::text("Find installed Java").size(13))
+                    .on_press(EditInstanceMessage::DetectSystemJava.into()),
+            ]
+            .into();
+        };
+        if found.is_empty() {
+            return row![
+                widget::Space::with_width(16),
+                widget::text("No Java installs found on this system")
+                    .size(12)
+                    .style(tsubtitle),
+            ]
+            .into();
+        }
+
+        widget::column(found.iter().map(|java| {
+            let path = java.path.to_string_lossy();
+            let vendor = if java.vendor.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", java.vendor)
+            };
+            let label = format!("Use system Java {}{vendor}", java.version);
+            row![
+                widget::Space::with_width(16),
+                widget::button(widget::text(label).size(13)).on_press_maybe(
+                    (path.trim() != java_override.trim())
+                        .then(|| EditInstanceMessage::JavaOverride(path.to_string()).into())
+                ),
+                widget::text(path.to_string())
+                    .size(12)
+                    .font(FONT_MONO)
+                    .style(tsubtitle),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .into()
+        }))
+        .spacing(5)
+        .into()
+    }
+
+    fn item_default_account<'a>(
+        &'a self,
+        accounts: &'a HashMap<String, AccountData>,
+    ) -> Column<'a> {
+        let mut choices: Vec<String> = accounts.keys().cloned().collect();
+        choices.sort_unstable();
+        choices.insert(0, NO_DEFAULT_ACCOUNT.to_owned());
+
+        let selected = self
+            .config
+            .default_account
+            .as_ref()
+            .and_then(|uuid| accounts.iter().find(|(_, n)| n.uuid == *uuid))
+            .map_or_else(|| NO_DEFAULT_ACCOUNT.to_owned(), |(key, _)| key.clone());
+
+        column![
+            row![
+                "Default account:",
+                horizontal_space(),
+                widget::pick_list(choices, Some(selected), |n| {
+                    EditInstanceMessage::DefaultAccountChanged(n).into()
+                })
+                .text_size(14)
+            ]
+            .align_y(Alignment::Center),
+            widget::text("Always launch this instance with this account")
+                .size(12)
+                .style(tsubtitle),
         ]
         .spacing(5)
     }
@@ -495,6 +600,11 @@ pub fn get_args_list(
                                 let msg = msg.clone();
                                 move |n| msg(ListMessage::Edit(n, i))
                             })
+                            .on_paste({
+                                let msg = msg.clone();
+                                move |n| msg(ListMessage::Paste(n, i))
+                            })
+                            .on_submit(msg(ListMessage::Submit(i)))
                     ]
                     .align_y(Alignment::Center)
                     .into()
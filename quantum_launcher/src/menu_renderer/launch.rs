@@ -3,6 +3,7 @@ use frostmark::MarkWidget;
 use iced::widget::{column, horizontal_space, row, text_editor, tooltip::Position, vertical_space};
 use iced::{Alignment, Length, Padding, widget};
 use ql_core::{Instance, InstanceKind, LAUNCHER_VERSION_NAME};
+use ql_instances::{LaunchOptions, QuickPlay};
 
 use crate::cli::EXPERIMENTAL_MMC_IMPORT;
 use crate::menu_renderer::onboarding::x86_warning;
@@ -29,6 +30,20 @@ use super::{Element, button_with_icon, shortcut_ctrl, tooltip};
 
 pub const TAB_BUTTON_WIDTH: f32 = 64.0;
 
+/// An account in the accounts dropdown,
+/// shown by its alias if it has one.
+#[derive(Debug, Clone, PartialEq)]
+struct AccountEntry<'a> {
+    key: &'a str,
+    label: &'a str,
+}
+
+impl std::fmt::Display for AccountEntry<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label)
+    }
+}
+
 const fn tab_height(decor: bool) -> f32 {
     if decor { 31.0 } else { 28.0 }
 }
@@ -82,7 +97,7 @@ impl Launcher {
                 LaunchTab::Log => self.get_tab_logs(menu, selected.kind).into(),
                 LaunchTab::Edit => {
                     if let Some(menu) = &menu.edit_instance {
-                        menu.view(selected, self.custom_jar.as_ref())
+                        menu.view(selected, self.custom_jar.as_ref(), &self.accounts)
                     } else {
                         column![
                             "Error: This instance hadn't finished downloading, or files are missing\n(Couldn't read config.json)",
@@ -114,6 +129,12 @@ impl Launcher {
         };
 
         let mmc_import = EXPERIMENTAL_MMC_IMPORT.read().unwrap();
+        let can_launch = !self.is_launching_game
+            && self
+                .selected_instance
+                .as_ref()
+                .is_some_and(|n| !n.is_server() && !self.processes.contains_key(n));
+        let servers = menu.multiplayer_servers.iter().filter(|_| can_launch);
 
         widget::stack!(
             column![
@@ -144,6 +165,38 @@ impl Launcher {
                             ctx_button(icons::file_gear_s(CTXI_SIZE), "Create Shortcut")
                                 .on_press(ShortcutMessage::Open.into()),
                         ]
+                        .push_maybe(can_launch.then(|| {
+                            ctx_button(icons::play_s(CTXI_SIZE), "Play in Safe Mode").on_press(
+                                LaunchMessage::StartWith(LaunchOptions {
+                                    safe_mode: true,
+                                    quick_play: None,
+                                })
+                                .into(),
+                            )
+                        }))
+                        .push_maybe(
+                            (servers.clone().next().is_some()).then(|| widget::text(
+                                "Join server:"
+                            )
+                            .size(12)
+                            .style(tsubtitle)),
+                        )
+                        .extend(servers.map(|server| {
+                            let name = if server.name.is_empty() {
+                                &server.ip
+                            } else {
+                                &server.name
+                            };
+                            ctx_button(icons::play_s(CTXI_SIZE), name)
+                                .on_press(
+                                    LaunchMessage::StartWith(LaunchOptions {
+                                        safe_mode: false,
+                                        quick_play: Some(QuickPlay::Multiplayer(server.ip.clone())),
+                                    })
+                                    .into(),
+                                )
+                                .into()
+                        }))
                         .push_maybe(mmc_import.then_some(widget::horizontal_rule(1)))
                         .push_maybe(mmc_import.then(|| {
                             widget::button(import_description())
@@ -177,7 +230,7 @@ impl Launcher {
                 self.get_client_play_button(selected)
             },
             Self::get_mods_button(),
-            Self::get_files_button(selected),
+            self.get_files_button(selected),
         ]
         .spacing(5)
         .wrap();
@@ -425,34 +478,68 @@ impl Launcher {
     }
 
     fn is_process_running(&self, instance: &Instance) -> bool {
-        self.processes.contains_key(instance)
+        self.processes.contains_key(instance) || self.detached_processes.contains_key(instance)
     }
 
     fn get_accounts_bar(&self, menu: &MenuLaunch) -> Element<'_> {
         let something_is_happening = self.java_recv.is_some() || menu.login_progress.is_some();
 
-        let dropdown: Element = if something_is_happening {
+        let dropdown: Element = if let Some(alias) = &menu.account_alias {
+            widget::text_input("Alias (empty to use username)...", alias)
+                .on_input(|n| AccountMessage::AliasInput(n).into())
+                .on_submit(AccountMessage::AliasSave.into())
+                .width(Length::Fill)
+                .into()
+        } else if something_is_happening {
             widget::text_input("", &self.account_selected)
                 .width(Length::Fill)
                 .into()
         } else {
-            widget::pick_list(
-                self.accounts_dropdown.as_slice(),
-                Some(&self.account_selected),
-                |n| AccountMessage::Selected(n).into(),
-            )
+            let entries: Vec<AccountEntry> = self
+                .accounts_dropdown
+                .iter()
+                .map(|key| AccountEntry {
+                    key,
+                    label: self
+                        .accounts
+                        .get(key)
+                        .and_then(|n| n.alias.as_deref())
+                        .unwrap_or(key),
+                })
+                .collect();
+            let selected = entries
+                .iter()
+                .find(|n| n.key == self.account_selected)
+                .cloned();
+            widget::pick_list(entries, selected, |n| {
+                AccountMessage::Selected(n.key.to_owned()).into()
+            })
             .width(Length::Fill)
             .into()
         };
 
+        let small_button = |text: &'static str, msg: AccountMessage| {
+            widget::button(widget::text(text).size(11))
+                .padding(3)
+                .on_press(msg.into())
+                .style(|n: &LauncherTheme, status| {
+                    n.style_button(status, StyleButton::FlatExtraDark)
+                })
+        };
+        let rename_button = if menu.account_alias.is_some() {
+            small_button("Done", AccountMessage::AliasSave)
+        } else {
+            small_button("Rename", AccountMessage::AliasEdit)
+        };
+
         widget::column![
             widget::row![widget::text(" Accounts:").size(14), horizontal_space()].push_maybe(
                 (self.account_selected != OFFLINE_ACCOUNT_NAME).then_some(
-                    widget::button(widget::text("Logout").size(11))
-                        .padding(3)
-                        .on_press(AccountMessage::LogoutCheck.into())
-                        .style(|n: &LauncherTheme, status| n
-                            .style_button(status, StyleButton::FlatExtraDark))
+                    widget::row![
+                        rename_button,
+                        small_button("Logout", AccountMessage::LogoutCheck)
+                    ]
+                    .spacing(5)
                 )
             ),
             dropdown
@@ -504,12 +591,15 @@ impl Launcher {
     }
 
     fn get_files_button(
+        &self,
         selected_instance: &Instance,
     ) -> widget::Button<'_, Message, LauncherTheme> {
+        let game_dir = self.edit_instance.as_ref().map_or_else(
+            || selected_instance.get_dot_minecraft_path(),
+            |n| n.config.get_game_dir(selected_instance),
+        );
         button_with_icon(icons::folder(), "Files", 16)
-            .on_press(Message::CoreOpenPath(
-                selected_instance.get_dot_minecraft_path(),
-            ))
+            .on_press(Message::CoreOpenPath(game_dir))
             .width(97)
     }
 
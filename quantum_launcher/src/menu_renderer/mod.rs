@@ -398,15 +398,8 @@ impl MenuCurseforgeManualDownload {
 
             widget::scrollable(
                 widget::column(self.not_allowed.iter().map(|entry| {
-                    let url = format!(
-                        "https://www.curseforge.com/minecraft/{}/{}/download/{}",
-                        entry.project_type,
-                        entry.slug,
-                        entry.file_id
-                    );
-
                     row![
-                        widget::button(widget::text("Open link").size(14)).on_press(Message::CoreOpenLink(url)),
+                        widget::button(widget::text("Open link").size(14)).on_press(Message::CoreOpenLink(entry.download_url())),
                         widget::text(&entry.name)
                             .shaping(widget::text::Shaping::Advanced)
                     ]
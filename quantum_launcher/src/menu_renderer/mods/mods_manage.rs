@@ -120,7 +120,7 @@ impl MenuEditMods {
                     tooltip(
                         button_with_icon(icons::folder_s(14), "Open", 14).on_press_with(|| {
                             Message::CoreOpenPath(
-                                selected_instance.get_dot_minecraft_path().join("mods"),
+                                self.config.get_game_dir(selected_instance).join("mods"),
                             )
                         }),
                         widget::text("Open Mods Folder").size(12),
@@ -558,27 +558,42 @@ impl MenuEditMods {
                     file_name: file_name.clone(),
                 });
 
-                let checkbox = select_box(
+                let base_name = file_name.strip_suffix(".disabled").unwrap_or(file_name);
+                let text_style = move |t: &LauncherTheme| {
+                    t.style_text(if is_enabled {
+                        Color::SecondLight
+                    } else {
+                        Color::Mid
+                    })
+                };
+
+                // Name and version read from the jar, if known
+                let label: Element = if let Some(info) = self.mods.local_mods.get(base_name) {
                     row![
-                        no_icon,
-                        widget::text(
-                            file_name
-                                .strip_suffix(".disabled")
-                                .unwrap_or(file_name)
-                                .to_owned(),
-                        )
+                        widget::text(&info.name)
+                            .shaping(widget::text::Shaping::Advanced)
+                            .style(text_style)
+                            .size(14)
+                            .width(self.width_name),
+                        widget::text(info.version.as_deref().unwrap_or_default())
+                            .style(|t: &LauncherTheme| t.style_text(Color::Mid))
+                            .font(FONT_MONO)
+                            .size(12)
+                    ]
+                    .align_y(Alignment::Center)
+                    .spacing(SPACING)
+                    .into()
+                } else {
+                    widget::text(base_name.to_owned())
                         .font(FONT_MONO)
                         .shaping(widget::text::Shaping::Advanced)
-                        .style(move |t: &LauncherTheme| {
-                            t.style_text(if is_enabled {
-                                Color::SecondLight
-                            } else {
-                                Color::Mid
-                            })
-                        })
+                        .style(text_style)
                         .size(14)
-                    ]
-                    .spacing(SPACING),
+                        .into()
+                };
+
+                let checkbox = select_box(
+                    row![no_icon, label].spacing(SPACING),
                     is_selected,
                     ManageModsMessage::SelectMod(file_name.clone(), None).into(),
                 )
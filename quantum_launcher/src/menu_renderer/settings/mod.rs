@@ -83,18 +83,18 @@ impl MenuLauncherSettings {
 }
 
 pub fn get_theme_selector() -> widget::Row<'static, Message, LauncherTheme> {
-    widget::row(LauncherThemeColor::ALL.iter().map(|color| {
+    widget::row(LauncherThemeColor::all().map(|color| {
         widget::button(widget::text(color.to_string()).size(13))
             .padding([2, 4])
-            .style(|theme: &LauncherTheme, s| {
+            .style(move |theme: &LauncherTheme, s| {
                 LauncherTheme {
-                    color: *color,
+                    color,
                     alpha: 1.0,
                     ..*theme
                 }
                 .style_button(s, StyleButton::Round)
             })
-            .on_press(LauncherSettingsMessage::ColorSchemePicked(*color).into())
+            .on_press(LauncherSettingsMessage::ColorSchemePicked(color).into())
             .into()
     }))
     .spacing(5)
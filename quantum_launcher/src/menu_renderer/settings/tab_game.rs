@@ -1,8 +1,8 @@
 use iced::{
-    Length,
+    Alignment, Length,
     widget::{self, column, row},
 };
-use ql_core::LAUNCHER_DIR;
+use ql_core::{LAUNCHER_DIR, MIN_RAM_MB_FOR_INSTANCE};
 
 use crate::{
     config::{AfterLaunchBehavior, LauncherConfig},
@@ -25,9 +25,12 @@ impl MenuLauncherSettings {
                     .on_press_with(|| Message::CoreOpenPath(LAUNCHER_DIR.clone())),
             ]],
             opt_changelog(config),
+            #[cfg(feature = "auto_update")]
+            opt_update_channel(config),
             opt_after_launch(config),
             opt_resolution(config),
             opt_java_args(config),
+            opt_new_instance_template(config),
             column![
                 "Global Pre-Launch Prefix:",
                 widget::text(PREFIX_EXPLANATION).size(12).style(tsubtitle),
@@ -69,6 +72,52 @@ fn opt_java_args(config: &LauncherConfig) -> Column<'_> {
     .spacing(10)
 }
 
+fn opt_new_instance_template(config: &LauncherConfig) -> Column<'_> {
+    let template = config.new_instance_template.as_ref();
+    column![
+        "New Instance Defaults:",
+        widget::text("Settings that newly created instances start with")
+            .size(12)
+            .style(tsubtitle),
+        row![
+            widget::text("Memory (MB):").size(14),
+            widget::text_input(
+                "2048",
+                &template
+                    .and_then(|n| n.ram_in_mb)
+                    .map_or(String::new(), |n| n.to_string())
+            )
+            .size(14)
+            .on_input(|n| LauncherSettingsMessage::TemplateRamChanged(n).into())
+            .width(100),
+        ]
+        .push_maybe(
+            template
+                .and_then(|n| n.ram_in_mb)
+                .filter(|n| *n < MIN_RAM_MB_FOR_INSTANCE)
+                .map(|_| {
+                    widget::text(format!("(at least {MIN_RAM_MB_FOR_INSTANCE} MB is used)"))
+                        .size(12)
+                        .style(tsubtitle)
+                })
+        )
+        .spacing(10)
+        .align_y(Alignment::Center),
+        widget::checkbox(
+            "Download assets",
+            template.and_then(|n| n.download_assets).unwrap_or(true),
+        )
+        .size(14)
+        .text_size(14)
+        .on_toggle(|n| LauncherSettingsMessage::TemplateToggleDownloadAssets(n).into()),
+        widget::text("Java Arguments:").size(14),
+        get_args_list(template.and_then(|n| n.java_args.as_deref()), |msg| {
+            LauncherSettingsMessage::TemplateJavaArgs(msg).into()
+        }),
+    ]
+    .spacing(10)
+}
+
 fn opt_resolution(config: &LauncherConfig) -> Column<'_> {
     resolution_dialog(
         config.global_settings.as_ref(),
@@ -120,3 +169,18 @@ fn opt_changelog(config: &LauncherConfig) -> Column<'_> {
     ]
     .spacing(5)
 }
+
+#[cfg(feature = "auto_update")]
+fn opt_update_channel(config: &LauncherConfig) -> Column<'_> {
+    column![
+        widget::checkbox(
+            "Receive beta updates",
+            config.c_update_channel() == crate::config::UpdateChannel::Beta,
+        )
+        .on_toggle(|n| LauncherSettingsMessage::ToggleBetaUpdates(n).into()),
+        widget::text("Beta versions get new features early, but may be unstable")
+            .size(12)
+            .style(tsubtitle),
+    ]
+    .spacing(5)
+}
@@ -9,11 +9,12 @@ use crate::state::{
 };
 use iced::{
     Task,
+    futures::executor::block_on,
     keyboard::{self, Key, key::Named},
 };
 use ql_core::{
-    Instance, err,
-    jarmod::{JarMod, JarMods},
+    Instance, IntoIoError, IntoStringError,
+    jarmod::{self, JarMods},
     pt,
 };
 use std::ffi::OsStr;
@@ -256,12 +257,13 @@ impl Launcher {
             }
         } else if let State::EditJarMods(menu) = &mut self.state {
             if extension == "jar" || extension == "zip" {
-                Self::load_jarmods_from_path(
+                if let Err(err) = Self::load_jarmods_from_path(
                     self.selected_instance.as_ref().unwrap(),
                     path,
-                    filename,
                     &mut menu.jarmods,
-                );
+                ) {
+                    self.set_error(err);
+                }
             }
             Task::none()
         } else if let State::InstallOptifine(MenuInstallOptifine::Choosing { .. }) = &mut self.state
@@ -276,26 +278,25 @@ impl Launcher {
         }
     }
 
-    fn load_jarmods_from_path(
+    /// Adds a jar mod file (from drag and drop or the file picker)
+    /// to the jar mods being edited, checking that it is one.
+    pub(crate) fn load_jarmods_from_path(
         selected_instance: &Instance,
         path: &Path,
-        filename: &str,
         jarmods: &mut JarMods,
-    ) {
-        let new_path = selected_instance
-            .get_instance_path()
-            .join("jarmods")
-            .join(filename);
-        if path != new_path {
-            if let Err(err) = std::fs::copy(path, &new_path) {
-                err!("Couldn't drag and drop mod file in: {err}");
-            } else if !jarmods.mods.iter().any(|n| n.filename == filename) {
-                jarmods.mods.push(JarMod {
-                    filename: filename.to_owned(),
-                    enabled: true,
-                });
-            }
-        }
+    ) -> Result<(), String> {
+        let name = path
+            .file_stem()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let bytes = std::fs::read(path).path(path).strerr()?;
+        block_on(jarmod::insert_into(
+            selected_instance,
+            jarmods,
+            &bytes,
+            &name,
+        ))
+        .strerr()
     }
 
     pub fn key_escape_back(&mut self, affect: bool) -> (bool, Task<Message>) {
@@ -1,15 +1,17 @@
 use crate::{
     Launcher, Message,
     menu_renderer::back_to_launch_screen,
+    message_update::add_file_done,
     state::{
-        AutoSaveKind, EditPresetsMessage, InfoMessage, LaunchTab, LogState, ManageModsMessage,
-        MenuEditMods, MenuInstallForge, MenuInstallOptifine, ProgressBar, SelectedState, State,
+        AutoSaveKind, EditPresetsMessage, InfoMessage, LaunchTab, LogState, MenuEditMods,
+        MenuImportModpack, MenuInstallForge, MenuInstallOptifine, ProgressBar, SelectedState,
+        State,
     },
     tick::sort_dependencies,
 };
 use iced::{Task, futures::executor::block_on, widget::scrollable::AbsoluteOffset};
 use ql_core::{
-    GenericProgress, Instance, IntoIoError, IntoStringError, JsonFileError, err,
+    CancellationToken, GenericProgress, Instance, IntoIoError, IntoStringError, JsonFileError, err,
     file_utils::exists,
     json::{VersionDetails, instance_config::InstanceConfigJson},
 };
@@ -208,23 +210,31 @@ impl Launcher {
 
     fn load_modpack_from_path(&mut self, path: PathBuf) -> Task<Message> {
         let (sender, receiver) = std::sync::mpsc::channel();
+        let cancel = CancellationToken::new();
 
-        self.state = State::ImportModpack(ProgressBar::with_recv(receiver));
+        self.state = State::ImportModpack(MenuImportModpack {
+            progress: ProgressBar::with_recv(receiver),
+            cancel: Some(cancel.clone()),
+        });
 
         Task::perform(
             ql_mod_manager::add_files(
                 self.selected_instance.clone().unwrap(),
                 vec![path],
                 Some(sender),
+                Some(cancel),
             ),
-            |n| ManageModsMessage::AddFileDone(n.strerr()).into(),
+            add_file_done,
         )
     }
 
     fn load_jar_from_path(&mut self, path: &Path, filename: &str) {
-        let selected_instance = self.instance();
-        let new_path = selected_instance
-            .get_dot_minecraft_path()
+        let State::EditMods(menu) = &self.state else {
+            return;
+        };
+        let new_path = menu
+            .config
+            .get_game_dir(self.instance())
             .join("mods")
             .join(filename);
         if *path != new_path {
@@ -289,8 +299,11 @@ impl Launcher {
     }
     #[cfg(feature = "auto_update")]
     pub fn update_download_start(&mut self) -> Task<Message> {
-        if let State::UpdateFound(crate::state::MenuLauncherUpdate { url, progress, .. }) =
-            &mut self.state
+        if let State::UpdateFound(crate::state::MenuLauncherUpdate {
+            url,
+            sha256,
+            progress,
+        }) = &mut self.state
         {
             let (sender, update_receiver) = std::sync::mpsc::channel();
             *progress = Some(ProgressBar::with_recv_and_msg(
@@ -299,9 +312,14 @@ impl Launcher {
             ));
 
             let url = url.clone();
+            let sha256 = sha256.clone();
 
             Task::perform(
-                async move { crate::launcher_update::install(url, sender).await.strerr() },
+                async move {
+                    crate::launcher_update::install(url, sha256, sender)
+                        .await
+                        .strerr()
+                },
                 Message::UpdateDownloadEnd,
             )
         } else {
@@ -329,10 +347,14 @@ impl Launcher {
 }
 
 pub async fn get_locally_installed_mods(
-    selected_instance: PathBuf,
+    selected_instance: Instance,
     blacklist: Vec<String>,
 ) -> HashSet<String> {
-    let mods_dir_path = selected_instance.join("mods");
+    let Ok(game_dir) = selected_instance.get_game_dir().await else {
+        err!("Error reading instance config");
+        return HashSet::new();
+    };
+    let mods_dir_path = game_dir.join("mods");
 
     let Ok(mut dir) = tokio::fs::read_dir(&mods_dir_path).await else {
         err!("Error reading mods directory");
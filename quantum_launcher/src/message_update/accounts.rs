@@ -56,6 +56,28 @@ impl Launcher {
                     no: back_to_launch_screen(None),
                 }
             }
+            AccountMessage::AliasEdit => {
+                let alias = self
+                    .accounts
+                    .get(&self.account_selected)
+                    .and_then(|n| n.alias.clone())
+                    .unwrap_or_default();
+                if let State::Launch(menu) = &mut self.state {
+                    menu.account_alias = Some(alias);
+                }
+            }
+            AccountMessage::AliasInput(alias) => {
+                if let State::Launch(menu) = &mut self.state {
+                    menu.account_alias = Some(alias);
+                }
+            }
+            AccountMessage::AliasSave => {
+                if let State::Launch(menu) = &mut self.state {
+                    if let Some(alias) = menu.account_alias.take() {
+                        self.set_account_alias(&alias);
+                    }
+                }
+            }
             AccountMessage::LittleSkinDeviceCodeReady {
                 user_code,
                 verification_uri,
@@ -125,8 +147,32 @@ impl Launcher {
 
                 return self.go_to_main_menu(None);
             }
-            AccountMessage::RefreshComplete(Ok(data)) => {
-                self.accounts.insert(data.get_username_modified(), data);
+            AccountMessage::AltRefreshNeedsOTP {
+                username,
+                is_littleskin,
+            } => {
+                // Log in again, with the 2FA code this time
+                self.is_launching_game = false;
+                self.java_recv = None;
+                self.state = State::LoginAlternate(MenuLoginAlternate {
+                    username,
+                    password: String::new(),
+                    is_loading: false,
+                    otp: Some(String::new()),
+                    show_password: false,
+                    is_from_welcome_screen: false,
+                    is_incorrect_password: false,
+
+                    is_littleskin,
+                    device_code_error: None,
+                    oauth: None,
+                });
+            }
+            AccountMessage::RefreshComplete(Ok(mut data)) => {
+                let key = data.get_username_modified();
+                // Only stored by the launcher, not the login server
+                data.alias = self.accounts.get(&key).and_then(|n| n.alias.clone());
+                self.accounts.insert(key, data);
 
                 let account_data = self.get_selected_account_data();
 
@@ -247,6 +293,9 @@ impl Launcher {
     }
 
     fn account_selected(&mut self, account: String) {
+        if let State::Launch(menu) = &mut self.state {
+            menu.account_alias = None;
+        }
         if account == NEW_ACCOUNT_NAME {
             self.state = State::AccountLogin;
         } else {
@@ -272,18 +321,51 @@ impl Launcher {
                     |n| AccountMessage::RefreshComplete(n.strerr()).into(),
                 )
             }
-            AccountType::ElyBy | AccountType::LittleSkin => Task::perform(
-                auth::yggdrasil::login_refresh(
-                    account.username.clone(),
-                    account.refresh_token.clone(),
-                    account.account_type,
-                ),
-                |n| AccountMessage::RefreshComplete(n.strerr()).into(),
-            ),
+            AccountType::ElyBy | AccountType::LittleSkin => {
+                let username = account.username.clone();
+                let is_littleskin = account.account_type == AccountType::LittleSkin;
+                Task::perform(
+                    auth::yggdrasil::login_refresh(
+                        account.username.clone(),
+                        account.refresh_token.clone(),
+                        account.account_type,
+                    ),
+                    move |n| match n.strerr() {
+                        Ok(auth::yggdrasil::Account::Account(data)) => {
+                            AccountMessage::RefreshComplete(Ok(data)).into()
+                        }
+                        Ok(auth::yggdrasil::Account::NeedsOTP) => {
+                            AccountMessage::AltRefreshNeedsOTP {
+                                username,
+                                is_littleskin,
+                            }
+                            .into()
+                        }
+                        Err(err) => AccountMessage::RefreshComplete(Err(err)).into(),
+                    },
+                )
+            }
         }
     }
 
-    fn account_response_3(&mut self, data: AccountData) -> Task<Message> {
+    /// Sets (or with an empty `alias`, clears)
+    /// the alias of the selected account.
+    fn set_account_alias(&mut self, alias: &str) {
+        let alias = Some(alias.trim())
+            .filter(|n| !n.is_empty())
+            .map(str::to_owned);
+        let key = &self.account_selected;
+        let Some(data) = self.accounts.get_mut(key) else {
+            return;
+        };
+        data.alias.clone_from(&alias);
+        if let Some(account) = self.config.accounts.as_mut().and_then(|n| n.get_mut(key)) {
+            account.alias = alias;
+        }
+        self.autosave.remove(&AutoSaveKind::LauncherConfig);
+    }
+
+    fn account_response_3(&mut self, mut data: AccountData) -> Task<Message> {
         self.autosave.remove(&AutoSaveKind::LauncherConfig);
         if data.username == OFFLINE_ACCOUNT_NAME || data.username == NEW_ACCOUNT_NAME {
             return self.go_to_main_menu(None);
@@ -291,10 +373,12 @@ impl Launcher {
         let username = data.get_username_modified();
 
         if self.accounts_dropdown.contains(&username) {
-            // Account already logged in
-            return self.go_to_main_menu(None);
+            // Logged in again (eg: with a 2FA code),
+            // the alias is only stored by the launcher
+            data.alias = self.accounts.get(&username).and_then(|n| n.alias.clone());
+        } else {
+            self.accounts_dropdown.insert(0, username.clone());
         }
-        self.accounts_dropdown.insert(0, username.clone());
 
         let config_accounts = self.config.accounts.get_or_insert_default();
         config_accounts.insert(username.clone(), ConfigAccount::from_account(&data));
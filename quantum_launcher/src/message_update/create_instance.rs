@@ -4,10 +4,11 @@ use ql_core::{
 };
 
 use crate::{
+    menu_renderer::back_to_launch_screen,
     message_handler::{SIDEBAR_LIMIT_LEFT, SIDEBAR_LIMIT_RIGHT},
     state::{
         AutoSaveKind, CreateInstanceMessage, InfoMessage, Launcher, MenuCreateInstance,
-        MenuCreateInstanceChoosing, Message, ProgressBar, State,
+        MenuCreateInstanceChoosing, Message, OFFLINE_ACCOUNT_NAME, ProgressBar, State,
     },
 };
 
@@ -33,12 +34,21 @@ impl Launcher {
             }
             CreateInstanceMessage::VersionsLoaded(res) => {
                 self.create_instance_finish_loading_versions_list(res);
+                return self.estimate_selected_size();
             }
             CreateInstanceMessage::VersionSelected(ver) => {
                 iflet!(self, selected_version, show_category_dropdown; {
                     *show_category_dropdown = false;
                     *selected_version = ver;
                 });
+                return self.estimate_selected_size();
+            }
+            CreateInstanceMessage::SizeEstimated(ver, assets, size) => {
+                iflet!(self, selected_version, download_assets, estimated_size; {
+                    if *selected_version == ver && *download_assets == assets {
+                        *estimated_size = size.ok();
+                    }
+                });
             }
 
             CreateInstanceMessage::SearchInput(t) => iflet!(self, search_box; {
@@ -64,6 +74,7 @@ impl Launcher {
                         *selected_version = sel.clone();
                     }
                 });
+                return self.estimate_selected_size();
             }
             CreateInstanceMessage::SidebarResize(ratio) => {
                 let window_width = self.window_state.size.0;
@@ -100,9 +111,12 @@ impl Launcher {
             CreateInstanceMessage::NameInput(name) => iflet!(self, instance_name; {
                 *instance_name = name;
             }),
-            CreateInstanceMessage::ChangeKind(t) => iflet!(self, kind; {
-                *kind = t;
-            }),
+            CreateInstanceMessage::ChangeKind(t) => {
+                iflet!(self, kind; {
+                    *kind = t;
+                });
+                return self.estimate_selected_size();
+            }
 
             CreateInstanceMessage::Start => return self.create_instance(),
             CreateInstanceMessage::End(Ok(instance)) => {
@@ -113,9 +127,37 @@ impl Launcher {
                     if is_server { "Server" } else { "Instance" }
                 ))));
             }
-            CreateInstanceMessage::ChangeAssetToggle(t) => iflet!(self, download_assets; {
-                *download_assets = t;
-            }),
+            CreateInstanceMessage::EndWithWarnings(instance, warnings) => {
+                self.selected_instance = Some(instance.clone());
+                let warnings: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+                self.state = State::ConfirmAction {
+                    msg1: "let offline accounts join this server (set online-mode=false)"
+                        .to_owned(),
+                    msg2: format!(
+                        "{}\n\nNote: Anyone will be able to join with any username",
+                        warnings.join("\n")
+                    ),
+                    yes: CreateInstanceMessage::AllowOfflineAccounts(instance).into(),
+                    no: back_to_launch_screen(Some(InfoMessage::success("Created Server"))),
+                };
+            }
+            CreateInstanceMessage::AllowOfflineAccounts(instance) => {
+                return Task::perform(
+                    async move {
+                        ql_servers::set_online_mode(instance.get_name(), false)
+                            .await
+                            .strerr()
+                            .map(|()| instance)
+                    },
+                    |n| CreateInstanceMessage::End(n).into(),
+                );
+            }
+            CreateInstanceMessage::ChangeAssetToggle(t) => {
+                iflet!(self, download_assets; {
+                    *download_assets = t;
+                });
+                return self.estimate_selected_size();
+            }
             CreateInstanceMessage::Import => {
                 if let Some(file) = rfd::FileDialog::new()
                     .set_title("Select an instance...")
@@ -173,6 +215,29 @@ then go to "Mods->Add File""#,
         });
     }
 
+    fn estimate_selected_size(&mut self) -> Task<Message> {
+        iflet!(self, selected_version, download_assets, kind, estimated_size; {
+            *estimated_size = None;
+            if *kind == InstanceKind::Server || selected_version.name.is_empty() {
+                return Task::none();
+            }
+            let version = selected_version.clone();
+            let download_assets = *download_assets;
+            return Task::perform(
+                async move {
+                    let size = ql_instances::estimate_instance_size(&version, download_assets)
+                        .await
+                        .strerr();
+                    (version, download_assets, size)
+                },
+                |(version, assets, size)| {
+                    CreateInstanceMessage::SizeEstimated(version, assets, size).into()
+                },
+            );
+        });
+        Task::none()
+    }
+
     fn go_to_create_screen(&mut self, kind: InstanceKind) -> Task<Message> {
         let (task, handle) = Task::perform(ql_instances::list_versions(), |n| {
             CreateInstanceMessage::VersionsLoaded(n.strerr()).into()
@@ -198,7 +263,13 @@ then go to "Mods->Add File""#,
                 kind: ListEntryKind::Release,
             },
             instance_name: String::new(),
-            download_assets: true,
+            download_assets: self
+                .config
+                .new_instance_template
+                .as_ref()
+                .and_then(|n| n.download_assets)
+                .unwrap_or(true),
+            estimated_size: None,
             search_box: String::new(),
             show_category_dropdown: false,
             selected_categories: self.config.c_persistent().get_create_instance_filters(),
@@ -243,6 +314,7 @@ then go to "Mods->Add File""#,
             };
             let download_assets = *download_assets;
             let kind = *kind;
+            let offline_account = self.account_selected == OFFLINE_ACCOUNT_NAME;
 
             self.state = State::Create(MenuCreateInstance::DownloadingInstance(progress));
 
@@ -250,19 +322,35 @@ then go to "Mods->Add File""#,
                 InstanceKind::Server => Task::perform(
                     async move {
                         let sender = sender;
-                        ql_servers::create_server(instance_name.clone(), version, Some(&sender))
-                            .await
-                            .strerr()
-                            .map(|n| Instance::server(&n))
+                        ql_servers::create_server(
+                            instance_name.clone(),
+                            version,
+                            Some(&sender),
+                            offline_account,
+                        )
+                        .await
+                        .strerr()
+                    },
+                    |n| match n {
+                        Ok(server) if !server.warnings.is_empty() => {
+                            let instance = Instance::server(&server.name);
+                            CreateInstanceMessage::EndWithWarnings(instance, server.warnings).into()
+                        }
+                        n => CreateInstanceMessage::End(
+                            n.map(|n| Instance::server(&n.name)),
+                        ).into(),
                     },
-                    |n| CreateInstanceMessage::End(n).into(),
                 ),
                 InstanceKind::Client => Task::perform(
                     ql_instances::create_instance(
                         instance_name.clone(),
                         version,
                         Some(sender),
-                        download_assets,
+                        ql_instances::CreateOptions {
+                            download_assets,
+                            install_java: true,
+                        },
+                        None,
                     ),
                     |n| CreateInstanceMessage::End(
                         n.strerr().map(|n| Instance::client(&n)),
@@ -2,12 +2,13 @@ use std::sync::Arc;
 
 use iced::Task;
 use ql_core::{
-    Instance, IntoIoError, IntoJsonError, IntoStringError, JsonFileError, LAUNCHER_DIR, err,
+    Instance, InstanceKind, IntoIoError, IntoJsonError, IntoStringError, JsonFileError,
+    LAUNCHER_DIR, MIN_RAM_MB_FOR_INSTANCE, err,
     json::{
         InstanceConfigJson,
         instance_config::{CustomJarConfig, MainClassMode},
     },
-    sanitize_instance_name,
+    sanitize_instance_name, validate_instance_name,
 };
 
 use crate::{
@@ -15,7 +16,7 @@ use crate::{
     state::{
         ADD_JAR_NAME, AutoSaveKind, CustomJarState, EditInstanceMessage, LaunchTab, Launcher,
         MainMenuMessage, MenuCreateInstance, MenuEditInstance, MenuLaunch, Message, NONE_JAR_NAME,
-        OPEN_FOLDER_JAR_NAME, ProgressBar, REMOVE_JAR_NAME, State, dir_watch, get_entries,
+        OPEN_FOLDER_JAR_NAME, ProgressBar, REMOVE_JAR_NAME, State, dir_watch, list_instances,
     },
 };
 
@@ -97,6 +98,20 @@ impl Launcher {
                     });
                 }
             }
+            EditInstanceMessage::DetectSystemJava => {
+                return Ok(Task::perform(ql_instances::detect_system_java(), |n| {
+                    EditInstanceMessage::SystemJavaDetected(n).into()
+                }));
+            }
+            EditInstanceMessage::SystemJavaDetected(found) => {
+                if let State::Launch(MenuLaunch {
+                    edit_instance: Some(menu),
+                    ..
+                }) = &mut self.state
+                {
+                    menu.system_java = Some(found);
+                }
+            }
             EditInstanceMessage::MemoryChanged(new_slider_value) => {
                 if let State::Launch(MenuLaunch {
                     edit_instance: Some(menu),
@@ -116,7 +131,7 @@ impl Launcher {
                 }) = &mut self.state
                 {
                     if let Ok(mb) = input.parse::<usize>() {
-                        if mb > 0 {
+                        if mb >= MIN_RAM_MB_FOR_INSTANCE {
                             menu.config.ram_in_mb = mb;
                             menu.slider_value = f32::log2(mb as f32);
                             menu.slider_text = format_memory(mb);
@@ -128,11 +143,32 @@ impl Launcher {
             EditInstanceMessage::LoggingToggle(t) => iflet_config!(&mut self.state, config <- {
                 config.enable_logger = Some(t);
             }),
+            EditInstanceMessage::AutoUpdateModsToggle(t) => {
+                iflet_config!(&mut self.state, config <- {
+                    config.auto_update_mods = Some(t);
+                })
+            }
+            EditInstanceMessage::PrivateAssetsToggle(t) => {
+                iflet_config!(&mut self.state, config <- {
+                    config.private_assets = Some(t);
+                })
+            }
+            EditInstanceMessage::DefaultAccountChanged(key) => {
+                let uuid = self.accounts.get(&key).map(|n| n.uuid.clone());
+                iflet_config!(&mut self.state, default_account, {
+                    *default_account = uuid;
+                });
+            }
             EditInstanceMessage::JavaArgsModeChanged(mode) => {
                 iflet_config!(&mut self.state, global_java_args_enable, {
                     *global_java_args_enable = Some(mode);
                 });
             }
+            EditInstanceMessage::JavaFlagsPresetChanged(preset) => {
+                iflet_config!(&mut self.state, java_flags_preset, {
+                    *java_flags_preset = Some(preset);
+                });
+            }
             EditInstanceMessage::JavaArgs(msg) => {
                 let split = self.should_split_args();
                 iflet_config!(&mut self.state, java_args, {
@@ -298,6 +334,7 @@ impl Launcher {
                 memory_input: memory_mb.to_string(),
                 is_editing_name: false,
                 arg_split_by_space: true,
+                system_java: None,
             });
             Ok(())
         }
@@ -421,11 +458,6 @@ impl Launcher {
         };
 
         let sanitized_name = sanitize_instance_name(menu.instance_name.clone());
-        if sanitized_name.is_empty() {
-            err!("New name is empty or invalid");
-            return Ok(Task::none());
-        }
-
         if *menu.old_instance_name == sanitized_name
             || *menu.old_instance_name == menu.instance_name
         {
@@ -434,6 +466,20 @@ impl Launcher {
             return Ok(Task::none());
         }
 
+        let existing: Vec<String> = match self.selected_instance.as_ref().map(|n| n.kind) {
+            Some(InstanceKind::Server) => self.server_list.as_ref(),
+            _ => self.client_list.as_ref(),
+        }
+        .into_iter()
+        .flatten()
+        .filter(|n| **n != *menu.old_instance_name)
+        .cloned()
+        .collect();
+        if let Err(error) = validate_instance_name(&sanitized_name, &existing) {
+            err!("Couldn't rename instance: {error}");
+            return Ok(Task::none());
+        }
+
         let instances_dir =
             LAUNCHER_DIR.join(if self.selected_instance.as_ref().unwrap().is_server() {
                 "servers"
@@ -465,7 +511,7 @@ impl Launcher {
             );
         }
 
-        Ok(Task::perform(get_entries(self.instance().kind), move |n| {
+        Ok(Task::perform(list_instances(), move |n| {
             Message::Multiple(vec![
                 Message::CoreListLoaded(n),
                 MainMenuMessage::InstanceSelected(instance.clone()).into(),
@@ -484,14 +530,19 @@ impl EditInstanceMessage {
             EditInstanceMessage::RenameEdit(_) |
             EditInstanceMessage::RenameApply | // ?
             EditInstanceMessage::CustomJarLoaded(_) |
+            EditInstanceMessage::DetectSystemJava |
+            EditInstanceMessage::SystemJavaDetected(_) |
             EditInstanceMessage::ConfigSaved(_) => false,
 
             EditInstanceMessage::MemoryChanged(_) |
             EditInstanceMessage::MemoryInputChanged(_) |
             EditInstanceMessage::LoggingToggle(_) |
+            EditInstanceMessage::AutoUpdateModsToggle(_) |
+            EditInstanceMessage::PrivateAssetsToggle(_) |
             EditInstanceMessage::SetMainClass(_, _) |
             EditInstanceMessage::JavaArgs(_) |
             EditInstanceMessage::JavaArgsModeChanged(_) |
+            EditInstanceMessage::JavaFlagsPresetChanged(_) |
             EditInstanceMessage::GameArgs(_) |
             EditInstanceMessage::PreLaunchPrefix(_) |
             EditInstanceMessage::PreLaunchPrefixModeChanged(_) |
@@ -500,6 +551,7 @@ impl EditInstanceMessage {
             EditInstanceMessage::WindowWidthChanged(_) |
             EditInstanceMessage::WindowHeightChanged(_) |
             EditInstanceMessage::CustomJarPathChanged(_) |
+            EditInstanceMessage::DefaultAccountChanged(_) |
             EditInstanceMessage::BrowseJavaOverride => true,
         }
     }
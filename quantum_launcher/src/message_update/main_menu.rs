@@ -1,11 +1,14 @@
-use std::process::ExitStatus;
+use std::{process::ExitStatus, time::Duration};
 
 use iced::{Task, futures::executor::block_on};
 use ql_core::{
-    Instance, InstanceKind, IntoStringError, LaunchedProcess, err, info, pt,
+    Instance, InstanceKind, IntoStringError, LaunchedProcess, RunningProcess, err, info,
+    json::InstanceConfigJson,
+    pt,
     read_log::{Diagnostic, ReadError},
 };
-use ql_instances::auth::AccountData;
+use ql_instances::{LaunchOptions, auth::AccountData};
+use ql_mod_manager::store::IntegrityWarning;
 use tokio::io::AsyncWriteExt;
 
 use crate::{
@@ -13,11 +16,14 @@ use crate::{
     message_handler::{SIDEBAR_LIMIT_LEFT, SIDEBAR_LIMIT_RIGHT},
     state::{
         AutoSaveKind, GameProcess, InfoMessage, LaunchMessage, LaunchModal, LaunchTab, Launcher,
-        MainMenuMessage, MenuLaunch, Message, OFFLINE_ACCOUNT_NAME, ProgressBar, SidebarMessage,
-        State,
+        MainMenuMessage, MenuLaunch, Message, ProgressBar, SidebarMessage, State,
     },
 };
 
+/// How long the game gets to save and
+/// quit on its own when stopped, before it's killed.
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
 impl Launcher {
     pub fn update_launch(&mut self, msg: LaunchMessage) -> Task<Message> {
         match msg {
@@ -28,31 +34,92 @@ impl Launcher {
             LaunchMessage::GameExited(Ok((status, instance, diagnostic))) => {
                 self.set_game_exited(status, &instance, diagnostic)
             }
-            LaunchMessage::Start => self.launch_start(),
+            LaunchMessage::Start => self.launch_start(LaunchOptions::default()),
+            LaunchMessage::StartWith(options) => {
+                if let State::Launch(menu) = &mut self.state {
+                    menu.modal = None;
+                }
+                self.launch_start(options)
+            }
+            LaunchMessage::ServersLoaded(result) => {
+                let servers = result.unwrap_or_else(|err| {
+                    err!(no_log, "Couldn't read multiplayer servers: {err}");
+                    Vec::new()
+                });
+                if let State::Launch(menu) = &mut self.state {
+                    menu.multiplayer_servers = servers;
+                }
+                Task::none()
+            }
             LaunchMessage::End(result) => self.finish_launching(result),
             LaunchMessage::Kill => self.kill_selected_instance(),
+            LaunchMessage::IntegrityChecked(warnings) => {
+                self.show_integrity_warnings(&warnings);
+                Task::none()
+            }
+            LaunchMessage::FoundRunning(found) => {
+                self.detached_processes = found
+                    .into_iter()
+                    .filter(|(instance, _)| !self.processes.contains_key(instance))
+                    .collect();
+                Task::none()
+            }
+            LaunchMessage::DetachedExited(instance, options) => {
+                self.detached_processes.remove(&instance);
+                if self.selected_instance.as_ref() == Some(&instance) {
+                    self.launch_start(options)
+                } else {
+                    Task::none()
+                }
+            }
         }
     }
 
-    fn launch_start(&mut self) -> Task<Message> {
+    fn launch_start(&mut self, options: LaunchOptions) -> Task<Message> {
         let Some(selected_instance) = &self.selected_instance else {
             return Task::none();
         };
         if self.processes.contains_key(selected_instance) {
             return Task::none();
         }
+        if self.detached_processes.contains_key(selected_instance) {
+            // Might have exited since we last checked
+            let instance = selected_instance.clone();
+            return Task::perform(
+                async move {
+                    let exited = RunningProcess::find(&instance).await.is_none();
+                    exited.then_some((instance, options))
+                },
+                |n| {
+                    n.map_or(Message::Nothing, |(instance, options)| {
+                        LaunchMessage::DetachedExited(instance, options).into()
+                    })
+                },
+            );
+        }
         self.logs.remove(selected_instance);
 
         match selected_instance.kind {
             InstanceKind::Client => {
-                if self.account_selected == OFFLINE_ACCOUNT_NAME
+                let default_account = block_on(InstanceConfigJson::read(selected_instance))
+                    .ok()
+                    .and_then(|n| n.default_account);
+                let selected_account = self.get_selected_account_data();
+                let account_data = ql_instances::auth::pick_launch_account(
+                    self.accounts.values(),
+                    default_account.as_deref(),
+                    selected_account.as_ref(),
+                )
+                .cloned();
+
+                if account_data.is_none()
                     && (self.config.username.is_empty() || self.config.username.contains(' '))
                 {
                     return Task::none();
                 }
 
                 self.is_launching_game = true;
-                let account_data = self.get_selected_account_data();
+                self.launch_options = options;
                 // If the user is loading an existing login from disk
                 // then first refresh the tokens
                 if let Some(account) = &account_data {
@@ -90,19 +157,49 @@ impl Launcher {
 
         let global_settings = self.config.global_settings.clone();
         let extra_java_args = self.config.extra_java_args.clone().unwrap_or_default();
+        let options = std::mem::take(&mut self.launch_options);
 
-        let instance_name = self.instance().name.clone();
-        Task::perform(
-            ql_instances::launch(
-                instance_name,
-                username,
-                Some(sender),
-                account_data,
-                global_settings,
-                extra_java_args,
-            ),
+        let instance = self.instance().clone();
+        let integrity_task = Task::perform(
+            async move { ql_mod_manager::store::check_integrity(&instance).await },
+            |n| LaunchMessage::IntegrityChecked(n).into(),
+        );
+
+        let instance = self.instance().clone();
+        let launch_task = Task::perform(
+            async move {
+                ql_mod_manager::store::update_before_launch(&instance).await;
+                ql_instances::launch(
+                    instance.name,
+                    username,
+                    Some(sender),
+                    account_data,
+                    global_settings,
+                    extra_java_args,
+                    options,
+                )
+                .await
+            },
             |n| LaunchMessage::End(n.strerr()).into(),
-        )
+        );
+        Task::batch([integrity_task, launch_task])
+    }
+
+    /// Doesn't stop the launch, just gives a hint
+    /// in case the game crashes.
+    fn show_integrity_warnings(&mut self, warnings: &[IntegrityWarning]) {
+        if warnings.is_empty() {
+            return;
+        }
+        let mut text = "Warning: this instance may crash on launch".to_owned();
+        for warning in warnings {
+            err!("{warning}");
+            text.push_str("\n- ");
+            text.push_str(&warning.to_string());
+        }
+        if let State::Launch(MenuLaunch { message, .. }) = &mut self.state {
+            *message = Some(InfoMessage::error(text));
+        }
     }
 
     fn set_game_exited(
@@ -229,9 +326,29 @@ impl Launcher {
         };
         match instance.kind {
             InstanceKind::Client => {
-                if let Some(process) = self.processes.remove(instance) {
-                    let mut child = block_on(process.child.child.lock());
-                    _ = child.start_kill();
+                if let Some(process) = self.detached_processes.remove(instance) {
+                    let instance = instance.clone();
+                    return Task::perform(
+                        async move {
+                            let result = process.kill(KILL_GRACE_PERIOD).await;
+                            RunningProcess::remove(&instance).await;
+                            result
+                        },
+                        |n| match n {
+                            Ok(()) => Message::Nothing,
+                            Err(err) => Message::Error(err.to_string()),
+                        },
+                    );
+                }
+                // Removed once it's exited, in `LaunchMessage::GameExited`
+                if let Some(process) = self.processes.get(instance) {
+                    return Task::perform(
+                        ql_core::terminate_client(process.child.child.clone(), KILL_GRACE_PERIOD),
+                        |n| match n {
+                            Ok(()) => Message::Nothing,
+                            Err(err) => Message::Error(err.to_string()),
+                        },
+                    );
                 }
             }
             InstanceKind::Server => {
@@ -291,6 +408,25 @@ impl Launcher {
                         ) => None,
                         _ => modal.clone(),
                     };
+
+                    // Servers to offer joining directly
+                    menu.multiplayer_servers.clear();
+                    if let (Some(LaunchModal::InstanceOptions), Some(instance)) =
+                        (&menu.modal, &self.selected_instance)
+                    {
+                        if !instance.is_server() {
+                            let instance = instance.clone();
+                            return Task::batch([
+                                t,
+                                Task::perform(
+                                    async move {
+                                        ql_instances::read_multiplayer_servers(&instance).await
+                                    },
+                                    |n| LaunchMessage::ServersLoaded(n.strerr()).into(),
+                                ),
+                            ]);
+                        }
+                    }
                     return t;
                 }
             }
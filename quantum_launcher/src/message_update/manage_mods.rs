@@ -1,16 +1,25 @@
 use iced::{Task, widget};
 use iced::{futures::executor::block_on, keyboard::Modifiers};
 use ql_core::file_utils::exists;
-use ql_core::{Instance, IntoIoError, IntoStringError, err, jarmod::JarMods};
-use ql_mod_manager::store::{ModId, ModIndex, SelectedMod};
+use ql_core::{CancellationToken, Instance, IntoIoError, IntoStringError, err, jarmod::JarMods};
+use ql_mod_manager::store::{CurseforgeNotAllowed, ModId, ModIndex, PackError, SelectedMod};
 use std::{collections::HashSet, path::PathBuf};
 
 use crate::state::{
     AutoSaveKind, ExportModsMessage, InfoMessage, InfoMessageKind, Launcher, ManageJarModsMessage,
     ManageModsMessage, MenuCurseforgeManualDownload, MenuEditJarMods, MenuEditMods,
-    MenuEditModsModal, Message, ProgressBar, SelectedState, State,
+    MenuEditModsModal, MenuImportModpack, Message, ProgressBar, SelectedState, State,
 };
 
+/// Result of [`ql_mod_manager::add_files`]. If the user
+/// cancelled it, goes back to the mods menu instead of an error.
+pub fn add_file_done(result: Result<HashSet<CurseforgeNotAllowed>, PackError>) -> Message {
+    match result {
+        Err(PackError::Cancelled(_)) => ManageModsMessage::Open.into(),
+        result => ManageModsMessage::AddFileDone(result.strerr()).into(),
+    }
+}
+
 impl Launcher {
     pub fn update_manage_mods(&mut self, msg: ManageModsMessage) -> Task<Message> {
         match msg {
@@ -56,6 +65,13 @@ impl Launcher {
             ManageModsMessage::AddFile(delete_file) => {
                 return self.add_file_select(delete_file);
             }
+            ManageModsMessage::AddFileCancel => {
+                if let State::ImportModpack(MenuImportModpack { cancel, .. }) = &mut self.state {
+                    if let Some(cancel) = cancel.take() {
+                        cancel.cancel();
+                    }
+                }
+            }
             ManageModsMessage::AddFileDone(Ok(not_allowed)) => {
                 if !not_allowed.is_empty() {
                     self.state = State::CurseforgeManualDownload(MenuCurseforgeManualDownload {
@@ -68,7 +84,7 @@ impl Launcher {
             ManageModsMessage::DeleteSelected => {
                 if let State::EditMods(menu) = &mut self.state {
                     let selected_instance = self.selected_instance.clone().unwrap();
-                    let mods_dir = selected_instance.get_dot_minecraft_path().join("mods");
+                    let mods_dir = menu.config.get_game_dir(&selected_instance).join("mods");
                     let command = Self::get_delete_mods_command(selected_instance, menu);
 
                     let local_mods_paths: Vec<&String> = menu
@@ -101,8 +117,10 @@ impl Launcher {
                 }
             }
             ManageModsMessage::DeleteOptiforge(name) => {
-                let mods_dir = self.instance().get_dot_minecraft_path().join("mods");
+                let instance = self.instance().clone();
+                let mut mods_dir = instance.get_dot_minecraft_path().join("mods");
                 if let State::EditMods(menu) = &mut self.state {
+                    mods_dir = menu.config.get_game_dir(&instance).join("mods");
                     menu.locally_installed_mods.remove(&name);
                     if let Some(mod_info) = &mut menu.config.mod_type_info {
                         if mod_info.optifine_jar.as_ref().is_some_and(|n| n == &name) {
@@ -408,15 +426,20 @@ impl Launcher {
         };
 
         let (sender, receiver) = std::sync::mpsc::channel();
-        self.state = State::ImportModpack(ProgressBar::with_recv(receiver));
+        let cancel = CancellationToken::new();
+        self.state = State::ImportModpack(MenuImportModpack {
+            progress: ProgressBar::with_recv(receiver),
+            cancel: Some(cancel.clone()),
+        });
 
         let files_task = Task::perform(
             ql_mod_manager::add_files(
                 self.selected_instance.clone().unwrap(),
                 paths.clone(),
                 Some(sender),
+                Some(cancel),
             ),
-            move |n| ManageModsMessage::AddFileDone(n.strerr()).into(),
+            add_file_done,
         );
         if delete_file {
             files_task.chain(Task::perform(
@@ -681,21 +704,22 @@ impl Launcher {
     }
 
     fn manage_jarmods_add_file_from_picker(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
+        let Some(path) = rfd::FileDialog::new()
             .add_filter("jar/zip", &["jar", "zip"])
             .set_title("Pick a Jar Mod Patch (.jar/.zip)")
             .pick_file()
-        {
-            if let Some(filename) = path.file_name() {
-                let dest = self
-                    .instance()
-                    .get_instance_path()
-                    .join("jarmods")
-                    .join(filename);
-                if let Err(err) = std::fs::copy(&path, dest) {
-                    self.set_error(format!("While picking jar mod to be added: {err}"));
-                }
-            }
+        else {
+            return;
+        };
+        let State::EditJarMods(menu) = &mut self.state else {
+            return;
+        };
+        if let Err(err) = Self::load_jarmods_from_path(
+            self.selected_instance.as_ref().unwrap(),
+            &path,
+            &mut menu.jarmods,
+        ) {
+            self.set_error(format!("While picking jar mod to be added: {err}"));
         }
     }
 
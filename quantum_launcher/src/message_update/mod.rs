@@ -16,7 +16,7 @@ mod presets;
 mod recommended;
 mod shortcuts;
 
-use crate::config::UiWindowDecorations;
+use crate::config::{UiWindowDecorations, UpdateChannel};
 use crate::state::{
     self, AutoSaveKind, GameLogMessage, InfoMessage, InstallFabricMessage, InstallOptifineMessage,
     InstallPaperMessage, InstanceNotes, Launcher, LauncherSettingsMessage, LauncherSettingsTab,
@@ -25,6 +25,7 @@ use crate::state::{
 };
 
 pub use discord_rpc::PresenceConnectionState;
+pub use manage_mods::add_file_done;
 
 pub const MSG_RESIZE: &str = "Resize your window to apply the changes.";
 
@@ -310,6 +311,14 @@ impl Launcher {
             LauncherSettingsMessage::ToggleModUpdateChangelog(t) => {
                 self.config.c_persistent().write_mod_update_changelog = t;
             }
+            LauncherSettingsMessage::ToggleBetaUpdates(t) => {
+                self.config.update_channel = Some(if t {
+                    UpdateChannel::Beta
+                } else {
+                    UpdateChannel::Stable
+                });
+                self.autosave.remove(&AutoSaveKind::LauncherConfig);
+            }
             LauncherSettingsMessage::AfterLaunchBehaviorChanged(behavior) => {
                 self.config.ui.get_or_insert_default().after_game_opens = behavior;
                 self.autosave.remove(&AutoSaveKind::LauncherConfig);
@@ -324,6 +333,23 @@ impl Launcher {
                 let split = self.should_split_args();
                 msg.apply(self.config.extra_java_args.get_or_insert_default(), split);
             }
+            LauncherSettingsMessage::TemplateRamChanged(input) => {
+                self.config.c_new_instance_template().ram_in_mb =
+                    input.trim().parse::<usize>().ok().filter(|n| *n > 0);
+            }
+            LauncherSettingsMessage::TemplateJavaArgs(msg) => {
+                let split = self.should_split_args();
+                msg.apply(
+                    self.config
+                        .c_new_instance_template()
+                        .java_args
+                        .get_or_insert_default(),
+                    split,
+                );
+            }
+            LauncherSettingsMessage::TemplateToggleDownloadAssets(t) => {
+                self.config.c_new_instance_template().download_assets = Some(t);
+            }
             LauncherSettingsMessage::GlobalPreLaunchPrefix(msg) => {
                 let split = self.should_split_args();
                 msg.apply(
@@ -2,15 +2,16 @@ use std::{collections::HashMap, time::Instant};
 
 use iced::{Task, futures::executor::block_on, widget::scrollable::AbsoluteOffset};
 use ql_core::{
-    InstanceConfigJson, InstanceKind, IntoStringError, JsonFileError, err, json::VersionDetails,
+    CancellationToken, InstanceConfigJson, InstanceKind, IntoStringError, JsonFileError, err,
+    json::VersionDetails,
 };
 use ql_mod_manager::store::{
-    self, ModId, ModIndex, Query, QueryType, StoreBackendType, get_description,
+    self, ModError, ModId, ModIndex, PackError, Query, QueryType, StoreBackendType, get_description,
 };
 
 use crate::state::{
-    InstallModsMessage, Launcher, MenuCurseforgeManualDownload, MenuModsDownload, Message,
-    ModCategoryState, ModOperation, ProgressBar, State,
+    InstallModsMessage, Launcher, MenuCurseforgeManualDownload, MenuImportModpack,
+    MenuModsDownload, Message, ModCategoryState, ModOperation, ProgressBar, State,
 };
 
 impl Launcher {
@@ -239,17 +240,27 @@ impl Launcher {
 
             InstallModsMessage::InstallModpack(id) => {
                 let (sender, receiver) = std::sync::mpsc::channel();
-                self.state = State::ImportModpack(ProgressBar::with_recv(receiver));
+                let cancel = CancellationToken::new();
+                self.state = State::ImportModpack(MenuImportModpack {
+                    progress: ProgressBar::with_recv(receiver),
+                    cancel: Some(cancel.clone()),
+                });
 
                 let selected_instance = self.selected_instance.clone().unwrap();
 
                 return Task::perform(
                     async move {
-                        store::download_mod(&id, &selected_instance, Some(sender))
+                        store::download_mod(&id, &selected_instance, Some(sender), Some(&cancel))
                             .await
                             .map(|not_allowed| (id, not_allowed))
                     },
-                    |n| InstallModsMessage::DownloadComplete(n.strerr()).into(),
+                    |n| match n {
+                        // Cancelled by the user, back to the store
+                        Err(ModError::Pack(err)) if matches!(*err, PackError::Cancelled(_)) => {
+                            InstallModsMessage::Open.into()
+                        }
+                        n => InstallModsMessage::DownloadComplete(n.strerr()).into(),
+                    },
                 );
             }
             InstallModsMessage::Uninstall(index) => {
@@ -361,7 +372,7 @@ impl Launcher {
         } else {
             Task::perform(
                 async move {
-                    store::download_mod(&id, &selected_instance, None)
+                    store::download_mod(&id, &selected_instance, None, None)
                         .await
                         .map(|not_allowed| (id, not_allowed))
                 },
@@ -0,0 +1,160 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+
+use notify::Watcher;
+use ql_core::{Instance, InstanceKind, IntoIoError, IoError, LAUNCHER_DIR, err};
+
+/// Events are collected until nothing happens for this long,
+/// so copying in an instance (thousands of events) is one change.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// An instance/server folder that was added or
+/// removed externally (or by the launcher itself).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstanceChange {
+    Added(Instance),
+    Removed(Instance),
+}
+
+/// Keeps watching the instance folders until dropped.
+pub struct InstanceWatcher {
+    _watchers: Vec<notify::RecommendedWatcher>,
+}
+
+/// Watches the `instances` and `servers` folders, sending an
+/// [`InstanceChange`] to `tx` whenever one is added or removed.
+pub fn watch_instances(tx: Sender<InstanceChange>) -> Result<InstanceWatcher, IoError> {
+    watch_instances_in(&LAUNCHER_DIR, tx)
+}
+
+fn watch_instances_in(
+    launcher_dir: &Path,
+    tx: Sender<InstanceChange>,
+) -> Result<InstanceWatcher, IoError> {
+    let (raw_tx, raw_rx) = mpsc::channel();
+    let mut watchers = Vec::new();
+    let mut roots = Vec::new();
+
+    for kind in [InstanceKind::Client, InstanceKind::Server] {
+        let root = launcher_dir.join(kind.get_dir_name());
+        std::fs::create_dir_all(&root).path(&root)?;
+
+        let raw_tx = raw_tx.clone();
+        // `notify` runs callbacks in its own thread.
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if res.is_ok() {
+                _ = raw_tx.send(());
+            }
+        })
+        .map_err(std::io::Error::other)
+        .path(&root)?;
+        watcher
+            .watch(&root, notify::RecursiveMode::NonRecursive)
+            .map_err(std::io::Error::other)
+            .path(&root)?;
+
+        watchers.push(watcher);
+        let existing = list_dirs(&root);
+        roots.push((kind, root, existing));
+    }
+
+    std::thread::spawn(move || send_changes(&raw_rx, &mut roots, &tx));
+
+    Ok(InstanceWatcher {
+        _watchers: watchers,
+    })
+}
+
+/// For each burst of events from `raw_rx`, sends what was
+/// added to/removed from `roots` (kind, folder, known entries).
+///
+/// Returns once `raw_rx` or `tx` is disconnected.
+fn send_changes(
+    raw_rx: &Receiver<()>,
+    roots: &mut [(InstanceKind, PathBuf, HashSet<String>)],
+    tx: &Sender<InstanceChange>,
+) {
+    // Wait for the first event, then until things calm down
+    while raw_rx.recv().is_ok() {
+        while raw_rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+        // Events only tell us *something* changed,
+        // compare the folder contents to find out what.
+        for (kind, root, known) in roots.iter_mut() {
+            let current = list_dirs(root);
+            let added = current
+                .difference(known)
+                .map(|n| InstanceChange::Added(Instance::new(n, *kind)));
+            let removed = known
+                .difference(&current)
+                .map(|n| InstanceChange::Removed(Instance::new(n, *kind)));
+            for change in added.chain(removed) {
+                if tx.send(change).is_err() {
+                    // Nobody's listening anymore
+                    return;
+                }
+            }
+            *known = current;
+        }
+    }
+}
+
+fn list_dirs(root: &Path) -> HashSet<String> {
+    match std::fs::read_dir(root) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter(|n| n.path().is_dir())
+            .filter_map(|n| n.file_name().to_str().map(str::to_owned))
+            .collect(),
+        Err(error) => {
+            err!("Couldn't read {root:?}: {error}");
+            HashSet::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_are_sent() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("instances/Old Instance")).unwrap();
+        std::fs::create_dir_all(dir.join("servers")).unwrap();
+        let mut roots: Vec<_> = [InstanceKind::Client, InstanceKind::Server]
+            .into_iter()
+            .map(|kind| {
+                let root = dir.join(kind.get_dir_name());
+                let known = list_dirs(&root);
+                (kind, root, known)
+            })
+            .collect();
+
+        std::fs::remove_dir(dir.join("instances/Old Instance")).unwrap();
+        std::fs::create_dir(dir.join("servers/New Server")).unwrap();
+        std::fs::write(dir.join("servers/not_a_server.txt"), "").unwrap();
+
+        // Stands in for `notify`: one burst of events, then it's gone
+        let (raw_tx, raw_rx) = mpsc::channel();
+        raw_tx.send(()).unwrap();
+        raw_tx.send(()).unwrap();
+        drop(raw_tx);
+
+        let (tx, rx) = mpsc::channel();
+        send_changes(&raw_rx, &mut roots, &tx);
+        let changes: Vec<_> = rx.try_iter().collect();
+        assert_eq!(
+            changes,
+            [
+                InstanceChange::Removed(Instance::new("Old Instance", InstanceKind::Client)),
+                InstanceChange::Added(Instance::new("New Server", InstanceKind::Server)),
+            ]
+        );
+    }
+}
@@ -20,8 +20,8 @@ use iced::{
     widget::{self, scrollable::AbsoluteOffset},
 };
 use ql_core::{
-    DownloadProgress, GenericProgress, Instance, InstanceKind, IntoStringError, ListEntry,
-    OptifineUniqueVersion,
+    CancellationToken, DownloadProgress, GenericProgress, Instance, InstanceKind, IntoStringError,
+    ListEntry, OptifineUniqueVersion,
     file_utils::DirItem,
     jarmod::JarMods,
     json::{InstanceConfigJson, VersionDetails, instance_config::MainClassMode},
@@ -112,6 +112,12 @@ pub struct MenuLaunch {
     sidebar_split: Option<widget::pane_grid::Split>,
 
     pub is_uploading_mclogs: bool,
+    /// The selected instance's multiplayer servers,
+    /// offered for quick play in [`LaunchModal::InstanceOptions`].
+    pub multiplayer_servers: Vec<ql_instances::ServerEntry>,
+    /// The alias being typed in for the selected
+    /// account, while it's being renamed.
+    pub account_alias: Option<String>,
 }
 
 impl Default for MenuLaunch {
@@ -157,6 +163,8 @@ impl MenuLaunch {
             sidebar_grid_state,
             log_state: None,
             is_uploading_mclogs: false,
+            multiplayer_servers: Vec::new(),
+            account_alias: None,
             sidebar_split,
             notes: None,
             modal: None,
@@ -203,6 +211,8 @@ pub struct MenuEditInstance {
 
     pub main_class_mode: Option<MainClassMode>,
     pub arg_split_by_space: bool,
+    /// Java installs already on the system, `None` if not searched yet
+    pub system_java: Option<Vec<ql_instances::DetectedJava>>,
 }
 
 pub enum SelectedState {
@@ -340,7 +350,7 @@ impl MenuEditMods {
             }
         }
         Task::perform(
-            get_locally_installed_mods(selected_instance.get_dot_minecraft_path(), blacklist),
+            get_locally_installed_mods(selected_instance.clone(), blacklist),
             |n| ManageModsMessage::LocalIndexLoaded(n).into(),
         )
     }
@@ -430,6 +440,9 @@ pub struct MenuCreateInstanceChoosing {
     pub selected_version: ListEntry,
     pub instance_name: String,
     pub download_assets: bool,
+    /// Download size of the selected version (in bytes),
+    /// `None` while loading or if unknown.
+    pub estimated_size: Option<u64>,
 }
 
 pub enum MenuInstallFabric {
@@ -476,6 +489,7 @@ pub struct MenuInstallForge {
 #[allow(unused)]
 pub struct MenuLauncherUpdate {
     pub url: String,
+    pub sha256: Option<String>,
     pub progress: Option<ProgressBar<GenericProgress>>,
 }
 
@@ -655,6 +669,12 @@ pub struct MenuCurseforgeManualDownload {
     pub delete_mods: bool,
 }
 
+pub struct MenuImportModpack {
+    pub progress: ProgressBar<GenericProgress>,
+    /// `None` if it can't be cancelled (or already was)
+    pub cancel: Option<CancellationToken>,
+}
+
 pub struct MenuExportInstance {
     pub entries: Option<Vec<(DirItem, bool)>>,
     pub progress: Option<ProgressBar<GenericProgress>>,
@@ -712,7 +732,7 @@ pub enum State {
     EditMods(MenuEditMods),
     ExportMods(MenuExportMods),
     EditJarMods(MenuEditJarMods),
-    ImportModpack(ProgressBar<GenericProgress>),
+    ImportModpack(MenuImportModpack),
     CurseforgeManualDownload(MenuCurseforgeManualDownload),
     ExportInstance(MenuExportInstance),
 
@@ -12,10 +12,10 @@ use crate::{
 use filthy_rich::PresenceClient;
 use iced::widget::{self, scrollable::AbsoluteOffset};
 use ql_core::{
-    Instance, InstanceKind, LaunchedProcess, ListEntry, Loader,
+    Instance, InstanceKind, InstanceSummary, LaunchedProcess, ListEntry, Loader, RunningProcess,
     file_utils::DirItem,
     jarmod::JarMods,
-    json::instance_config::{MainClassMode, PreLaunchPrefixMode},
+    json::instance_config::{JavaFlagsPreset, MainClassMode, PreLaunchPrefixMode},
     read_log::Diagnostic,
 };
 use ql_instances::auth::{
@@ -61,6 +61,8 @@ pub enum CreateInstanceMessage {
     NameInput(String),
     ChangeAssetToggle(bool),
     ChangeKind(InstanceKind),
+    /// Version, whether assets are downloaded, and the download size.
+    SizeEstimated(ListEntry, bool, Res<u64>),
 
     SearchInput(String),
     SearchSubmit,
@@ -69,6 +71,9 @@ pub enum CreateInstanceMessage {
 
     Start,
     End(Res<Instance>),
+    /// Server created, but with warnings to show first.
+    EndWithWarnings(Instance, Vec<ql_servers::ServerWarning>),
+    AllowOfflineAccounts(Instance),
 
     #[allow(unused)]
     Import,
@@ -81,16 +86,21 @@ pub enum EditInstanceMessage {
     ReinstallLibraries,
     UpdateAssets,
     BrowseJavaOverride,
+    DetectSystemJava,
+    SystemJavaDetected(Vec<ql_instances::DetectedJava>),
 
     JavaOverride(String),
     JavaOverrideVersion(usize),
     MemoryChanged(f32),
     MemoryInputChanged(String),
     LoggingToggle(bool),
+    AutoUpdateModsToggle(bool),
+    PrivateAssetsToggle(bool),
     SetMainClass(Option<MainClassMode>, Option<String>),
 
     JavaArgs(ListMessage),
     JavaArgsModeChanged(bool),
+    JavaFlagsPresetChanged(JavaFlagsPreset),
     GameArgs(ListMessage),
     ToggleSplitArg(bool),
 
@@ -106,6 +116,9 @@ pub enum EditInstanceMessage {
 
     CustomJarPathChanged(String),
     CustomJarLoaded(Res<Vec<String>>),
+
+    /// Account key, or [`crate::state::NO_DEFAULT_ACCOUNT`]
+    DefaultAccountChanged(String),
 }
 
 #[derive(Debug, Clone)]
@@ -138,6 +151,9 @@ pub enum ManageModsMessage {
     /// The field represents whether to delete the file after importing it.
     AddFile(bool),
     AddFileDone(Res<HashSet<CurseforgeNotAllowed>>),
+    /// Stops installing a modpack,
+    /// undoing what was installed so far.
+    AddFileCancel,
 
     SelectAll,
     SetModal(Option<MenuEditModsModal>),
@@ -250,7 +266,17 @@ pub enum AccountMessage {
     Response3(Res<AccountData>),
     LogoutCheck,
     LogoutConfirm,
+    /// Start renaming (setting the alias of) the selected account
+    AliasEdit,
+    AliasInput(String),
+    AliasSave,
     RefreshComplete(Res<AccountData>),
+    /// An ely.by/littleskin account needs to
+    /// log in again with a 2FA code.
+    AltRefreshNeedsOTP {
+        username: String,
+        is_littleskin: bool,
+    },
 
     OpenMenu {
         is_from_welcome_screen: bool,
@@ -295,12 +321,18 @@ pub enum LauncherSettingsMessage {
     ToggleWindowSize(bool),
     ToggleInstanceRemembering(bool),
     ToggleModUpdateChangelog(bool),
+    #[allow(unused)]
+    ToggleBetaUpdates(bool),
     AfterLaunchBehaviorChanged(crate::config::AfterLaunchBehavior),
     #[allow(unused)]
     ToggleWindowDecorations(bool),
 
     GlobalJavaArgs(ListMessage),
     GlobalPreLaunchPrefix(ListMessage),
+
+    TemplateRamChanged(String),
+    TemplateJavaArgs(ListMessage),
+    TemplateToggleDownloadAssets(bool),
 }
 
 #[derive(Debug, Clone)]
@@ -348,30 +380,48 @@ impl RpcText {
 #[derive(Debug, Clone)]
 pub enum ListMessage {
     Add,
+    /// Typing into an entry (kept as-is until submitted)
     Edit(String, usize),
+    /// Pasting into an entry (the whole new text)
+    Paste(String, usize),
+    /// Pressing Enter in an entry
+    Submit(usize),
     Delete(usize),
     ShiftUp(usize),
     ShiftDown(usize),
 }
 
 impl ListMessage {
+    /// If `split` is enabled, pasting into an entry or pressing
+    /// Enter splits it into separate (unquoted) arguments,
+    /// respecting quotes (see [`ql_core::split_args`]).
+    ///
+    /// While typing, the entry is kept as-is, so a half-typed
+    /// quote doesn't get split. This also means pressing Enter in an
+    /// entry with spaces splits it again, unless it's re-quoted.
     pub fn apply(self, l: &mut Vec<String>, split: bool) {
         match self {
             ListMessage::Add => {
                 l.push(String::new());
             }
             ListMessage::Edit(msg, idx) => {
-                if split && msg.contains(' ') {
-                    l.remove(idx);
-                    let mut insert_idx = idx;
-                    for s in msg.split(' ').filter(|n| !n.is_empty()) {
-                        l.insert(insert_idx, s.to_owned());
-                        insert_idx += 1;
-                    }
-                } else if let Some(entry) = l.get_mut(idx) {
+                if let Some(entry) = l.get_mut(idx) {
                     *entry = msg;
                 }
             }
+            ListMessage::Paste(msg, idx) => {
+                if let Some(entry) = l.get_mut(idx) {
+                    *entry = msg;
+                }
+                if split {
+                    split_entry(l, idx);
+                }
+            }
+            ListMessage::Submit(idx) => {
+                if split {
+                    split_entry(l, idx);
+                }
+            }
             ListMessage::Delete(i) => {
                 if i < l.len() {
                     l.remove(i);
@@ -391,6 +441,16 @@ impl ListMessage {
     }
 }
 
+fn split_entry(l: &mut Vec<String>, idx: usize) {
+    let Some(entry) = l.get(idx) else {
+        return;
+    };
+    let args = ql_core::split_args(entry);
+    if !args.is_empty() {
+        l.splice(idx..=idx, args);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum NotesMessage {
     Loaded(Res<String>),
@@ -461,9 +521,16 @@ pub enum ModDescriptionMessage {
 #[derive(Debug, Clone)]
 pub enum LaunchMessage {
     Start,
+    StartWith(ql_instances::LaunchOptions),
+    ServersLoaded(Res<Vec<ql_instances::ServerEntry>>),
     End(Res<LaunchedProcess>),
     Kill,
     GameExited(Res<(ExitStatus, Instance, Option<Diagnostic>)>),
+    IntegrityChecked(Vec<ql_mod_manager::store::IntegrityWarning>),
+    FoundRunning(Vec<(Instance, RunningProcess)>),
+    /// A game from an earlier session, that was
+    /// in the way of launching, has since exited.
+    DetachedExited(Instance, ql_instances::LaunchOptions),
 }
 
 #[derive(Debug, Clone)]
@@ -526,11 +593,12 @@ pub enum Message {
     CoreOpenPath(PathBuf),
     CoreCopyText(String),
     CoreTick,
-    CoreListLoaded(Res<(Vec<String>, InstanceKind)>),
+    CoreListLoaded(Res<Vec<InstanceSummary>>),
     CoreOpenChangeLog,
     CoreOpenIntro,
     CoreEvent(iced::Event, iced::event::Status),
     CoreCleanComplete(Res),
+    CoreMigrateComplete(Res<ql_instances::MigrationReport>),
     CoreFocusNext,
     CoreTryQuit,
     CoreHideModal,
@@ -547,6 +615,8 @@ pub enum Message {
     UpdateDownloadStart,
     #[cfg(feature = "auto_update")]
     UpdateDownloadEnd(Res),
+    #[cfg(feature = "auto_update")]
+    UpdateRollback,
 
     ServerCommandEdit(String),
     ServerCommandSubmit,
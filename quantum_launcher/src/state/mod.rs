@@ -12,12 +12,14 @@ use filthy_rich::PresenceClient;
 use iced::Task;
 use notify::Watcher;
 use ql_core::{
-    GenericProgress, Instance, InstanceKind, IntoIoError, IntoStringError, IoError, JsonFileError,
-    LAUNCHER_DIR, LAUNCHER_VERSION_NAME, LaunchedProcess, Progress, err,
-    file_utils::{self, exists},
-    read_log::LogLine,
+    GenericProgress, Instance, InstanceKind, InstanceSummary, IntoIoError, IntoStringError,
+    IoError, JsonFileError, LAUNCHER_DIR, LAUNCHER_VERSION_NAME, LaunchedProcess, Progress,
+    RunningProcess, err, file_utils, read_log::LogLine,
+};
+use ql_instances::{
+    LaunchOptions,
+    auth::{AccountData, AccountType, ms::CLIENT_ID},
 };
-use ql_instances::auth::{AccountData, AccountType, ms::CLIENT_ID};
 use tokio::process::ChildStdin;
 
 use crate::{
@@ -27,14 +29,18 @@ use crate::{
 };
 
 mod images;
+mod instance_watch;
 mod menu;
 mod message;
 pub use images::ImageState;
+pub use instance_watch::{InstanceChange, InstanceWatcher, watch_instances};
 pub use menu::*;
 pub use message::*;
 
 pub const OFFLINE_ACCOUNT_NAME: &str = "(Offline)";
 pub const NEW_ACCOUNT_NAME: &str = "+ Add Account";
+/// Instance setting: launch with whichever account is selected
+pub const NO_DEFAULT_ACCOUNT: &str = "(Selected account)";
 
 pub const ADD_JAR_NAME: &str = "+ Add JAR";
 pub const REMOVE_JAR_NAME: &str = "- Remove Selected";
@@ -60,6 +66,9 @@ pub struct Launcher {
     pub log_scroll: isize,
     pub tick_timer: usize,
     pub is_launching_game: bool,
+    /// For the game being launched (kept while
+    /// its account is refreshed, before launching).
+    pub launch_options: LaunchOptions,
 
     pub discord_ipc_client: Option<PresenceClient>,
     pub discord_connection_state: Arc<Mutex<PresenceConnectionState>>,
@@ -75,10 +84,12 @@ pub struct Launcher {
 
     pub client_list: Option<Vec<String>>,
     pub server_list: Option<Vec<String>>,
-    pub client_watcher: Option<DirWatcher>,
-    pub server_watcher: Option<DirWatcher>,
+    pub instance_watcher: Option<(InstanceWatcher, Receiver<InstanceChange>)>,
 
     pub processes: HashMap<Instance, GameProcess>,
+    /// Games still running from an earlier launcher session
+    /// (see [`RunningProcess`]). Their logs can't be read.
+    pub detached_processes: HashMap<Instance, RunningProcess>,
     pub logs: HashMap<Instance, InstanceLog>,
 
     pub window_state: WindowState,
@@ -162,8 +173,8 @@ impl Launcher {
         launch.resize_sidebar(SIDEBAR_WIDTH);
         let launch = State::Launch(launch);
 
-        // The version field was added in 0.3
-        let version = config.version.as_deref().unwrap_or("0.3.0");
+        // Filled in by `LauncherConfig::load_s` for pre-v0.3 configs
+        let version = config.version.as_deref().unwrap_or(LAUNCHER_VERSION_NAME);
 
         let state = if is_new_user {
             State::Welcome(MenuWelcome::P1InitialScreen)
@@ -210,18 +221,19 @@ impl Launcher {
 
             client_list: None,
             server_list: None,
-            client_watcher: None,
-            server_watcher: None,
+            instance_watcher: None,
             java_recv: None,
             custom_jar: None,
 
             logs: HashMap::new(),
             processes: HashMap::new(),
+            detached_processes: HashMap::new(),
 
             keys_pressed: HashSet::new(),
 
             is_log_open: false,
             is_launching_game: false,
+            launch_options: LaunchOptions::default(),
 
             discord_ipc_client: None,
             discord_connection_state: Arc::new(Mutex::new(PresenceConnectionState::Uninitialized)),
@@ -270,13 +282,13 @@ impl Launcher {
             java_recv: None,
             client_list: None,
             server_list: None,
-            client_watcher: None,
-            server_watcher: None,
+            instance_watcher: None,
             selected_instance: None,
             custom_jar: None,
 
             is_log_open: false,
             is_launching_game: false,
+            launch_options: LaunchOptions::default(),
 
             log_scroll: 0,
             tick_timer: 0,
@@ -286,6 +298,7 @@ impl Launcher {
 
             logs: HashMap::new(),
             processes: HashMap::new(),
+            detached_processes: HashMap::new(),
             accounts: HashMap::new(),
             keys_pressed: HashSet::new(),
 
@@ -399,6 +412,7 @@ fn load_account(
                         .username_nice
                         .clone()
                         .unwrap_or_else(|| username.to_owned()),
+                    alias: account.alias.clone(),
                 },
             );
         }
@@ -412,26 +426,22 @@ fn load_account(
     }
 }
 
-pub async fn get_entries(kind: InstanceKind) -> Res<(Vec<String>, InstanceKind)> {
-    let dir_path = kind.get_root_directory();
-    if !exists(&dir_path).await {
-        tokio::fs::create_dir_all(&dir_path)
-            .await
-            .path(&dir_path)
-            .strerr()?;
-        return Ok((Vec::new(), kind));
-    }
+/// [`ql_core::list_instances`], for use with [`Message::CoreListLoaded`].
+pub async fn list_instances() -> Res<Vec<InstanceSummary>> {
+    ql_core::list_instances().await.strerr()
+}
 
-    Ok((
-        file_utils::read_filenames_from_dir(&dir_path)
-            .await
-            .strerr()?
-            .into_iter()
-            .filter(|n| !n.is_file)
-            .map(|n| n.name)
-            .collect(),
-        kind,
-    ))
+/// Looks for games still running from an earlier launcher session,
+/// for use with [`LaunchMessage::FoundRunning`].
+pub async fn find_running_processes(clients: Vec<String>) -> Vec<(Instance, RunningProcess)> {
+    let mut found = Vec::new();
+    for name in clients {
+        let instance = Instance::client(&name);
+        if let Some(process) = RunningProcess::find(&instance).await {
+            found.push((instance, process));
+        }
+    }
+    found
 }
 
 pub struct ProgressBar<T: Progress> {
@@ -1,12 +1,26 @@
+use serde::{Deserialize, Deserializer};
+
 use super::styles::{BORDER_RADIUS, BORDER_WIDTH};
 
+/// A color scheme, one color per [`Color`].
+///
+/// Can also be loaded from a theme file (see [`super::custom`]),
+/// with each color written as a hex code like `"#3a2436"`.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
 pub struct Palette {
+    #[serde(deserialize_with = "hex_color")]
     extra_dark: [u8; 3],
+    #[serde(deserialize_with = "hex_color")]
     dark: [u8; 3],
+    #[serde(deserialize_with = "hex_color")]
     second_dark: [u8; 3],
+    #[serde(deserialize_with = "hex_color")]
     mid: [u8; 3],
+    #[serde(deserialize_with = "hex_color")]
     second_light: [u8; 3],
+    #[serde(deserialize_with = "hex_color")]
     light: [u8; 3],
+    #[serde(deserialize_with = "hex_color")]
     white: [u8; 3],
 }
 
@@ -116,6 +130,24 @@ impl Color {
     }
 }
 
+/// Parses `"#rrggbb"` (the `#` is optional).
+pub fn parse_hex(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 3], D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    parse_hex(&hex).ok_or_else(|| {
+        serde::de::Error::custom(format!("invalid color {hex:?}, expected \"#rrggbb\""))
+    })
+}
+
 pub trait IntoIced {
     fn into_color(self) -> iced::Color;
 }
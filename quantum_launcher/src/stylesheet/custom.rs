@@ -0,0 +1,169 @@
+//! User-made color schemes, loaded from
+//! `QuantumLauncher/themes/*.toml`.
+//!
+//! A theme file looks like this
+//! (every color is required, `name` defaults to the file name):
+//!
+//! ```toml
+//! name = "Forest"
+//! extra_dark = "#101a12"
+//! dark = "#1c2b1f"
+//! second_dark = "#2f4a34"
+//! mid = "#5f8f66"
+//! second_light = "#a8d5a2"
+//! light = "#d8f0d0"
+//! white = "#f0fff0"
+//! ```
+//!
+//! Like the built-in schemes, these are written for dark mode
+//! and get inverted in light mode.
+
+use std::{path::Path, sync::LazyLock};
+
+use ql_core::{LAUNCHER_DIR, err};
+use serde::Deserialize;
+
+use super::{color::Palette, styles::LauncherThemeColor};
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct CustomTheme {
+    #[serde(default)]
+    pub name: String,
+    #[serde(flatten)]
+    pub palette: Palette,
+}
+
+impl CustomTheme {
+    /// Parses a theme file. `fallback_name` is used
+    /// if the file doesn't specify a name.
+    pub fn parse(toml: &str, fallback_name: &str) -> Result<Self, String> {
+        let mut theme: Self = toml::from_str(toml).map_err(|n| n.to_string())?;
+        theme.name = theme.name.trim().to_owned();
+        if theme.name.is_empty() {
+            fallback_name.clone_into(&mut theme.name);
+        }
+        Ok(theme)
+    }
+}
+
+/// All the themes in `QuantumLauncher/themes/`,
+/// read once when first needed.
+///
+/// Themes are leaked so that
+/// [`super::styles::LauncherThemeColor`] can stay `Copy`,
+/// they live for the whole program anyway.
+pub static CUSTOM_THEMES: LazyLock<Vec<&'static CustomTheme>> =
+    LazyLock::new(|| load_themes(&LAUNCHER_DIR.join("themes")));
+
+fn load_themes(dir: &Path) -> Vec<&'static CustomTheme> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut themes: Vec<&'static CustomTheme> = Vec::new();
+    for path in entries.filter_map(Result::ok).map(|n| n.path()) {
+        if path.extension().is_none_or(|n| n != "toml") {
+            continue;
+        }
+        let fallback_name = path
+            .file_stem()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let theme = std::fs::read_to_string(&path)
+            .map_err(|n| n.to_string())
+            .and_then(|n| CustomTheme::parse(&n, &fallback_name));
+        match theme {
+            // Saved by name in the config, so it'd load as the built-in one
+            Ok(theme)
+                if LauncherThemeColor::ALL
+                    .iter()
+                    .any(|n| n.to_string() == theme.name) =>
+            {
+                err!(
+                    "Skipping theme {path:?}: name {:?} is used by a built-in theme",
+                    theme.name
+                );
+            }
+            Ok(theme) if themes.iter().any(|n| n.name == theme.name) => {
+                err!(
+                    "Skipping theme {path:?}: name {:?} is already taken",
+                    theme.name
+                );
+            }
+            Ok(theme) => themes.push(Box::leak(Box::new(theme))),
+            Err(error) => err!("Couldn't load theme {path:?}:\n{error}"),
+        }
+    }
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stylesheet::{
+        color::{Color, IntoIced},
+        styles::{LauncherTheme, LauncherThemeColor, LauncherThemeLightness},
+    };
+
+    const SAMPLE: &str = r##"
+name = "Forest"
+extra_dark = "#101a12"
+dark = "#1c2b1f"
+second_dark = "#2f4a34"
+mid = "#5f8f66"
+second_light = "a8d5a2"
+light = "#d8f0d0"
+white = "#f0fff0"
+"##;
+
+    #[test]
+    fn loads_sample_theme() {
+        let theme: &'static CustomTheme =
+            Box::leak(Box::new(CustomTheme::parse(SAMPLE, "forest").unwrap()));
+        assert_eq!(theme.name, "Forest");
+
+        let theme = LauncherTheme {
+            lightness: LauncherThemeLightness::Dark,
+            color: LauncherThemeColor::Custom(theme),
+            alpha: 1.0,
+            system_dark_mode: true,
+        };
+        assert_eq!(theme.get(Color::Mid), [0x5f, 0x8f, 0x66].into_color());
+        assert_eq!(
+            theme.get(Color::SecondLight),
+            [0xa8, 0xd5, 0xa2].into_color()
+        );
+        assert_eq!(theme.color.to_string(), "Forest");
+    }
+
+    #[test]
+    fn skips_malformed_themes() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let unnamed = SAMPLE.replace("name = \"Forest\"", "");
+        std::fs::write(dir.join("mine.toml"), unnamed).unwrap();
+        std::fs::write(
+            dir.join("bad_color.toml"),
+            SAMPLE.replace("#5f8f66", "green"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("missing.toml"),
+            "name = \"Half\"\nmid = \"#000000\"",
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a theme").unwrap();
+        std::fs::write(
+            dir.join("builtin.toml"),
+            SAMPLE.replace("Forest", "Sky Blue"),
+        )
+        .unwrap();
+
+        let themes = load_themes(dir);
+        assert_eq!(themes.len(), 1);
+        assert_eq!(themes[0].name, "mine");
+    }
+}
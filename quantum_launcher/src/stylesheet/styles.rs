@@ -10,24 +10,28 @@ use crate::stylesheet::color::{ADWAITA_DARK, ADWAITA_LIGHT};
 
 use super::{
     color::{BROWN, CATPPUCCIN, Color, HALLOWEEN, PURPLE, SKY_BLUE, TEAL},
+    custom::{CUSTOM_THEMES, CustomTheme},
     widgets::{IsFlat, StyleButton, StyleScrollable},
 };
 
 pub const BORDER_WIDTH: f32 = 1.0;
 pub const BORDER_RADIUS: f32 = 8.0;
 
+/// Stored in the config by name (see the [`Display`]
+/// and [`FromStr`] impls), unknown names fall back to the default.
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
 pub enum LauncherThemeColor {
     Brown,
-    #[serde(rename = "Sky Blue")]
     SkyBlue,
     Catppuccin,
     Teal,
     Halloween,
     Adwaita,
     #[default]
-    #[serde(other)]
     Purple,
+    /// Loaded from `QuantumLauncher/themes/` (see [`super::custom`])
+    Custom(&'static CustomTheme),
 }
 
 impl LauncherThemeColor {
@@ -40,6 +44,14 @@ impl LauncherThemeColor {
         Self::Halloween,
         Self::Adwaita,
     ];
+
+    /// [`LauncherThemeColor::ALL`], followed by the user's custom themes.
+    pub fn all() -> impl Iterator<Item = Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .chain(CUSTOM_THEMES.iter().copied().map(Self::Custom))
+    }
 }
 
 impl Display for LauncherThemeColor {
@@ -52,6 +64,7 @@ impl Display for LauncherThemeColor {
             LauncherThemeColor::Teal => "Teal",
             LauncherThemeColor::Halloween => "Halloween",
             LauncherThemeColor::Adwaita => "Adwaita",
+            LauncherThemeColor::Custom(theme) => theme.name.as_str(),
         })
     }
 }
@@ -69,13 +82,29 @@ impl FromStr for LauncherThemeColor {
             "Halloween" => LauncherThemeColor::Halloween,
             "Adwaita" => LauncherThemeColor::Adwaita,
             _ => {
-                err!("Unknown style: {s:?}");
-                LauncherThemeColor::default()
+                if let Some(theme) = CUSTOM_THEMES.iter().find(|n| n.name == s) {
+                    LauncherThemeColor::Custom(*theme)
+                } else {
+                    err!("Unknown style: {s:?}");
+                    LauncherThemeColor::default()
+                }
             }
         })
     }
 }
 
+impl From<String> for LauncherThemeColor {
+    fn from(value: String) -> Self {
+        value.parse().unwrap_or_default()
+    }
+}
+
+impl From<LauncherThemeColor> for String {
+    fn from(value: LauncherThemeColor) -> Self {
+        value.to_string()
+    }
+}
+
 #[derive(Serialize, Deserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub enum LauncherThemeLightness {
     Light,
@@ -158,6 +187,7 @@ impl LauncherTheme {
             LauncherThemeColor::Catppuccin => &CATPPUCCIN,
             LauncherThemeColor::Teal => &TEAL,
             LauncherThemeColor::Halloween => &HALLOWEEN,
+            LauncherThemeColor::Custom(theme) => &theme.palette,
             LauncherThemeColor::Adwaita => unreachable!(),
         }
     }
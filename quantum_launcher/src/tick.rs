@@ -14,8 +14,8 @@ use ql_mod_manager::store::{ModConfig, ModId, ModIndex};
 use crate::state::{
     AutoSaveKind, EditInstanceMessage, GameProcess, InstallModsMessage, InstanceLog, LaunchModal,
     LaunchTab, Launcher, LogState, ManageJarModsMessage, MenuCreateInstance, MenuEditMods,
-    MenuExportInstance, MenuInstallFabric, MenuInstallOptifine, MenuLaunch, MenuLoginMS,
-    MenuModsDownload, MenuRecommendedMods, Message, ModListEntry, State,
+    MenuExportInstance, MenuImportModpack, MenuInstallFabric, MenuInstallOptifine, MenuLaunch,
+    MenuLoginMS, MenuModsDownload, MenuRecommendedMods, Message, ModListEntry, State,
 };
 use crate::{config::SIDEBAR_WIDTH, state::InfoMessage};
 
@@ -159,7 +159,7 @@ impl Launcher {
                 }
             }
             State::AccountLoginProgress(progress)
-            | State::ImportModpack(progress)
+            | State::ImportModpack(MenuImportModpack { progress, .. })
             | State::ExportInstance(MenuExportInstance {
                 progress: Some(progress),
                 ..
@@ -1,6 +1,7 @@
 use iced::{Task, futures::executor::block_on};
-use ql_core::{InstanceKind, IntoIoError, IntoStringError, err, file_utils::DirItem, info};
-use std::fmt::Write;
+use ql_core::{
+    InstanceKind, InstanceSummary, IntoIoError, IntoStringError, err, file_utils::DirItem, info,
+};
 use tokio::io::AsyncWriteExt;
 
 #[allow(unused)]
@@ -10,9 +11,9 @@ use owo_colors::OwoColorize;
 use crate::launcher_update::UpdateCheckInfo;
 use crate::{
     state::{
-        AutoSaveKind, CustomJarState, DirWatcher, GameProcess, InfoMessage, Launcher,
+        AutoSaveKind, CustomJarState, GameProcess, InfoMessage, LaunchMessage, Launcher,
         LauncherSettingsMessage, ManageModsMessage, MenuExportInstance, MenuLicense, MenuWelcome,
-        Message, ProgressBar, State, dir_watch, get_entries,
+        Message, ProgressBar, State, find_running_processes, list_instances, watch_instances,
     },
     stylesheet::styles::LauncherThemeLightness,
 };
@@ -39,10 +40,30 @@ impl Launcher {
                     self.close_launcher();
                 }
             }
+            #[cfg(feature = "auto_update")]
+            Message::UpdateRollback => {
+                if let Err(err) = crate::launcher_update::rollback_launcher_update() {
+                    self.set_error(err);
+                } else {
+                    self.close_launcher();
+                }
+            }
 
             Message::CoreCleanComplete(Err(err)) => {
                 err!(no_log, "{err}");
             }
+            Message::CoreMigrateComplete(Ok(report)) => {
+                if !report.is_empty() {
+                    info!(
+                        no_log,
+                        "Migrated {} old launcher files",
+                        report.changes.len()
+                    );
+                }
+            }
+            Message::CoreMigrateComplete(Err(err)) => {
+                err!(no_log, "Couldn't migrate old launcher files: {err}");
+            }
 
             Message::UninstallLoaderEnd(Err(err))
             | Message::InstallForgeEnd(Err(err))
@@ -111,13 +132,11 @@ impl Launcher {
                 }
             }
             Message::CoreCopyLog => {
-                let text = ql_core::print::get();
-
-                let mut log = String::new();
-                for (line, kind) in text {
-                    _ = writeln!(log, "{kind} {line}");
-                }
-                return iced::clipboard::write(format!("QuantumLauncher Log:\n{log}"));
+                let instance = self.selected_instance.clone();
+                return Task::perform(
+                    async move { ql_instances::collect_log_for_sharing(instance.as_ref()).await },
+                    Message::CoreCopyText,
+                );
             }
             Message::CoreImageDownloaded(res) => match res {
                 Ok(image) => {
@@ -129,6 +148,11 @@ impl Launcher {
             },
             Message::CoreTick => {
                 self.tick_timer = self.tick_timer.wrapping_add(1);
+                #[cfg(feature = "auto_update")]
+                if self.tick_timer == 1 {
+                    // UI has loaded, the launcher isn't broken
+                    crate::launcher_update::mark_launch_succeeded();
+                }
                 let mut tasks = self.images.task_get_imgs_to_load();
                 tasks.push(self.tick());
                 tasks.push(self.task_read_system_theme());
@@ -149,13 +173,12 @@ impl Launcher {
                     tasks.push(CustomJarState::load());
                 }
 
-                let mut watch_reload = |watcher: Option<&DirWatcher>, kind| {
-                    if watcher.is_some_and(DirWatcher::has_changed) {
-                        tasks.push(Task::perform(get_entries(kind), Message::CoreListLoaded));
+                if let Some((_, changes)) = &self.instance_watcher {
+                    // Many changes at once only need one reload
+                    if changes.try_iter().count() > 0 {
+                        tasks.push(Task::perform(list_instances(), Message::CoreListLoaded));
                     }
-                };
-                watch_reload(self.client_watcher.as_ref(), InstanceKind::Client);
-                watch_reload(self.server_watcher.as_ref(), InstanceKind::Server);
+                }
 
                 return Task::batch(tasks);
             }
@@ -183,9 +206,10 @@ impl Launcher {
                     self.autosave.remove(&AutoSaveKind::LauncherConfig);
                     ql_core::pt!(no_log, "{}", "Latest version".bright_black());
                 }
-                Ok(UpdateCheckInfo::NewVersion { url }) => {
+                Ok(UpdateCheckInfo::NewVersion { url, sha256 }) => {
                     self.state = State::UpdateFound(crate::state::MenuLauncherUpdate {
                         url,
+                        sha256,
                         progress: None,
                     });
                 }
@@ -231,8 +255,20 @@ impl Launcher {
                     _ = block_on(future);
                 }
             }
-            Message::CoreListLoaded(Ok((list, kind))) => {
-                self.core_list_loaded(list, kind);
+            Message::CoreListLoaded(Ok(list)) => {
+                let (servers, clients): (Vec<_>, Vec<_>) =
+                    list.into_iter().partition(|n| n.is_server);
+                let names = |list: Vec<InstanceSummary>| -> Vec<String> {
+                    list.into_iter().map(|n| n.name).collect()
+                };
+                let (clients, servers) = (names(clients), names(servers));
+
+                let find_task = Task::perform(find_running_processes(clients.clone()), |n| {
+                    LaunchMessage::FoundRunning(n).into()
+                });
+                self.core_list_loaded(clients, InstanceKind::Client);
+                self.core_list_loaded(servers, InstanceKind::Server);
+                return find_task;
             }
             Message::CoreCopyText(txt) => {
                 return iced::clipboard::write(txt);
@@ -278,14 +314,15 @@ impl Launcher {
                     entries: None,
                     progress: None,
                 });
+                let instance = self.selected_instance.clone().unwrap();
                 return Task::perform(
-                    ql_core::file_utils::read_filenames_from_dir(
-                        self.selected_instance
-                            .clone()
-                            .unwrap()
-                            .get_dot_minecraft_path(),
-                    ),
-                    |n| Message::ExportInstanceLoaded(n.strerr()),
+                    async move {
+                        let game_dir = instance.get_game_dir().await.strerr()?;
+                        ql_core::file_utils::read_filenames_from_dir(game_dir)
+                            .await
+                            .strerr()
+                    },
+                    Message::ExportInstanceLoaded,
                 );
             }
             Message::ExportInstanceLoaded(res) => {
@@ -421,22 +458,18 @@ impl Launcher {
             self.unselect_instance();
         }
 
-        let (self_list, self_watcher) = match kind {
-            InstanceKind::Client => (&mut self.client_list, &mut self.client_watcher),
-            InstanceKind::Server => (&mut self.server_list, &mut self.server_watcher),
+        let self_list = match kind {
+            InstanceKind::Client => &mut self.client_list,
+            InstanceKind::Server => &mut self.server_list,
         };
         *self_list = Some(list);
 
-        if self_watcher.is_none() {
-            let dir = kind.get_root_directory();
-            let watcher = match dir_watch(dir) {
-                Ok(n) => n,
-                Err(err) => {
-                    err!("Couldn't start dir watcher! {err}");
-                    return;
-                }
-            };
-            *self_watcher = Some(watcher);
+        if self.instance_watcher.is_none() {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            match watch_instances(sender) {
+                Ok(watcher) => self.instance_watcher = Some((watcher, receiver)),
+                Err(err) => err!("Couldn't start dir watcher! {err}"),
+            }
         }
     }
 
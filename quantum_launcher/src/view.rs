@@ -10,7 +10,8 @@ use crate::{
         view_log_upload_result,
     },
     state::{
-        Launcher, MenuCreateInstance, MenuCreateInstanceChoosing, Message, State, WindowMessage,
+        Launcher, ManageModsMessage, MenuCreateInstance, MenuCreateInstanceChoosing, Message,
+        State, WindowMessage,
     },
     stylesheet::{color::Color, styles::LauncherTheme, widgets::StyleButton},
 };
@@ -148,12 +149,18 @@ impl Launcher {
             State::ChangeLog => view_changelog(&self.config),
             State::Welcome(menu) => menu.view(&self.config),
             State::EditJarMods(menu) => menu.view(self.instance()),
-            State::ImportModpack(progress) => {
-                widget::column![widget::text("Installing mods..."), progress.view()]
-                    .padding(10)
-                    .spacing(10)
-                    .into()
-            }
+            State::ImportModpack(menu) => widget::column![
+                widget::text("Installing mods..."),
+                menu.progress.view(),
+                widget::button("Cancel").on_press_maybe(
+                    menu.cancel
+                        .is_some()
+                        .then_some(ManageModsMessage::AddFileCancel.into())
+                ),
+            ]
+            .padding(10)
+            .spacing(10)
+            .into(),
             State::LogUploadResult { url } => view_log_upload_result(url),
             State::CreateShortcut(menu) => menu.view(&self.accounts_dropdown),
             State::LoginAlternate(menu) => menu.view(self.tick_timer),
@@ -18,6 +18,7 @@ pub async fn launch(name: &str, timeout: f32, cli: &Cli) -> bool {
             None,
             None,
             Vec::new(),
+            ql_instances::LaunchOptions::default(),
         )
         .await,
     );
@@ -1,7 +1,7 @@
 use std::{fmt::Display, path::PathBuf, process::exit};
 
 use clap::Parser;
-use ql_core::{LAUNCHER_DIR, ListEntry, Loader, do_jobs, eeprintln, print::LogConfig};
+use ql_core::{LAUNCHER_DIR, ListEntry, Loader, NameError, do_jobs, eeprintln, print::LogConfig};
 use ql_instances::DownloadError;
 
 use crate::version::{VERSIONS_LWJGL2, VERSIONS_LWJGL3, Version};
@@ -157,9 +157,19 @@ fn setup_dir() {
 }
 
 async fn create_instance(version: String) -> Result<(), DownloadError> {
-    match ql_instances::create_instance(version.clone(), ListEntry::new(version), None, false).await
+    match ql_instances::create_instance(
+        version.clone(),
+        ListEntry::new(version),
+        None,
+        ql_instances::CreateOptions {
+            download_assets: false,
+            install_java: false,
+        },
+        None,
+    )
+    .await
     {
-        Ok(_) | Err(DownloadError::InstanceAlreadyExists(_)) => Ok(()),
+        Ok(_) | Err(DownloadError::InvalidName(NameError::AlreadyExists(_))) => Ok(()),
         Err(err) => Err(err),
     }
 }
@@ -168,5 +178,6 @@ fn set_terminal(terminal: bool) {
     ql_core::print::set_config(LogConfig {
         terminal,
         file: false,
+        ..Default::default()
     })
 }